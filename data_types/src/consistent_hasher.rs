@@ -1,5 +1,6 @@
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
 
 /// A ConsistentHasher implements a simple consistent hashing mechanism
 /// that maps a point to the nearest "node" N.
@@ -30,12 +31,30 @@ where
     }
 
     pub fn find<H: Hash>(&self, point: H) -> Option<T> {
-        let point_hash = Self::hash(point);
-        self.ring
-            .iter()
-            .find(|(node_hash, _)| node_hash > &point_hash)
-            .or_else(|| self.ring.first())
-            .map(|(_, node)| *node)
+        self.position(point).map(|idx| self.ring[idx].1)
+    }
+
+    /// Returns an assigner that maps points to nodes like [`find`](Self::find)
+    /// does, except that no node is assigned more than `load_factor` times
+    /// the mean number of points assigned per node, rounded up. Points that
+    /// would overflow a node are assigned to the next node on the ring that
+    /// has capacity left.
+    ///
+    /// This is "consistent hashing with bounded loads". Panics if
+    /// `load_factor` is less than 1.0, as the nodes couldn't take all points.
+    pub fn bounded_loads(&self, load_factor: f64) -> BoundedLoads<'_, T> {
+        assert!(
+            load_factor >= 1.0,
+            "load factor must be at least 1.0, got {}",
+            load_factor
+        );
+
+        BoundedLoads {
+            hasher: self,
+            load_factor,
+            loads: vec![0; self.ring.len()],
+            assigned: 0,
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -46,6 +65,21 @@ where
         self.ring.len()
     }
 
+    // Returns the index in the ring of the node `point` maps to.
+    fn position<H: Hash>(&self, point: H) -> Option<usize> {
+        if self.ring.is_empty() {
+            return None;
+        }
+
+        let point_hash = Self::hash(point);
+        Some(
+            self.ring
+                .iter()
+                .position(|(node_hash, _)| node_hash > &point_hash)
+                .unwrap_or(0),
+        )
+    }
+
     fn hash<H: Hash>(h: H) -> u64 {
         let mut hasher = DefaultHasher::new();
         h.hash(&mut hasher);
@@ -53,6 +87,135 @@ where
     }
 }
 
+impl<T> ConsistentHasher<T>
+where
+    T: Copy + Hash + PartialEq,
+{
+    /// Removes `node` from the ring, returning the ranges of hash values it
+    /// owned along with the node that owns each of them now.
+    ///
+    /// Returns no ranges if `node` is not in the ring, or if it was the only
+    /// node in the ring, as there's no successor to take over its points.
+    pub fn remove_node(&mut self, node: T) -> Vec<RingRange<T>> {
+        let idx = match self.ring.iter().position(|(_, n)| *n == node) {
+            Some(idx) => idx,
+            None => return vec![],
+        };
+
+        // a node owns the points hashing to at least the hash of its
+        // predecessor and less than its own hash, wrapping around the ring
+        let (node_hash, _) = self.ring.remove(idx);
+        if self.ring.is_empty() {
+            return vec![];
+        }
+
+        let (predecessor_hash, successor) = match idx {
+            0 => (self.ring[self.ring.len() - 1].0, self.ring[0].1),
+            _ => (self.ring[idx - 1].0, self.ring[idx % self.ring.len()].1),
+        };
+
+        let mut ranges = Vec::with_capacity(2);
+        if predecessor_hash < node_hash {
+            ranges.push(RingRange {
+                range: predecessor_hash..=node_hash - 1,
+                successor,
+            });
+        } else {
+            ranges.push(RingRange {
+                range: predecessor_hash..=u64::MAX,
+                successor,
+            });
+            if node_hash > 0 {
+                ranges.push(RingRange {
+                    range: 0..=node_hash - 1,
+                    successor,
+                });
+            }
+        }
+
+        ranges
+    }
+}
+
+/// Assigns points to the nodes of a [`ConsistentHasher`] while keeping track
+/// of how many points each node was assigned, so that no node exceeds its
+/// capacity. See [`ConsistentHasher::bounded_loads`].
+#[derive(Debug, Clone)]
+pub struct BoundedLoads<'a, T>
+where
+    T: Copy + Hash,
+{
+    hasher: &'a ConsistentHasher<T>,
+    load_factor: f64,
+
+    /// The number of points assigned to each node, by position in the ring
+    loads: Vec<usize>,
+
+    /// The total number of points assigned
+    assigned: usize,
+}
+
+impl<'a, T> BoundedLoads<'a, T>
+where
+    T: Copy + Hash,
+{
+    /// Assigns `point` to the node it maps to, or to the next node on the
+    /// ring with capacity left if that node is full.
+    ///
+    /// Returns `None` if there are no nodes.
+    pub fn assign<H: Hash>(&mut self, point: H) -> Option<T> {
+        let start = self.hasher.position(point)?;
+
+        // The capacity accounts for the point being assigned, so that the
+        // nodes can always take it.
+        let capacity = self.capacity(self.assigned + 1);
+        let len = self.loads.len();
+        let idx = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|idx| self.loads[*idx] < capacity)
+            .expect("nodes have capacity for every point");
+
+        self.loads[idx] += 1;
+        self.assigned += 1;
+        Some(self.hasher.ring[idx].1)
+    }
+
+    /// Returns the number of points assigned to each node, in ring order.
+    pub fn loads(&self) -> impl Iterator<Item = (T, usize)> + '_ {
+        self.hasher
+            .ring
+            .iter()
+            .zip(&self.loads)
+            .map(|((_, node), load)| (*node, *load))
+    }
+
+    // The most points a node may be assigned out of `points` in total.
+    fn capacity(&self, points: usize) -> usize {
+        let mean = points as f64 / self.loads.len() as f64;
+        (mean * self.load_factor).ceil() as usize
+    }
+}
+
+/// A range of hash values on a [`ConsistentHasher`] ring and the node that
+/// owns them.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RingRange<T> {
+    /// The hash values in this range
+    pub range: RangeInclusive<u64>,
+
+    /// The node points in this range map to
+    pub successor: T,
+}
+
+impl<T> RingRange<T> {
+    /// Returns true if the hash of `point` falls within this range.
+    pub fn contains<H: Hash>(&self, point: H) -> bool {
+        let mut hasher = DefaultHasher::new();
+        point.hash(&mut hasher);
+        self.range.contains(&hasher.finish())
+    }
+}
+
 impl<T> From<ConsistentHasher<T>> for Vec<T>
 where
     T: Copy + Hash,
@@ -164,4 +327,77 @@ mod tests {
         assert_eq!(ch.find(14), Some(20));
         assert_eq!(ch.find(18), Some(20));
     }
+
+    #[test]
+    fn test_bounded_loads() {
+        let nodes: Vec<u32> = (0..10).collect();
+        let ch = ConsistentHasher::new(&nodes);
+        let points = 10_000;
+
+        let mut bounded = ch.bounded_loads(1.1);
+        for point in 0..points {
+            assert!(bounded.assign(point).is_some());
+        }
+
+        let max_load = (points as f64 / nodes.len() as f64 * 1.1).ceil() as usize;
+        let loads: Vec<_> = bounded.loads().collect();
+        assert_eq!(loads.len(), nodes.len());
+        assert_eq!(loads.iter().map(|(_, load)| load).sum::<usize>(), points);
+        for (node, load) in loads {
+            assert!(
+                load <= max_load,
+                "node {} was assigned {} points, more than {}",
+                node,
+                load,
+                max_load
+            );
+        }
+
+        // without a bound, points map to the same nodes as with find
+        let mut unbounded = ch.bounded_loads(f64::INFINITY);
+        for point in 0..points {
+            assert_eq!(unbounded.assign(point), ch.find(point));
+        }
+
+        let empty = ConsistentHasher::<u32>::new(&[]);
+        assert_eq!(empty.bounded_loads(1.0).assign(1), None);
+    }
+
+    #[test]
+    fn test_remove_node() {
+        let before = ConsistentHasher::new(&[10, 20, 30]);
+
+        // the node in the middle of the ring, i.e. neither the first nor the
+        // last by hash
+        let middle = before.ring[1].1;
+
+        let mut after = before.clone();
+        let ranges = after.remove_node(middle);
+        assert_eq!(after.len(), 2);
+        assert!(!ranges.is_empty());
+
+        let mut displaced = 0;
+        for point in 0..1000 {
+            let covered: Vec<_> = ranges.iter().filter(|r| r.contains(point)).collect();
+
+            if before.find(point) == Some(middle) {
+                // points the removed node owned are covered by exactly one
+                // range, whose successor owns them now
+                assert_eq!(covered.len(), 1);
+                assert_eq!(after.find(point), Some(covered[0].successor));
+                displaced += 1;
+            } else {
+                // all other points are unaffected
+                assert!(covered.is_empty());
+                assert_eq!(after.find(point), before.find(point));
+            }
+        }
+        assert!(displaced > 0);
+
+        // removing a node that isn't there, or the last node, displaces nothing
+        assert!(after.remove_node(middle).is_empty());
+        let mut single = ConsistentHasher::new(&[10]);
+        assert!(single.remove_node(10).is_empty());
+        assert!(single.is_empty());
+    }
 }
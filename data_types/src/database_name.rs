@@ -51,29 +51,55 @@ pub enum DatabaseNameError {
 )]
 pub struct DatabaseName<'a>(Cow<'a, str>);
 
+/// The reason a candidate name is not a valid database name.
+enum Violation {
+    Length,
+    BadChar { offset: usize },
+}
+
+/// Checks `name` against the database name rules without allocating.
+fn check(name: &str) -> Result<(), Violation> {
+    if !LENGTH_CONSTRAINT.contains(&name.len()) {
+        return Err(Violation::Length);
+    }
+
+    // Validate the name contains only valid characters.
+    //
+    // NOTE: If changing these characters, please update the error message
+    // above.
+    if let Some(offset) = name.chars().position(|c| c.is_control()) {
+        return Err(Violation::BadChar { offset });
+    };
+
+    Ok(())
+}
+
 impl<'a> DatabaseName<'a> {
     pub fn new<T: Into<Cow<'a, str>>>(name: T) -> Result<Self, DatabaseNameError> {
         let name: Cow<'a, str> = name.into();
+        Self::validate(&name)?;
+        Ok(Self(name))
+    }
 
-        if !LENGTH_CONSTRAINT.contains(&name.len()) {
-            return Err(DatabaseNameError::LengthConstraint {
-                name: name.to_string(),
-            });
-        }
+    /// Returns true if `name` is a valid database name.
+    ///
+    /// Unlike `new` and `validate`, this never allocates.
+    pub fn is_valid(name: &str) -> bool {
+        check(name).is_ok()
+    }
 
-        // Validate the name contains only valid characters.
-        //
-        // NOTE: If changing these characters, please update the error message
-        // above.
-        if let Some(bad_char_offset) = name.chars().position(|c| c.is_control()) {
-            return BadChars {
-                bad_char_offset,
+    /// Checks that `name` is a valid database name without constructing a
+    /// `DatabaseName`.
+    pub fn validate(name: &str) -> Result<(), DatabaseNameError> {
+        match check(name) {
+            Ok(()) => Ok(()),
+            Err(Violation::Length) => LengthConstraint { name }.fail(),
+            Err(Violation::BadChar { offset }) => BadChars {
+                bad_char_offset: offset,
                 name,
             }
-            .fail();
-        };
-
-        Ok(Self(name))
+            .fail(),
+        }
     }
 
     pub fn as_str(&self) -> &str {
@@ -181,6 +207,40 @@ mod tests {
         assert_contains!(got.to_string() , "Database name 'my_example\ndb' contains invalid character. Character number 10 is a control which is not allowed.");
     }
 
+    #[test]
+    fn test_validate() {
+        let too_long = "a".repeat(LENGTH_CONSTRAINT.end() + 1);
+
+        assert!(matches!(
+            DatabaseName::validate(""),
+            Err(DatabaseNameError::LengthConstraint { .. })
+        ));
+        assert!(matches!(
+            DatabaseName::validate(&too_long),
+            Err(DatabaseNameError::LengthConstraint { .. })
+        ));
+        assert!(matches!(
+            DatabaseName::validate("example\tdb"),
+            Err(DatabaseNameError::BadChars {
+                bad_char_offset: 7,
+                ..
+            })
+        ));
+        assert!(matches!(
+            DatabaseName::validate("\u{007f}example"),
+            Err(DatabaseNameError::BadChars {
+                bad_char_offset: 0,
+                ..
+            })
+        ));
+        DatabaseName::validate("my-example-db_with_underscores and spaces").unwrap();
+
+        assert!(!DatabaseName::is_valid(""));
+        assert!(!DatabaseName::is_valid(&too_long));
+        assert!(!DatabaseName::is_valid("my_example\ndb"));
+        assert!(DatabaseName::is_valid("my_example_db"));
+    }
+
     #[test]
     fn test_ok_chars() {
         let db = DatabaseName::new("my-example-db_with_underscores and spaces").unwrap();
@@ -1,13 +1,17 @@
 use std::num::NonZeroU64;
 use std::time::Duration;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     hash::{Hash, Hasher},
     num::{NonZeroU32, NonZeroUsize},
+    ops::Range,
     sync::Arc,
 };
 
-use chrono::{TimeZone, Utc};
+use chrono::{
+    format::{Item, Parsed, StrftimeItems},
+    DateTime, TimeZone, Utc,
+};
 use regex::Regex;
 use snafu::{OptionExt, Snafu};
 
@@ -28,10 +32,55 @@ pub enum Error {
 
     #[snafu(display("No shards defined"))]
     NoShardsDefined,
+
+    #[snafu(display(
+        "Partition key of length {} exceeds the maximum length of {}",
+        key_len,
+        max_len
+    ))]
+    PartitionKeyTooLong { key_len: usize, max_len: usize },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// A problem found while linting [`DatabaseRules`] before they are applied.
+#[derive(Debug, Snafu, Clone, PartialEq, Eq)]
+pub enum RuleValidationIssue {
+    #[snafu(display("Partition template part {} has an invalid regex: {}", index, message))]
+    InvalidPartitionRegex { index: usize, message: String },
+
+    #[snafu(display(
+        "Partition template part {} has an invalid time format '{}'",
+        index,
+        format
+    ))]
+    InvalidPartitionTimeFormat { index: usize, format: String },
+
+    #[snafu(display("Soft buffer size {} exceeds the hard buffer size {}", soft, hard))]
+    BufferSizeSoftExceedsHard { soft: usize, hard: usize },
+
+    #[snafu(display("Shard {} is routed to but not defined", shard_id))]
+    UndefinedShard { shard_id: ShardId },
+
+    #[snafu(display("Shard {} has no nodes", shard_id))]
+    EmptyNodeGroup { shard_id: ShardId },
+
+    #[snafu(display(
+        "Shard {} requires {} replicas but only has {} nodes",
+        shard_id,
+        replication_factor,
+        nodes
+    ))]
+    ReplicationFactorExceedsNodeGroup {
+        shard_id: ShardId,
+        replication_factor: usize,
+        nodes: usize,
+    },
+
+    #[snafu(display("Invalid write buffer connection string '{}'", connection_string))]
+    InvalidWriteBufferConnectionString { connection_string: String },
+}
+
 /// DatabaseRules contains the rules for replicating data, sending data to
 /// subscribers, and querying data for a single database.
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -56,6 +105,13 @@ pub struct DatabaseRules {
 
     /// An optional connection string to a write buffer.
     pub write_buffer_connection_string: Option<String>,
+
+    /// The maximum length in bytes of a partition key, or `None` for no
+    /// limit. Longer keys are handled according to `partition_key_overflow`.
+    pub max_partition_key_len: Option<NonZeroUsize>,
+
+    /// How partition keys longer than `max_partition_key_len` are handled.
+    pub partition_key_overflow: PartitionKeyOverflow,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -76,7 +132,19 @@ pub enum RoutingRules {
 
 impl DatabaseRules {
     pub fn partition_key(&self, line: &ParsedLine<'_>, default_time: i64) -> Result<String> {
-        self.partition_template.partition_key(line, default_time)
+        let key = self.partition_template.partition_key(line, default_time)?;
+
+        match self.max_partition_key_len {
+            Some(max_len) if key.len() > max_len.get() => match self.partition_key_overflow {
+                PartitionKeyOverflow::Error => PartitionKeyTooLong {
+                    key_len: key.len(),
+                    max_len: max_len.get(),
+                }
+                .fail(),
+                PartitionKeyOverflow::HashTruncate => Ok(hash_truncate(&key, max_len.get())),
+            },
+            _ => Ok(key),
+        }
     }
 
     pub fn new(name: DatabaseName<'static>) -> Self {
@@ -87,6 +155,8 @@ impl DatabaseRules {
             routing_rules: None,
             worker_cleanup_avg_sleep: Duration::from_secs(500),
             write_buffer_connection_string: None,
+            max_partition_key_len: None,
+            partition_key_overflow: Default::default(),
         }
     }
 
@@ -95,6 +165,52 @@ impl DatabaseRules {
     }
 }
 
+/// What to do with a partition key that is longer than
+/// [`DatabaseRules::max_partition_key_len`].
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum PartitionKeyOverflow {
+    /// Reject the row, so that distinct keys can never collide
+    Error,
+    /// Truncate the key, replacing the overflow with a hash of the full key
+    HashTruncate,
+}
+
+impl Default for PartitionKeyOverflow {
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// Shortens `key` to `max_len` bytes by replacing its end with `#` followed
+/// by the hex encoded hash of the whole key. The hash keeps truncated keys
+/// with a common prefix distinct.
+///
+/// The hash (64 bit FNV-1a) must not change between releases or platforms,
+/// as the truncated keys identify persisted partitions.
+fn hash_truncate(key: &str, max_len: usize) -> String {
+    let suffix = format!("#{:016x}", fnv1a_64(key.as_bytes()));
+
+    let mut prefix_len = max_len.saturating_sub(suffix.len());
+    while !key.is_char_boundary(prefix_len) {
+        prefix_len -= 1;
+    }
+
+    let mut truncated = format!("{}{}", &key[..prefix_len], suffix);
+    // the suffix is ASCII, so this can't split a character
+    truncated.truncate(max_len);
+    truncated
+}
+
+/// 64 bit FNV-1a hash of `bytes`.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
 /// Generates a partition key based on the line and the default time.
 pub trait Partitioner {
     fn partition_key(&self, _line: &ParsedLine<'_>, _default_time: i64) -> Result<String>;
@@ -172,6 +288,10 @@ pub struct LifecycleRules {
 
     /// Maximum age of a write before triggering persistence
     pub persist_age_threshold_seconds: NonZeroU32,
+
+    /// Drop partitions once all the times their key can represent are older
+    /// than this. Partitions whose key isn't derived from time are kept.
+    pub retention_duration: Option<Duration>,
 }
 
 impl LifecycleRules {
@@ -179,6 +299,20 @@ impl LifecycleRules {
     pub fn late_arrive_window(&self) -> Duration {
         Duration::from_secs(self.late_arrive_window_seconds.get() as u64)
     }
+
+    /// Returns the problems with these rules, such as a soft buffer size
+    /// larger than the hard one.
+    pub fn validate(&self) -> Vec<RuleValidationIssue> {
+        match (self.buffer_size_soft, self.buffer_size_hard) {
+            (Some(soft), Some(hard)) if soft > hard => {
+                vec![RuleValidationIssue::BufferSizeSoftExceedsHard {
+                    soft: soft.get(),
+                    hard: hard.get(),
+                }]
+            }
+            _ => vec![],
+        }
+    }
 }
 
 impl Default for LifecycleRules {
@@ -203,6 +337,7 @@ impl Default for LifecycleRules {
             persist_row_threshold: NonZeroUsize::new(DEFAULT_PERSIST_ROW_THRESHOLD).unwrap(),
             persist_age_threshold_seconds: NonZeroU32::new(DEFAULT_PERSIST_AGE_THRESHOLD_SECONDS)
                 .unwrap(),
+            retention_duration: None,
         }
     }
 }
@@ -295,22 +430,237 @@ pub enum ColumnValue {
 ///
 /// The key is constructed in order of the template parts; thus ordering changes
 /// what partition key is generated.
-#[derive(Debug, Default, Eq, PartialEq, Clone)]
+///
+/// The parts are joined with `separator`. When a template has more than one
+/// part, any occurrence of the separator (or of the `\` escape character) in a
+/// table name or column value is escaped with a `\` so that distinct rows
+/// cannot produce the same key. Time formats are not escaped as their shape is
+/// fixed by the template.
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct PartitionTemplate {
     pub parts: Vec<TemplatePart>,
+    pub separator: String,
+}
+
+/// The separator used to join partition key parts if none is configured.
+pub const DEFAULT_PARTITION_KEY_SEPARATOR: &str = "-";
+
+/// The character used to escape the separator within partition key parts.
+pub const PARTITION_KEY_ESCAPE: char = '\\';
+
+impl Default for PartitionTemplate {
+    fn default() -> Self {
+        Self {
+            parts: vec![],
+            separator: DEFAULT_PARTITION_KEY_SEPARATOR.to_string(),
+        }
+    }
+}
+
+impl PartitionTemplate {
+    /// Returns the problems with this template, such as regexes or time
+    /// formats that can't be applied.
+    pub fn validate(&self) -> Vec<RuleValidationIssue> {
+        let invalid_time_format =
+            |format: &str| StrftimeItems::new(format).any(|item| matches!(item, Item::Error));
+
+        let mut issues = vec![];
+        for (index, part) in self.parts.iter().enumerate() {
+            match part {
+                TemplatePart::RegexCapture(RegexCapture { regex, .. }) => {
+                    if let Err(e) = Regex::new(regex) {
+                        issues.push(RuleValidationIssue::InvalidPartitionRegex {
+                            index,
+                            message: e.to_string(),
+                        })
+                    }
+                }
+                TemplatePart::TimeFormat(format)
+                | TemplatePart::StrftimeColumn(StrftimeColumn { format, .. })
+                | TemplatePart::TimeAndColumn(TimeAndColumn { format, .. }) => {
+                    if invalid_time_format(format) {
+                        issues.push(RuleValidationIssue::InvalidPartitionTimeFormat {
+                            index,
+                            format: format.clone(),
+                        })
+                    }
+                }
+                TemplatePart::Table | TemplatePart::Column(_) => {}
+            }
+        }
+        issues
+    }
+
+    /// Escapes the escape character and the separator within a single part
+    /// of a partition key.
+    fn escape_part(&self, part: &str) -> String {
+        let mut escaped = String::with_capacity(part.len());
+        let mut remaining = part;
+
+        while !remaining.is_empty() {
+            if !self.separator.is_empty() && remaining.starts_with(self.separator.as_str()) {
+                escaped.push(PARTITION_KEY_ESCAPE);
+                escaped.push_str(&self.separator);
+                remaining = &remaining[self.separator.len()..];
+                continue;
+            }
+
+            let c = remaining.chars().next().expect("remaining is not empty");
+            if c == PARTITION_KEY_ESCAPE {
+                escaped.push(PARTITION_KEY_ESCAPE);
+            }
+            escaped.push(c);
+            remaining = &remaining[c.len_utf8()..];
+        }
+
+        escaped
+    }
+
+    /// Splits a partition key into the byte ranges of its parts, skipping
+    /// escaped separators. The separator must not be empty.
+    fn split_key(&self, partition_key: &str) -> Vec<Range<usize>> {
+        let separator = self.separator.as_str();
+        let mut ranges = vec![];
+        let mut start = 0;
+        let mut pos = 0;
+
+        while pos < partition_key.len() {
+            let remaining = &partition_key[pos..];
+            if remaining.starts_with(separator) {
+                ranges.push(start..pos);
+                pos += separator.len();
+                start = pos;
+                continue;
+            }
+
+            let c = remaining.chars().next().expect("remaining is not empty");
+            pos += c.len_utf8();
+            if c == PARTITION_KEY_ESCAPE {
+                let escaped = &partition_key[pos..];
+                if escaped.starts_with(separator) {
+                    pos += separator.len();
+                } else if let Some(c) = escaped.chars().next() {
+                    pos += c.len_utf8();
+                }
+            }
+        }
+        ranges.push(start..pos);
+
+        ranges
+    }
+
+    /// Returns the first [`TemplatePart::TimeFormat`] of the template and the
+    /// part of `partition_key` it produced.
+    fn time_part<'a>(&'a self, partition_key: &'a str) -> Option<(&'a str, &'a str)> {
+        let (index, format) = self
+            .parts
+            .iter()
+            .enumerate()
+            .find_map(|(i, part)| match part {
+                TemplatePart::TimeFormat(format) => Some((i, format.as_str())),
+                _ => None,
+            })?;
+
+        if self.parts.len() == 1 {
+            return Some((format, partition_key));
+        }
+        if self.separator.is_empty() {
+            return None;
+        }
+
+        // Formatted times aren't escaped, but their shape is fixed by the
+        // format so they always span the same number of separators
+        let parts_in = |format: &str| {
+            Utc.timestamp(0, 0)
+                .format(format)
+                .to_string()
+                .matches(self.separator.as_str())
+                .count()
+                + 1
+        };
+
+        let first = self.parts[..index]
+            .iter()
+            .map(|part| match part {
+                TemplatePart::TimeFormat(format) => parts_in(format),
+                TemplatePart::TimeAndColumn(TimeAndColumn { format, .. }) => parts_in(format),
+                _ => 1,
+            })
+            .sum::<usize>();
+        let last = first + parts_in(format) - 1;
+
+        let ranges = self.split_key(partition_key);
+        let start = ranges.get(first)?.start;
+        let end = ranges.get(last)?.end;
+        Some((format, &partition_key[start..end]))
+    }
+
+    /// Returns true if every time that could be stored under `partition_key`
+    /// is before `cutoff`.
+    ///
+    /// This is only known for keys produced by a template with a
+    /// [`TemplatePart::TimeFormat`] part that includes at least the year; it
+    /// is assumed to describe a contiguous range of time such as
+    /// `"%Y-%m-%d %H:00:00"`. For any other key this returns false.
+    pub fn partition_is_before(&self, partition_key: &str, cutoff: DateTime<Utc>) -> bool {
+        let (format, time) = match self.time_part(partition_key) {
+            Some(time_part) => time_part,
+            None => return false,
+        };
+
+        match parse_time_start(format, time) {
+            // the range starts before the cutoff but doesn't contain it, so
+            // it must end before it
+            Some(start) => start < cutoff && cutoff.format(format).to_string() != time,
+            None => false,
+        }
+    }
+}
+
+/// Parses `time`, formatted with `format`, into the earliest instant that
+/// formats to it. Fields missing from the format default to their minimum.
+fn parse_time_start(format: &str, time: &str) -> Option<DateTime<Utc>> {
+    let mut parsed = Parsed::new();
+    chrono::format::parse(&mut parsed, time, StrftimeItems::new(format)).ok()?;
+
+    if parsed.ordinal.is_none() {
+        if parsed.month.is_none() {
+            parsed.set_month(1).ok()?;
+        }
+        if parsed.day.is_none() {
+            parsed.set_day(1).ok()?;
+        }
+    }
+    if parsed.hour_div_12.is_none() && parsed.hour_mod_12.is_none() {
+        parsed.set_hour(0).ok()?;
+    }
+    if parsed.minute.is_none() {
+        parsed.set_minute(0).ok()?;
+    }
+    if parsed.second.is_none() {
+        parsed.set_second(0).ok()?;
+    }
+
+    let naive = parsed.to_naive_datetime_with_offset(0).ok()?;
+    Some(DateTime::from_utc(naive, Utc))
 }
 
 impl Partitioner for PartitionTemplate {
     fn partition_key(&self, line: &ParsedLine<'_>, default_time: i64) -> Result<String> {
+        // a single part can't be confused with a neighbouring one so it is
+        // left as is, which keeps keys of existing single part templates stable
+        let escape = self.parts.len() > 1;
+        let escape_value = |v: String| if escape { self.escape_part(&v) } else { v };
+
         let parts: Vec<_> = self
             .parts
             .iter()
             .map(|p| match p {
-                TemplatePart::Table => line.series.measurement.to_string(),
+                TemplatePart::Table => escape_value(line.series.measurement.to_string()),
                 TemplatePart::Column(column) => match line.tag_value(&column) {
-                    Some(v) => format!("{}_{}", column, v),
+                    Some(v) => escape_value(format!("{}_{}", column, v)),
                     None => match line.field_value(&column) {
-                        Some(v) => format!("{}_{}", column, v),
+                        Some(v) => escape_value(format!("{}_{}", column, v)),
                         None => "".to_string(),
                     },
                 },
@@ -318,11 +668,19 @@ impl Partitioner for PartitionTemplate {
                     let nanos = line.timestamp.unwrap_or(default_time);
                     Utc.timestamp_nanos(nanos).format(&format).to_string()
                 }
+                TemplatePart::TimeAndColumn(TimeAndColumn { column, format }) => {
+                    let nanos = line.timestamp.unwrap_or(default_time);
+                    let time = Utc.timestamp_nanos(nanos).format(&format).to_string();
+                    match line.tag_value(&column) {
+                        Some(v) => format!("{}_{}_{}", time, column, self.escape_part(v.as_str())),
+                        None => time,
+                    }
+                }
                 _ => unimplemented!(),
             })
             .collect();
 
-        Ok(parts.join("-"))
+        Ok(parts.join(&self.separator))
     }
 }
 
@@ -344,6 +702,9 @@ pub enum TemplatePart {
     RegexCapture(RegexCapture),
     /// Applies a `strftime` pattern to some column other than "time"
     StrftimeColumn(StrftimeColumn),
+    /// Combines the formatted "time" column and the value of a tag in a single
+    /// part
+    TimeAndColumn(TimeAndColumn),
 }
 
 /// `RegexCapture` is for pulling parts of a string column into the partition
@@ -369,11 +730,47 @@ pub struct StrftimeColumn {
     pub format: String,
 }
 
-/// A routing config defines the destination where to route all data plane operations
+/// [`TimeAndColumn`] produces a single partition key part from the "time"
+/// column, formatted using a `strftime` style string, and the value of a tag.
+///
+/// The part is the formatted time followed by the tag name and value, joined
+/// with `_`. For example, a format of "%Y-%m-%d" and the column "tenant" will
+/// produce partition key parts such as "2021-03-14_tenant_acme". As the
+/// format and column name are fixed by the template, only the tag value
+/// varies at the end of the part and so the time bucket and value cannot be
+/// confused with each other.
+///
+/// The tag value is always escaped like other parts (see
+/// [`PartitionTemplate`]), even if it is the only part of the template. If
+/// the row does not have the tag, the part is just the formatted time.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TimeAndColumn {
+    pub column: String,
+    pub format: String,
+}
+
+/// A routing config defines the destinations where to route all data plane operations
 /// for a given database.
+///
+/// Every write is mirrored to each of the `targets`, e.g. a production cluster and an
+/// analytics cluster.
 #[derive(Debug, Eq, PartialEq, Clone, Default)]
 pub struct RoutingConfig {
-    pub target: NodeGroup,
+    pub targets: Vec<NodeGroup>,
+
+    /// Minimum number of targets that must accept a write for it to succeed.
+    /// If `None`, all targets must accept the write.
+    pub quorum: Option<NonZeroUsize>,
+}
+
+impl RoutingConfig {
+    /// Number of targets that must accept a write for it to succeed.
+    pub fn required_acks(&self) -> usize {
+        match self.quorum {
+            Some(quorum) => quorum.get().min(self.targets.len()),
+            None => self.targets.len(),
+        }
+    }
 }
 
 /// ShardId maps to a nodegroup that holds the the shard.
@@ -419,7 +816,25 @@ pub struct ShardConfig {
 /// Configuration for a specific IOx shard
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Shard {
-    Iox(NodeGroup),
+    Iox {
+        node_group: NodeGroup,
+
+        /// Number of nodes of the group that must accept a write before it is
+        /// acknowledged. If `None`, a single node accepting it is enough.
+        replication_factor: Option<NonZeroUsize>,
+    },
+}
+
+impl Shard {
+    /// Number of nodes that must accept a write to this shard for it to
+    /// succeed.
+    pub fn required_acks(&self) -> usize {
+        match self {
+            Self::Iox {
+                replication_factor, ..
+            } => replication_factor.map_or(1, NonZeroUsize::get),
+        }
+    }
 }
 
 struct LineHasher<'a, 'b, 'c> {
@@ -443,6 +858,68 @@ impl<'a, 'b, 'c> Hash for LineHasher<'a, 'b, 'c> {
     }
 }
 
+impl ShardConfig {
+    /// Returns the shard `line` is assigned to when written, or `None` if no
+    /// sharding rule matches it. Other sharding errors, such as a hash ring
+    /// without shards, are returned as is.
+    ///
+    /// This runs the same matchers and hashing as writes do, without any side
+    /// effects, so it can be used to plan capacity ahead of a write.
+    pub fn explain_shard(&self, line: &ParsedLine<'_>) -> Result<Option<ShardId>> {
+        match self.shard(line) {
+            Ok(shard_id) => Ok(Some(shard_id)),
+            Err(Error::NoShardingRuleMatches { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Counts how many of `lines` would be assigned to each shard. Lines that
+    /// no sharding rule matches are counted under `None`. Returns the first
+    /// other sharding error encountered.
+    pub fn preview(&self, lines: &[ParsedLine<'_>]) -> Result<BTreeMap<Option<ShardId>, usize>> {
+        let mut counts = BTreeMap::new();
+        for line in lines {
+            *counts.entry(self.explain_shard(line)?).or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Returns the problems with this config, such as rules routing to
+    /// shards that aren't defined or shards without any node.
+    pub fn validate(&self) -> Vec<RuleValidationIssue> {
+        let mut routed: Vec<ShardId> = self.specific_targets.iter().map(|t| t.shard).collect();
+        if let Some(hash_ring) = &self.hash_ring {
+            routed.extend(Vec::from(hash_ring.shards.clone()));
+        }
+        routed.sort_unstable();
+        routed.dedup();
+
+        let mut issues: Vec<_> = routed
+            .into_iter()
+            .filter(|shard_id| !self.shards.contains_key(shard_id))
+            .map(|shard_id| RuleValidationIssue::UndefinedShard { shard_id })
+            .collect();
+
+        let mut shard_ids: Vec<_> = self.shards.keys().copied().collect();
+        shard_ids.sort_unstable();
+        for shard_id in shard_ids {
+            let shard = &self.shards[&shard_id];
+            let Shard::Iox { node_group, .. } = shard;
+            if node_group.is_empty() {
+                issues.push(RuleValidationIssue::EmptyNodeGroup { shard_id })
+            } else if shard.required_acks() > node_group.len() {
+                issues.push(RuleValidationIssue::ReplicationFactorExceedsNodeGroup {
+                    shard_id,
+                    replication_factor: shard.required_acks(),
+                    nodes: node_group.len(),
+                })
+            }
+        }
+
+        issues
+    }
+}
+
 impl Sharder for ShardConfig {
     fn shard(&self, line: &ParsedLine<'_>) -> Result<ShardId, Error> {
         for i in &self.specific_targets {
@@ -543,6 +1020,7 @@ mod tests {
     fn partition_key_with_table() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Table],
+            ..Default::default()
         };
 
         let line = parse_line("cpu foo=1 10");
@@ -558,6 +1036,7 @@ mod tests {
     fn partition_key_with_int_field() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Column("foo".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu foo=1 10");
@@ -573,6 +1052,7 @@ mod tests {
     fn partition_key_with_float_field() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Column("foo".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu foo=1.1 10");
@@ -588,6 +1068,7 @@ mod tests {
     fn partition_key_with_string_field() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Column("foo".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu foo=\"asdf\" 10");
@@ -603,6 +1084,7 @@ mod tests {
     fn partition_key_with_bool_field() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Column("bar".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu bar=true 10");
@@ -618,6 +1100,7 @@ mod tests {
     fn partition_key_with_tag_column() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Column("region".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu,region=west usage_user=23.2 10");
@@ -633,6 +1116,7 @@ mod tests {
     fn partition_key_with_missing_column() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::Column("not_here".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu,foo=asdf bar=true 10");
@@ -644,10 +1128,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn partition_key_max_len() {
+        let mut rules = DatabaseRules::new(DatabaseName::new("db").unwrap());
+        rules.partition_template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("region".to_string())],
+            ..Default::default()
+        };
+        rules.max_partition_key_len = NonZeroUsize::new(24);
+
+        // under the limit
+        let line = parse_line("cpu,region=west foo=1 10");
+        assert_eq!(
+            rules.partition_key(&line, ARBITRARY_DEFAULT_TIME).unwrap(),
+            "region_west"
+        );
+
+        // too long, rejected by default
+        let line = parse_line("cpu,region=west-1-availability-zone-a foo=1 10");
+        let err = rules
+            .partition_key(&line, ARBITRARY_DEFAULT_TIME)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PartitionKeyTooLong {
+                key_len: 33,
+                max_len: 24
+            }
+        ));
+
+        // too long, truncated with a stable hash suffix
+        rules.partition_key_overflow = PartitionKeyOverflow::HashTruncate;
+        let key = rules.partition_key(&line, ARBITRARY_DEFAULT_TIME).unwrap();
+        assert_eq!(key, "region_#5f9f42c8058d8108");
+        assert_eq!(
+            rules.partition_key(&line, ARBITRARY_DEFAULT_TIME).unwrap(),
+            key
+        );
+
+        // keys sharing a prefix remain distinct
+        let other = parse_line("cpu,region=west-1-availability-zone-b foo=1 10");
+        assert_ne!(
+            rules.partition_key(&other, ARBITRARY_DEFAULT_TIME).unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn fnv1a_64_reference_values() {
+        assert_eq!(fnv1a_64(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
     #[test]
     fn partition_key_with_time() {
         let template = PartitionTemplate {
             parts: vec![TemplatePart::TimeFormat("%Y-%m-%d %H:%M:%S".to_string())],
+            ..Default::default()
         };
 
         let line = parse_line("cpu,foo=asdf bar=true 1602338097000000000");
@@ -664,6 +1201,7 @@ mod tests {
         let format_string = "%Y-%m-%d %H:%M:%S";
         let template = PartitionTemplate {
             parts: vec![TemplatePart::TimeFormat(format_string.to_string())],
+            ..Default::default()
         };
 
         let default_time = Utc::now();
@@ -685,6 +1223,7 @@ mod tests {
                 TemplatePart::Column("usage_system".to_string()),
                 TemplatePart::TimeFormat("%Y-%m-%d %H:%M:%S".to_string()),
             ],
+            ..Default::default()
         };
 
         let line = parse_line(
@@ -698,6 +1237,157 @@ mod tests {
         );
     }
 
+    #[test]
+    fn partition_key_escapes_separator() {
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Column("a".to_string()),
+                TemplatePart::Column("b".to_string()),
+            ],
+            ..Default::default()
+        };
+
+        let line1 = parse_line("cpu,a=x-y,b=z usage=1 10");
+        let line2 = parse_line("cpu,a=x,b=y-z usage=1 10");
+
+        let key1 = template
+            .partition_key(&line1, ARBITRARY_DEFAULT_TIME)
+            .unwrap();
+        let key2 = template
+            .partition_key(&line2, ARBITRARY_DEFAULT_TIME)
+            .unwrap();
+
+        assert_eq!(key1, r"a_x\-y-b_z");
+        assert_eq!(key2, r"a_x-b_y\-z");
+        assert_ne!(key1, key2);
+    }
+
+    #[test]
+    fn partition_key_with_time_and_column() {
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::TimeAndColumn(TimeAndColumn {
+                column: "tenant".to_string(),
+                format: "%Y-%m-%d".to_string(),
+            })],
+            ..Default::default()
+        };
+
+        let key = |lp| {
+            template
+                .partition_key(&parse_line(lp), ARBITRARY_DEFAULT_TIME)
+                .unwrap()
+        };
+
+        // same time bucket, different tag
+        let key1 = key("cpu,tenant=a usage=1 1602338097000000000");
+        let key2 = key("cpu,tenant=b usage=1 1602338097000000000");
+        assert_eq!(key1, "2020-10-10_tenant_a");
+        assert_eq!(key2, "2020-10-10_tenant_b");
+
+        // same tag, different time bucket
+        let key3 = key("cpu,tenant=a usage=1 1602438097000000000");
+        assert_eq!(key3, "2020-10-11_tenant_a");
+
+        // same time bucket and tag
+        let key4 = key("cpu,tenant=a,host=x usage=2 1602338098000000000");
+        assert_eq!(key1, key4);
+
+        // separator in the tag value is escaped
+        assert_eq!(
+            key("cpu,tenant=a-b usage=1 1602338097000000000"),
+            r"2020-10-10_tenant_a\-b"
+        );
+
+        // missing tag
+        assert_eq!(key("cpu usage=1 1602338097000000000"), "2020-10-10");
+    }
+
+    #[test]
+    fn partition_key_with_custom_separator() {
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Table,
+                TemplatePart::Column("region".to_string()),
+                TemplatePart::TimeFormat("%Y-%m-%d".to_string()),
+            ],
+            separator: "/".to_string(),
+        };
+
+        let line = parse_line("cpu,region=us/west usage_user=22.1 1602338097000000000");
+        assert_eq!(
+            r"cpu/region_us\/west/2020-10-10",
+            template
+                .partition_key(&line, ARBITRARY_DEFAULT_TIME)
+                .unwrap()
+        );
+
+        // a single part is never escaped
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("region".to_string())],
+            separator: "/".to_string(),
+        };
+        assert_eq!(
+            "region_us/west",
+            template
+                .partition_key(&line, ARBITRARY_DEFAULT_TIME)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn partition_is_before() {
+        let cutoff = Utc.ymd(2021, 3, 14).and_hms(12, 30, 0);
+
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::TimeFormat("%Y-%m-%d %H:00:00".to_string())],
+            ..Default::default()
+        };
+        assert!(template.partition_is_before("2021-03-14 11:00:00", cutoff));
+        // contains the cutoff
+        assert!(!template.partition_is_before("2021-03-14 12:00:00", cutoff));
+        assert!(!template.partition_is_before("2021-03-15 00:00:00", cutoff));
+        assert!(!template.partition_is_before("not a time", cutoff));
+
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::TimeFormat("%Y-%m".to_string())],
+            ..Default::default()
+        };
+        assert!(template.partition_is_before("2021-02", cutoff));
+        assert!(!template.partition_is_before("2021-03", cutoff));
+
+        // the time is found among parts with escaped separators
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Table,
+                TemplatePart::TimeFormat("%Y-%m-%d".to_string()),
+                TemplatePart::Column("region".to_string()),
+            ],
+            ..Default::default()
+        };
+        let line = parse_line("my-cpu,region=us-west foo=1 1615680000000000000");
+        let key = template
+            .partition_key(&line, ARBITRARY_DEFAULT_TIME)
+            .unwrap();
+        assert_eq!(key, r"my\-cpu-2021-03-14-region_us\-west");
+        assert!(!template.partition_is_before(&key, cutoff));
+        assert!(template.partition_is_before(&key, Utc.ymd(2021, 3, 15).and_hms(0, 0, 0)));
+        assert!(template.partition_is_before("cpu-2021-03-13-region_west", cutoff));
+
+        // keys that aren't derived from time are never before the cutoff
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Table],
+            ..Default::default()
+        };
+        assert!(!template.partition_is_before("cpu", cutoff));
+
+        // nor are times without a year
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::TimeFormat("%H".to_string())],
+            ..Default::default()
+        };
+        assert!(!template.partition_is_before("01", cutoff));
+    }
+
     #[test]
     #[allow(clippy::trivial_regex)]
     fn test_sharder() {
@@ -794,6 +1484,21 @@ mod tests {
         let err = shard_config.shard(&line).unwrap_err();
 
         assert!(matches!(err, Error::NoShardsDefined));
+
+        // explaining the shard reports the error rather than no shard
+        let err = shard_config.explain_shard(&line).unwrap_err();
+        assert!(matches!(err, Error::NoShardsDefined));
+        let err = shard_config.preview(&[line]).unwrap_err();
+        assert!(matches!(err, Error::NoShardsDefined));
+
+        // without a hash ring, lines no rule matches are assigned no shard
+        let shard_config = ShardConfig::default();
+        let line = parse_line("cpu,t1=1 f1=1 10");
+        assert_eq!(shard_config.explain_shard(&line).unwrap(), None);
+        assert_eq!(
+            shard_config.preview(&[line]).unwrap(),
+            vec![(None, 1)].into_iter().collect()
+        );
     }
 
     fn parsed_lines(lp: &str) -> Vec<ParsedLine<'_>> {
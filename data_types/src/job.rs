@@ -3,8 +3,13 @@ use serde::{Deserialize, Serialize};
 /// Metadata associated with a set of background tasks
 /// Used in combination with TrackerRegistry
 ///
+/// Serialized as JSON objects with the variant name in snake case under
+/// `kind`, next to the fields of the variant, e.g.
+/// `{"kind": "wipe_preserved_catalog", "db_name": "foo"}`.
+///
 /// TODO: Serde is temporary until prost adds JSON support
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Job {
     Dummy {
         nanos: Vec<u64>,
@@ -38,6 +43,22 @@ pub enum Job {
     WipePreservedCatalog {
         db_name: String,
     },
+
+    /// Copy a chunk into a new read buffer chunk of another database
+    CopyChunk {
+        db_name: String,
+        partition_key: String,
+        table_name: String,
+        chunk_id: u32,
+        dst_db_name: String,
+    },
+
+    /// Drop the chunks of a partition older than the retention period
+    DropExpiredPartition {
+        db_name: String,
+        partition_key: String,
+        table_name: String,
+    },
 }
 
 impl Job {
@@ -49,6 +70,8 @@ impl Job {
             Self::WriteChunk { db_name, .. } => Some(db_name),
             Self::CompactChunks { db_name, .. } => Some(db_name),
             Self::WipePreservedCatalog { db_name, .. } => Some(db_name),
+            Self::CopyChunk { db_name, .. } => Some(db_name),
+            Self::DropExpiredPartition { db_name, .. } => Some(db_name),
         }
     }
 
@@ -60,6 +83,8 @@ impl Job {
             Self::WriteChunk { partition_key, .. } => Some(partition_key),
             Self::CompactChunks { partition_key, .. } => Some(partition_key),
             Self::WipePreservedCatalog { .. } => None,
+            Self::CopyChunk { partition_key, .. } => Some(partition_key),
+            Self::DropExpiredPartition { partition_key, .. } => Some(partition_key),
         }
     }
 
@@ -71,6 +96,8 @@ impl Job {
             Self::WriteChunk { chunk_id, .. } => Some(*chunk_id),
             Self::CompactChunks { .. } => None,
             Self::WipePreservedCatalog { .. } => None,
+            Self::CopyChunk { chunk_id, .. } => Some(*chunk_id),
+            Self::DropExpiredPartition { .. } => None,
         }
     }
 
@@ -82,6 +109,8 @@ impl Job {
             Self::WriteChunk { .. } => "Writing chunk to Object Storage",
             Self::CompactChunks { .. } => "Compacting chunks to ReadBuffer",
             Self::WipePreservedCatalog { .. } => "Wipe preserved catalog",
+            Self::CopyChunk { .. } => "Copying chunk to another database",
+            Self::DropExpiredPartition { .. } => "Dropping expired partition",
         }
     }
 }
@@ -3,6 +3,7 @@
 
 use std::{borrow::Cow, mem};
 
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
 use std::iter::FromIterator;
@@ -172,6 +173,51 @@ impl ColumnSummary {
         mem::size_of::<Self>() + self.name.len() + self.stats.size()
     }
 
+    /// Returns true if this column holds the Influx data model timestamp
+    pub fn is_timestamp(&self) -> bool {
+        matches!(self.influxdb_type, Some(InfluxDbType::Timestamp))
+    }
+
+    /// Returns the `(min, max)` of a timestamp column as `DateTime`s,
+    /// or `None` if this is not a timestamp column or the range is
+    /// not known
+    pub fn time_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if !self.is_timestamp() {
+            return None;
+        }
+
+        match &self.stats {
+            Statistics::I64(StatValues {
+                min: Some(min),
+                max: Some(max),
+                ..
+            }) => Some((Utc.timestamp_nanos(*min), Utc.timestamp_nanos(*max))),
+            _ => None,
+        }
+    }
+
+    /// Return the minimum value, if any, formatted as a string.
+    ///
+    /// Unlike [`Statistics::min_as_str`], timestamp columns are
+    /// rendered as RFC3339 rather than as raw nanoseconds
+    pub fn min_as_str(&self) -> Option<Cow<'_, str>> {
+        match self.time_range() {
+            Some((min, _)) => Some(Cow::Owned(format_timestamp(min))),
+            None => self.stats.min_as_str(),
+        }
+    }
+
+    /// Return the maximum value, if any, formatted as a string.
+    ///
+    /// Unlike [`Statistics::max_as_str`], timestamp columns are
+    /// rendered as RFC3339 rather than as raw nanoseconds
+    pub fn max_as_str(&self) -> Option<Cow<'_, str>> {
+        match self.time_range() {
+            Some((_, max)) => Some(Cow::Owned(format_timestamp(max))),
+            None => self.stats.max_as_str(),
+        }
+    }
+
     // Updates statistics from other if the same type, otherwise a noop
     pub fn update_from(&mut self, other: &Self) {
         match (&mut self.stats, &other.stats) {
@@ -201,6 +247,10 @@ impl ColumnSummary {
     }
 }
 
+fn format_timestamp(ts: DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
 /// Column name, statistics which encode type information
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 pub struct Column {
@@ -520,6 +570,37 @@ impl IsNan for f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn column_summary_time_range() {
+        let col = ColumnSummary {
+            name: "time".to_string(),
+            influxdb_type: Some(InfluxDbType::Timestamp),
+            stats: Statistics::I64(StatValues {
+                min: Some(1_600_000_000_000_000_000),
+                max: Some(1_600_000_001_500_000_000),
+                count: 2,
+                distinct_count: None,
+            }),
+        };
+
+        let (min, max) = col.time_range().unwrap();
+        assert_eq!(min.timestamp_nanos(), 1_600_000_000_000_000_000);
+        assert_eq!(max.timestamp_nanos(), 1_600_000_001_500_000_000);
+        assert_eq!(col.min_as_str().unwrap(), "2020-09-13T12:26:40Z");
+        assert_eq!(col.max_as_str().unwrap(), "2020-09-13T12:26:41.500Z");
+
+        // stored value is unchanged
+        assert_eq!(col.stats.min_as_str().unwrap(), "1600000000000000000");
+
+        // non timestamp columns have no time range
+        let col = ColumnSummary {
+            influxdb_type: Some(InfluxDbType::Field),
+            ..col
+        };
+        assert!(col.time_range().is_none());
+        assert_eq!(col.min_as_str().unwrap(), "1600000000000000000");
+    }
+
     #[test]
     fn statistics_update() {
         let mut stat = StatValues::new_with_value(23);
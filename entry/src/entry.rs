@@ -1,19 +1,37 @@
 //! This module contains helper code for building `Entry` from line protocol and the
 //! `DatabaseRules` configuration.
 
-use std::{collections::BTreeMap, convert::TryFrom, fmt::Formatter, num::NonZeroU64};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::{TryFrom, TryInto},
+    fmt::Formatter,
+    num::NonZeroU64,
+    sync::Arc,
+};
 
+use arrow::{
+    array::{
+        ArrayRef, BooleanArray, DictionaryArray, Float64Array, Int64Array, StringArray,
+        TimestampNanosecondArray, UInt64Array,
+    },
+    datatypes::Int32Type,
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
 use chrono::{DateTime, TimeZone, Utc};
 use flatbuffers::{FlatBufferBuilder, Follow, ForwardsUOffset, Vector, VectorIter, WIPOffset};
 use ouroboros::self_referencing;
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 
 use data_types::{
-    database_rules::{Error as DataError, Partitioner, ShardId, Sharder},
+    database_rules::{Error as DataError, PartitionTemplate, Partitioner, ShardId, Sharder},
     server_id::ServerId,
 };
 use influxdb_line_protocol::{FieldValue, ParsedLine};
-use internal_types::schema::{InfluxColumnType, InfluxFieldType, TIME_COLUMN_NAME};
+use internal_types::schema::{
+    builder::{Error as SchemaBuilderError, SchemaBuilder},
+    InfluxColumnType, InfluxFieldType, TIME_COLUMN_NAME,
+};
 
 use crate::entry_fb;
 
@@ -39,6 +57,34 @@ pub enum Error {
         source: ColumnError,
     },
 
+    #[snafu(display(
+        "timestamp {} on line {} of table {} is outside the valid range [{}, {}]",
+        timestamp,
+        line_number,
+        table,
+        MIN_NANO_TIME,
+        MAX_NANO_TIME
+    ))]
+    TimestampOutOfRange {
+        table: String,
+        line_number: usize,
+        timestamp: i64,
+    },
+
+    #[snafu(display("row {} of table {} has no timestamp", row, table))]
+    RowTimestampMissing { table: String, row: usize },
+
+    #[snafu(display("line {} has an empty measurement name", line_number))]
+    EmptyMeasurement { line_number: usize },
+
+    #[snafu(display(
+        "table {} has a tag or field named '{}' on line {}, which is reserved for the timestamp",
+        table,
+        TIME_COLUMN_NAME,
+        line_number
+    ))]
+    ReservedColumnName { table: String, line_number: usize },
+
     #[snafu(display("invalid flatbuffers: field {} is required", field))]
     FlatbufferFieldMissing { field: String },
 
@@ -50,6 +96,12 @@ pub enum Error {
 
     #[snafu(display("'time' column must be i64 type"))]
     TimeColumnWrongType,
+
+    #[snafu(display("Error building schema for table batch: {}", source))]
+    BuildingSchema { source: SchemaBuilderError },
+
+    #[snafu(display("Error creating record batch for table batch: {}", source))]
+    CreatingRecordBatch { source: ArrowError },
 }
 
 #[derive(Debug, Snafu)]
@@ -61,9 +113,93 @@ pub enum ColumnError {
     },
 }
 
+/// Errors decoding an `Entry` from its serialized flatbuffers form
+#[derive(Debug, Snafu)]
+pub enum DecodeError {
+    #[snafu(display(
+        "entry is truncated: expected at least {} bytes but got {}",
+        expected,
+        actual
+    ))]
+    Truncated { expected: usize, actual: usize },
+
+    #[snafu(display("{}", source))]
+    InvalidEntryFlatbuffer {
+        source: flatbuffers::InvalidFlatbuffer,
+    },
+}
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum ValidationError {
+    #[snafu(display("partition write has no partition key"))]
+    MissingPartitionKey,
+
+    #[snafu(display("partition write has an empty partition key"))]
+    EmptyPartitionKey,
+
+    #[snafu(display("table batch has no table name"))]
+    MissingTableName,
+
+    #[snafu(display("table {} has a column without a name", table))]
+    MissingColumnName { table: String },
+
+    #[snafu(display("delete has no table name"))]
+    MissingDeleteTableName,
+
+    #[snafu(display("delete from table {} has no predicate", table))]
+    MissingDeletePredicate { table: String },
+
+    #[snafu(display("column {} of table {} has invalid values", column, table))]
+    InvalidColumnValues { table: String, column: String },
+
+    #[snafu(display("table {} has more than one column named {}", table, column))]
+    DuplicateColumn { table: String, column: String },
+
+    #[snafu(display(
+        "column {} of table {} has {} rows but the table batch has {}",
+        column,
+        table,
+        actual,
+        expected
+    ))]
+    InconsistentRowCount {
+        table: String,
+        column: String,
+        expected: usize,
+        actual: usize,
+    },
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 type ColumnResult<T, E = ColumnError> = std::result::Result<T, E>;
 
+/// The minimum valid timestamp in nanoseconds. `i64::MIN` and `i64::MIN + 1`
+/// are reserved, as in InfluxDB, and are used as unbounded time range
+/// markers.
+pub const MIN_NANO_TIME: i64 = i64::MIN + 2;
+
+/// The maximum valid timestamp in nanoseconds. `i64::MAX` is reserved as the
+/// unbounded end of time ranges.
+pub const MAX_NANO_TIME: i64 = i64::MAX - 1;
+
+/// How to handle a field whose numeric type differs between lines of the
+/// same table in a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldTypeCoercion {
+    /// Reject any type change within a batch
+    Strict,
+
+    /// Promote mixed i64, u64 and f64 values of a field to f64. Changes
+    /// between any other types are still rejected.
+    PromoteToFloat,
+}
+
+impl Default for FieldTypeCoercion {
+    fn default() -> Self {
+        Self::Strict
+    }
+}
+
 /// Converts parsed line protocol into a collection of ShardedEntry with the
 /// underlying flatbuffers bytes generated.
 pub fn lines_to_sharded_entries(
@@ -71,10 +207,34 @@ pub fn lines_to_sharded_entries(
     default_time: i64,
     sharder: Option<&impl Sharder>,
     partitioner: &impl Partitioner,
+) -> Result<Vec<ShardedEntry>> {
+    lines_to_sharded_entries_with_coercion(
+        lines,
+        default_time,
+        sharder,
+        partitioner,
+        FieldTypeCoercion::Strict,
+    )
+}
+
+/// Like [`lines_to_sharded_entries`] but with the given policy for fields
+/// whose type changes within the batch.
+pub fn lines_to_sharded_entries_with_coercion(
+    lines: &[ParsedLine<'_>],
+    default_time: i64,
+    sharder: Option<&impl Sharder>,
+    partitioner: &impl Partitioner,
+    coercion: FieldTypeCoercion,
 ) -> Result<Vec<ShardedEntry>> {
     let mut sharded_lines = BTreeMap::new();
 
-    for line in lines {
+    for (line_number, line) in lines.iter().enumerate() {
+        // an empty measurement would silently produce a table without a name
+        ensure!(
+            !line.series.measurement.is_empty(),
+            EmptyMeasurement { line_number }
+        );
+
         let shard_id = match &sharder {
             Some(s) => Some(s.shard(line).context(GeneratingShardId)?),
             None => None,
@@ -96,7 +256,9 @@ pub fn lines_to_sharded_entries(
 
     let sharded_entries = sharded_lines
         .into_iter()
-        .map(|(shard_id, partitions)| build_sharded_entry(shard_id, partitions, default_time))
+        .map(|(shard_id, partitions)| {
+            build_sharded_entry(shard_id, partitions, default_time, coercion)
+        })
         .collect::<Result<Vec<_>>>()?;
 
     Ok(sharded_entries)
@@ -106,15 +268,60 @@ fn build_sharded_entry(
     shard_id: Option<ShardId>,
     partitions: BTreeMap<String, BTreeMap<&str, Vec<&ParsedLine<'_>>>>,
     default_time: i64,
+    coercion: FieldTypeCoercion,
 ) -> Result<ShardedEntry> {
+    let partitions = partitions
+        .iter()
+        .map(|(partition_key, tables)| {
+            let tables = tables
+                .iter()
+                .map(|(table_name, lines)| {
+                    build_table_batch(table_name, lines, default_time, coercion)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok((partition_key.as_str(), tables))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let entry = build_entry(partitions);
+
+    Ok(ShardedEntry { shard_id, entry })
+}
+
+fn build_table_batch<'a>(
+    table_name: &'a str,
+    lines: &[&'a ParsedLine<'_>],
+    default_time: i64,
+    coercion: FieldTypeCoercion,
+) -> Result<TableBatchBuilder<'a>> {
+    let mut builder = TableBatchBuilder::new(table_name, coercion);
+
+    for &line in lines {
+        let tags = line
+            .series
+            .tag_set
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value.as_str()));
+        let fields = line
+            .field_set
+            .iter()
+            .map(|(key, value)| (key.as_str(), FieldRef::from(value)));
+
+        builder.push_row(tags, fields, line.timestamp.unwrap_or(default_time))?;
+    }
+
+    Ok(builder)
+}
+
+/// Serializes the given partitions and their table batches into an `Entry`
+fn build_entry<'a>(partitions: Vec<(&str, Vec<TableBatchBuilder<'a>>)>) -> Entry {
     let mut fbb = flatbuffers::FlatBufferBuilder::new_with_capacity(1024);
 
     let partition_writes = partitions
         .into_iter()
-        .map(|(partition_key, tables)| {
-            build_partition_write(&mut fbb, partition_key, tables, default_time)
-        })
-        .collect::<Result<Vec<_>>>()?;
+        .map(|(partition_key, tables)| build_partition_write(&mut fbb, partition_key, tables))
+        .collect::<Vec<_>>();
     let partition_writes = fbb.create_vector(&partition_writes);
 
     let write_operations = entry_fb::WriteOperations::create(
@@ -134,157 +341,462 @@ fn build_sharded_entry(
     fbb.finish(entry, None);
 
     let (mut data, idx) = fbb.collapse();
-    let entry = Entry::try_from(data.split_off(idx))
-        .expect("Flatbuffer data just constructed should be valid");
+    Entry::try_from(data.split_off(idx)).expect("Flatbuffer data just constructed should be valid")
+}
 
-    Ok(ShardedEntry { shard_id, entry })
+/// Builds an `Entry` that deletes the rows matching a predicate from each of
+/// the given tables. Every element of `deletes` is a `(table_name, predicate)`
+/// pair.
+pub fn deletes_to_entry(deletes: &[(&str, &str)]) -> Entry {
+    let mut fbb = flatbuffers::FlatBufferBuilder::new_with_capacity(1024);
+
+    let deletes = deletes
+        .iter()
+        .map(|(table_name, predicate)| {
+            let table_name = fbb.create_string(table_name);
+            let predicate = fbb.create_string(predicate);
+            entry_fb::Delete::create(
+                &mut fbb,
+                &entry_fb::DeleteArgs {
+                    table_name: Some(table_name),
+                    predicate: Some(predicate),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let deletes = fbb.create_vector(&deletes);
+
+    let delete_operations = entry_fb::DeleteOperations::create(
+        &mut fbb,
+        &entry_fb::DeleteOperationsArgs {
+            deletes: Some(deletes),
+        },
+    );
+    let entry = entry_fb::Entry::create(
+        &mut fbb,
+        &entry_fb::EntryArgs {
+            operation_type: entry_fb::Operation::delete,
+            operation: Some(delete_operations.as_union_value()),
+        },
+    );
+
+    fbb.finish(entry, None);
+
+    let (mut data, idx) = fbb.collapse();
+    Entry::try_from(data.split_off(idx)).expect("Flatbuffer data just constructed should be valid")
 }
 
 fn build_partition_write<'a>(
     fbb: &mut FlatBufferBuilder<'a>,
-    partition_key: String,
-    tables: BTreeMap<&str, Vec<&'a ParsedLine<'_>>>,
-    default_time: i64,
-) -> Result<flatbuffers::WIPOffset<entry_fb::PartitionWrite<'a>>> {
-    let partition_key = fbb.create_string(&partition_key);
+    partition_key: &str,
+    tables: Vec<TableBatchBuilder<'a>>,
+) -> flatbuffers::WIPOffset<entry_fb::PartitionWrite<'a>> {
+    let partition_key = fbb.create_string(partition_key);
 
     let table_batches = tables
         .into_iter()
-        .map(|(table_name, lines)| build_table_write_batch(fbb, table_name, lines, default_time))
-        .collect::<Result<Vec<_>>>()?;
+        .map(|table| table.build_flatbuffer(fbb))
+        .collect::<Vec<_>>();
     let table_batches = fbb.create_vector(&table_batches);
 
-    Ok(entry_fb::PartitionWrite::create(
+    entry_fb::PartitionWrite::create(
         fbb,
         &entry_fb::PartitionWriteArgs {
             key: Some(partition_key),
             table_batches: Some(table_batches),
         },
-    ))
+    )
 }
 
-fn build_table_write_batch<'a>(
-    fbb: &mut FlatBufferBuilder<'a>,
-    table_name: &str,
-    lines: Vec<&'a ParsedLine<'_>>,
-    default_time: i64,
-) -> Result<flatbuffers::WIPOffset<entry_fb::TableWriteBatch<'a>>> {
-    let mut columns = BTreeMap::new();
-    for (i, line) in lines.iter().enumerate() {
-        let row_number = i + 1;
-
-        if let Some(tagset) = &line.series.tag_set {
-            for (key, value) in tagset {
-                let key = key.as_str();
-                let builder = columns
-                    .entry(key)
-                    .or_insert_with(ColumnBuilder::new_tag_column);
-                builder.null_to_row(row_number);
-                builder
-                    .push_tag(value.as_str())
-                    .context(TableColumnTypeMismatch {
-                        table: table_name,
-                        column: key,
-                        line_number: i,
-                    })?;
-            }
+/// A field value borrowed while building the columns of a table batch
+#[derive(Debug, Clone, Copy)]
+enum FieldRef<'a> {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(&'a str),
+    Bool(bool),
+}
+
+impl<'a> From<&'a FieldValue<'_>> for FieldRef<'a> {
+    fn from(value: &'a FieldValue<'_>) -> Self {
+        match value {
+            FieldValue::I64(v) => Self::I64(*v),
+            FieldValue::U64(v) => Self::U64(*v),
+            FieldValue::F64(v) => Self::F64(*v),
+            FieldValue::String(v) => Self::String(v.as_str()),
+            FieldValue::Boolean(v) => Self::Bool(*v),
+        }
+    }
+}
+
+/// Builds the columns of a single table batch row by row, checking that the
+/// type of each column stays the same across rows.
+#[derive(Debug)]
+struct TableBatchBuilder<'a> {
+    table_name: &'a str,
+    columns: BTreeMap<&'a str, ColumnBuilder<'a>>,
+    rows: usize,
+    coercion: FieldTypeCoercion,
+}
+
+impl<'a> TableBatchBuilder<'a> {
+    fn new(table_name: &'a str, coercion: FieldTypeCoercion) -> Self {
+        Self {
+            table_name,
+            columns: BTreeMap::new(),
+            rows: 0,
+            coercion,
+        }
+    }
+
+    fn push_row(
+        &mut self,
+        tags: impl IntoIterator<Item = (&'a str, &'a str)>,
+        fields: impl IntoIterator<Item = (&'a str, FieldRef<'a>)>,
+        time: i64,
+    ) -> Result<()> {
+        let table_name = self.table_name;
+        let coercion = self.coercion;
+        let columns = &mut self.columns;
+        let line_number = self.rows;
+        let row_number = line_number + 1;
+
+        for (key, value) in tags {
+            ensure!(
+                key != TIME_COLUMN_NAME,
+                ReservedColumnName {
+                    table: table_name,
+                    line_number,
+                }
+            );
+
+            let builder = columns
+                .entry(key)
+                .or_insert_with(ColumnBuilder::new_tag_column);
+            builder.null_to_row(row_number);
+            builder.push_tag(value).context(TableColumnTypeMismatch {
+                table: table_name,
+                column: key,
+                line_number,
+            })?;
         }
 
-        for (key, val) in &line.field_set {
-            let key = key.as_str();
+        for (key, value) in fields {
+            ensure!(
+                key != TIME_COLUMN_NAME,
+                ReservedColumnName {
+                    table: table_name,
+                    line_number,
+                }
+            );
 
-            match val {
-                FieldValue::Boolean(b) => {
+            let res = match value {
+                FieldRef::Bool(b) => {
                     let builder = columns
                         .entry(key)
                         .or_insert_with(ColumnBuilder::new_bool_column);
                     builder.null_to_row(row_number);
-                    builder.push_bool(*b).context(TableColumnTypeMismatch {
-                        table: table_name,
-                        column: key,
-                        line_number: i,
-                    })?;
+                    builder.push_bool(b)
                 }
-                FieldValue::U64(v) => {
+                FieldRef::U64(v) => {
                     let builder = columns
                         .entry(key)
                         .or_insert_with(ColumnBuilder::new_u64_column);
                     builder.null_to_row(row_number);
-                    builder.push_u64(*v).context(TableColumnTypeMismatch {
-                        table: table_name,
-                        column: key,
-                        line_number: i,
-                    })?;
+                    match coercion {
+                        FieldTypeCoercion::PromoteToFloat if builder.is_f64() => {
+                            builder.push_f64(v as f64)
+                        }
+                        FieldTypeCoercion::PromoteToFloat if builder.is_i64() => {
+                            builder.promote_to_f64();
+                            builder.push_f64(v as f64)
+                        }
+                        _ => builder.push_u64(v),
+                    }
                 }
-                FieldValue::F64(v) => {
+                FieldRef::F64(v) => {
                     let builder = columns
                         .entry(key)
                         .or_insert_with(ColumnBuilder::new_f64_column);
                     builder.null_to_row(row_number);
-                    builder.push_f64(*v).context(TableColumnTypeMismatch {
-                        table: table_name,
-                        column: key,
-                        line_number: i,
-                    })?;
+                    if coercion == FieldTypeCoercion::PromoteToFloat {
+                        builder.promote_to_f64();
+                    }
+                    builder.push_f64(v)
                 }
-                FieldValue::I64(v) => {
+                FieldRef::I64(v) => {
                     let builder = columns
                         .entry(key)
                         .or_insert_with(ColumnBuilder::new_i64_column);
                     builder.null_to_row(row_number);
-                    builder.push_i64(*v).context(TableColumnTypeMismatch {
-                        table: table_name,
-                        column: key,
-                        line_number: i,
-                    })?;
+                    match coercion {
+                        FieldTypeCoercion::PromoteToFloat if builder.is_f64() => {
+                            builder.push_f64(v as f64)
+                        }
+                        FieldTypeCoercion::PromoteToFloat if builder.is_u64() => {
+                            builder.promote_to_f64();
+                            builder.push_f64(v as f64)
+                        }
+                        _ => builder.push_i64(v),
+                    }
                 }
-                FieldValue::String(v) => {
+                FieldRef::String(v) => {
                     let builder = columns
                         .entry(key)
                         .or_insert_with(ColumnBuilder::new_string_column);
                     builder.null_to_row(row_number);
-                    builder
-                        .push_string(v.as_str())
-                        .context(TableColumnTypeMismatch {
-                            table: table_name,
-                            column: key,
-                            line_number: i,
-                        })?;
+                    builder.push_string(v)
                 }
-            }
+            };
+            res.context(TableColumnTypeMismatch {
+                table: table_name,
+                column: key,
+                line_number,
+            })?;
         }
 
+        ensure!(
+            (MIN_NANO_TIME..=MAX_NANO_TIME).contains(&time),
+            TimestampOutOfRange {
+                table: table_name,
+                line_number,
+                timestamp: time,
+            }
+        );
+
         let builder = columns
             .entry(TIME_COLUMN_NAME)
             .or_insert_with(ColumnBuilder::new_time_column);
-        builder
-            .push_time(line.timestamp.unwrap_or(default_time))
-            .context(TableColumnTypeMismatch {
-                table: table_name,
-                column: TIME_COLUMN_NAME,
-                line_number: i,
-            })?;
+        builder.push_time(time).context(TableColumnTypeMismatch {
+            table: table_name,
+            column: TIME_COLUMN_NAME,
+            line_number,
+        })?;
 
         for b in columns.values_mut() {
             b.null_to_row(row_number + 1);
         }
+        self.rows += 1;
+
+        Ok(())
     }
 
-    let columns = columns
-        .into_iter()
-        .map(|(column_name, builder)| builder.build_flatbuffer(fbb, column_name))
-        .collect::<Vec<_>>();
-    let columns = fbb.create_vector(&columns);
+    fn build_flatbuffer(
+        self,
+        fbb: &mut FlatBufferBuilder<'a>,
+    ) -> flatbuffers::WIPOffset<entry_fb::TableWriteBatch<'a>> {
+        let columns = self
+            .columns
+            .into_iter()
+            .map(|(column_name, builder)| builder.build_flatbuffer(fbb, column_name))
+            .collect::<Vec<_>>();
+        let columns = fbb.create_vector(&columns);
 
-    let table_name = fbb.create_string(table_name);
+        let table_name = fbb.create_string(self.table_name);
 
-    Ok(entry_fb::TableWriteBatch::create(
-        fbb,
-        &entry_fb::TableWriteBatchArgs {
-            name: Some(table_name),
-            columns: Some(columns),
-        },
-    ))
+        entry_fb::TableWriteBatch::create(
+            fbb,
+            &entry_fb::TableWriteBatchArgs {
+                name: Some(table_name),
+                columns: Some(columns),
+            },
+        )
+    }
+}
+
+/// Builds an `Entry` from structured data without formatting it as line
+/// protocol first.
+///
+/// Rows are added to the current table of the current partition, set with
+/// [`begin_partition`](Self::begin_partition) and
+/// [`begin_table`](Self::begin_table). Each row is built by setting its tags,
+/// fields and timestamp and is then finished with
+/// [`end_row`](Self::end_row). Partitions and tables may be begun again to
+/// add more rows to them.
+///
+/// Column types are checked when the entry is built, in the same way as for
+/// entries built from line protocol.
+#[derive(Debug, Default)]
+pub struct EntryBuilder {
+    partitions: BTreeMap<String, BTreeMap<String, Vec<BuilderRow>>>,
+    partition_key: Option<String>,
+    table_name: Option<String>,
+    row: BuilderRow,
+}
+
+#[derive(Debug, Default)]
+struct BuilderRow {
+    tags: Vec<(String, String)>,
+    fields: Vec<(String, BuilderFieldValue)>,
+    time: Option<i64>,
+}
+
+#[derive(Debug)]
+enum BuilderFieldValue {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Bool(bool),
+}
+
+impl BuilderFieldValue {
+    fn as_field_ref(&self) -> FieldRef<'_> {
+        match self {
+            Self::I64(v) => FieldRef::I64(*v),
+            Self::U64(v) => FieldRef::U64(*v),
+            Self::F64(v) => FieldRef::F64(*v),
+            Self::String(v) => FieldRef::String(v),
+            Self::Bool(v) => FieldRef::Bool(*v),
+        }
+    }
+}
+
+impl From<&FieldValue<'_>> for BuilderFieldValue {
+    fn from(value: &FieldValue<'_>) -> Self {
+        match value {
+            FieldValue::I64(v) => Self::I64(*v),
+            FieldValue::U64(v) => Self::U64(*v),
+            FieldValue::F64(v) => Self::F64(*v),
+            FieldValue::String(v) => Self::String(v.to_string()),
+            FieldValue::Boolean(v) => Self::Bool(*v),
+        }
+    }
+}
+
+impl EntryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `key` the current partition, which has no current table until
+    /// [`begin_table`](Self::begin_table) is called.
+    pub fn begin_partition(&mut self, key: impl Into<String>) -> &mut Self {
+        let key = key.into();
+        self.partitions.entry(key.clone()).or_default();
+        self.partition_key = Some(key);
+        self.table_name = None;
+        self
+    }
+
+    /// Makes `name` the current table of the current partition.
+    ///
+    /// # Panics
+    ///
+    /// If no partition has been begun.
+    pub fn begin_table(&mut self, name: impl Into<String>) -> &mut Self {
+        let partition_key = self
+            .partition_key
+            .as_ref()
+            .expect("begin_partition must be called before begin_table");
+        let name = name.into();
+        self.partitions
+            .get_mut(partition_key)
+            .expect("current partition exists")
+            .entry(name.clone())
+            .or_default();
+        self.table_name = Some(name);
+        self
+    }
+
+    /// Sets the tag `column` of the current row, replacing any value set
+    /// before.
+    pub fn tag(&mut self, column: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        let column = column.into();
+        let value = value.into();
+        match self.row.tags.iter_mut().find(|(c, _)| c == &column) {
+            Some((_, v)) => *v = value,
+            None => self.row.tags.push((column, value)),
+        }
+        self
+    }
+
+    /// Sets the field `column` of the current row, replacing any value set
+    /// before.
+    pub fn field(&mut self, column: impl Into<String>, value: FieldValue<'_>) -> &mut Self {
+        let column = column.into();
+        let value = BuilderFieldValue::from(&value);
+        match self.row.fields.iter_mut().find(|(c, _)| c == &column) {
+            Some((_, v)) => *v = value,
+            None => self.row.fields.push((column, value)),
+        }
+        self
+    }
+
+    /// Sets the timestamp of the current row in nanoseconds since the epoch.
+    pub fn timestamp(&mut self, ns: i64) -> &mut Self {
+        self.row.time = Some(ns);
+        self
+    }
+
+    /// Finishes the current row, adding it to the current table.
+    ///
+    /// # Panics
+    ///
+    /// If no table has been begun.
+    pub fn end_row(&mut self) -> &mut Self {
+        let partition_key = self
+            .partition_key
+            .as_ref()
+            .expect("begin_partition must be called before end_row");
+        let table_name = self
+            .table_name
+            .as_ref()
+            .expect("begin_table must be called before end_row");
+
+        let row = std::mem::take(&mut self.row);
+        self.partitions
+            .get_mut(partition_key)
+            .expect("current partition exists")
+            .get_mut(table_name)
+            .expect("current table exists")
+            .push(row);
+        self
+    }
+
+    /// Builds the entry from all finished rows. A row that has not been
+    /// finished with [`end_row`](Self::end_row) is not included, nor are
+    /// partitions and tables without rows.
+    ///
+    /// Returns an error if a row has no timestamp or if the type of a column
+    /// changes between rows of a table.
+    pub fn build(&self) -> Result<Entry> {
+        let partitions = self
+            .partitions
+            .iter()
+            .filter(|(_, tables)| tables.values().any(|rows| !rows.is_empty()))
+            .map(|(partition_key, tables)| {
+                let tables = tables
+                    .iter()
+                    .filter(|(_, rows)| !rows.is_empty())
+                    .map(|(table_name, rows)| {
+                        let mut builder =
+                            TableBatchBuilder::new(table_name, FieldTypeCoercion::Strict);
+
+                        for (i, row) in rows.iter().enumerate() {
+                            let time = row.time.context(RowTimestampMissing {
+                                table: table_name,
+                                row: i,
+                            })?;
+                            let tags = row.tags.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+                            let fields = row
+                                .fields
+                                .iter()
+                                .map(|(k, v)| (k.as_str(), v.as_field_ref()));
+
+                            builder.push_row(tags, fields, time)?;
+                        }
+
+                        Ok(builder)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok((partition_key.as_str(), tables))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(build_entry(partitions))
+    }
 }
 
 /// Holds a shard id to the associated entry. If there is no ShardId, then
@@ -318,6 +830,65 @@ impl Entry {
         self.borrow_data()
     }
 
+    /// Checks that the entry is well formed: every partition write has a
+    /// non-empty key, and every table batch has a name and uniquely named
+    /// columns with valid values that all have the same number of rows.
+    ///
+    /// Partition keys may only be empty if `partition_template` has no parts,
+    /// as is the case for the default template, which produces empty keys.
+    pub fn validate(&self, partition_template: &PartitionTemplate) -> Result<(), ValidationError> {
+        for partition_write in self.partition_writes().unwrap_or_default() {
+            let key = partition_write.fb.key().context(MissingPartitionKey)?;
+            ensure!(
+                !key.is_empty() || partition_template.parts.is_empty(),
+                EmptyPartitionKey
+            );
+
+            for table_batch in partition_write.table_batches() {
+                let table = table_batch.fb.name().context(MissingTableName)?;
+                let mut expected = None;
+                let mut names = BTreeSet::new();
+
+                let columns = table_batch.fb.columns();
+                for column in columns.iter().flat_map(|cols| cols.iter()) {
+                    let name = column.name().context(MissingColumnName { table })?;
+                    ensure!(
+                        names.insert(name),
+                        DuplicateColumn {
+                            table,
+                            column: name,
+                        }
+                    );
+
+                    let actual = try_column_row_count(&column).context(InvalidColumnValues {
+                        table,
+                        column: name,
+                    })?;
+                    let expected = *expected.get_or_insert(actual);
+                    ensure!(
+                        actual == expected,
+                        InconsistentRowCount {
+                            table,
+                            column: name,
+                            expected,
+                            actual,
+                        }
+                    );
+                }
+            }
+        }
+
+        for delete in self.deletes().unwrap_or_default() {
+            let table = delete.fb.table_name().context(MissingDeleteTableName)?;
+            ensure!(
+                delete.fb.predicate().is_some(),
+                MissingDeletePredicate { table }
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn partition_writes(&self) -> Option<Vec<PartitionWrite<'_>>> {
         match self.fb().operation_as_write().as_ref() {
             Some(w) => w
@@ -327,17 +898,142 @@ impl Entry {
             None => None,
         }
     }
+
+    /// Returns the deletes of this entry, or `None` if it is not a delete
+    /// operation.
+    pub fn deletes(&self) -> Option<Vec<Delete<'_>>> {
+        self.fb()
+            .operation_as_delete()
+            .and_then(|d| d.deletes())
+            .map(|d| d.iter().map(|fb| Delete { fb }).collect::<Vec<_>>())
+    }
+
+    /// Returns the minimum and maximum timestamp of the rows written by this
+    /// entry, across all partitions and tables.
+    ///
+    /// Only the time columns are decoded. Returns `None` if this entry is not
+    /// a write or holds no timestamps.
+    pub fn time_range(&self) -> Option<(i64, i64)> {
+        let mut range: Option<(i64, i64)> = None;
+
+        for partition_write in self.partition_writes()? {
+            for table_batch in partition_write.table_batches() {
+                let times = table_batch
+                    .columns()
+                    .into_iter()
+                    .find(|c| c.is_time())
+                    .and_then(|c| c.values().i64_values())
+                    .unwrap_or_default();
+
+                for t in times.into_iter().flatten() {
+                    range = Some(match range {
+                        Some((min, max)) => (min.min(t), max.max(t)),
+                        None => (t, t),
+                    });
+                }
+            }
+        }
+
+        range
+    }
+
+    /// Returns the columns of every table written by this entry along with
+    /// their type, deduplicated across partitions.
+    ///
+    /// Only column metadata is inspected, no column values are decoded. Tables
+    /// are returned sorted by name. If a column appears with different types in
+    /// different partitions, the first type encountered is reported.
+    pub fn schema_summary(&self) -> Vec<TableColumnSchema> {
+        let mut tables: BTreeMap<&str, BTreeMap<&str, InfluxColumnType>> = BTreeMap::new();
+
+        if let Some(w) = self.fb().operation_as_write() {
+            for partition_write in w.partition_writes().iter().flat_map(|v| v.iter()) {
+                for table_batch in partition_write
+                    .table_batches()
+                    .iter()
+                    .flat_map(|v| v.iter())
+                {
+                    let table_name = table_batch
+                        .name()
+                        .expect("name must be present in flatbuffers TableWriteBatch");
+                    let table = tables.entry(table_name).or_default();
+
+                    for fb in table_batch.columns().iter().flat_map(|v| v.iter()) {
+                        let name = fb
+                            .name()
+                            .expect("name must be present in flatbuffers Column");
+                        table
+                            .entry(name)
+                            .or_insert_with(|| Column { fb, row_count: 0 }.influx_type());
+                    }
+                }
+            }
+        }
+
+        tables
+            .into_iter()
+            .map(|(table_name, columns)| TableColumnSchema {
+                table_name: table_name.to_string(),
+                columns: columns
+                    .into_iter()
+                    .map(|(name, influx_type)| (name.to_string(), influx_type))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// The columns of a single table written by an `Entry`, as returned by
+/// [`Entry::schema_summary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableColumnSchema {
+    /// The name of the table
+    pub table_name: String,
+
+    /// The columns of the table and their types, sorted by column name
+    pub columns: BTreeMap<String, InfluxColumnType>,
+}
+
+/// The smallest possible flatbuffer: the offset to the root table followed
+/// by the offset from that table to its vtable.
+const MIN_FLATBUFFER_SIZE: usize = flatbuffers::SIZE_UOFFSET + flatbuffers::SIZE_SOFFSET;
+
+/// Checks that `data` is long enough to hold the root table it refers to,
+/// so that truncated buffers can be told apart from corrupt ones.
+fn ensure_not_truncated(data: &[u8]) -> Result<(), DecodeError> {
+    ensure!(
+        data.len() >= MIN_FLATBUFFER_SIZE,
+        Truncated {
+            expected: MIN_FLATBUFFER_SIZE,
+            actual: data.len(),
+        }
+    );
+
+    let root_offset = u32::from_le_bytes(data[..flatbuffers::SIZE_UOFFSET].try_into().unwrap());
+    let expected = (root_offset as usize).saturating_add(flatbuffers::SIZE_SOFFSET);
+    ensure!(
+        data.len() >= expected,
+        Truncated {
+            expected,
+            actual: data.len(),
+        }
+    );
+
+    Ok(())
 }
 
 impl TryFrom<Vec<u8>> for Entry {
-    type Error = flatbuffers::InvalidFlatbuffer;
+    type Error = DecodeError;
 
     fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        ensure_not_truncated(&data)?;
+
         EntryTryBuilder {
             data,
             fb_builder: |data| flatbuffers::root::<entry_fb::Entry<'_>>(data),
         }
         .try_build()
+        .context(InvalidEntryFlatbuffer)
     }
 }
 
@@ -372,6 +1068,27 @@ impl<'a> PartitionWrite<'a> {
     }
 }
 
+/// Wrapper struct for the flatbuffers Delete. Removes the rows of a single
+/// table that match a predicate, across all partitions.
+#[derive(Debug)]
+pub struct Delete<'a> {
+    fb: entry_fb::Delete<'a>,
+}
+
+impl<'a> Delete<'a> {
+    pub fn table_name(&self) -> &str {
+        self.fb
+            .table_name()
+            .expect("table_name must be present in the flatbuffer Delete")
+    }
+
+    pub fn predicate(&self) -> &str {
+        self.fb
+            .predicate()
+            .expect("predicate must be present in the flatbuffer Delete")
+    }
+}
+
 /// Wrapper struct for the flatbuffers TableBatch. Has convenience methods for
 /// iterating through the data in columnar format.
 #[derive(Debug)]
@@ -429,49 +1146,91 @@ impl<'a> TableBatch<'a> {
     }
 
     pub fn row_count(&self) -> usize {
-        if let Some(cols) = self.fb.columns() {
-            if let Some(c) = cols.iter().next() {
-                let null_count = match c.null_mask() {
-                    Some(m) => m.iter().map(|b| b.count_ones() as usize).sum(),
-                    None => 0,
-                };
-
-                let value_count = match c.values_type() {
-                    entry_fb::ColumnValues::BoolValues => {
-                        c.values_as_bool_values().unwrap().values().unwrap().len()
-                    }
-                    entry_fb::ColumnValues::U64Values => {
-                        c.values_as_u64values().unwrap().values().unwrap().len()
-                    }
-                    entry_fb::ColumnValues::F64Values => {
-                        c.values_as_f64values().unwrap().values().unwrap().len()
-                    }
-                    entry_fb::ColumnValues::I64Values => {
-                        c.values_as_i64values().unwrap().values().unwrap().len()
-                    }
-                    entry_fb::ColumnValues::StringValues => {
-                        c.values_as_string_values().unwrap().values().unwrap().len()
-                    }
-                    entry_fb::ColumnValues::BytesValues => {
-                        c.values_as_bytes_values().unwrap().values().unwrap().len()
-                    }
-                    _ => panic!("invalid column flatbuffers"),
-                };
-
-                return value_count + null_count;
-            }
+        match self.fb.columns().and_then(|cols| cols.iter().next()) {
+            Some(c) => column_row_count(&c),
+            None => 0,
         }
-
-        0
     }
-}
 
-/// Wrapper struct for the flatbuffers Column. Has a convenience method to
-/// return an iterator for the values in the column.
-#[derive(Debug)]
-pub struct Column<'a> {
-    fb: entry_fb::Column<'a>,
-    pub row_count: usize,
+    /// Converts this table batch into an Arrow `RecordBatch`. Tag columns are
+    /// dictionary encoded and nulls in the flatbuffers columns are carried
+    /// over as nulls in the resulting Arrow arrays.
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let columns = self.columns();
+
+        let mut schema_builder = SchemaBuilder::new();
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(columns.len());
+
+        for column in &columns {
+            let influx_type = column.influx_type();
+            schema_builder.influx_column(column.name(), influx_type);
+
+            let array: ArrayRef = match (influx_type, column.values()) {
+                (InfluxColumnType::Tag, TypedValuesIterator::String(values)) => {
+                    Arc::new(values.collect::<DictionaryArray<Int32Type>>())
+                }
+                (_, TypedValuesIterator::String(values)) => {
+                    Arc::new(values.collect::<StringArray>())
+                }
+                (InfluxColumnType::Timestamp, TypedValuesIterator::I64(values)) => Arc::new(
+                    TimestampNanosecondArray::from_opt_vec(values.collect(), None),
+                ),
+                (_, TypedValuesIterator::I64(values)) => {
+                    Arc::new(Int64Array::from(values.collect::<Vec<_>>()))
+                }
+                (_, TypedValuesIterator::F64(values)) => {
+                    Arc::new(Float64Array::from(values.collect::<Vec<_>>()))
+                }
+                (_, TypedValuesIterator::U64(values)) => {
+                    Arc::new(UInt64Array::from(values.collect::<Vec<_>>()))
+                }
+                (_, TypedValuesIterator::Bool(values)) => {
+                    Arc::new(BooleanArray::from(values.collect::<Vec<_>>()))
+                }
+            };
+
+            arrays.push(array);
+        }
+
+        let schema = schema_builder.build().context(BuildingSchema)?;
+
+        RecordBatch::try_new(schema.into(), arrays).context(CreatingRecordBatch)
+    }
+}
+
+/// Returns the number of rows in a flatbuffers column, i.e. the number of
+/// values plus the number of nulls.
+fn column_row_count(c: &entry_fb::Column<'_>) -> usize {
+    try_column_row_count(c).expect("invalid column flatbuffers")
+}
+
+/// Returns the number of rows of a column, or `None` if its values are
+/// missing or of an unknown type.
+fn try_column_row_count(c: &entry_fb::Column<'_>) -> Option<usize> {
+    let null_count = match c.null_mask() {
+        Some(m) => m.iter().map(|b| b.count_ones() as usize).sum(),
+        None => c.all_null_row_count() as usize,
+    };
+
+    let value_count = match c.values_type() {
+        entry_fb::ColumnValues::BoolValues => c.values_as_bool_values()?.values()?.len(),
+        entry_fb::ColumnValues::U64Values => c.values_as_u64values()?.values()?.len(),
+        entry_fb::ColumnValues::F64Values => c.values_as_f64values()?.values()?.len(),
+        entry_fb::ColumnValues::I64Values => c.values_as_i64values()?.values()?.len(),
+        entry_fb::ColumnValues::StringValues => c.values_as_string_values()?.values()?.len(),
+        entry_fb::ColumnValues::BytesValues => c.values_as_bytes_values()?.values()?.len(),
+        _ => return None,
+    };
+
+    Some(value_count + null_count)
+}
+
+/// Wrapper struct for the flatbuffers Column. Has a convenience method to
+/// return an iterator for the values in the column.
+#[derive(Debug)]
+pub struct Column<'a> {
+    fb: entry_fb::Column<'a>,
+    pub row_count: usize,
 }
 
 impl<'a> Column<'a> {
@@ -528,12 +1287,27 @@ impl<'a> Column<'a> {
         self.fb.logical_column_type() == entry_fb::LogicalColumnType::Time
     }
 
+    /// Returns true if every row of this column is null.
+    pub fn is_all_null(&self) -> bool {
+        self.fb.all_null_row_count() > 0
+    }
+
+    /// All null columns are stored without a null mask, so they are decoded
+    /// with an empty one: rows past the end of a mask are null.
+    fn null_mask(&self) -> Option<&'a [u8]> {
+        if self.is_all_null() {
+            Some(&[])
+        } else {
+            self.fb.null_mask()
+        }
+    }
+
     pub fn values(&self) -> TypedValuesIterator<'a> {
         match self.fb.values_type() {
             entry_fb::ColumnValues::BoolValues => TypedValuesIterator::Bool(BoolIterator {
                 row_count: self.row_count,
                 position: 0,
-                null_mask: self.fb.null_mask(),
+                null_mask: self.null_mask(),
                 value_position: 0,
                 values: self
                     .fb
@@ -554,7 +1328,7 @@ impl<'a> Column<'a> {
                 TypedValuesIterator::String(StringIterator {
                     row_count: self.row_count,
                     position: 0,
-                    null_mask: self.fb.null_mask(),
+                    null_mask: self.null_mask(),
                     values,
                 })
             }
@@ -570,7 +1344,7 @@ impl<'a> Column<'a> {
                 TypedValuesIterator::I64(ValIterator {
                     row_count: self.row_count,
                     position: 0,
-                    null_mask: self.fb.null_mask(),
+                    null_mask: self.null_mask(),
                     values_iter,
                 })
             }
@@ -586,7 +1360,7 @@ impl<'a> Column<'a> {
                 TypedValuesIterator::F64(ValIterator {
                     row_count: self.row_count,
                     position: 0,
-                    null_mask: self.fb.null_mask(),
+                    null_mask: self.null_mask(),
                     values_iter,
                 })
             }
@@ -602,7 +1376,7 @@ impl<'a> Column<'a> {
                 TypedValuesIterator::U64(ValIterator {
                     row_count: self.row_count,
                     position: 0,
-                    null_mask: self.fb.null_mask(),
+                    null_mask: self.null_mask(),
                     values_iter,
                 })
             }
@@ -676,7 +1450,7 @@ impl<'a> Iterator for BoolIterator<'a> {
     type Item = Option<bool>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.position >= self.row_count || self.value_position >= self.values.len() {
+        if self.position >= self.row_count {
             return None;
         }
 
@@ -685,7 +1459,7 @@ impl<'a> Iterator for BoolIterator<'a> {
             return Some(None);
         }
 
-        let val = Some(self.values[self.value_position]);
+        let val = self.values.get(self.value_position).copied();
         self.value_position += 1;
 
         Some(val)
@@ -775,6 +1549,24 @@ impl NullMaskBuilder {
         self.position += 1;
     }
 
+    /// Appends `count` nulls, setting whole bytes at a time where possible
+    fn push_nulls(&mut self, mut count: usize) {
+        // fill up the remaining bits of the last byte
+        while count > 0 && self.position <= BITS_IN_BYTE {
+            self.push(true);
+            count -= 1;
+        }
+
+        // the last byte is now full, so append whole bytes of nulls
+        let full_bytes = count / BITS_IN_BYTE;
+        self.bytes.resize(self.bytes.len() + full_bytes, u8::MAX);
+        count -= full_bytes * BITS_IN_BYTE;
+
+        for _ in 0..count {
+            self.push(true);
+        }
+    }
+
     #[allow(dead_code)]
     fn to_bool_vec(&self) -> Vec<bool> {
         (1..self.row_count() + 1)
@@ -795,6 +1587,11 @@ impl NullMaskBuilder {
 
         false
     }
+
+    fn is_all_null(&self) -> bool {
+        let null_count: usize = self.bytes.iter().map(|b| b.count_ones() as usize).sum();
+        null_count > 0 && null_count == self.row_count()
+    }
 }
 
 impl std::fmt::Debug for NullMaskBuilder {
@@ -895,14 +1692,36 @@ impl<'a> ColumnBuilder<'a> {
 
     // ensures there are at least as many rows (or nulls) to row_number - 1
     fn null_to_row(&mut self, row_number: usize) {
-        let mut row_count = self.nulls.row_count();
+        let row_count = self.nulls.row_count();
 
-        while row_count < row_number - 1 {
-            self.nulls.push(true);
-            row_count += 1;
+        if row_count < row_number - 1 {
+            self.nulls.push_nulls(row_number - 1 - row_count);
         }
     }
 
+    fn is_f64(&self) -> bool {
+        matches!(self.values, ColumnRaw::F64(_))
+    }
+
+    fn is_i64(&self) -> bool {
+        matches!(self.values, ColumnRaw::I64(_))
+    }
+
+    fn is_u64(&self) -> bool {
+        matches!(self.values, ColumnRaw::U64(_))
+    }
+
+    // converts the values of an integer column to f64, leaving other column
+    // types unchanged
+    fn promote_to_f64(&mut self) {
+        let values = match &self.values {
+            ColumnRaw::I64(values) => values.iter().map(|v| *v as f64).collect(),
+            ColumnRaw::U64(values) => values.iter().map(|v| *v as f64).collect(),
+            _ => return,
+        };
+        self.values = ColumnRaw::F64(values);
+    }
+
     fn push_tag(&mut self, value: &'a str) -> ColumnResult<()> {
         match &mut self.values {
             ColumnRaw::Tag(values) => {
@@ -1035,7 +1854,14 @@ impl<'a> ColumnBuilder<'a> {
         column_name: &str,
     ) -> WIPOffset<entry_fb::Column<'a>> {
         let name = Some(fbb.create_string(column_name));
-        let null_mask = if self.nulls.has_nulls() {
+        // Columns without any values only need their row count, not a mask
+        // with every bit set
+        let all_null_row_count = if self.nulls.is_all_null() {
+            self.nulls.row_count() as u32
+        } else {
+            0
+        };
+        let null_mask = if all_null_row_count == 0 && self.nulls.has_nulls() {
             Some(fbb.create_vector_direct(&self.nulls.bytes))
         } else {
             None
@@ -1165,6 +1991,7 @@ impl<'a> ColumnBuilder<'a> {
                 values_type,
                 values: Some(values),
                 null_mask,
+                all_null_row_count,
             },
         )
     }
@@ -1233,11 +2060,35 @@ enum InnerClockValueError {
 #[derive(Debug, Snafu)]
 pub enum SequencedEntryError {
     #[snafu(display("{}", source))]
-    InvalidFlatbuffer {
-        source: flatbuffers::InvalidFlatbuffer,
-    },
+    InvalidFlatbuffer { source: DecodeError },
+
+    #[snafu(display(
+        "framed sequenced entry is truncated: expected {} bytes but got {}",
+        expected,
+        actual
+    ))]
+    FrameTruncated { expected: usize, actual: usize },
+
+    #[snafu(display(
+        "framed sequenced entry checksum mismatch: expected {:#010x} but got {:#010x}",
+        expected,
+        actual
+    ))]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
+    #[snafu(display("framed sequenced entry has invalid sequence marker {}", marker))]
+    InvalidSequenceMarker { marker: u8 },
 }
 
+/// Size of the length prefix of a framed sequenced entry.
+const FRAME_LEN_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Size of the checksum suffix of a framed sequenced entry.
+const FRAME_CRC_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Size of the sequence in the payload of a framed sequenced entry, if present.
+const FRAME_SEQUENCE_SIZE: usize = std::mem::size_of::<u32>() + std::mem::size_of::<u64>();
+
 #[derive(Debug)]
 pub struct SequencedEntry {
     entry: Entry,
@@ -1294,9 +2145,94 @@ impl SequencedEntry {
         self.entry.partition_writes()
     }
 
+    pub fn deletes(&self) -> Option<Vec<Delete<'_>>> {
+        self.entry.deletes()
+    }
+
     pub fn sequence(&self) -> Option<&Sequence> {
         self.sequence.as_ref()
     }
+
+    /// Serializes this entry along with its sequence, framed for transfer.
+    ///
+    /// The frame consists of the payload length as a little endian `u32`,
+    /// the payload itself and a little endian CRC32 checksum of the payload.
+    /// The payload is a marker byte indicating whether a sequence is present,
+    /// followed by the sequence id and number if so, followed by the entry
+    /// flatbuffer.
+    pub fn to_framed_bytes(&self) -> Vec<u8> {
+        let data = self.entry.data();
+        let sequence_size = match self.sequence {
+            Some(_) => FRAME_SEQUENCE_SIZE,
+            None => 0,
+        };
+        let payload_len = 1 + sequence_size + data.len();
+
+        let mut bytes = Vec::with_capacity(FRAME_LEN_SIZE + payload_len + FRAME_CRC_SIZE);
+        bytes.extend_from_slice(&(payload_len as u32).to_le_bytes());
+        match self.sequence {
+            Some(sequence) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&sequence.id.to_le_bytes());
+                bytes.extend_from_slice(&sequence.number.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(data);
+
+        let checksum = crc32fast::hash(&bytes[FRAME_LEN_SIZE..]);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Deserializes an entry framed by [`to_framed_bytes`](Self::to_framed_bytes),
+    /// validating its length and checksum before decoding it.
+    pub fn from_framed_bytes(bytes: &[u8]) -> Result<Self, SequencedEntryError> {
+        let header_len = FRAME_LEN_SIZE + 1;
+        ensure_frame_len(bytes, header_len + FRAME_CRC_SIZE)?;
+
+        let (len, rest) = bytes.split_at(FRAME_LEN_SIZE);
+        let payload_len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        ensure_frame_len(rest, payload_len + FRAME_CRC_SIZE)?;
+
+        let (payload, rest) = rest.split_at(payload_len);
+        let expected = u32::from_le_bytes(rest[..FRAME_CRC_SIZE].try_into().unwrap());
+        let actual = crc32fast::hash(payload);
+        ensure!(expected == actual, ChecksumMismatch { expected, actual });
+
+        let (marker, payload) = payload.split_first().context(FrameTruncated {
+            expected: 1_usize,
+            actual: 0_usize,
+        })?;
+        let (sequence, data) = match marker {
+            0 => (None, payload),
+            1 => {
+                ensure_frame_len(payload, FRAME_SEQUENCE_SIZE)?;
+                let (sequence, data) = payload.split_at(FRAME_SEQUENCE_SIZE);
+                let (id, number) = sequence.split_at(std::mem::size_of::<u32>());
+                let sequence = Sequence::new(
+                    u32::from_le_bytes(id.try_into().unwrap()),
+                    u64::from_le_bytes(number.try_into().unwrap()),
+                );
+                (Some(sequence), data)
+            }
+            marker => return InvalidSequenceMarker { marker: *marker }.fail(),
+        };
+
+        let entry = Entry::try_from(data.to_vec()).context(InvalidFlatbuffer)?;
+        Ok(Self { entry, sequence })
+    }
+}
+
+fn ensure_frame_len(bytes: &[u8], expected: usize) -> Result<(), SequencedEntryError> {
+    ensure!(
+        bytes.len() >= expected,
+        FrameTruncated {
+            expected,
+            actual: bytes.len(),
+        }
+    );
+    Ok(())
 }
 
 pub mod test_helpers {
@@ -1311,6 +2247,74 @@ pub mod test_helpers {
     // being written as multiple writes.
     const LP_BATCH_SIZE: usize = 10000;
 
+    /// Builds an entry with a single table batch made of the given i64
+    /// columns, without any of the checks done by `lines_to_sharded_entries`.
+    pub fn entry_with_columns(partition_key: &str, columns: &[(&str, &[i64])]) -> Entry {
+        entry_with_raw_columns(partition_key, |fbb| {
+            columns
+                .iter()
+                .map(|(name, values)| {
+                    let mut builder = ColumnBuilder::new_i64_column();
+                    for v in values.iter() {
+                        builder.push_i64(*v).unwrap();
+                    }
+                    builder.build_flatbuffer(fbb, name)
+                })
+                .collect()
+        })
+    }
+
+    /// Builds an entry with a single table batch made of the columns created
+    /// by `build_columns`, which may be malformed.
+    pub(crate) fn entry_with_raw_columns(
+        partition_key: &str,
+        build_columns: impl for<'a> FnOnce(
+            &mut FlatBufferBuilder<'a>,
+        ) -> Vec<WIPOffset<entry_fb::Column<'a>>>,
+    ) -> Entry {
+        let mut fbb = FlatBufferBuilder::new_with_capacity(1024);
+
+        let columns = build_columns(&mut fbb);
+        let columns = fbb.create_vector(&columns);
+        let table_name = fbb.create_string("cpu");
+        let table_batch = entry_fb::TableWriteBatch::create(
+            &mut fbb,
+            &entry_fb::TableWriteBatchArgs {
+                name: Some(table_name),
+                columns: Some(columns),
+            },
+        );
+        let table_batches = fbb.create_vector(&[table_batch]);
+
+        let partition_key = fbb.create_string(partition_key);
+        let partition_write = entry_fb::PartitionWrite::create(
+            &mut fbb,
+            &entry_fb::PartitionWriteArgs {
+                key: Some(partition_key),
+                table_batches: Some(table_batches),
+            },
+        );
+        let partition_writes = fbb.create_vector(&[partition_write]);
+
+        let write_operations = entry_fb::WriteOperations::create(
+            &mut fbb,
+            &entry_fb::WriteOperationsArgs {
+                partition_writes: Some(partition_writes),
+            },
+        );
+        let entry = entry_fb::Entry::create(
+            &mut fbb,
+            &entry_fb::EntryArgs {
+                operation_type: entry_fb::Operation::write,
+                operation: Some(write_operations.as_union_value()),
+            },
+        );
+        fbb.finish(entry, None);
+
+        let (mut data, idx) = fbb.collapse();
+        Entry::try_from(data.split_off(idx)).unwrap()
+    }
+
     /// Converts the line protocol to a single `Entry` with a single shard and
     /// a single partition.
     pub fn lp_to_entry(lp: &str) -> Entry {
@@ -1438,7 +2442,7 @@ pub mod test_helpers {
 
 #[cfg(test)]
 mod tests {
-    use data_types::database_rules::NO_SHARD_CONFIG;
+    use data_types::database_rules::{TemplatePart, NO_SHARD_CONFIG};
     use influxdb_line_protocol::parse_lines;
 
     use super::test_helpers::*;
@@ -1749,6 +2753,62 @@ mod tests {
         assert_eq!(&values, &[None, Some(23.2), None]);
     }
 
+    #[test]
+    fn shard_config_preview_matches_sharding() {
+        use data_types::{
+            consistent_hasher::ConsistentHasher,
+            database_rules::{HashRing, ShardConfig},
+        };
+
+        let shard_config = ShardConfig {
+            hash_ring: Some(HashRing {
+                table_name: true,
+                columns: vec!["host".to_string()],
+                shards: ConsistentHasher::new(&[1, 2]),
+            }),
+            ..Default::default()
+        };
+
+        let lp = (0..100)
+            .map(|i| format!("cpu,host=host{} val=1 10", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+
+        let preview = shard_config.preview(&lines).unwrap();
+
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            Some(&shard_config),
+            &hour_partitioner(),
+        )
+        .unwrap();
+        let actual = sharded_entries
+            .iter()
+            .map(|sharded_entry| {
+                let rows = sharded_entry
+                    .entry
+                    .partition_writes()
+                    .unwrap()
+                    .iter()
+                    .flat_map(|pw| pw.table_batches())
+                    .map(|tb| tb.row_count())
+                    .sum::<usize>();
+                (sharded_entry.shard_id, rows)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(preview, actual);
+        // both shards receive data
+        assert_eq!(preview.len(), 2);
+        assert_eq!(preview.values().sum::<usize>(), 100);
+
+        assert!(lines
+            .iter()
+            .all(|line| shard_config.explain_shard(line).unwrap().is_some()));
+    }
+
     #[test]
     fn null_mask_builder() {
         let mut m = NullMaskBuilder::new();
@@ -1797,6 +2857,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn null_mask_builder_push_nulls() {
+        // start from every offset within a byte to cover the partial byte
+        // handling at both ends
+        for prefix in 0..=BITS_IN_BYTE {
+            for &count in &[0, 1, 7, 8, 9, 4096, 5003] {
+                let mut bulk = NullMaskBuilder::new();
+                let mut single = NullMaskBuilder::new();
+                for i in 0..prefix {
+                    bulk.push(i % 2 == 0);
+                    single.push(i % 2 == 0);
+                }
+
+                bulk.push_nulls(count);
+                for _ in 0..count {
+                    single.push(true);
+                }
+
+                assert_eq!(bulk.row_count(), prefix + count);
+                assert_eq!(bulk.bytes, single.bytes);
+                assert_eq!(bulk.position, single.position);
+                assert_eq!(bulk.to_bool_vec(), single.to_bool_vec());
+                assert_eq!(bulk.has_nulls(), single.has_nulls());
+
+                // subsequent pushes must continue where the bulk append ended
+                bulk.push(false);
+                single.push(false);
+                assert_eq!(bulk.to_bool_vec(), single.to_bool_vec());
+            }
+        }
+    }
+
     #[test]
     fn row_count_edge_cases() {
         let lp = vec!["a val=1i 1"].join("\n");
@@ -2020,6 +3112,292 @@ mod tests {
         assert!(sharded_entries.is_err());
     }
 
+    #[test]
+    fn field_type_coercion() {
+        let lp = vec!["a val=1i 1", "a val=2.5 2", "a val=3u 3", "a val=4i 4"].join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+
+        let sharded_entries = lines_to_sharded_entries_with_coercion(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            sharder(1).as_ref(),
+            &partitioner(1),
+            FieldTypeCoercion::PromoteToFloat,
+        )
+        .unwrap();
+
+        let partition_writes = sharded_entries[0].entry.partition_writes().unwrap();
+        let table_batches = partition_writes[0].table_batches();
+        let columns = table_batches[0].columns();
+        let col = columns.iter().find(|c| c.name() == "val").unwrap();
+        let values = col.values().f64_values().unwrap();
+        assert_eq!(values, vec![Some(1.0), Some(2.5), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn field_type_coercion_mixed_integers() {
+        for lp in &["a val=1i 1\na val=2u 2", "a val=1u 1\na val=2i 2"] {
+            let lines: Vec<_> = parse_lines(lp).map(|l| l.unwrap()).collect();
+
+            let sharded_entries = lines_to_sharded_entries(
+                &lines,
+                ARBITRARY_DEFAULT_TIME,
+                sharder(1).as_ref(),
+                &partitioner(1),
+            );
+            assert!(matches!(
+                sharded_entries,
+                Err(Error::TableColumnTypeMismatch { .. })
+            ));
+
+            let sharded_entries = lines_to_sharded_entries_with_coercion(
+                &lines,
+                ARBITRARY_DEFAULT_TIME,
+                sharder(1).as_ref(),
+                &partitioner(1),
+                FieldTypeCoercion::PromoteToFloat,
+            )
+            .unwrap();
+
+            let partition_writes = sharded_entries[0].entry.partition_writes().unwrap();
+            let table_batches = partition_writes[0].table_batches();
+            let columns = table_batches[0].columns();
+            let col = columns.iter().find(|c| c.name() == "val").unwrap();
+            let values = col.values().f64_values().unwrap();
+            assert_eq!(values, vec![Some(1.0), Some(2.0)], "{}", lp);
+        }
+    }
+
+    #[test]
+    fn empty_measurement() {
+        let mut lines: Vec<_> = parse_lines("cpu val=1 10\ncpu val=2 20")
+            .map(|l| l.unwrap())
+            .collect();
+        lines[1].series.measurement = "".into();
+
+        let err = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            sharder(1).as_ref(),
+            &partitioner(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::EmptyMeasurement { line_number: 1 }));
+    }
+
+    #[test]
+    fn reserved_column_name() {
+        let convert = |lp: &str| {
+            let lines: Vec<_> = parse_lines(lp).map(|l| l.unwrap()).collect();
+            lines_to_sharded_entries(
+                &lines,
+                ARBITRARY_DEFAULT_TIME,
+                sharder(1).as_ref(),
+                &partitioner(1),
+            )
+        };
+
+        let err = convert("cpu,host=a val=1 10\ncpu,time=b val=2 20").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ReservedColumnName { line_number: 1, .. }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "table cpu has a tag or field named 'time' on line 1, which is reserved for the timestamp"
+        );
+
+        let err = convert("cpu time=1 10").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ReservedColumnName { line_number: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn timestamp_range() {
+        let convert = |lp: &str| {
+            let lines: Vec<_> = parse_lines(lp).map(|l| l.unwrap()).collect();
+            lines_to_sharded_entries(
+                &lines,
+                ARBITRARY_DEFAULT_TIME,
+                sharder(1).as_ref(),
+                &partitioner(1),
+            )
+        };
+
+        let time_values = |sharded_entries: Vec<ShardedEntry>| {
+            let partition_writes = sharded_entries[0].entry.partition_writes().unwrap();
+            let table_batches = partition_writes[0].table_batches();
+            let columns = table_batches[0].columns();
+            let col = columns
+                .iter()
+                .find(|c| c.name() == TIME_COLUMN_NAME)
+                .unwrap();
+            col.values().i64_values().unwrap()
+        };
+
+        let entries = convert("cpu val=1 1617286224000000000").unwrap();
+        assert_eq!(time_values(entries), vec![Some(1617286224000000000)]);
+
+        let lp = format!("cpu val=1 {}\ncpu val=2 {}", MIN_NANO_TIME, MAX_NANO_TIME);
+        let entries = convert(&lp).unwrap();
+        assert_eq!(
+            time_values(entries),
+            vec![Some(MIN_NANO_TIME), Some(MAX_NANO_TIME)]
+        );
+
+        let lp = format!("cpu val=1 10\ncpu val=2 {}", i64::MAX);
+        let err = convert(&lp).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TimestampOutOfRange {
+                line_number: 1,
+                timestamp: i64::MAX,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "timestamp {} on line 1 of table cpu is outside the valid range [{}, {}]",
+                i64::MAX,
+                MIN_NANO_TIME,
+                MAX_NANO_TIME
+            )
+        );
+
+        let err = convert(&format!("cpu val=1 {}", i64::MIN)).unwrap_err();
+        assert!(matches!(err, Error::TimestampOutOfRange { .. }));
+    }
+
+    #[test]
+    fn time_range() {
+        let lp = vec![
+            "cpu,host=a val=1 20",
+            "mem,host=a val=1 5",
+            "cpu,host=b val=2 100",
+            "disk,host=a val=1 50",
+            "cpu,host=c val=3 7",
+        ]
+        .join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            sharder(1).as_ref(),
+            &partitioner(3),
+        )
+        .unwrap();
+
+        let entry = &sharded_entries[0].entry;
+        assert_eq!(entry.partition_writes().unwrap().len(), 3);
+        assert_eq!(entry.time_range(), Some((5, 100)));
+
+        // deletes carry no rows
+        let entry = deletes_to_entry(&[("cpu", "host=a")]);
+        assert_eq!(entry.time_range(), None);
+    }
+
+    #[test]
+    fn field_type_coercion_rejects_string() {
+        let lp = vec!["a val=1i 1", "a val=\"foo\" 2", "a val=2.5 3"].join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+
+        for coercion in &[FieldTypeCoercion::Strict, FieldTypeCoercion::PromoteToFloat] {
+            let sharded_entries = lines_to_sharded_entries_with_coercion(
+                &lines,
+                ARBITRARY_DEFAULT_TIME,
+                sharder(1).as_ref(),
+                &partitioner(1),
+                *coercion,
+            );
+
+            assert!(
+                matches!(sharded_entries, Err(Error::TableColumnTypeMismatch { .. })),
+                "{:?}",
+                coercion
+            );
+        }
+    }
+
+    #[test]
+    fn entry_builder() {
+        let lp = vec![
+            "cpu,host=a,region=west user=23.1,system=66.1 123",
+            "cpu,host=b user=1.5 456",
+            "mem,host=a used=23432i,free=12u,label=\"x\",ok=true 123",
+        ]
+        .join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            NO_SHARD_CONFIG,
+            &partitioner(1),
+        )
+        .unwrap();
+        let expected = &sharded_entries[0].entry;
+
+        let mut builder = EntryBuilder::new();
+        builder
+            .begin_partition("key_0")
+            .begin_table("mem")
+            .tag("host", "a")
+            .field("used", FieldValue::I64(23432))
+            .field("free", FieldValue::U64(12))
+            .field("label", FieldValue::String("x".into()))
+            .field("ok", FieldValue::Boolean(true))
+            .timestamp(123)
+            .end_row()
+            .begin_table("cpu")
+            .tag("host", "a")
+            .tag("region", "west")
+            .field("user", FieldValue::F64(23.1))
+            .field("system", FieldValue::F64(66.1))
+            .timestamp(123)
+            .end_row()
+            .tag("host", "b")
+            .field("user", FieldValue::F64(1.5))
+            .timestamp(456)
+            .end_row()
+            // empty tables and partitions are skipped
+            .begin_table("disk")
+            .begin_partition("key_1");
+        let entry = builder.build().unwrap();
+
+        assert_eq!(entry.data(), expected.data());
+    }
+
+    #[test]
+    fn entry_builder_errors() {
+        let mut builder = EntryBuilder::new();
+        builder
+            .begin_partition("key")
+            .begin_table("cpu")
+            .field("val", FieldValue::I64(1))
+            .timestamp(1)
+            .end_row()
+            .field("val", FieldValue::F64(2.0))
+            .timestamp(2)
+            .end_row();
+        assert!(matches!(
+            builder.build(),
+            Err(Error::TableColumnTypeMismatch { line_number: 1, .. })
+        ));
+
+        let mut builder = EntryBuilder::new();
+        builder
+            .begin_partition("key")
+            .begin_table("cpu")
+            .field("val", FieldValue::I64(1))
+            .end_row();
+        assert!(matches!(
+            builder.build(),
+            Err(Error::RowTimestampMissing { row: 0, .. })
+        ));
+    }
+
     #[test]
     fn logical_type_conflict() {
         let lp = vec!["a,host=a val=1i 1", "a host=\"b\" 123"].join("\n");
@@ -2035,6 +3413,331 @@ mod tests {
         assert!(sharded_entries.is_err());
     }
 
+    #[test]
+    fn schema_summary() {
+        let lp = vec![
+            "cpu,host=a val=1i 10",
+            "mem,val=b used=23.1 20",
+            "cpu,host=b,region=west val=2i 30",
+            "mem,val=c used=12.2,free=1u 40",
+        ]
+        .join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+
+        // spread the lines over multiple partitions so columns need deduplicating
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            sharder(1).as_ref(),
+            &partitioner(3),
+        )
+        .unwrap();
+        let entry = &sharded_entries[0].entry;
+        assert_eq!(entry.partition_writes().unwrap().len(), 3);
+
+        let summary = entry.schema_summary();
+
+        let expected = vec![
+            TableColumnSchema {
+                table_name: "cpu".to_string(),
+                columns: vec![
+                    ("host".to_string(), InfluxColumnType::Tag),
+                    ("region".to_string(), InfluxColumnType::Tag),
+                    (TIME_COLUMN_NAME.to_string(), InfluxColumnType::Timestamp),
+                    (
+                        "val".to_string(),
+                        InfluxColumnType::Field(InfluxFieldType::Integer),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            },
+            TableColumnSchema {
+                table_name: "mem".to_string(),
+                columns: vec![
+                    (
+                        "free".to_string(),
+                        InfluxColumnType::Field(InfluxFieldType::UInteger),
+                    ),
+                    (TIME_COLUMN_NAME.to_string(), InfluxColumnType::Timestamp),
+                    (
+                        "used".to_string(),
+                        InfluxColumnType::Field(InfluxFieldType::Float),
+                    ),
+                    ("val".to_string(), InfluxColumnType::Tag),
+                ]
+                .into_iter()
+                .collect(),
+            },
+        ];
+
+        assert_eq!(summary, expected);
+    }
+
+    #[test]
+    fn table_batch_to_record_batch() {
+        use arrow::{array::Array, datatypes::DataType};
+
+        let lp = vec![
+            "cpu,host=a,region=west b=true,f=1.5,i=1i,s=\"one\",u=1u 10",
+            "cpu,host=b f=2.5,u=2u 20",
+            "cpu,host=a,region=east b=false,i=3i,s=\"three\" 30",
+        ]
+        .join("\n");
+        let entry = lp_to_entry(&lp);
+        let partition_writes = entry.partition_writes().unwrap();
+        let table_batches = partition_writes[0].table_batches();
+        let batch = table_batches[0].to_record_batch().unwrap();
+
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.num_columns(), 8);
+
+        let column = |name: &str| -> ArrayRef {
+            let (idx, _) = batch.schema().column_with_name(name).unwrap();
+            Arc::clone(batch.column(idx))
+        };
+
+        let host = column("host");
+        let host = host
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        let host_dictionary = host.values();
+        let host_values = host_dictionary
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let host: Vec<_> = host
+            .keys()
+            .iter()
+            .map(|k| k.map(|k| host_values.value(k as usize)))
+            .collect();
+        assert_eq!(host, vec![Some("a"), Some("b"), Some("a")]);
+
+        let region = column("region");
+        let region = region
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        assert_eq!(region.null_count(), 1);
+        assert!(region.is_null(1));
+
+        let b = column("b");
+        let b = b.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert_eq!(
+            b.iter().collect::<Vec<_>>(),
+            vec![Some(true), None, Some(false)]
+        );
+
+        let f = column("f");
+        let f = f.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(
+            f.iter().collect::<Vec<_>>(),
+            vec![Some(1.5), Some(2.5), None]
+        );
+
+        let i = column("i");
+        let i = i.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(i.iter().collect::<Vec<_>>(), vec![Some(1), None, Some(3)]);
+
+        let s = column("s");
+        let s = s.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            vec![Some("one"), None, Some("three")]
+        );
+
+        let u = column("u");
+        let u = u.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(u.iter().collect::<Vec<_>>(), vec![Some(1), Some(2), None]);
+
+        let time = column(TIME_COLUMN_NAME);
+        assert!(matches!(time.data_type(), DataType::Timestamp(_, None)));
+        let time = time
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(
+            time.iter().collect::<Vec<_>>(),
+            vec![Some(10), Some(20), Some(30)]
+        );
+
+        // the schema carries the logical column types
+        let schema = internal_types::schema::Schema::try_from(batch.schema()).unwrap();
+        let (influx_type, _) = schema.field(schema.find_index_of("host").unwrap());
+        assert_eq!(influx_type, Some(InfluxColumnType::Tag));
+        let (influx_type, _) = schema.field(schema.find_index_of("u").unwrap());
+        assert_eq!(
+            influx_type,
+            Some(InfluxColumnType::Field(InfluxFieldType::UInteger))
+        );
+    }
+
+    #[test]
+    fn validate_entry() {
+        let entry = lp_to_entry("cpu,host=a val=1i 10\nmem free=2u 20\ncpu val=3i 30");
+        entry.validate(&PartitionTemplate::default()).unwrap();
+
+        let entry = entry_with_columns("key", &[("val", &[1, 2]), ("time", &[10, 20])]);
+        entry.validate(&PartitionTemplate::default()).unwrap();
+    }
+
+    #[test]
+    fn validate_entry_empty_partition_key() {
+        let entry = entry_with_columns("", &[("val", &[1]), ("time", &[10])]);
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Table],
+            ..Default::default()
+        };
+        assert_eq!(
+            entry.validate(&template),
+            Err(ValidationError::EmptyPartitionKey)
+        );
+        entry.validate(&PartitionTemplate::default()).unwrap();
+
+        // the default partition template produces empty keys
+        let lines: Vec<_> = parse_lines("cpu val=1i 10").map(|l| l.unwrap()).collect();
+        let entry = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            NO_SHARD_CONFIG,
+            &PartitionTemplate::default(),
+        )
+        .unwrap()
+        .pop()
+        .unwrap()
+        .entry;
+        entry.validate(&PartitionTemplate::default()).unwrap();
+    }
+
+    #[test]
+    fn validate_entry_malformed_column() {
+        let entry = entry_with_raw_columns("key", |fbb| {
+            vec![entry_fb::Column::create(
+                fbb,
+                &entry_fb::ColumnArgs::default(),
+            )]
+        });
+        assert_eq!(
+            entry.validate(&PartitionTemplate::default()),
+            Err(ValidationError::MissingColumnName {
+                table: "cpu".to_string(),
+            })
+        );
+
+        let entry = entry_with_raw_columns("key", |fbb| {
+            let name = fbb.create_string("val");
+            vec![entry_fb::Column::create(
+                fbb,
+                &entry_fb::ColumnArgs {
+                    name: Some(name),
+                    ..Default::default()
+                },
+            )]
+        });
+        assert_eq!(
+            entry.validate(&PartitionTemplate::default()),
+            Err(ValidationError::InvalidColumnValues {
+                table: "cpu".to_string(),
+                column: "val".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn validate_entry_duplicate_column() {
+        let entry = entry_with_columns(
+            "key",
+            &[("val", &[1, 2]), ("val", &[3, 4]), ("time", &[10, 20])],
+        );
+        assert_eq!(
+            entry.validate(&PartitionTemplate::default()),
+            Err(ValidationError::DuplicateColumn {
+                table: "cpu".to_string(),
+                column: "val".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn validate_entry_inconsistent_row_count() {
+        let entry = entry_with_columns("key", &[("val", &[1, 2]), ("time", &[10])]);
+        assert_eq!(
+            entry.validate(&PartitionTemplate::default()),
+            Err(ValidationError::InconsistentRowCount {
+                table: "cpu".to_string(),
+                column: "time".to_string(),
+                expected: 2,
+                actual: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn all_null_column() {
+        use arrow::array::Array;
+
+        let mut table = TableBatchBuilder::new("cpu", FieldTypeCoercion::Strict);
+        for time in 1..=3 {
+            table
+                .push_row(
+                    vec![("host", "a")],
+                    vec![("val", FieldRef::I64(time))],
+                    time,
+                )
+                .unwrap();
+        }
+
+        // columns that only ever received nulls, e.g. when backfilling a wide schema
+        for (name, mut column) in vec![
+            ("empty_bool", ColumnBuilder::new_bool_column()),
+            ("empty_f64", ColumnBuilder::new_f64_column()),
+            ("empty_string", ColumnBuilder::new_string_column()),
+        ] {
+            column.null_to_row(table.rows + 1);
+            table.columns.insert(name, column);
+        }
+
+        let entry = build_entry(vec![("key", vec![table])]);
+        entry.validate(&PartitionTemplate::default()).unwrap();
+
+        let partition_writes = entry.partition_writes().unwrap();
+        let table_batches = partition_writes[0].table_batches();
+        let table_batch = &table_batches[0];
+        assert_eq!(table_batch.row_count(), 3);
+
+        let columns = table_batch.columns();
+        let column = |name| columns.iter().find(|c| c.name() == name).unwrap();
+
+        for name in &["empty_bool", "empty_f64", "empty_string"] {
+            let column = column(*name);
+            assert!(column.is_all_null());
+            assert!(column.inner().null_mask().is_none());
+            assert_eq!(column.inner().all_null_row_count(), 3);
+        }
+        assert!(!column("val").is_all_null());
+
+        assert_eq!(
+            column("empty_bool").values().bool_values().unwrap(),
+            vec![None, None, None]
+        );
+        assert_eq!(
+            column("empty_f64").values().f64_values().unwrap(),
+            vec![None, None, None]
+        );
+        match column("empty_string").values() {
+            TypedValuesIterator::String(values) => {
+                assert_eq!(values.collect::<Vec<_>>(), vec![None, None, None])
+            }
+            _ => panic!("wrong type"),
+        }
+
+        let batch = table_batch.to_record_batch().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        let empty_f64 = batch.schema().index_of("empty_f64").unwrap();
+        assert_eq!(batch.column(empty_f64).null_count(), 3);
+    }
+
     #[test]
     fn min_max_time() {
         let entry = lp_to_entry("m val=1 10000000123");
@@ -2052,4 +3755,123 @@ mod tests {
         assert_eq!(min, ts);
         assert_eq!(max, Utc.timestamp(12, 3));
     }
+
+    #[test]
+    fn decode_empty_entry() {
+        let err = Entry::try_from(vec![]).unwrap_err();
+        assert!(matches!(
+            err,
+            DecodeError::Truncated {
+                expected: 8,
+                actual: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn decode_truncated_entry() {
+        let data = lp_to_entry("cpu,host=a val=1 10").data().to_vec();
+        let root_offset = u32::from_le_bytes(data[..4].try_into().unwrap()) as usize;
+
+        let err = Entry::try_from(data[..root_offset + 2].to_vec()).unwrap_err();
+        assert!(matches!(err, DecodeError::Truncated { .. }));
+    }
+
+    #[test]
+    fn decode_corrupt_entry() {
+        let mut data = lp_to_entry("cpu,host=a val=1 10").data().to_vec();
+        let root_offset = u32::from_le_bytes(data[..4].try_into().unwrap()) as usize;
+
+        // point the root table at a vtable outside of the buffer
+        data[root_offset..root_offset + 4].copy_from_slice(&i32::MIN.to_le_bytes());
+
+        let err = Entry::try_from(data).unwrap_err();
+        assert!(matches!(err, DecodeError::InvalidEntryFlatbuffer { .. }));
+    }
+
+    #[test]
+    fn framed_sequenced_entry() {
+        let entry = lp_to_entry("cpu,host=a val=1 10\ncpu,host=b val=2 20");
+        let data = entry.data().to_vec();
+        let sequenced = SequencedEntry::new_from_sequence(Sequence::new(3, 42), entry).unwrap();
+
+        let framed = sequenced.to_framed_bytes();
+        let decoded = SequencedEntry::from_framed_bytes(&framed).unwrap();
+        let sequence = decoded.sequence().unwrap();
+        assert_eq!(sequence.id, 3);
+        assert_eq!(sequence.number, 42);
+        assert_eq!(decoded.entry.data(), &data[..]);
+
+        let unsequenced = SequencedEntry::new_unsequenced(Entry::try_from(data).unwrap());
+        let decoded = SequencedEntry::from_framed_bytes(&unsequenced.to_framed_bytes()).unwrap();
+        assert!(decoded.sequence().is_none());
+
+        // flip a bit in the middle of the entry
+        let mut corrupted = framed.clone();
+        let middle = corrupted.len() / 2;
+        corrupted[middle] ^= 0x10;
+        let err = SequencedEntry::from_framed_bytes(&corrupted).unwrap_err();
+        assert!(matches!(err, SequencedEntryError::ChecksumMismatch { .. }));
+
+        let err = SequencedEntry::from_framed_bytes(&framed[..framed.len() - 1]).unwrap_err();
+        assert!(matches!(err, SequencedEntryError::FrameTruncated { .. }));
+    }
+
+    #[test]
+    fn delete_entry() {
+        let entry = deletes_to_entry(&[("cpu", "host = 'a'"), ("mem", "time < 100")]);
+        assert!(entry.partition_writes().is_none());
+
+        let sequenced = SequencedEntry::from_framed_bytes(
+            &SequencedEntry::new_unsequenced(entry).to_framed_bytes(),
+        )
+        .unwrap();
+        let deletes = sequenced.deletes().unwrap();
+        let deletes: Vec<_> = deletes
+            .iter()
+            .map(|d| (d.table_name(), d.predicate()))
+            .collect();
+        assert_eq!(deletes, vec![("cpu", "host = 'a'"), ("mem", "time < 100")]);
+
+        assert!(lp_to_entry("cpu val=1 10").deletes().is_none());
+    }
+
+    #[test]
+    fn validate_delete_entry() {
+        deletes_to_entry(&[("cpu", "host = 'a'")])
+            .validate(&PartitionTemplate::default())
+            .unwrap();
+
+        let mut fbb = flatbuffers::FlatBufferBuilder::new();
+        let table_name = fbb.create_string("cpu");
+        let delete = entry_fb::Delete::create(
+            &mut fbb,
+            &entry_fb::DeleteArgs {
+                table_name: Some(table_name),
+                predicate: None,
+            },
+        );
+        let deletes = fbb.create_vector(&[delete]);
+        let delete_operations = entry_fb::DeleteOperations::create(
+            &mut fbb,
+            &entry_fb::DeleteOperationsArgs {
+                deletes: Some(deletes),
+            },
+        );
+        let entry = entry_fb::Entry::create(
+            &mut fbb,
+            &entry_fb::EntryArgs {
+                operation_type: entry_fb::Operation::delete,
+                operation: Some(delete_operations.as_union_value()),
+            },
+        );
+        fbb.finish(entry, None);
+        let (mut data, idx) = fbb.collapse();
+        let entry = Entry::try_from(data.split_off(idx)).unwrap();
+
+        assert!(matches!(
+            entry.validate(&PartitionTemplate::default()).unwrap_err(),
+            ValidationError::MissingDeletePredicate { table } if table == "cpu"
+        ));
+    }
 }
@@ -1224,6 +1224,7 @@ pub mod influxdata {
                         args: &'args ColumnArgs<'args>,
                     ) -> flatbuffers::WIPOffset<Column<'bldr>> {
                         let mut builder = ColumnBuilder::new(_fbb);
+                        builder.add_all_null_row_count(args.all_null_row_count);
                         if let Some(x) = args.null_mask {
                             builder.add_null_mask(x);
                         }
@@ -1243,6 +1244,7 @@ pub mod influxdata {
                     pub const VT_VALUES_TYPE: flatbuffers::VOffsetT = 8;
                     pub const VT_VALUES: flatbuffers::VOffsetT = 10;
                     pub const VT_NULL_MASK: flatbuffers::VOffsetT = 12;
+                    pub const VT_ALL_NULL_ROW_COUNT: flatbuffers::VOffsetT = 14;
 
                     #[inline]
                     pub fn name(&self) -> Option<&'a str> {
@@ -1282,6 +1284,12 @@ pub mod influxdata {
                             .map(|v| v.safe_slice())
                     }
                     #[inline]
+                    pub fn all_null_row_count(&self) -> u32 {
+                        self._tab
+                            .get::<u32>(Column::VT_ALL_NULL_ROW_COUNT, Some(0))
+                            .unwrap()
+                    }
+                    #[inline]
                     #[allow(non_snake_case)]
                     pub fn values_as_i64values(&self) -> Option<I64Values<'a>> {
                         if self.values_type() == ColumnValues::I64Values {
@@ -1364,6 +1372,7 @@ pub mod influxdata {
         }
      })?
      .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, u8>>>(&"null_mask", Self::VT_NULL_MASK, false)?
+     .visit_field::<u32>(&"all_null_row_count", Self::VT_ALL_NULL_ROW_COUNT, false)?
      .finish();
                         Ok(())
                     }
@@ -1374,6 +1383,7 @@ pub mod influxdata {
                     pub values_type: ColumnValues,
                     pub values: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
                     pub null_mask: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, u8>>>,
+                    pub all_null_row_count: u32,
                 }
                 impl<'a> Default for ColumnArgs<'a> {
                     #[inline]
@@ -1384,6 +1394,7 @@ pub mod influxdata {
                             values_type: ColumnValues::NONE,
                             values: None,
                             null_mask: None,
+                            all_null_row_count: 0,
                         }
                     }
                 }
@@ -1437,6 +1448,14 @@ pub mod influxdata {
                         );
                     }
                     #[inline]
+                    pub fn add_all_null_row_count(&mut self, all_null_row_count: u32) {
+                        self.fbb_.push_slot::<u32>(
+                            Column::VT_ALL_NULL_ROW_COUNT,
+                            all_null_row_count,
+                            0,
+                        );
+                    }
+                    #[inline]
                     pub fn new(
                         _fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>,
                     ) -> ColumnBuilder<'a, 'b> {
@@ -1508,6 +1527,7 @@ pub mod influxdata {
                             }
                         };
                         ds.field("null_mask", &self.null_mask());
+                        ds.field("all_null_row_count", &self.all_null_row_count());
                         ds.finish()
                     }
                 }
@@ -1,14 +1,18 @@
 use std::convert::{TryFrom, TryInto};
+use std::num::NonZeroUsize;
 use std::time::Duration;
 
 use thiserror::Error;
 
 use data_types::database_rules::{
-    ColumnType, ColumnValue, DatabaseRules, Order, RoutingConfig, RoutingRules,
+    ColumnType, ColumnValue, DatabaseRules, Order, PartitionKeyOverflow, RoutingConfig,
+    RoutingRules,
 };
 use data_types::DatabaseName;
 
-use crate::google::{FieldViolation, FieldViolationExt, FromFieldOpt, FromFieldString};
+use crate::google::{
+    FieldViolation, FieldViolationExt, FromField, FromFieldOpt, FromFieldString, FromFieldVec,
+};
 use crate::influxdata::iox::management::v1 as management;
 
 mod lifecycle;
@@ -26,6 +30,13 @@ impl From<DatabaseRules> for management::DatabaseRules {
             write_buffer_connection_string: rules
                 .write_buffer_connection_string
                 .unwrap_or_default(),
+            max_partition_key_len: rules
+                .max_partition_key_len
+                .map(|x| x.get() as u64)
+                .unwrap_or_default(),
+            partition_key_overflow: management::PartitionKeyOverflow::from(
+                rules.partition_key_overflow,
+            ) as _,
         }
     }
 }
@@ -58,6 +69,11 @@ impl TryFrom<management::DatabaseRules> for DatabaseRules {
 
         let write_buffer_connection_string = proto.write_buffer_connection_string.optional();
 
+        let max_partition_key_len = NonZeroUsize::new(proto.max_partition_key_len as usize);
+        let partition_key_overflow = proto
+            .partition_key_overflow()
+            .scope("partition_key_overflow")?;
+
         Ok(Self {
             name,
             partition_template,
@@ -65,6 +81,29 @@ impl TryFrom<management::DatabaseRules> for DatabaseRules {
             routing_rules,
             worker_cleanup_avg_sleep,
             write_buffer_connection_string,
+            max_partition_key_len,
+            partition_key_overflow,
+        })
+    }
+}
+
+impl From<PartitionKeyOverflow> for management::PartitionKeyOverflow {
+    fn from(overflow: PartitionKeyOverflow) -> Self {
+        match overflow {
+            PartitionKeyOverflow::Error => Self::Error,
+            PartitionKeyOverflow::HashTruncate => Self::HashTruncate,
+        }
+    }
+}
+
+impl TryFrom<management::PartitionKeyOverflow> for PartitionKeyOverflow {
+    type Error = FieldViolation;
+
+    fn try_from(proto: management::PartitionKeyOverflow) -> Result<Self, Self::Error> {
+        Ok(match proto {
+            management::PartitionKeyOverflow::Unspecified => Self::default(),
+            management::PartitionKeyOverflow::Error => Self::Error,
+            management::PartitionKeyOverflow::HashTruncate => Self::HashTruncate,
         })
     }
 }
@@ -100,7 +139,11 @@ impl TryFrom<management::database_rules::RoutingRules> for RoutingRules {
 impl From<RoutingConfig> for management::RoutingConfig {
     fn from(routing_config: RoutingConfig) -> Self {
         Self {
-            target: Some(routing_config.target.into()),
+            targets: routing_config.targets.into_iter().map(Into::into).collect(),
+            quorum: routing_config
+                .quorum
+                .map(|x| x.get() as u64)
+                .unwrap_or_default(),
         }
     }
 }
@@ -109,8 +152,13 @@ impl TryFrom<management::RoutingConfig> for RoutingConfig {
     type Error = FieldViolation;
 
     fn try_from(proto: management::RoutingConfig) -> Result<Self, Self::Error> {
+        if proto.targets.is_empty() {
+            return Err(FieldViolation::required("targets"));
+        }
+
         Ok(Self {
-            target: proto.target.required("target")?,
+            targets: proto.targets.vec_field("targets")?,
+            quorum: (proto.quorum as usize).try_into().ok(),
         })
     }
 }
@@ -122,12 +170,18 @@ pub enum DecodeError {
 
     #[error("validation failed: {0}")]
     ValidationError(#[from] FieldViolation),
+
+    #[error("failed to decode JSON: {0}")]
+    JsonDecodeError(#[from] serde_json::Error),
 }
 
 #[derive(Debug, Error)]
 pub enum EncodeError {
     #[error("failed to encode protobuf: {0}")]
     EncodeError(#[from] prost::EncodeError),
+
+    #[error("failed to encode JSON: {0}")]
+    JsonEncodeError(#[from] serde_json::Error),
 }
 
 pub fn decode_database_rules(bytes: prost::bytes::Bytes) -> Result<DatabaseRules, DecodeError> {
@@ -143,6 +197,19 @@ pub fn encode_database_rules(
     Ok(prost::Message::encode(&encoded, bytes)?)
 }
 
+/// Decodes rules encoded by [`encode_database_rules_json`].
+pub fn decode_database_rules_json(bytes: &[u8]) -> Result<DatabaseRules, DecodeError> {
+    let message: management::DatabaseRules = serde_json::from_slice(bytes)?;
+    Ok(message.try_into()?)
+}
+
+/// Encodes rules as pretty-printed JSON of their protobuf representation,
+/// intended for humans rather than as a storage format.
+pub fn encode_database_rules_json(rules: DatabaseRules) -> Result<Vec<u8>, EncodeError> {
+    let encoded: management::DatabaseRules = rules.into();
+    Ok(serde_json::to_vec_pretty(&encoded)?)
+}
+
 impl From<Order> for management::Order {
     fn from(o: Order) -> Self {
         match o {
@@ -241,5 +308,29 @@ mod tests {
 
         // These should be none as preserved on non-protobuf DatabaseRules
         assert!(back.routing_rules.is_none());
+
+        assert_eq!(rules.max_partition_key_len, None);
+        assert_eq!(rules.partition_key_overflow, PartitionKeyOverflow::Error);
+    }
+
+    #[test]
+    fn test_database_rules_partition_key_limit() {
+        let protobuf = management::DatabaseRules {
+            name: "database".to_string(),
+            max_partition_key_len: 64,
+            partition_key_overflow: management::PartitionKeyOverflow::HashTruncate as _,
+            ..Default::default()
+        };
+
+        let rules: DatabaseRules = protobuf.clone().try_into().unwrap();
+        assert_eq!(rules.max_partition_key_len, NonZeroUsize::new(64));
+        assert_eq!(
+            rules.partition_key_overflow,
+            PartitionKeyOverflow::HashTruncate
+        );
+
+        let back: management::DatabaseRules = rules.into();
+        assert_eq!(back.max_partition_key_len, 64);
+        assert_eq!(back.partition_key_overflow, protobuf.partition_key_overflow);
     }
 }
@@ -1,5 +1,6 @@
 use std::convert::{TryFrom, TryInto};
 use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
+use std::time::Duration;
 
 use data_types::database_rules::{
     LifecycleRules, Sort, SortOrder, DEFAULT_CATALOG_TRANSACTIONS_UNTIL_CHECKPOINT,
@@ -45,6 +46,10 @@ impl From<LifecycleRules> for management::LifecycleRules {
             late_arrive_window_seconds: config.late_arrive_window_seconds.get(),
             persist_row_threshold: config.persist_row_threshold.get() as u64,
             persist_age_threshold_seconds: config.persist_age_threshold_seconds.get(),
+            retention_seconds: config
+                .retention_duration
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
         }
     }
 }
@@ -79,6 +84,10 @@ impl TryFrom<management::LifecycleRules> for LifecycleRules {
                 }),
             persist_age_threshold_seconds: NonZeroU32::new(proto.persist_age_threshold_seconds)
                 .unwrap_or_else(|| NonZeroU32::new(DEFAULT_PERSIST_AGE_THRESHOLD_SECONDS).unwrap()),
+            retention_duration: match proto.retention_seconds {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
         })
     }
 }
@@ -171,6 +180,7 @@ mod tests {
             late_arrive_window_seconds: 23,
             persist_row_threshold: 57,
             persist_age_threshold_seconds: 23,
+            retention_seconds: 86400,
         };
 
         let config: LifecycleRules = protobuf.clone().try_into().unwrap();
@@ -220,6 +230,8 @@ mod tests {
             back.persist_age_threshold_seconds,
             protobuf.persist_age_threshold_seconds
         );
+        assert_eq!(config.retention_duration, Some(Duration::from_secs(86400)));
+        assert_eq!(back.retention_seconds, protobuf.retention_seconds);
     }
 
     #[test]
@@ -1,6 +1,9 @@
 use std::convert::TryFrom;
 
-use data_types::database_rules::{PartitionTemplate, RegexCapture, StrftimeColumn, TemplatePart};
+use data_types::database_rules::{
+    PartitionTemplate, RegexCapture, StrftimeColumn, TemplatePart, TimeAndColumn,
+    DEFAULT_PARTITION_KEY_SEPARATOR,
+};
 
 use crate::google::protobuf::Empty;
 use crate::google::{FieldViolation, FromFieldOpt, FromFieldString, FromFieldVec};
@@ -8,8 +11,14 @@ use crate::influxdata::iox::management::v1 as management;
 
 impl From<PartitionTemplate> for management::PartitionTemplate {
     fn from(pt: PartitionTemplate) -> Self {
+        let separator = match pt.separator.as_str() {
+            DEFAULT_PARTITION_KEY_SEPARATOR => String::new(),
+            _ => pt.separator,
+        };
+
         Self {
             parts: pt.parts.into_iter().map(Into::into).collect(),
+            separator,
         }
     }
 }
@@ -19,7 +28,12 @@ impl TryFrom<management::PartitionTemplate> for PartitionTemplate {
 
     fn try_from(proto: management::PartitionTemplate) -> Result<Self, Self::Error> {
         let parts = proto.parts.vec_field("parts")?;
-        Ok(Self { parts })
+        let separator = match proto.separator.as_str() {
+            "" => DEFAULT_PARTITION_KEY_SEPARATOR.to_string(),
+            _ => proto.separator,
+        };
+
+        Ok(Self { parts, separator })
     }
 }
 
@@ -40,6 +54,9 @@ impl From<TemplatePart> for management::partition_template::part::Part {
                 Self::StrfTime(ColumnFormat { column, format })
             }
             TemplatePart::TimeFormat(format) => Self::Time(format),
+            TemplatePart::TimeAndColumn(TimeAndColumn { column, format }) => {
+                Self::TimeAndColumn(ColumnFormat { column, format })
+            }
         }
     }
 }
@@ -64,6 +81,12 @@ impl TryFrom<management::partition_template::part::Part> for TemplatePart {
                 })
             }
             Part::Time(format) => Self::TimeFormat(format.required("time")?),
+            Part::TimeAndColumn(ColumnFormat { column, format }) => {
+                Self::TimeAndColumn(TimeAndColumn {
+                    column: column.required("time_and_column.column")?,
+                    format: format.required("time_and_column.format")?,
+                })
+            }
         })
     }
 }
@@ -94,7 +117,10 @@ mod tests {
     fn test_partition_template_default() {
         let protobuf = management::DatabaseRules {
             name: "database".to_string(),
-            partition_template: Some(management::PartitionTemplate { parts: vec![] }),
+            partition_template: Some(management::PartitionTemplate {
+                parts: vec![],
+                separator: String::new(),
+            }),
             ..Default::default()
         };
 
@@ -111,6 +137,7 @@ mod tests {
             name: "database".to_string(),
             partition_template: Some(management::PartitionTemplate {
                 parts: vec![Default::default()],
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -140,7 +167,14 @@ mod tests {
                         format: "format".to_string(),
                     })),
                 },
+                management::partition_template::Part {
+                    part: Some(Part::TimeAndColumn(ColumnFormat {
+                        column: "tenant".to_string(),
+                        format: "%Y".to_string(),
+                    })),
+                },
             ],
+            separator: "/".to_string(),
         };
 
         let pt: PartitionTemplate = protobuf.clone().try_into().unwrap();
@@ -154,9 +188,14 @@ mod tests {
                 TemplatePart::RegexCapture(RegexCapture {
                     column: "column".to_string(),
                     regex: "format".to_string()
+                }),
+                TemplatePart::TimeAndColumn(TimeAndColumn {
+                    column: "tenant".to_string(),
+                    format: "%Y".to_string()
                 })
             ]
         );
+        assert_eq!(pt.separator, "/");
         assert_eq!(protobuf, back);
     }
 
@@ -170,6 +209,7 @@ mod tests {
                     ..Default::default()
                 })),
             }],
+            ..Default::default()
         };
 
         let res: Result<PartitionTemplate, _> = protobuf.try_into();
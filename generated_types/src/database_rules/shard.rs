@@ -112,10 +112,21 @@ impl TryFrom<management::HashRing> for HashRing {
 
 impl From<Shard> for management::Shard {
     fn from(shard: Shard) -> Self {
-        let sink = match shard {
-            Shard::Iox(node_group) => management::shard::Sink::Iox(node_group.into()),
+        let (sink, replication_factor) = match shard {
+            Shard::Iox {
+                node_group,
+                replication_factor,
+            } => (
+                management::shard::Sink::Iox(node_group.into()),
+                replication_factor,
+            ),
         };
-        management::Shard { sink: Some(sink) }
+        management::Shard {
+            sink: Some(sink),
+            replication_factor: replication_factor
+                .map(|x| x.get() as u64)
+                .unwrap_or_default(),
+        }
     }
 }
 
@@ -124,8 +135,12 @@ impl TryFrom<management::Shard> for Shard {
 
     fn try_from(proto: management::Shard) -> Result<Self, Self::Error> {
         let sink = proto.sink.ok_or_else(|| FieldViolation::required(""))?;
+        let replication_factor = (proto.replication_factor as usize).try_into().ok();
         Ok(match sink {
-            management::shard::Sink::Iox(node_group) => Shard::Iox(node_group.scope("node_group")?),
+            management::shard::Sink::Iox(node_group) => Shard::Iox {
+                node_group: node_group.scope("node_group")?,
+                replication_factor,
+            },
         })
     }
 }
@@ -351,6 +366,7 @@ mod tests {
                                 management::node_group::Node { id: 12 },
                             ],
                         })),
+                        replication_factor: 2,
                     },
                 ),
                 (
@@ -359,6 +375,7 @@ mod tests {
                         sink: Some(management::shard::Sink::Iox(management::NodeGroup {
                             nodes: vec![management::node_group::Node { id: 20 }],
                         })),
+                        replication_factor: 0,
                     },
                 ),
             ]
@@ -371,11 +388,17 @@ mod tests {
 
         assert_eq!(shard_config.shards.len(), 2);
         assert!(
-            matches!(&shard_config.shards[&1], Shard::Iox(node_group) if node_group.len() == 3)
+            matches!(&shard_config.shards[&1], Shard::Iox { node_group, .. } if node_group.len() == 3)
         );
+        assert_eq!(shard_config.shards[&1].required_acks(), 2);
         assert!(
-            matches!(&shard_config.shards[&2], Shard::Iox(node_group) if node_group.len() == 1)
+            matches!(&shard_config.shards[&2], Shard::Iox { node_group, .. } if node_group.len() == 1)
         );
+        assert_eq!(shard_config.shards[&2].required_acks(), 1);
+
+        let back: management::ShardConfig = shard_config.into();
+        assert_eq!(back.shards[&1].replication_factor, 2);
+        assert_eq!(back.shards[&2].replication_factor, 0);
     }
 
     #[test]
@@ -44,6 +44,28 @@ impl From<Job> for management::operation_metadata::Job {
                 table_name,
                 chunks,
             }),
+            Job::CopyChunk {
+                db_name,
+                partition_key,
+                table_name,
+                chunk_id,
+                dst_db_name,
+            } => Self::CopyChunk(management::CopyChunk {
+                db_name,
+                partition_key,
+                table_name,
+                chunk_id,
+                dst_db_name,
+            }),
+            Job::DropExpiredPartition {
+                db_name,
+                partition_key,
+                table_name,
+            } => Self::DropExpiredPartition(management::DropExpiredPartition {
+                db_name,
+                partition_key,
+                table_name,
+            }),
         }
     }
 }
@@ -89,6 +111,28 @@ impl From<management::operation_metadata::Job> for Job {
                 table_name,
                 chunks,
             },
+            Job::CopyChunk(management::CopyChunk {
+                db_name,
+                partition_key,
+                table_name,
+                chunk_id,
+                dst_db_name,
+            }) => Self::CopyChunk {
+                db_name,
+                partition_key,
+                table_name,
+                chunk_id,
+                dst_db_name,
+            },
+            Job::DropExpiredPartition(management::DropExpiredPartition {
+                db_name,
+                partition_key,
+                table_name,
+            }) => Self::DropExpiredPartition {
+                db_name,
+                partition_key,
+                table_name,
+            },
         }
     }
 }
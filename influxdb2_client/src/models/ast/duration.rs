@@ -18,10 +18,179 @@ pub struct Duration {
     pub unit: Option<String>,
 }
 
+/// Units of fixed length, largest first, paired with their length in
+/// nanoseconds. Calendar units (`mo`, `y`) have no fixed length and are not
+/// listed.
+const FIXED_UNITS: &[(&str, i64)] = &[
+    ("w", 7 * 24 * 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
 impl Duration {
     /// A pair consisting of length of time and the unit of time measured. It is
     /// the atomic unit from which all duration literals are composed.
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// A duration of `magnitude` in the Flux unit `unit`, e.g. `"m"` or `"mo"`
+    pub fn with_unit(magnitude: i32, unit: impl Into<String>) -> Self {
+        Self {
+            r#type: None,
+            magnitude: Some(magnitude),
+            unit: Some(unit.into()),
+        }
+    }
+
+    /// A duration of `n` nanoseconds
+    pub fn nanoseconds(n: i32) -> Self {
+        Self::with_unit(n, "ns")
+    }
+
+    /// A duration of `n` microseconds
+    pub fn microseconds(n: i32) -> Self {
+        Self::with_unit(n, "us")
+    }
+
+    /// A duration of `n` milliseconds
+    pub fn milliseconds(n: i32) -> Self {
+        Self::with_unit(n, "ms")
+    }
+
+    /// A duration of `n` seconds
+    pub fn seconds(n: i32) -> Self {
+        Self::with_unit(n, "s")
+    }
+
+    /// A duration of `n` minutes
+    pub fn minutes(n: i32) -> Self {
+        Self::with_unit(n, "m")
+    }
+
+    /// A duration of `n` hours
+    pub fn hours(n: i32) -> Self {
+        Self::with_unit(n, "h")
+    }
+
+    /// A duration of `n` days
+    pub fn days(n: i32) -> Self {
+        Self::with_unit(n, "d")
+    }
+
+    /// A duration of `n` weeks
+    pub fn weeks(n: i32) -> Self {
+        Self::with_unit(n, "w")
+    }
+
+    /// A duration of `n` calendar months
+    pub fn months(n: i32) -> Self {
+        Self::with_unit(n, "mo")
+    }
+
+    /// A duration of `n` calendar years
+    pub fn years(n: i32) -> Self {
+        Self::with_unit(n, "y")
+    }
+
+    /// Converts a `std::time::Duration` using the largest unit that
+    /// represents it exactly.
+    ///
+    /// Returns `None` if the magnitude does not fit in an `i32` for that unit.
+    pub fn from_std(duration: std::time::Duration) -> Option<Self> {
+        let nanos = duration.as_nanos();
+        let (unit, unit_nanos) = FIXED_UNITS
+            .iter()
+            .find(|(_, unit_nanos)| nanos % *unit_nanos as u128 == 0)
+            .expect("every duration is a whole number of nanoseconds");
+
+        let magnitude = nanos / *unit_nanos as u128;
+        if magnitude > i32::MAX as u128 {
+            return None;
+        }
+        Some(Self::with_unit(magnitude as i32, *unit))
+    }
+
+    /// The length of this duration in nanoseconds.
+    ///
+    /// Returns `None` if the magnitude or unit is missing, or if the unit is a
+    /// calendar unit (`mo`, `y`) with no fixed length.
+    pub fn to_nanos(&self) -> Option<i64> {
+        let magnitude = self.magnitude?;
+        let unit = match self.unit.as_deref()? {
+            "µs" => "us",
+            unit => unit,
+        };
+        let (_, unit_nanos) = FIXED_UNITS.iter().find(|(name, _)| *name == unit)?;
+        i64::from(magnitude).checked_mul(*unit_nanos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructors() {
+        assert_eq!(
+            serde_json::to_value(Duration::minutes(5)).unwrap(),
+            serde_json::json!({"magnitude": 5, "unit": "m"})
+        );
+        assert_eq!(
+            serde_json::to_value(Duration::months(2)).unwrap(),
+            serde_json::json!({"magnitude": 2, "unit": "mo"})
+        );
+
+        assert_eq!(Duration::hours(2), Duration::with_unit(2, "h"));
+        assert_eq!(
+            Duration::hours(2).to_nanos(),
+            Duration::minutes(120).to_nanos()
+        );
+        assert_eq!(Duration::seconds(3).to_nanos(), Some(3_000_000_000));
+        assert_eq!(Duration::weeks(1).to_nanos(), Duration::days(7).to_nanos());
+        assert_eq!(Duration::with_unit(4, "µs").to_nanos(), Some(4_000));
+
+        assert_eq!(Duration::months(1).to_nanos(), None);
+        assert_eq!(Duration::years(1).to_nanos(), None);
+        assert_eq!(Duration::new().to_nanos(), None);
+    }
+
+    #[test]
+    fn from_std() {
+        use std::time::Duration as StdDuration;
+
+        assert_eq!(
+            Duration::from_std(StdDuration::from_secs(90)),
+            Some(Duration::seconds(90))
+        );
+        assert_eq!(
+            Duration::from_std(StdDuration::from_secs(2 * 60 * 60)),
+            Some(Duration::hours(2))
+        );
+        assert_eq!(
+            Duration::from_std(StdDuration::from_micros(1_500)),
+            Some(Duration::microseconds(1_500))
+        );
+        assert_eq!(
+            Duration::from_std(StdDuration::from_nanos(0)),
+            Some(Duration::weeks(0))
+        );
+        assert_eq!(
+            Duration::from_std(StdDuration::from_nanos(i32::MAX as u64 + 1)),
+            None
+        );
+
+        let std = StdDuration::from_millis(12_345);
+        let duration = Duration::from_std(std).unwrap();
+        assert_eq!(
+            serde_json::to_value(&duration).unwrap(),
+            serde_json::json!({"magnitude": 12_345, "unit": "ms"})
+        );
+        assert_eq!(duration.to_nanos(), Some(std.as_nanos() as i64));
+    }
 }
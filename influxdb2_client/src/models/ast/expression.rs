@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::ast::{CallExpression, Duration, Property, PropertyKey};
+
 /// Expression AST
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct Expression {
@@ -81,4 +83,223 @@ impl Expression {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn of_type(r#type: &str) -> Self {
+        Self {
+            r#type: Some(r#type.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// An identifier, e.g. `from`
+    pub fn identifier(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::of_type("Identifier")
+        }
+    }
+
+    /// A string literal, e.g. `"telegraf"`
+    pub fn string(value: impl Into<String>) -> Self {
+        Self {
+            value: Some(value.into()),
+            ..Self::of_type("StringLiteral")
+        }
+    }
+
+    /// A duration literal composed of `values`, e.g. `1h30m`
+    pub fn duration(values: impl IntoIterator<Item = Duration>) -> Self {
+        Self {
+            values: values.into_iter().collect(),
+            ..Self::of_type("DurationLiteral")
+        }
+    }
+
+    /// A unary expression applying `operator` to `argument`, e.g. `-1h`
+    pub fn unary(operator: impl Into<String>, argument: Self) -> Self {
+        Self {
+            operator: Some(operator.into()),
+            argument: Some(Box::new(argument)),
+            ..Self::of_type("UnaryExpression")
+        }
+    }
+
+    /// A binary expression, e.g. `r._measurement == "cpu"`
+    pub fn binary(operator: impl Into<String>, left: Self, right: Self) -> Self {
+        Self {
+            operator: Some(operator.into()),
+            left: Some(Box::new(left)),
+            right: Some(Box::new(right)),
+            ..Self::of_type("BinaryExpression")
+        }
+    }
+
+    /// An object literal, e.g. `{bucket: "telegraf"}`
+    pub fn object<K: Into<String>>(properties: impl IntoIterator<Item = (K, Self)>) -> Self {
+        Self {
+            properties: properties
+                .into_iter()
+                .map(|(key, value)| Property {
+                    r#type: Some("Property".to_string()),
+                    key: Some(PropertyKey {
+                        r#type: Some("Identifier".to_string()),
+                        name: Some(key.into()),
+                        value: None,
+                    }),
+                    value: Some(value),
+                })
+                .collect(),
+            ..Self::of_type("ObjectExpression")
+        }
+    }
+
+    /// Accesses `property` of `object`, e.g. `r._measurement`
+    pub fn member(object: Self, property: impl Into<String>) -> Self {
+        Self {
+            object: Some(Box::new(object)),
+            property: Some(Box::new(PropertyKey {
+                r#type: Some("Identifier".to_string()),
+                name: Some(property.into()),
+                value: None,
+            })),
+            ..Self::of_type("MemberExpression")
+        }
+    }
+
+    /// Calls the function `fn_name` with the named `args`, e.g.
+    /// `from(bucket: "telegraf")`
+    pub fn call<K: Into<String>>(
+        fn_name: impl Into<String>,
+        args: impl IntoIterator<Item = (K, Self)>,
+    ) -> Self {
+        let call = call_expression(fn_name, args);
+        Self {
+            callee: call.callee,
+            arguments: call.arguments,
+            ..Self::of_type("CallExpression")
+        }
+    }
+
+    /// Pipes `target` into the function `fn_name` called with the named
+    /// `args`, e.g. `target |> range(start: -1h)`
+    pub fn pipe<K: Into<String>>(
+        target: Self,
+        fn_name: impl Into<String>,
+        args: impl IntoIterator<Item = (K, Self)>,
+    ) -> Self {
+        Self {
+            argument: Some(Box::new(target)),
+            call: Some(call_expression(fn_name, args)),
+            ..Self::of_type("PipeExpression")
+        }
+    }
+}
+
+/// Flux functions take a single object argument holding the named arguments,
+/// omitted entirely when there are none.
+fn call_expression<K: Into<String>>(
+    fn_name: impl Into<String>,
+    args: impl IntoIterator<Item = (K, Expression)>,
+) -> CallExpression {
+    let object = Expression::object(args);
+    let arguments = if object.properties.is_empty() {
+        vec![]
+    } else {
+        vec![object]
+    };
+
+    CallExpression {
+        r#type: Some("CallExpression".to_string()),
+        callee: Some(Box::new(Expression::identifier(fn_name))),
+        arguments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pipeline() {
+        let from = Expression::call("from", vec![("bucket", Expression::string("telegraf"))]);
+        let range = Expression::pipe(
+            from,
+            "range",
+            vec![(
+                "start",
+                Expression::unary("-", Expression::duration(vec![Duration::hours(1)])),
+            )],
+        );
+
+        assert_eq!(
+            serde_json::to_value(&range).unwrap(),
+            json!({
+                "type": "PipeExpression",
+                "argument": {
+                    "type": "CallExpression",
+                    "callee": {"type": "Identifier", "name": "from"},
+                    "arguments": [{
+                        "type": "ObjectExpression",
+                        "properties": [{
+                            "type": "Property",
+                            "key": {"type": "Identifier", "name": "bucket"},
+                            "value": {"type": "StringLiteral", "value": "telegraf"}
+                        }]
+                    }]
+                },
+                "call": {
+                    "type": "CallExpression",
+                    "callee": {"type": "Identifier", "name": "range"},
+                    "arguments": [{
+                        "type": "ObjectExpression",
+                        "properties": [{
+                            "type": "Property",
+                            "key": {"type": "Identifier", "name": "start"},
+                            "value": {
+                                "type": "UnaryExpression",
+                                "operator": "-",
+                                "argument": {
+                                    "type": "DurationLiteral",
+                                    "values": [{"magnitude": 1, "unit": "h"}]
+                                }
+                            }
+                        }]
+                    }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn member_and_empty_call() {
+        let expr = Expression::binary(
+            "==",
+            Expression::member(Expression::identifier("r"), "_measurement"),
+            Expression::string("cpu"),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&expr).unwrap(),
+            json!({
+                "type": "BinaryExpression",
+                "operator": "==",
+                "left": {
+                    "type": "MemberExpression",
+                    "object": {"type": "Identifier", "name": "r"},
+                    "property": {"type": "Identifier", "name": "_measurement"}
+                },
+                "right": {"type": "StringLiteral", "value": "cpu"}
+            })
+        );
+
+        let call = Expression::call("yield", Vec::<(String, Expression)>::new());
+        assert_eq!(
+            serde_json::to_value(&call).unwrap(),
+            json!({
+                "type": "CallExpression",
+                "callee": {"type": "Identifier", "name": "yield"}
+            })
+        );
+    }
 }
@@ -472,13 +472,33 @@ impl PartialEq<String> for EscapedStr<'_> {
 }
 
 pub fn parse_lines(input: &str) -> impl Iterator<Item = Result<ParsedLine<'_>>> {
-    split_lines(input).filter_map(|line| {
+    parse_lines_with_line_numbers(input).map(|(_, _, res)| res)
+}
+
+/// Like [`parse_lines`], but also returns the 1-based number of the line of
+/// `input` each result starts on, along with the text that was parsed.
+///
+/// This is useful to point users at the lines of a batch that could not be
+/// parsed. Blank lines and comments are skipped but still counted.
+pub fn parse_lines_with_line_numbers(
+    input: &str,
+) -> impl Iterator<Item = (usize, &str, Result<ParsedLine<'_>>)> {
+    let mut line_number = 1;
+    let mut position = 0;
+
+    split_lines(input).filter_map(move |line| {
         let i = trim_leading(line);
 
         if i.is_empty() {
             return None;
         }
 
+        // `i` is a subslice of `input`, so its offset can be derived from
+        // their addresses
+        let offset = i.as_ptr() as usize - input.as_ptr() as usize;
+        line_number += input[position..offset].matches('\n').count();
+        position = offset;
+
         let res = match parse_line(i) {
             Ok((remaining, line)) => {
                 // should have parsed the whole input line, if any
@@ -500,7 +520,7 @@ pub fn parse_lines(input: &str) -> impl Iterator<Item = Result<ParsedLine<'_>>>
         if let Some(Err(r)) = &res {
             debug!("Error parsing line: '{}'. Error was {:?}", line, r);
         }
-        res
+        res.map(|res| (line_number, i, res))
     })
 }
 
@@ -1662,6 +1682,25 @@ foo value2=2i 123"#;
         assert_eq!(vals[1].field_set[0].1.unwrap_i64(), 2);
     }
 
+    #[test]
+    fn parse_lines_reports_line_numbers() {
+        let input =
+            "foo value1=1i 123\n\n# comment\nbar value2= 123\nbaz value3=\"a\nb\" 1\nqux value4=4i";
+        let results: Vec<_> = parse_lines_with_line_numbers(input)
+            .map(|(line_number, line, res)| (line_number, line.trim_end(), res.is_ok()))
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                (1, "foo value1=1i 123", true),
+                (4, "bar value2= 123", false),
+                (5, "baz value3=\"a\nb\" 1", true),
+                (7, "qux value4=4i", true),
+            ]
+        );
+    }
+
     #[test]
     fn parse_multiple_measurements_become_multiple_points() {
         let input = r#"foo value1=1i 123
@@ -337,9 +337,26 @@ impl Schema {
                 let columns = self.compute_select_indicies(columns)?;
                 self.select_by_indices(&columns)
             }
+            Selection::Except(columns) => {
+                let columns = self.compute_except_indices(columns);
+                self.select_by_indices(&columns)
+            }
         })
     }
 
+    /// Returns the field indexes of all columns not named in `columns`.
+    ///
+    /// Names that do not correspond to a column are ignored.
+    pub fn compute_except_indices(&self, columns: &[&str]) -> Vec<usize> {
+        self.inner
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| !columns.contains(&field.name().as_str()))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
     /// Returns the field indexes for a given selection
     ///
     /// Returns an error if a corresponding column isn't found
@@ -378,6 +395,19 @@ impl Schema {
         self.select(Selection::Some(selection))
     }
 
+    /// Returns a Schema with the columns of both this schema and `other`,
+    /// sorted by name, such as when a table gains new fields over time.
+    ///
+    /// Columns present in both must have the same InfluxDB column type
+    /// (tag, field or time), data type and nullability, and the measurement
+    /// names must not conflict. See [`SchemaMerger`](merge::SchemaMerger).
+    pub fn merge(&self, other: &Self) -> merge::Result<Self> {
+        Ok(merge::SchemaMerger::new()
+            .merge(self)?
+            .merge(other)?
+            .build())
+    }
+
     /// Returns the sort key if any
     pub fn sort_key(&self) -> Option<SortKey<'_>> {
         // Find all the sorted columns
@@ -1208,4 +1238,66 @@ mod test {
             }
         } if &column_name == "time" ));
     }
+
+    #[test]
+    fn test_merge() {
+        let schema1 = SchemaBuilder::new()
+            .influx_field("the_field", String)
+            .tag("tag1")
+            .timestamp()
+            .measurement("the_measurement")
+            .build()
+            .unwrap()
+            .sort_fields_by_name();
+
+        // identical schemas
+        assert_eq!(schema1.merge(&schema1).unwrap(), schema1);
+
+        // adds a field and a tag, keeping their roles
+        let schema2 = SchemaBuilder::new()
+            .influx_field("new_field", Float)
+            .tag("tag2")
+            .timestamp()
+            .build()
+            .unwrap();
+
+        let merged = schema1.merge(&schema2).unwrap();
+        let expected = SchemaBuilder::new()
+            .influx_field("new_field", Float)
+            .influx_field("the_field", String)
+            .tag("tag1")
+            .tag("tag2")
+            .timestamp()
+            .measurement("the_measurement")
+            .build()
+            .unwrap()
+            .sort_fields_by_name();
+        assert_eq!(merged, expected);
+        assert_eq!(
+            merged.field(merged.find_index_of("tag2").unwrap()).0,
+            Some(Tag)
+        );
+        assert_eq!(
+            merged.field(merged.find_index_of("new_field").unwrap()).0,
+            Some(Field(Float))
+        );
+
+        // a shared column with a different type is an error
+        let schema3 = SchemaBuilder::new()
+            .influx_field("the_field", Integer)
+            .build()
+            .unwrap();
+        let err = schema1.merge(&schema3).unwrap_err();
+        assert!(matches!(
+            err,
+            merge::Error::TryMergeBadColumnType { field_name, .. } if field_name == "the_field"
+        ));
+
+        // as is a column that is a tag in one schema and a field in the other
+        let schema4 = SchemaBuilder::new()
+            .influx_field("tag1", String)
+            .build()
+            .unwrap();
+        assert!(schema1.merge(&schema4).is_err());
+    }
 }
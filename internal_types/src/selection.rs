@@ -10,4 +10,9 @@ pub enum Selection<'a> {
 
     /// Return only the named columns
     Some(&'a [&'a str]),
+
+    /// Return all columns except the named ones. Named columns that do not
+    /// exist are ignored.
+    /// The columns are returned in an arbitrary order
+    Except(&'a [&'a str]),
 }
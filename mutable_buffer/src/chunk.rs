@@ -164,7 +164,8 @@ impl MBChunk {
 
     /// Returns the schema for a given selection
     ///
-    /// If Selection::All the returned columns are sorted by name
+    /// If Selection::All or Selection::Except the returned columns are sorted
+    /// by name
     pub fn schema(&self, selection: Selection<'_>) -> Result<Schema> {
         let mut schema_builder = SchemaBuilder::new();
         let schema = match selection {
@@ -185,6 +186,18 @@ impl MBChunk {
                 }
                 schema_builder.build().context(InternalSchema)?
             }
+            Selection::Except(cols) => {
+                for (column_name, column) in self.columns.iter() {
+                    if !cols.contains(&column_name.as_str()) {
+                        schema_builder.influx_column(column_name, column.influx_type());
+                    }
+                }
+
+                schema_builder
+                    .build()
+                    .context(InternalSchema)?
+                    .sort_fields_by_name()
+            }
         };
 
         Ok(schema)
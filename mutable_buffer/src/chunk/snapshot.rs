@@ -77,17 +77,26 @@ impl ChunkSnapshot {
                     .schema
                     .compute_select_indicies(columns)
                     .context(SelectColumns)?;
-                let schema = self.schema.select_by_indices(&projection).into();
-                let columns = projection
-                    .into_iter()
-                    .map(|x| Arc::clone(self.batch.column(x)))
-                    .collect();
-
-                RecordBatch::try_new(schema, columns).expect("failed to project record batch")
+                self.project(projection)
+            }
+            Selection::Except(columns) => {
+                let projection = self.schema.compute_except_indices(columns);
+                self.project(projection)
             }
         })
     }
 
+    /// Returns a RecordBatch containing the columns at the given indices
+    fn project(&self, projection: Vec<usize>) -> RecordBatch {
+        let schema = self.schema.select_by_indices(&projection).into();
+        let columns = projection
+            .into_iter()
+            .map(|x| Arc::clone(self.batch.column(x)))
+            .collect();
+
+        RecordBatch::try_new(schema, columns).expect("failed to project record batch")
+    }
+
     /// Returns a given selection of column names from a table
     pub fn column_names(&self, selection: Selection<'_>) -> Option<BTreeSet<String>> {
         let fields = self.schema.inner().fields().iter();
@@ -102,6 +111,10 @@ impl ChunkSnapshot {
                     }
                 })
                 .collect(),
+            Selection::Except(cols) => fields
+                .filter(|x| !cols.contains(&x.name().as_str()))
+                .map(|x| x.name().clone())
+                .collect(),
             Selection::All => fields.map(|x| x.name().clone()).collect(),
         })
     }
@@ -358,6 +358,10 @@ impl Column {
 /// Construct a validity mask from the given column's null mask
 fn construct_valid_mask(column: &EntryColumn<'_>) -> Result<Vec<u8>> {
     let buf_len = (column.row_count + 7) >> 3;
+    if column.is_all_null() {
+        return Ok(vec![0; buf_len]);
+    }
+
     match column.inner().null_mask() {
         Some(data) => {
             ensure!(
@@ -21,7 +21,7 @@ use object_store::{
     path::{parsed::DirsAndFileName, parts::PathPart, ObjectStorePath, Path},
     ObjectStore, ObjectStoreApi,
 };
-use observability_deps::tracing::{info, warn};
+use observability_deps::tracing::{error, info, warn};
 use parking_lot::RwLock;
 use prost::{DecodeError, EncodeError, Message};
 use snafu::{OptionExt, ResultExt, Snafu};
@@ -179,6 +179,9 @@ pub enum Error {
 
     #[snafu(display("Cannot commit transaction: {}", source))]
     CommitError { source: Box<Error> },
+
+    #[snafu(display("Cannot detect parquet files orphaned by catalog repair: {}", source))]
+    OrphanedFilesDetection { source: Box<crate::cleanup::Error> },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -210,6 +213,47 @@ pub trait CatalogState {
     fn remove(&mut self, path: DirsAndFileName) -> Result<()>;
 }
 
+/// Report of a catalog that was repaired while loading it, see [`PreservedCatalog::load_with_repair`].
+#[derive(Debug)]
+pub struct CatalogRepair {
+    /// Revision counter of the first transaction that could not be loaded.
+    pub corrupt_revision: u64,
+
+    /// Path of the corrupt transaction file, `None` if the transaction is missing.
+    pub corrupt_path: Option<Path>,
+
+    /// Error that was raised while loading the corrupt transaction.
+    pub error: Error,
+
+    /// Revision counter of the last good transaction, which is the state the catalog was loaded at.
+    pub last_good_revision: u64,
+
+    /// Transaction and checkpoint files that were deleted to truncate the catalog to the last good transaction.
+    pub deleted: Vec<Path>,
+
+    /// Parquet files that are no longer referenced by the repaired catalog, e.g. because they were added by one of
+    /// the dropped transactions.
+    ///
+    /// These files are NOT deleted by the repair. They are picked up by the regular cleanup of unreferenced parquet
+    /// files (see [`get_unreferenced_parquet_files`](crate::cleanup::get_unreferenced_parquet_files)).
+    pub orphaned_files: Vec<Path>,
+}
+
+impl std::fmt::Display for CatalogRepair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "transaction {} is corrupt ({}), truncated catalog to transaction {} ({} catalog files deleted, {} parquet \
+            files orphaned)",
+            self.corrupt_revision,
+            self.error,
+            self.last_good_revision,
+            self.deleted.len(),
+            self.orphaned_files.len(),
+        )
+    }
+}
+
 /// In-memory view of the preserved catalog.
 pub struct PreservedCatalog {
     // We need an RWLock AND a semaphore, so that readers are NOT blocked during an open transactions. Note that this
@@ -347,16 +391,68 @@ impl PreservedCatalog {
         db_name: String,
         state_data: S::EmptyInput,
     ) -> Result<Option<(Self, S)>>
+    where
+        S: CatalogState + Send + Sync,
+    {
+        let res = Self::load_inner(object_store, server_id, db_name, state_data, false).await?;
+        Ok(res.map(|(catalog, state, _repair)| (catalog, state)))
+    }
+
+    /// Load existing catalog from store, if it exists, and repair it if a transaction is corrupt.
+    ///
+    /// This works like [`load`](Self::load), but if a transaction cannot be read (e.g. because it cannot be
+    /// deserialized or it does not match its predecessor), the catalog is truncated to the last good transaction: all
+    /// transaction and checkpoint files from the corrupt revision onwards are deleted and the catalog is loaded at the
+    /// last good revision. The returned [`CatalogRepair`] is `Some` if that happened and lists the parquet files that
+    /// are no longer referenced by the catalog.
+    ///
+    /// Errors that leave no good state to fall back to (e.g. a corrupt checkpoint that loading starts at), store read
+    /// errors and errors while applying an otherwise valid transaction to the state are still returned.
+    ///
+    /// **Transactions after the corrupt one are lost, so only use this if you would otherwise wipe the catalog!**
+    pub async fn load_with_repair<S>(
+        object_store: Arc<ObjectStore>,
+        server_id: ServerId,
+        db_name: String,
+        state_data: S::EmptyInput,
+    ) -> Result<Option<(Self, S, Option<CatalogRepair>)>>
+    where
+        S: CatalogState + Send + Sync,
+    {
+        let res = Self::load_inner(object_store, server_id, db_name, state_data, true).await?;
+
+        match res {
+            Some((catalog, state, Some(mut repair))) => {
+                // nobody else has access to the catalog yet, so it is safe to scan for unreferenced files
+                repair.orphaned_files =
+                    crate::cleanup::get_unreferenced_parquet_files(&catalog, usize::MAX)
+                        .await
+                        .map_err(Box::new)
+                        .context(OrphanedFilesDetection)?;
+                Ok(Some((catalog, state, Some(repair))))
+            }
+            res => Ok(res),
+        }
+    }
+
+    async fn load_inner<S>(
+        object_store: Arc<ObjectStore>,
+        server_id: ServerId,
+        db_name: String,
+        state_data: S::EmptyInput,
+        repair: bool,
+    ) -> Result<Option<(Self, S, Option<CatalogRepair>)>>
     where
         S: CatalogState + Send + Sync,
     {
         // parse all paths into revisions
+        let files = list_files(&object_store, server_id, &db_name).await?;
         let mut transactions: HashMap<u64, Uuid> = HashMap::new();
         let mut max_revision = None;
         let mut last_checkpoint = None;
-        for (_path, file_type, revision_counter, uuid) in
-            list_files(&object_store, server_id, &db_name).await?
-        {
+        for (_path, file_type, revision_counter, uuid) in &files {
+            let (file_type, revision_counter, uuid) = (*file_type, *revision_counter, *uuid);
+
             // keep track of the max
             max_revision = Some(
                 max_revision
@@ -414,32 +510,87 @@ impl PreservedCatalog {
         let max_revision = max_revision.expect("transactions list is not empty here");
 
         // read and replay delta revisions
+        let mut repaired = None;
         for rev in start_revision..=max_revision {
-            let uuid = transactions.get(&rev).context(MissingTransaction {
-                revision_counter: rev,
-            })?;
-            let tkey = TransactionKey {
-                revision_counter: rev,
-                uuid: *uuid,
-            };
             let file_type = if Some(rev) == last_checkpoint {
                 FileType::Checkpoint
             } else {
                 FileType::Transaction
             };
-            OpenTransaction::load_and_apply(
-                &object_store,
-                server_id,
-                &db_name,
-                &tkey,
-                &mut state,
-                &last_tkey,
-                file_type,
-            )
-            .await?;
-            last_tkey = Some(tkey);
+            let tkey = transactions.get(&rev).map(|uuid| TransactionKey {
+                revision_counter: rev,
+                uuid: *uuid,
+            });
+            let path = tkey
+                .as_ref()
+                .map(|tkey| file_path(&object_store, server_id, &db_name, tkey, file_type));
+
+            let res = match (&tkey, &path) {
+                (Some(tkey), Some(path)) => {
+                    OpenTransaction::load_and_verify(
+                        &object_store,
+                        path,
+                        tkey,
+                        &last_tkey,
+                        file_type,
+                    )
+                    .await
+                }
+                _ => MissingTransaction {
+                    revision_counter: rev,
+                }
+                .fail(),
+            };
+            let proto = match res {
+                Ok(proto) => proto,
+                Err(e) => {
+                    error!(%e, %db_name, revision_counter = rev, ?path, "cannot load catalog transaction");
+
+                    match &last_tkey {
+                        Some(last_good) if repair && !matches!(e, Error::Read { .. }) => {
+                            repaired = Some((rev, path, e, last_good.revision_counter));
+                            break;
+                        }
+                        _ => return Err(e),
+                    }
+                }
+            };
+
+            OpenTransaction::apply(&proto, &mut state, &object_store)?;
+            last_tkey = tkey;
         }
 
+        // truncate catalog to the last good transaction
+        let repair = match repaired {
+            Some((corrupt_revision, corrupt_path, error, last_good_revision)) => {
+                let mut deleted = vec![];
+                for (path, _file_type, revision_counter, _uuid) in files {
+                    if revision_counter > last_good_revision {
+                        object_store.delete(&path).await.context(Write)?;
+                        deleted.push(path);
+                    }
+                }
+
+                warn!(
+                    %db_name,
+                    corrupt_revision,
+                    last_good_revision,
+                    n_deleted = deleted.len(),
+                    "truncated catalog to last good transaction"
+                );
+
+                Some(CatalogRepair {
+                    corrupt_revision,
+                    corrupt_path,
+                    error,
+                    last_good_revision,
+                    deleted,
+                    orphaned_files: vec![],
+                })
+            }
+            None => None,
+        };
+
         Ok(Some((
             Self {
                 previous_tkey: RwLock::new(last_tkey),
@@ -449,6 +600,7 @@ impl PreservedCatalog {
                 db_name,
             },
             state,
+            repair,
         )))
     }
 
@@ -673,6 +825,30 @@ async fn load_transaction_proto(
     Ok(proto)
 }
 
+/// Rewrites an encoded transaction or checkpoint file so that the parquet
+/// files it references are looked up under the directory of `server_id`.
+///
+/// File paths start with the ID of the server that wrote them, so this is
+/// needed when the files of a database are moved to another server.
+pub fn relocate_transaction(data: &[u8], server_id: ServerId) -> Result<Vec<u8>> {
+    let mut proto = proto::Transaction::decode(data).context(Deserialization {})?;
+
+    for action in &mut proto.actions {
+        let path = match action.action.as_mut() {
+            Some(proto::transaction::action::Action::AddParquet(a)) => a.path.as_mut(),
+            Some(proto::transaction::action::Action::RemoveParquet(a)) => a.path.as_mut(),
+            _ => None,
+        };
+        if let Some(first) = path.and_then(|path| path.directories.first_mut()) {
+            *first = server_id.to_string();
+        }
+    }
+
+    let mut data = Vec::new();
+    proto.encode(&mut data).context(Serialization {})?;
+    Ok(data)
+}
+
 /// Parse UUID from protobuf.
 fn parse_uuid(s: &str) -> Result<Option<Uuid>> {
     if s.is_empty() {
@@ -861,21 +1037,18 @@ impl OpenTransaction {
         Ok(())
     }
 
-    async fn load_and_apply<S>(
-        object_store: &Arc<ObjectStore>,
-        server_id: ServerId,
-        db_name: &str,
+    /// Load transaction from store and check that it fits into the chain of transactions.
+    ///
+    /// This does NOT touch any catalog state, so a failure leaves the state at the previous transaction.
+    async fn load_and_verify(
+        object_store: &ObjectStore,
+        path: &Path,
         tkey: &TransactionKey,
-        state: &mut S,
         last_tkey: &Option<TransactionKey>,
         file_type: FileType,
-    ) -> Result<()>
-    where
-        S: CatalogState + Send,
-    {
+    ) -> Result<proto::Transaction> {
         // recover state from store
-        let path = file_path(object_store, server_id, db_name, tkey, file_type);
-        let proto = load_transaction_proto(object_store, &path).await?;
+        let proto = load_transaction_proto(object_store, path).await?;
 
         // sanity-check file content
         if proto.version != TRANSACTION_VERSION {
@@ -927,7 +1100,18 @@ impl OpenTransaction {
             });
         }
 
-        // apply
+        Ok(proto)
+    }
+
+    /// Apply loaded and verified transaction to the catalog state.
+    fn apply<S>(
+        proto: &proto::Transaction,
+        state: &mut S,
+        object_store: &Arc<ObjectStore>,
+    ) -> Result<()>
+    where
+        S: CatalogState,
+    {
         for action in &proto.actions {
             if let Some(action) = action.action.as_ref() {
                 Self::handle_action(state, action, object_store)?;
@@ -2080,6 +2264,219 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_repair_truncates_to_last_good_transaction() {
+        let object_store = make_object_store();
+        let server_id = make_server_id();
+        let db_name = "db1";
+        let trace = assert_single_catalog_inmem_works(&object_store, server_id, db_name).await;
+
+        // break last transaction file
+        assert_eq!(trace.tkeys[2].revision_counter, 2);
+        let path = file_path(
+            &object_store,
+            server_id,
+            db_name,
+            &trace.tkeys[2],
+            FileType::Transaction,
+        );
+        let data = Bytes::from("foo");
+        let len = data.len();
+        object_store
+            .put(
+                &path,
+                futures::stream::once(async move { Ok(data) }),
+                Some(len),
+            )
+            .await
+            .unwrap();
+
+        // repairing load ends up at the last good transaction
+        let (catalog, state, repair) = PreservedCatalog::load_with_repair::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let repair = repair.unwrap();
+        assert_eq!(repair.corrupt_revision, 2);
+        assert_eq!(repair.corrupt_path, Some(path.clone()));
+        assert_eq!(
+            repair.error.to_string(),
+            "Error during deserialization: failed to decode Protobuf message: invalid wire type value: 6"
+        );
+        assert_eq!(repair.last_good_revision, 1);
+        assert_eq!(repair.deleted, vec![path]);
+        assert_eq!(catalog.revision_counter(), 1);
+        assert_catalog_parquet_files(&state, &get_catalog_parquet_files(&trace.states[1]));
+
+        // catalog is usable again
+        let t = catalog.open_transaction().await;
+        assert_eq!(t.revision_counter(), 2);
+        t.commit().await.unwrap();
+        drop(catalog);
+
+        let (catalog, state) = PreservedCatalog::load::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(catalog.revision_counter(), 2);
+        assert_catalog_parquet_files(&state, &get_catalog_parquet_files(&trace.states[1]));
+
+        // repairing an intact catalog does nothing
+        let (_catalog, _state, repair) = PreservedCatalog::load_with_repair::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(repair.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repair_needs_good_transaction() {
+        let object_store = make_object_store();
+        let server_id = make_server_id();
+        let db_name = "db1";
+        let trace = assert_single_catalog_inmem_works(&object_store, server_id, db_name).await;
+
+        // break first transaction file
+        let path = file_path(
+            &object_store,
+            server_id,
+            db_name,
+            &trace.tkeys[0],
+            FileType::Transaction,
+        );
+        checked_delete(&object_store, &path).await;
+
+        // nothing to fall back to
+        let res = PreservedCatalog::load_with_repair::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await;
+        assert_eq!(res.unwrap_err().to_string(), "Missing transaction: 0");
+    }
+
+    #[tokio::test]
+    async fn test_repair_reports_orphaned_files() {
+        let object_store = make_object_store();
+        let server_id = make_server_id();
+        let db_name = crate::test_utils::db_name();
+
+        let (catalog, _state) = PreservedCatalog::new_empty::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await
+        .unwrap();
+
+        // file from a good transaction
+        {
+            let mut t = catalog.open_transaction().await;
+            let (path, md) = make_metadata(&object_store, "foo", chunk_addr(1)).await;
+            t.add_parquet(&path.into(), &md).unwrap();
+            t.commit().await.unwrap();
+        }
+
+        // file from a transaction that will be dropped by the repair
+        let orphaned = {
+            let mut t = catalog.open_transaction().await;
+            let (path, md) = make_metadata(&object_store, "foo", chunk_addr(2)).await;
+            t.add_parquet(&path.clone().into(), &md).unwrap();
+            t.commit().await.unwrap();
+            path
+        };
+        break_catalog_with_weird_version(&catalog).await;
+        drop(catalog);
+
+        let (catalog, _state, repair) = PreservedCatalog::load_with_repair::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let repair = repair.unwrap();
+        assert_eq!(repair.corrupt_revision, 2);
+        assert_eq!(repair.last_good_revision, 1);
+        assert_eq!(repair.orphaned_files, vec![orphaned]);
+        assert_eq!(catalog.revision_counter(), 1);
+    }
+
+    #[test]
+    fn test_relocate_transaction() {
+        let path = |file_name: &str| proto::Path {
+            directories: vec!["1".to_string(), "db1".to_string(), "data".to_string()],
+            file_name: file_name.to_string(),
+        };
+        let transaction = proto::Transaction {
+            actions: vec![
+                proto::transaction::Action {
+                    action: Some(proto::transaction::action::Action::AddParquet(
+                        proto::AddParquet {
+                            path: Some(path("added.parquet")),
+                            metadata: vec![],
+                        },
+                    )),
+                },
+                proto::transaction::Action {
+                    action: Some(proto::transaction::action::Action::RemoveParquet(
+                        proto::RemoveParquet {
+                            path: Some(path("removed.parquet")),
+                        },
+                    )),
+                },
+            ],
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        transaction.encode(&mut data).unwrap();
+
+        let server_id = ServerId::new(NonZeroU32::new(2).unwrap());
+        let data = relocate_transaction(&data, server_id).unwrap();
+        let relocated = proto::Transaction::decode(&data[..]).unwrap();
+
+        let paths: Vec<_> = relocated
+            .actions
+            .iter()
+            .map(|action| match action.action.as_ref().unwrap() {
+                proto::transaction::action::Action::AddParquet(a) => a.path.clone().unwrap(),
+                proto::transaction::action::Action::RemoveParquet(a) => a.path.clone().unwrap(),
+                _ => panic!("unexpected action"),
+            })
+            .collect();
+        assert_eq!(paths.len(), 2);
+        for (path, file_name) in paths.iter().zip(&["added.parquet", "removed.parquet"]) {
+            assert_eq!(path.directories, vec!["2", "db1", "data"]);
+            assert_eq!(&path.file_name, file_name);
+        }
+
+        // anything but a transaction is rejected
+        assert!(matches!(
+            relocate_transaction(b"not a transaction", server_id),
+            Err(Error::Deserialization { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_transaction_handle_debug() {
         let object_store = make_object_store();
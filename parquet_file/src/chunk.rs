@@ -232,6 +232,10 @@ impl ParquetChunk {
                     }
                 })
                 .collect(),
+            Selection::Except(cols) => fields
+                .filter(|x| !cols.contains(&x.name().as_str()))
+                .map(|x| x.name().clone())
+                .collect(),
             Selection::All => fields.map(|x| x.name().clone()).collect(),
         })
     }
@@ -251,6 +251,11 @@ impl Storage {
                     }
                 })
                 .collect(),
+            Selection::Except(cols) => fields
+                .enumerate()
+                .filter(|(_, x)| !cols.contains(&x.name().as_str()))
+                .map(|(p, _)| p)
+                .collect(),
             Selection::All => fields.enumerate().map(|(p, _)| p).collect(),
         }
     }
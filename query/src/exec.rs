@@ -12,6 +12,7 @@ pub mod stringset;
 mod task;
 pub use context::{DEFAULT_CATALOG, DEFAULT_SCHEMA};
 use futures::{future, Future};
+pub use task::DEFAULT_WORKER_PRIORITY;
 
 use std::sync::Arc;
 
@@ -109,11 +110,24 @@ impl Executor {
     /// Creates a new executor with a single dedicated thread pool with
     /// num_threads
     pub fn new(num_threads: usize) -> Self {
-        let exec = DedicatedExecutor::new("IOx Executor Thread", num_threads);
+        Self::new_with_worker_priority(num_threads, DEFAULT_WORKER_PRIORITY)
+    }
+
+    /// Creates a new executor with a single dedicated thread pool with
+    /// num_threads, whose threads run with the specified `priority`
+    /// (value passed to `libc::setpriority`, higher is less favorable)
+    pub fn new_with_worker_priority(num_threads: usize, priority: i32) -> Self {
+        let exec =
+            DedicatedExecutor::new_with_priority("IOx Executor Thread", num_threads, priority);
 
         Self { exec }
     }
 
+    /// Returns the number of threads in the dedicated thread pool
+    pub fn num_threads(&self) -> usize {
+        self.exec.num_threads()
+    }
+
     /// Executes this plan and returns the resulting set of strings
     pub async fn to_string_set(&self, plan: StringSetPlan) -> Result<StringSetRef> {
         match plan {
@@ -248,6 +262,20 @@ impl Executor {
             .context(DataFusionExecution)
     }
 
+    /// Like [`collect`](Self::collect), but stops executing the plan as soon
+    /// as it produced more than `max_rows` rows, returning `None` in that
+    /// case.
+    pub async fn collect_with_row_limit(
+        &self,
+        physical_plan: Arc<dyn ExecutionPlan>,
+        max_rows: usize,
+    ) -> Result<Option<Vec<RecordBatch>>> {
+        self.new_context()
+            .collect_with_row_limit(physical_plan, max_rows)
+            .await
+            .context(DataFusionExecution)
+    }
+
     /// Create a new execution context, suitable for executing a new query
     pub fn new_context(&self) -> IOxExecutionContext {
         IOxExecutionContext::new(self.exec.clone())
@@ -520,6 +548,34 @@ mod tests {
         assert_eq!(results, to_set(&["f1", "f2"]));
     }
 
+    #[tokio::test]
+    async fn collect_with_row_limit() {
+        let batch = RecordBatch::try_from_iter_with_nullable(vec![(
+            "f1",
+            to_string_array(&["foo", "bar"]),
+            true,
+        )])
+        .expect("created new record batch");
+        let scan = make_plan(batch.schema(), vec![batch.clone(), batch]);
+
+        let executor = Executor::new(1);
+        let ctx = executor.new_context();
+        let physical_plan = ctx.prepare_plan(&scan).expect("planned");
+
+        let batches = executor
+            .collect_with_row_limit(Arc::clone(&physical_plan), 4)
+            .await
+            .expect("Executed plan")
+            .expect("within limit");
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 4);
+
+        let batches = executor
+            .collect_with_row_limit(physical_plan, 3)
+            .await
+            .expect("Executed plan");
+        assert!(batches.is_none());
+    }
+
     /// return a set for testing
     fn to_set(strs: &[&str]) -> StringSetRef {
         StringSetRef::new(strs.iter().map(|s| s.to_string()).collect::<StringSet>())
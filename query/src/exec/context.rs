@@ -15,6 +15,7 @@ use datafusion::{
     },
     prelude::*,
 };
+use futures::StreamExt;
 
 use crate::exec::{
     schema_pivot::{SchemaPivotExec, SchemaPivotNode},
@@ -189,6 +190,45 @@ impl IOxExecutionContext {
         })?
     }
 
+    /// Like [`collect`](Self::collect), but stops executing the plan as soon
+    /// as it produced more than `max_rows` rows, returning `None` in that
+    /// case.
+    pub async fn collect_with_row_limit(
+        &self,
+        physical_plan: Arc<dyn ExecutionPlan>,
+        max_rows: usize,
+    ) -> Result<Option<Vec<RecordBatch>>> {
+        debug!(
+            "Running plan with row limit {}, physical:\n{}",
+            max_rows,
+            displayable(physical_plan.as_ref()).indent()
+        );
+
+        self.exec
+            .spawn(async move {
+                let mut stream = execute_merged(physical_plan).await?;
+
+                let mut rows = 0;
+                let mut batches = vec![];
+                while let Some(batch) = stream.next().await {
+                    let batch = batch?;
+                    rows += batch.num_rows();
+                    if rows > max_rows {
+                        return Ok(None);
+                    }
+                    batches.push(batch);
+                }
+                Ok::<_, Error>(Some(batches))
+            })
+            .await
+            .map_err(|e| {
+                Error::Execution(format!(
+                    "Error running IOxExecutionContext::collect_with_row_limit: {}",
+                    e
+                ))
+            })?
+    }
+
     /// Executes the physical plan and produces a RecordBatchStream to stream
     /// over the result that iterates over the results.
     pub async fn execute(
@@ -196,20 +236,25 @@ impl IOxExecutionContext {
         physical_plan: Arc<dyn ExecutionPlan>,
     ) -> Result<SendableRecordBatchStream> {
         self.exec
-            .spawn(async move {
-                if physical_plan.output_partitioning().partition_count() <= 1 {
-                    physical_plan.execute(0).await
-                } else {
-                    // merge into a single partition
-                    let plan = MergeExec::new(physical_plan);
-                    // MergeExec must produce a single partition
-                    assert_eq!(1, plan.output_partitioning().partition_count());
-                    plan.execute(0).await
-                }
-            })
+            .spawn(execute_merged(physical_plan))
             .await
             .map_err(|e| {
                 Error::Execution(format!("Error running IOxExecutionContext::execute: {}", e))
             })?
     }
 }
+
+/// Executes the physical plan, merging its output into a single stream
+async fn execute_merged(
+    physical_plan: Arc<dyn ExecutionPlan>,
+) -> Result<SendableRecordBatchStream> {
+    if physical_plan.output_partitioning().partition_count() <= 1 {
+        physical_plan.execute(0).await
+    } else {
+        // merge into a single partition
+        let plan = MergeExec::new(physical_plan);
+        // MergeExec must produce a single partition
+        assert_eq!(1, plan.output_partitioning().partition_count());
+        plan.execute(0).await
+    }
+}
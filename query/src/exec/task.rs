@@ -21,6 +21,9 @@ pub type Error = tokio::sync::oneshot::error::RecvError;
 #[derive(Clone)]
 pub struct DedicatedExecutor {
     state: Arc<Mutex<State>>,
+
+    /// The number of worker threads of the dedicated tokio executor
+    num_threads: usize,
 }
 
 /// Runs futures (and any `tasks` that are `tokio::task::spawned` by
@@ -35,7 +38,7 @@ struct State {
 }
 
 /// The default worker priority (value passed to `libc::setpriority`);
-const WORKER_PRIORITY: i32 = 10;
+pub const DEFAULT_WORKER_PRIORITY: i32 = 10;
 
 impl std::fmt::Debug for DedicatedExecutor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -62,6 +65,13 @@ impl DedicatedExecutor {
     /// happens when a runtime is dropped from within an asynchronous
     /// context.', .../tokio-1.4.0/src/runtime/blocking/shutdown.rs:51:21
     pub fn new(thread_name: &str, num_threads: usize) -> Self {
+        Self::new_with_priority(thread_name, num_threads, DEFAULT_WORKER_PRIORITY)
+    }
+
+    /// Creates a new `DedicatedExecutor` like [`new`](Self::new) but with
+    /// the worker thread priority set to `priority` (value passed to
+    /// `libc::setpriority`) rather than [`DEFAULT_WORKER_PRIORITY`].
+    pub fn new_with_priority(thread_name: &str, num_threads: usize, priority: i32) -> Self {
         let thread_name = thread_name.to_string();
 
         let (tx, rx) = std::sync::mpsc::channel::<Task>();
@@ -71,7 +81,7 @@ impl DedicatedExecutor {
                 .enable_all()
                 .thread_name(&thread_name)
                 .worker_threads(num_threads)
-                .on_thread_start(move || set_current_thread_priority(WORKER_PRIORITY))
+                .on_thread_start(move || set_current_thread_priority(priority))
                 .build()
                 .expect("Creating tokio runtime");
 
@@ -98,9 +108,15 @@ impl DedicatedExecutor {
 
         Self {
             state: Arc::new(Mutex::new(state)),
+            num_threads,
         }
     }
 
+    /// Returns the number of worker threads of this executor
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
     /// Runs the specified Future (and any tasks it spawns) on the
     /// `DedicatedExecutor`.
     ///
@@ -190,7 +206,7 @@ mod tests {
 
     #[cfg(not(unix))]
     fn get_current_thread_priority() -> i32 {
-        WORKER_PRIORITY
+        DEFAULT_WORKER_PRIORITY
     }
 
     #[tokio::test]
@@ -245,7 +261,18 @@ mod tests {
 
         let dedicated_task = exec.spawn(async move { get_current_thread_priority() });
 
-        assert_eq!(dedicated_task.await.unwrap(), WORKER_PRIORITY);
+        assert_eq!(dedicated_task.await.unwrap(), DEFAULT_WORKER_PRIORITY);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn custom_worker_priority() {
+        let exec = DedicatedExecutor::new_with_priority("Test DedicatedExecutor", 3, 15);
+        assert_eq!(exec.num_threads(), 3);
+
+        let dedicated_task = exec.spawn(async move { get_current_thread_priority() });
+
+        assert_eq!(dedicated_task.await.unwrap(), 15);
     }
 
     #[tokio::test]
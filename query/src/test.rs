@@ -792,6 +792,12 @@ impl QueryChunk for TestChunk {
         let column_names = match selection {
             Selection::All => self.all_column_names(),
             Selection::Some(cols) => self.specific_column_names_selection(cols),
+            Selection::Except(cols) => self.all_column_names().map(|names| {
+                names
+                    .into_iter()
+                    .filter(|col| !cols.contains(&col.as_str()))
+                    .collect()
+            }),
         };
 
         Ok(column_names)
@@ -19,7 +19,7 @@ pub fn read_filter(c: &mut Criterion) {
 
     let mut chunk = RBChunk::new("table", read_buffer::ChunkMetrics::new_unregistered());
     let row_group = generate_row_group(200_000, &mut rng);
-    read_buffer::benchmarks::upsert_table_with_row_group(&mut chunk, "table", row_group);
+    chunk.upsert_row_group(row_group);
 
     read_filter_no_pred_vary_proj(c, &chunk);
     read_filter_with_pred_vary_proj(c, &chunk);
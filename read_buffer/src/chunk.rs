@@ -1,14 +1,25 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
-    convert::TryFrom,
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet},
+    convert::{TryFrom, TryInto},
+    hash::{Hash, Hasher},
 };
 
+use hashbrown::HashMap;
 use metrics::{Gauge, GaugeValue, KeyValue};
-use snafu::{ResultExt, Snafu};
+use parking_lot::Mutex;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 
-use arrow::record_batch::RecordBatch;
+use arrow::{
+    datatypes::DataType,
+    ipc::{reader::StreamReader, writer::StreamWriter},
+    record_batch::RecordBatch,
+};
 use data_types::{chunk_metadata::ChunkColumnSummary, partition_metadata::TableSummary};
-use internal_types::{schema::builder::Error as SchemaError, schema::Schema, selection::Selection};
+use internal_types::{
+    schema::builder::Error as SchemaError,
+    schema::{InfluxColumnType, Schema, TIME_COLUMN_NAME},
+    selection::Selection,
+};
 use observability_deps::tracing::info;
 
 use crate::row_group::{ColumnName, Predicate};
@@ -31,33 +42,177 @@ pub enum Error {
     #[snafu(display("table '{}' does not exist", table_name))]
     TableNotFound { table_name: String },
 
+    #[snafu(display(
+        "cannot merge chunk for table '{}' into chunk for table '{}'",
+        actual,
+        expected
+    ))]
+    TableNameMismatch { expected: String, actual: String },
+
+    #[snafu(display("cannot merge chunks of table '{}' with different schemas", table_name))]
+    SchemaMismatch { table_name: String },
+
     #[snafu(display("column '{}' does not exist in table '{}'", column_name, table_name))]
     ColumnDoesNotExist {
         column_name: String,
         table_name: String,
     },
+
+    #[snafu(display("invalid serialized chunk: {}", msg))]
+    InvalidChunkBytes { msg: String },
+
+    #[snafu(display("error decoding serialized row group: {}", source))]
+    RowGroupDecodeError { source: arrow::error::ArrowError },
+
+    #[snafu(display("error converting row group to a record batch: {}", source))]
+    RowGroupConversionError { source: crate::row_group::Error },
+
+    #[snafu(display("error encoding serialized row group: {}", source))]
+    RowGroupEncodeError { source: arrow::error::ArrowError },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// The maximum number of predicates whose results are memoized by each of a
+/// chunk's predicate caches.
+const PREDICATE_CACHE_CAPACITY: usize = 64;
+
+/// Identifies, and versions, the format written by `Chunk::to_bytes`.
+const SERIALIZED_CHUNK_MAGIC: &[u8; 8] = b"IOXRBC01";
+
 /// A `Chunk` is a horizontal partition of data for a single table.
 pub struct Chunk {
-    // All metrics for the chunk.
-    metrics: ChunkMetrics,
+    // All metrics for the chunk. Behind a lock so that rows can be deleted
+    // from a shared chunk.
+    metrics: Mutex<ChunkMetrics>,
 
     // The table associated with the chunk.
     pub(crate) table: Table,
+
+    // Memoized results of `could_pass_predicate` and `satisfies_predicate`.
+    // Both are cleared whenever the data in the chunk changes.
+    could_pass_cache: Mutex<PredicateCache>,
+    satisfies_cache: Mutex<PredicateCache>,
 }
 
 impl Chunk {
     /// Initialises a new `Chunk` with the associated chunk ID.
     pub fn new(table_name: impl Into<String>, metrics: ChunkMetrics) -> Self {
         Self {
-            metrics,
+            metrics: Mutex::new(metrics),
             table: Table::new(table_name.into()),
+            could_pass_cache: Default::default(),
+            satisfies_cache: Default::default(),
         }
     }
 
+    /// Serializes the data of the chunk so that an equivalent chunk can be
+    /// recreated with [`Chunk::from_bytes`], e.g., in another process.
+    ///
+    /// Each row group is stored as an Arrow IPC stream, which preserves
+    /// NULLs, along with the encodings of its string columns. The encodings
+    /// of other columns are determined by their data, and so are chosen
+    /// identically when the row group is recreated.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let row_groups = self.table.row_groups_snapshot();
+
+        let mut bytes = SERIALIZED_CHUNK_MAGIC.to_vec();
+        bytes.extend_from_slice(&(row_groups.len() as u32).to_le_bytes());
+
+        for row_group in row_groups {
+            let dictionary_columns = row_group.dictionary_encoded_columns();
+            bytes.extend_from_slice(&(dictionary_columns.len() as u32).to_le_bytes());
+            for name in dictionary_columns {
+                bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+                bytes.extend_from_slice(name.as_bytes());
+            }
+
+            let rb = row_group
+                .to_record_batch()
+                .context(RowGroupConversionError)?;
+            let mut ipc = vec![];
+            let mut writer =
+                StreamWriter::try_new(&mut ipc, &rb.schema()).context(RowGroupEncodeError)?;
+            writer.write(&rb).context(RowGroupEncodeError)?;
+            writer.finish().context(RowGroupEncodeError)?;
+            std::mem::drop(writer);
+
+            bytes.extend_from_slice(&(ipc.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&ipc);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Recreates a chunk for `table_name` from data serialized by
+    /// [`Chunk::to_bytes`].
+    ///
+    /// The schema of each row group is validated before it is decoded, so
+    /// corrupt or foreign data results in an error rather than a panic.
+    pub fn from_bytes(
+        table_name: impl Into<String>,
+        bytes: &[u8],
+        metrics: ChunkMetrics,
+    ) -> Result<Self> {
+        let mut chunk = Self::new(table_name, metrics);
+        let mut bytes = SerializedChunkReader(bytes);
+
+        ensure!(
+            bytes.take(SERIALIZED_CHUNK_MAGIC.len())? == SERIALIZED_CHUNK_MAGIC,
+            InvalidChunkBytes {
+                msg: "unknown format"
+            }
+        );
+
+        let row_groups = bytes.u32()?;
+        for _ in 0..row_groups {
+            let mut dictionary_columns = BTreeSet::new();
+            for _ in 0..bytes.u32()? {
+                let len = bytes.u32()? as usize;
+                let name = std::str::from_utf8(bytes.take(len)?).map_err(|e| {
+                    Error::InvalidChunkBytes {
+                        msg: format!("invalid column name: {}", e),
+                    }
+                })?;
+                dictionary_columns.insert(name.to_string());
+            }
+
+            let len = bytes.u64()? as usize;
+            let rb = StreamReader::try_new(bytes.take(len)?)
+                .context(RowGroupDecodeError)?
+                .next()
+                .context(InvalidChunkBytes {
+                    msg: "missing row group data",
+                })?
+                .context(RowGroupDecodeError)?;
+
+            validate_serialized_row_group(&rb, &dictionary_columns)?;
+            let row_group = RowGroup::from_record_batch_with_encodings(rb, &dictionary_columns);
+            ensure!(
+                chunk.table.can_add_row_group(&row_group),
+                InvalidChunkBytes {
+                    msg: "row groups have different schemas"
+                }
+            );
+            chunk.upsert_row_group(row_group);
+        }
+
+        ensure!(
+            bytes.0.is_empty(),
+            InvalidChunkBytes {
+                msg: "trailing data"
+            }
+        );
+
+        Ok(chunk)
+    }
+
+    // Invalidates all memoized predicate results.
+    fn clear_predicate_caches(&self) {
+        self.could_pass_cache.lock().clear();
+        self.satisfies_cache.lock().clear();
+    }
+
     // The total size taken up by an empty instance of `Chunk`.
     fn base_size() -> usize {
         std::mem::size_of::<Self>()
@@ -75,6 +230,26 @@ impl Chunk {
         self.table.column_sizes()
     }
 
+    /// Returns the min/max, NULL count, approximate distinct count and
+    /// encodings of the selected columns, without scanning column data.
+    pub fn column_statistics(&self, columns: Selection<'_>) -> Vec<table::ColumnStats> {
+        self.table.column_statistics(&columns)
+    }
+
+    /// Returns an approximate count of the distinct non-NULL values in the
+    /// column without materialising them. This is nearly free for dictionary
+    /// and RLE encoded columns, which track their cardinality.
+    ///
+    /// An error is returned if the column does not exist.
+    pub fn column_cardinality_estimate(&self, column_name: &str) -> Result<u64> {
+        self.table
+            .column_cardinality_estimate(column_name)
+            .context(ColumnDoesNotExist {
+                column_name,
+                table_name: self.table.name(),
+            })
+    }
+
     /// The total estimated size in bytes of this `Chunk` and all contained
     /// data if the data was not compressed but was stored contiguously in
     /// vectors. `include_nulls` allows the caller to factor in NULL values or
@@ -88,32 +263,94 @@ impl Chunk {
         self.table.rows()
     }
 
+    /// Determines if this chunk contains no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows() == 0
+    }
+
     /// The total number of row groups in all tables in this chunk.
     pub(crate) fn row_groups(&self) -> usize {
         self.table.row_groups()
     }
 
     /// Add a row_group to a table in the chunk, updating all Chunk meta data.
-    pub(crate) fn upsert_table_with_row_group(&mut self, row_group: RowGroup) {
+    ///
+    /// This is cheap compared to creating the row group, see
+    /// [`Chunk::compress`].
+    pub fn upsert_row_group(&mut self, row_group: RowGroup) {
         // track new row group statistics to update column-based metrics.
         let storage_statistics = row_group.column_storage_statistics();
 
         self.table.add_row_group(row_group);
+        self.clear_predicate_caches();
 
         // Get and set new size of chunk on memory tracker
         let size = Self::base_size() + self.table.size();
-        self.metrics.memory_bytes.set(size);
+        let metrics = self.metrics.get_mut();
+        metrics.memory_bytes.set(size);
 
         // update column metrics associated with column storage
-        self.metrics
-            .update_column_storage_statistics(&storage_statistics);
+        metrics.update_column_storage_statistics(&storage_statistics);
+    }
+
+    /// Moves all data from `other`, which must be a chunk for the same table
+    /// with the same schema, into this chunk.
+    pub fn merge_with(&mut self, other: Self) -> Result<()> {
+        ensure!(
+            self.table.name() == other.table.name(),
+            TableNameMismatch {
+                expected: self.table.name(),
+                actual: other.table.name(),
+            }
+        );
+        ensure!(
+            self.table.has_same_schema(&other.table),
+            SchemaMismatch {
+                table_name: self.table.name(),
+            }
+        );
+
+        // the column storage moves from the other chunk's metrics to these.
+        let storage_statistics = other.table.column_storage_statistics();
+        let Self {
+            metrics: other_metrics,
+            table: other_table,
+            ..
+        } = other;
+        other_metrics
+            .into_inner()
+            .remove_column_storage_statistics(&storage_statistics);
+
+        self.table.absorb(other_table);
+        self.clear_predicate_caches();
+
+        // Get and set new size of chunk on memory tracker
+        let size = Self::base_size() + self.table.size();
+        let metrics = self.metrics.get_mut();
+        metrics.memory_bytes.set(size);
+
+        metrics.update_column_storage_statistics(&storage_statistics);
+
+        Ok(())
     }
 
     /// Add a record batch of data to to a `Table` in the chunk.
     ///
-    /// The data is converted to a `RowGroup` outside of any locking so the
-    /// caller does not need to be concerned about the size of the update.
+    /// This compresses the data with [`Chunk::compress`] before adding it, so
+    /// the chunk is held exclusively for the whole conversion. Callers that
+    /// ingest many record batches can compress them concurrently and add the
+    /// results with [`Chunk::upsert_row_group`] instead.
     pub fn upsert_table(&mut self, table_name: &str, table_data: RecordBatch) {
+        let row_group = Self::compress(table_name, table_data);
+        self.upsert_row_group(row_group)
+    }
+
+    /// Converts a record batch of data for the named table into a `RowGroup`
+    /// that can be added to a chunk with [`Chunk::upsert_row_group`].
+    ///
+    /// This call is expensive but doesn't need access to any chunk, so it can
+    /// be done outside of any locking and in parallel.
+    pub fn compress(table_name: &str, table_data: RecordBatch) -> RowGroup {
         // TEMPORARY: print record batch information
         for (column, field) in table_data
             .columns()
@@ -140,7 +377,6 @@ impl Chunk {
             .sum::<usize>();
         let columns = table_data.num_columns();
 
-        // This call is expensive. Complete it before locking.
         let now = std::time::Instant::now();
         let row_group = RowGroup::from(table_data);
         let compressing_took = now.elapsed();
@@ -158,11 +394,37 @@ impl Chunk {
             "{:.2}%",
             (1.0 - (rg_size as f64 / raw_size_null as f64)) * 100.0
         );
-        let table_name = self.table.name();
 
-        info!(%rows, %columns, rg_size, mub_rb_size, %mub_rb_comp, raw_size_null, raw_size_no_null, %raw_rb_comp, ?table_name, ?compressing_took, "row group added");
+        info!(%rows, %columns, rg_size, mub_rb_size, %mub_rb_comp, raw_size_null, raw_size_no_null, %raw_rb_comp, ?table_name, ?compressing_took, "row group compressed");
+
+        row_group
+    }
+
+    /// Deletes all rows that satisfy the predicate, returning the number of
+    /// rows deleted.
+    ///
+    /// Deleted rows no longer appear in the results of any query on the
+    /// chunk, and the chunk's metrics are updated to reflect the remaining
+    /// data. Queries already running against the chunk are unaffected.
+    pub fn delete(&self, predicate: &Predicate) -> Result<u64> {
+        let storage_statistics = self.table.column_storage_statistics();
+
+        let deleted = self.table.delete(predicate).context(TableError)?;
+        if deleted == 0 {
+            return Ok(0);
+        }
+        self.clear_predicate_caches();
+
+        // Get and set new size of chunk on memory tracker
+        let size = Self::base_size() + self.table.size();
+        let mut metrics = self.metrics.lock();
+        metrics.memory_bytes.set(size);
+
+        // replace column metrics associated with the removed column storage
+        metrics.remove_column_storage_statistics(&storage_statistics);
+        metrics.update_column_storage_statistics(&self.table.column_storage_statistics());
 
-        self.upsert_table_with_row_group(row_group)
+        Ok(deleted)
     }
 
     //
@@ -193,6 +455,9 @@ impl Chunk {
     /// columns, optionally filtered by the provided predicate. Results are
     /// merged across all row groups.
     ///
+    /// Ungrouped `Min`, `Max` and `Count` aggregates without a predicate are
+    /// answered from column statistics rather than by scanning row groups.
+    ///
     /// Note: `read_aggregate` currently only supports grouping on "tag"
     /// columns.
     pub(crate) fn read_aggregate(
@@ -210,13 +475,26 @@ impl Chunk {
     // ---- Schema queries
     //
 
-    /// Determines if one of more rows in the provided table could possibly
+    /// Determines if one of more rows in the chunk's table could possibly
     /// match the provided predicate.
     ///
-    /// If the provided table does not exist then `could_pass_predicate` returns
-    /// `false`.
+    /// If the chunk's table contains no rows then `could_pass_predicate`
+    /// returns `false` without evaluating the predicate.
+    ///
+    /// Results are memoized until the data in the chunk changes.
     pub fn could_pass_predicate(&self, predicate: Predicate) -> bool {
-        self.table.could_pass_predicate(&predicate)
+        if self.is_empty() {
+            return false;
+        }
+
+        let key = PredicateCache::key(&predicate);
+        if let Some(result) = self.could_pass_cache.lock().get(key, &predicate) {
+            return result;
+        }
+
+        let result = self.table.could_pass_predicate(&predicate);
+        self.could_pass_cache.lock().insert(key, predicate, result);
+        result
     }
 
     /// Return table summaries or all tables in this chunk.
@@ -228,9 +506,19 @@ impl Chunk {
         vec![self.table.table_summary()]
     }
 
+    /// Returns the complete schema of the chunk's table, including all
+    /// columns and their Influx column types.
+    pub fn schema(&self) -> Schema {
+        Schema::try_from(&ResultSchema {
+            select_columns: self.table.meta().schema_for_selection(&Selection::All),
+            ..ResultSchema::default()
+        })
+        .expect("table metadata describes a valid schema")
+    }
+
     /// Returns a schema object for a `read_filter` operation using the provided
     /// column selection. An error is returned if the specified columns do not
-    /// exist.
+    /// exist, unless they are columns excluded via `Selection::Except`.
     pub fn read_filter_table_schema(&self, columns: Selection<'_>) -> Result<Schema> {
         // Validate columns exist in table.
         let table_meta = self.table.meta();
@@ -248,10 +536,7 @@ impl Chunk {
 
         // Build a table schema
         Schema::try_from(&ResultSchema {
-            select_columns: match columns {
-                Selection::All => table_meta.schema_for_all_columns(),
-                Selection::Some(column_names) => table_meta.schema_for_column_names(column_names),
-            },
+            select_columns: table_meta.schema_for_selection(&columns),
             ..ResultSchema::default()
         })
         .context(TableSchemaError)
@@ -260,8 +545,25 @@ impl Chunk {
     /// Determines if at least one row in the Chunk satisfies the provided
     /// predicate. `satisfies_predicate` will return true if it is guaranteed
     /// that at least one row in the Chunk will satisfy the predicate.
+    ///
+    /// An empty Chunk never satisfies a predicate.
+    ///
+    /// Results are memoized until the data in the chunk changes.
     pub fn satisfies_predicate(&self, predicate: &Predicate) -> bool {
-        self.table.satisfies_predicate(predicate)
+        if self.is_empty() {
+            return false;
+        }
+
+        let key = PredicateCache::key(predicate);
+        if let Some(result) = self.satisfies_cache.lock().get(key, predicate) {
+            return result;
+        }
+
+        let result = self.table.satisfies_predicate(predicate);
+        self.satisfies_cache
+            .lock()
+            .insert(key, predicate.clone(), result);
+        result
     }
 
     /// Returns the distinct set of column names that contain data matching the
@@ -294,11 +596,17 @@ impl Chunk {
     /// wherein execution can be short-circuited for distinct values that have
     /// already been found. Callers can simply provide an empty `BTreeMap` to
     /// skip this behaviour.
+    ///
+    /// If `limit` is provided then no more values are collected for a column
+    /// once it has `limit` distinct values. The values returned are then an
+    /// arbitrary sample of the column's values, not the first `limit` values
+    /// in any particular order.
     pub fn column_values(
         &self,
         predicate: Predicate,
         columns: Selection<'_>,
         dst: BTreeMap<String, BTreeSet<String>>,
+        limit: Option<usize>,
     ) -> Result<BTreeMap<String, BTreeSet<String>>> {
         let columns = match columns {
             Selection::All => {
@@ -308,10 +616,16 @@ impl Chunk {
                 .fail();
             }
             Selection::Some(columns) => columns,
+            Selection::Except(_) => {
+                return UnsupportedOperation {
+                    msg: "column_values does not support Except columns".to_owned(),
+                }
+                .fail();
+            }
         };
 
         self.table
-            .column_values(&predicate, columns, dst)
+            .column_values(&predicate, columns, dst, limit)
             .context(TableError)
     }
 }
@@ -322,6 +636,138 @@ impl std::fmt::Debug for Chunk {
     }
 }
 
+// Ensures a row group decoded from serialized chunk data has a valid
+// time-series schema, with column types a `RowGroup` can be created from.
+fn validate_serialized_row_group(
+    rb: &RecordBatch,
+    dictionary_columns: &BTreeSet<String>,
+) -> Result<()> {
+    let schema = Schema::try_from(rb.schema()).map_err(|e| Error::InvalidChunkBytes {
+        msg: format!("invalid row group schema: {}", e),
+    })?;
+
+    let mut has_time_column = false;
+    for (influx_type, field) in schema.iter() {
+        match influx_type {
+            Some(InfluxColumnType::Timestamp) => {
+                ensure!(
+                    field.name() == TIME_COLUMN_NAME,
+                    InvalidChunkBytes {
+                        msg: format!("invalid timestamp column '{}'", field.name()),
+                    }
+                );
+                has_time_column = true;
+            }
+            Some(InfluxColumnType::Tag) | Some(InfluxColumnType::Field(_)) => {}
+            None => {
+                return InvalidChunkBytes {
+                    msg: format!("column '{}' has no column type", field.name()),
+                }
+                .fail()
+            }
+        }
+    }
+    ensure!(
+        has_time_column,
+        InvalidChunkBytes {
+            msg: "row group has no time column"
+        }
+    );
+
+    for name in dictionary_columns {
+        let is_string_column = schema
+            .find_index_of(name)
+            .map(|idx| match schema.field(idx).1.data_type() {
+                DataType::Utf8 => true,
+                DataType::Dictionary(_, value) => value.as_ref() == &DataType::Utf8,
+                _ => false,
+            })
+            .unwrap_or(false);
+        ensure!(
+            is_string_column,
+            InvalidChunkBytes {
+                msg: format!("dictionary encoding for unknown string column '{}'", name),
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Reads the values written by `Chunk::to_bytes`, failing if there aren't
+/// enough bytes left.
+struct SerializedChunkReader<'a>(&'a [u8]);
+
+impl<'a> SerializedChunkReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        ensure!(
+            n <= self.0.len(),
+            InvalidChunkBytes {
+                msg: "unexpected end of data"
+            }
+        );
+
+        let (taken, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+/// Memoized results of evaluating predicates against a chunk's statistics.
+///
+/// Entries are keyed by a hash of the predicate's expressions. The predicate
+/// itself is retained so that a hash collision is treated as a cache miss.
+#[derive(Debug, Default)]
+struct PredicateCache {
+    entries: HashMap<u64, (Predicate, bool)>,
+
+    // The number of lookups served from the cache.
+    #[cfg(test)]
+    hits: usize,
+}
+
+impl PredicateCache {
+    fn key(predicate: &Predicate) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        predicate.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn get(&mut self, key: u64, predicate: &Predicate) -> Option<bool> {
+        let result = self
+            .entries
+            .get(&key)
+            .filter(|(cached, _)| cached == predicate)
+            .map(|(_, result)| *result);
+
+        #[cfg(test)]
+        if result.is_some() {
+            self.hits += 1;
+        }
+
+        result
+    }
+
+    fn insert(&mut self, key: u64, predicate: Predicate, result: bool) {
+        if self.entries.len() >= PREDICATE_CACHE_CAPACITY {
+            self.entries.clear();
+        }
+        self.entries.insert(key, (predicate, result));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 #[derive(Debug)]
 pub struct ChunkMetrics {
     /// keep track of memory used by table data in chunk
@@ -386,6 +832,21 @@ impl ChunkMetrics {
 
     // Updates column storage statistics for the Read Buffer.
     fn update_column_storage_statistics(&mut self, statistics: &[Statistics]) {
+        self.apply_column_storage_statistics(statistics, Gauge::inc)
+    }
+
+    // Removes column storage statistics for the Read Buffer, for example when
+    // the rows they describe have been deleted.
+    fn remove_column_storage_statistics(&mut self, statistics: &[Statistics]) {
+        self.apply_column_storage_statistics(statistics, Gauge::decr)
+    }
+
+    // Applies `op` to every column storage metric using the statistics.
+    fn apply_column_storage_statistics(
+        &mut self,
+        statistics: &[Statistics],
+        op: fn(&mut Gauge, usize, &[KeyValue]),
+    ) {
         for stat in statistics {
             let labels = &[
                 KeyValue::new("encoding", stat.enc_type.clone()),
@@ -393,13 +854,14 @@ impl ChunkMetrics {
             ];
 
             // update number of columns
-            self.columns_total.inc(1, labels);
+            op(&mut self.columns_total, 1, labels);
 
             // update bytes associated with columns
-            self.column_bytes_total.inc(stat.bytes, labels);
+            op(&mut self.column_bytes_total, stat.bytes, labels);
 
             // update raw estimated bytes of NULL values
-            self.column_raw_bytes_total.inc(
+            op(
+                &mut self.column_raw_bytes_total,
                 stat.raw_bytes - stat.raw_bytes_no_null,
                 &[
                     KeyValue::new("encoding", stat.enc_type.clone()),
@@ -409,7 +871,8 @@ impl ChunkMetrics {
             );
 
             // update raw estimated bytes of non-NULL values
-            self.column_raw_bytes_total.inc(
+            op(
+                &mut self.column_raw_bytes_total,
                 stat.raw_bytes_no_null,
                 &[
                     KeyValue::new("encoding", stat.enc_type.clone()),
@@ -419,7 +882,8 @@ impl ChunkMetrics {
             );
 
             // update number of NULL values
-            self.column_values_total.inc(
+            op(
+                &mut self.column_values_total,
                 stat.nulls as usize,
                 &[
                     KeyValue::new("encoding", stat.enc_type.clone()),
@@ -429,7 +893,8 @@ impl ChunkMetrics {
             );
 
             // update number of non-NULL values
-            self.column_values_total.inc(
+            op(
+                &mut self.column_values_total,
                 (stat.values - stat.nulls) as usize,
                 &[
                     KeyValue::new("encoding", stat.enc_type.clone()),
@@ -625,6 +1090,21 @@ mod test {
         }
     }
 
+    #[test]
+    fn upsert_row_groups_compressed_concurrently() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| Chunk::compress("a_table", gen_recordbatch())))
+            .collect();
+        for handle in handles {
+            chunk.upsert_row_group(handle.join().unwrap());
+        }
+
+        assert_eq!(chunk.rows(), 12);
+        assert_eq!(chunk.row_groups(), 4);
+    }
+
     #[test]
     fn add_remove_tables() {
         let reg = metrics::TestMetricRegistry::new(Arc::new(metrics::MetricRegistry::new()));
@@ -786,6 +1266,291 @@ mod test {
         ));
     }
 
+    #[test]
+    fn schema() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+
+        let schema = chunk.schema();
+        assert_eq!(
+            schema,
+            chunk.read_filter_table_schema(Selection::All).unwrap()
+        );
+        assert_eq!(schema.len(), 5);
+    }
+
+    #[test]
+    fn column_statistics() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+        chunk.upsert_table("a_table", gen_recordbatch());
+
+        let stats = chunk.column_statistics(Selection::Some(&["region", "sketchy_sensor"]));
+        assert_eq!(stats.len(), 2);
+
+        let region = &stats[0];
+        assert_eq!(region.name, "region");
+        assert_eq!(region.influxdb_type, Some(InfluxDbType::Tag));
+        assert_eq!(region.stats.min_as_str().unwrap(), "east");
+        assert_eq!(region.stats.max_as_str().unwrap(), "west");
+        assert_eq!(region.null_count, 0);
+        assert_eq!(region.distinct_count, NonZeroU64::new(2));
+        assert!(!region.encodings.is_empty());
+
+        let sensor = &stats[1];
+        assert_eq!(sensor.name, "sketchy_sensor");
+        assert_eq!(sensor.influxdb_type, Some(InfluxDbType::Field));
+        assert_eq!(sensor.stats.count(), 6);
+        assert_eq!(sensor.null_count, 2);
+        assert!((sensor.null_fraction() - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(sensor.distinct_count, None);
+        assert!(!sensor.encodings.is_empty());
+
+        // all columns
+        assert_eq!(chunk.column_statistics(Selection::All).len(), 5);
+    }
+
+    #[test]
+    fn merge_with() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+
+        let mut other = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        other.upsert_table("a_table", gen_recordbatch());
+        other.upsert_table("a_table", gen_recordbatch());
+
+        chunk.merge_with(other).unwrap();
+        assert_eq!(chunk.rows(), 9);
+        assert_eq!(chunk.row_groups(), 3);
+
+        // chunks for other tables are rejected
+        let mut other = Chunk::new("b_table", ChunkMetrics::new_unregistered());
+        other.upsert_table("b_table", gen_recordbatch());
+        assert!(matches!(
+            chunk.merge_with(other),
+            Err(Error::TableNameMismatch { .. })
+        ));
+        assert_eq!(chunk.rows(), 9);
+    }
+
+    #[test]
+    fn to_from_bytes() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+        chunk.upsert_table("a_table", gen_recordbatch());
+
+        // a row group with a high cardinality tag, which is dictionary encoded
+        let rows = 2_000;
+        let schema = SchemaBuilder::new()
+            .non_null_tag("region")
+            .non_null_field("counter", Float64)
+            .non_null_field("active", Boolean)
+            .timestamp()
+            .field("sketchy_sensor", Float64)
+            .build()
+            .unwrap()
+            .into();
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(
+                (0..rows)
+                    .map(|i| format!("region-{}", i))
+                    .collect::<Vec<_>>()
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ),
+            Arc::new(Float64Array::from(
+                (0..rows).map(|i| i as f64).collect::<Vec<_>>(),
+            )),
+            Arc::new(BooleanArray::from(
+                (0..rows).map(|i| i % 3 == 0).collect::<Vec<_>>(),
+            )),
+            Arc::new(TimestampNanosecondArray::from_vec(
+                (0..rows as i64).collect(),
+                None,
+            )),
+            Arc::new(Float64Array::from(
+                (0..rows)
+                    .map(|i| if i % 2 == 0 { Some(i as f64) } else { None })
+                    .collect::<Vec<_>>(),
+            )),
+        ];
+        chunk.upsert_table("a_table", RecordBatch::try_new(schema, data).unwrap());
+        assert_eq!(chunk.row_groups(), 3);
+
+        let bytes = chunk.to_bytes().unwrap();
+        let decoded =
+            Chunk::from_bytes("a_table", &bytes, ChunkMetrics::new_unregistered()).unwrap();
+
+        assert_eq!(decoded.rows(), chunk.rows());
+        assert_eq!(decoded.row_groups(), chunk.row_groups());
+        assert_eq!(decoded.table_summaries(), chunk.table_summaries());
+
+        let encodings = |chunk: &Chunk| {
+            chunk
+                .table
+                .column_storage_statistics()
+                .into_iter()
+                .map(|stats| stats.enc_type)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(encodings(&decoded), encodings(&chunk));
+        assert!(encodings(&decoded).iter().any(|enc| enc == "DICT"));
+
+        let read_all = |chunk: &Chunk| {
+            let batches = chunk
+                .read_filter("a_table", Predicate::default(), Selection::All)
+                .collect::<Vec<_>>();
+            arrow::util::pretty::pretty_format_batches(&batches).unwrap()
+        };
+        assert_eq!(read_all(&decoded), read_all(&chunk));
+
+        // corrupted data is rejected
+        assert!(matches!(
+            Chunk::from_bytes(
+                "a_table",
+                &bytes[..bytes.len() - 1],
+                ChunkMetrics::new_unregistered()
+            ),
+            Err(Error::InvalidChunkBytes { .. })
+        ));
+        assert!(matches!(
+            Chunk::from_bytes("a_table", b"nonsense", ChunkMetrics::new_unregistered()),
+            Err(Error::InvalidChunkBytes { .. })
+        ));
+    }
+
+    #[test]
+    fn from_bytes_invalid_row_groups() {
+        // serializes record batches as row groups without dictionary encoded
+        // columns.
+        let serialize = |batches: Vec<RecordBatch>| {
+            let mut bytes = SERIALIZED_CHUNK_MAGIC.to_vec();
+            bytes.extend_from_slice(&(batches.len() as u32).to_le_bytes());
+            for rb in batches {
+                bytes.extend_from_slice(&0_u32.to_le_bytes());
+
+                let mut ipc = vec![];
+                let mut writer = StreamWriter::try_new(&mut ipc, &rb.schema()).unwrap();
+                writer.write(&rb).unwrap();
+                writer.finish().unwrap();
+                std::mem::drop(writer);
+
+                bytes.extend_from_slice(&(ipc.len() as u64).to_le_bytes());
+                bytes.extend_from_slice(&ipc);
+            }
+            bytes
+        };
+        let from_bytes =
+            |bytes: &[u8]| Chunk::from_bytes("a_table", bytes, ChunkMetrics::new_unregistered());
+
+        // columns without an InfluxDB column type
+        let schema = Arc::new(arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new("time", Int64, false),
+            arrow::datatypes::Field::new("value", arrow::datatypes::DataType::Int32, false),
+        ]));
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![1, 2])),
+            Arc::new(arrow::array::Int32Array::from(vec![1, 2])),
+        ];
+        let rb = RecordBatch::try_new(schema, data).unwrap();
+        assert!(matches!(
+            from_bytes(&serialize(vec![rb])),
+            Err(Error::InvalidChunkBytes { .. })
+        ));
+
+        // no time column
+        let schema = SchemaBuilder::new()
+            .non_null_tag("region")
+            .build()
+            .unwrap()
+            .into();
+        let data: Vec<ArrayRef> = vec![Arc::new(StringArray::from(vec!["west", "east"]))];
+        let rb = RecordBatch::try_new(schema, data).unwrap();
+        assert!(matches!(
+            from_bytes(&serialize(vec![rb])),
+            Err(Error::InvalidChunkBytes { .. })
+        ));
+
+        // row groups with different schemas
+        let schema = SchemaBuilder::new()
+            .non_null_tag("region")
+            .timestamp()
+            .build()
+            .unwrap()
+            .into();
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["west", "east"])),
+            Arc::new(TimestampNanosecondArray::from_vec(vec![1, 2], None)),
+        ];
+        let rb = RecordBatch::try_new(schema, data).unwrap();
+        assert!(matches!(
+            from_bytes(&serialize(vec![gen_recordbatch(), rb])),
+            Err(Error::InvalidChunkBytes { .. })
+        ));
+
+        // dictionary encoding of a column that isn't a string column
+        let mut bytes = serialize(vec![gen_recordbatch()]);
+        let name = b"counter";
+        let mut encodings = 1_u32.to_le_bytes().to_vec();
+        encodings.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        encodings.extend_from_slice(name);
+        let offset = SERIALIZED_CHUNK_MAGIC.len() + 4;
+        bytes.splice(offset..offset + 4, encodings);
+        assert!(matches!(
+            from_bytes(&bytes),
+            Err(Error::InvalidChunkBytes { .. })
+        ));
+
+        // valid row groups are accepted
+        let chunk = from_bytes(&serialize(vec![gen_recordbatch(), gen_recordbatch()])).unwrap();
+        assert_eq!(chunk.row_groups(), 2);
+    }
+
+    #[test]
+    fn read_filter_except() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+
+        // Columns that don't exist are ignored.
+        let selection = Selection::Except(&["sketchy_sensor", "random column name"]);
+
+        let schema = chunk.read_filter_table_schema(selection).unwrap();
+        let exp_schema: Arc<Schema> = SchemaBuilder::new()
+            .tag("region")
+            .field("counter", Float64)
+            .field("active", Boolean)
+            .timestamp()
+            .build()
+            .unwrap()
+            .into();
+        assert_eq!(Arc::new(schema), exp_schema);
+
+        let mut itr = chunk.read_filter("a_table", Predicate::default(), selection);
+        let rb = itr.next().unwrap();
+        assert!(itr.next().is_none());
+
+        assert_eq!(rb.num_columns(), 4);
+        assert!(rb.schema().index_of("sketchy_sensor").is_err());
+        assert_rb_column_equals(
+            &rb,
+            "region",
+            &Values::Dictionary(vec![1, 1, 0], vec![Some("east"), Some("west")]),
+        );
+        assert_rb_column_equals(&rb, "counter", &Values::F64(vec![1.2, 3.3, 45.3]));
+        assert_rb_column_equals(
+            &rb,
+            "active",
+            &Values::Bool(vec![Some(true), Some(false), Some(true)]),
+        );
+        assert_rb_column_equals(&rb, "time", &Values::I64(vec![11111111, 222222, 3333]));
+
+        let result = chunk
+            .column_names(Predicate::default(), selection, BTreeSet::new())
+            .unwrap();
+        assert_eq!(result, to_set(&["active", "counter", "region", "time"]));
+    }
+
     #[test]
     fn table_summaries() {
         let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
@@ -976,6 +1741,175 @@ mod test {
         assert!(itr.next().is_none());
     }
 
+    #[test]
+    fn delete() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+        chunk.upsert_table("a_table", gen_recordbatch());
+        assert_eq!(chunk.rows(), 6);
+
+        // no rows in the time range
+        let predicate = Predicate::with_time_range(&[], 20_000_000, 30_000_000);
+        assert_eq!(chunk.delete(&predicate).unwrap(), 0);
+        assert_eq!(chunk.rows(), 6);
+
+        let predicate = Predicate::with_time_range(&[], 0, 300_000);
+        assert_eq!(chunk.delete(&predicate).unwrap(), 4);
+        assert_eq!(chunk.rows(), 2);
+        assert_eq!(chunk.row_groups(), 2);
+
+        let mut itr = chunk.read_filter("a_table", Predicate::default(), Selection::All);
+        for _ in 0..2 {
+            let rb = itr.next().unwrap();
+            assert_rb_column_equals(&rb, "time", &Values::I64(vec![11111111]));
+            assert_rb_column_equals(
+                &rb,
+                "region",
+                &Values::Dictionary(vec![0], vec![Some("west")]),
+            );
+            assert_rb_column_equals(&rb, "counter", &Values::F64(vec![1.2]));
+        }
+        assert!(itr.next().is_none());
+
+        // deleted rows are no longer visible to the predicate
+        assert!(!chunk.satisfies_predicate(&predicate));
+
+        // deleting all remaining rows drops the row groups
+        let schema = chunk.schema();
+        assert_eq!(chunk.delete(&Predicate::default()).unwrap(), 2);
+        assert!(chunk.is_empty());
+        assert_eq!(chunk.row_groups(), 0);
+        let mut itr = chunk.read_filter("a_table", Predicate::default(), Selection::All);
+        assert!(itr.next().is_none());
+
+        // but the table keeps its schema, and has no column values
+        assert_eq!(chunk.schema(), schema);
+        let summary = chunk.table_summaries().remove(0);
+        assert_eq!(summary.columns.len(), 5);
+        let counter = summary
+            .columns
+            .iter()
+            .find(|c| c.name == "counter")
+            .unwrap();
+        assert!(matches!(
+            &counter.stats,
+            Statistics::F64(StatValues {
+                min: None,
+                max: None,
+                count: 0,
+                ..
+            })
+        ));
+
+        // rows can be added to the table again
+        chunk.upsert_table("a_table", gen_recordbatch());
+        assert_eq!(chunk.rows(), 3);
+        assert_eq!(chunk.row_groups(), 1);
+    }
+
+    #[test]
+    fn size_raw_nulls() {
+        use crate::column::encoding::null_size_raw;
+
+        let schema = SchemaBuilder::new()
+            .tag("region")
+            .field("temp", Float64)
+            .field("active", Boolean)
+            .field("count", Int64)
+            .field("status", Utf8)
+            .timestamp()
+            .build()
+            .unwrap()
+            .into();
+
+        // 1 NULL tag, 2 NULL floats, 1 NULL bool, 3 NULL integers and 2 NULL
+        // strings
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(
+                vec![Some("west"), None, Some("east"), Some("west")]
+                    .into_iter()
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ),
+            Arc::new(Float64Array::from(vec![Some(1.2), None, None, Some(4.5)])),
+            Arc::new(BooleanArray::from(vec![
+                Some(true),
+                Some(false),
+                None,
+                Some(true),
+            ])),
+            Arc::new(Int64Array::from(vec![None, None, Some(3), None])),
+            Arc::new(StringArray::from(vec![None, Some("ok"), None, Some("ok")])),
+            Arc::new(TimestampNanosecondArray::from_vec(vec![1, 2, 3, 4], None)),
+        ];
+        let rb = RecordBatch::try_new(schema, data).unwrap();
+
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", rb);
+
+        let expected_nulls = null_size_raw::<String>()
+            + 2 * null_size_raw::<f64>()
+            + null_size_raw::<bool>()
+            + 3 * null_size_raw::<i64>()
+            + 2 * null_size_raw::<String>();
+        assert_eq!(expected_nulls, 24 + 16 + 1 + 24 + 48);
+        assert_eq!(chunk.size_raw(true) - chunk.size_raw(false), expected_nulls);
+    }
+
+    #[test]
+    fn column_cardinality_estimate() {
+        let gen_regions = |regions: Vec<Option<&str>>| {
+            let schema = SchemaBuilder::new()
+                .tag("region")
+                .timestamp()
+                .build()
+                .unwrap()
+                .into();
+
+            let times = (0..regions.len() as i64).collect::<Vec<_>>();
+            let data: Vec<ArrayRef> = vec![
+                Arc::new(regions.into_iter().collect::<DictionaryArray<Int32Type>>()),
+                Arc::new(TimestampNanosecondArray::from_vec(times, None)),
+            ];
+
+            RecordBatch::try_new(schema, data).unwrap()
+        };
+
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_regions(vec![Some("west"), Some("east")]));
+        chunk.upsert_table(
+            "a_table",
+            gen_regions(vec![Some("north"), None, Some("south")]),
+        );
+        chunk.upsert_table(
+            "a_table",
+            gen_regions(vec![Some("west"), Some("north"), Some("west")]),
+        );
+
+        let exact = chunk
+            .column_values(
+                Predicate::default(),
+                Selection::Some(&["region"]),
+                BTreeMap::new(),
+                None,
+            )
+            .unwrap()["region"]
+            .len() as u64;
+        assert_eq!(exact, 4);
+
+        let estimate = chunk.column_cardinality_estimate("region").unwrap();
+        assert!(
+            (estimate as f64 - exact as f64).abs() <= exact as f64 * 0.5,
+            "estimate {} too far from {}",
+            estimate,
+            exact
+        );
+
+        assert!(matches!(
+            chunk.column_cardinality_estimate("zone"),
+            Err(Error::ColumnDoesNotExist { .. })
+        ));
+    }
+
     #[test]
     fn could_pass_predicate() {
         let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
@@ -990,6 +1924,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn predicate_cache() {
+        let mut chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        chunk.upsert_table("a_table", gen_recordbatch());
+
+        let predicate = Predicate::new(vec![BinaryExpr::from(("region", "=", "east"))]);
+        assert!(chunk.could_pass_predicate(predicate.clone()));
+        assert_eq!(chunk.could_pass_cache.lock().hits, 0);
+
+        // the second evaluation is served from the cache
+        assert!(chunk.could_pass_predicate(predicate.clone()));
+        assert_eq!(chunk.could_pass_cache.lock().hits, 1);
+
+        // a different predicate is a miss
+        let other = Predicate::new(vec![BinaryExpr::from(("region", "=", "north"))]);
+        assert!(!chunk.could_pass_predicate(other));
+        assert_eq!(chunk.could_pass_cache.lock().hits, 1);
+
+        let time_predicate = Predicate::with_time_range(&[], 0, 300_000);
+        let satisfied = chunk.satisfies_predicate(&time_predicate);
+        assert_eq!(chunk.satisfies_predicate(&time_predicate), satisfied);
+        assert_eq!(chunk.satisfies_cache.lock().hits, 1);
+
+        // adding data invalidates the cache
+        chunk.upsert_table("a_table", gen_recordbatch());
+        assert!(chunk.could_pass_predicate(predicate.clone()));
+        assert_eq!(chunk.could_pass_cache.lock().hits, 1);
+        assert!(chunk.could_pass_predicate(predicate));
+        assert_eq!(chunk.could_pass_cache.lock().hits, 2);
+
+        chunk.satisfies_predicate(&time_predicate);
+        assert_eq!(chunk.satisfies_cache.lock().hits, 1);
+    }
+
+    #[test]
+    fn empty_chunk_predicates() {
+        let chunk = Chunk::new("a_table", ChunkMetrics::new_unregistered());
+        assert!(chunk.is_empty());
+
+        assert!(!chunk.could_pass_predicate(Predicate::default()));
+        assert!(!chunk.satisfies_predicate(&Predicate::default()));
+
+        let predicate = Predicate::new(vec![BinaryExpr::from(("region", "=", "east"))]);
+        assert!(!chunk.could_pass_predicate(predicate.clone()));
+        assert!(!chunk.satisfies_predicate(&predicate));
+
+        let mut chunk = chunk;
+        chunk.upsert_table("a_table", gen_recordbatch());
+        assert!(!chunk.is_empty());
+        assert!(chunk.could_pass_predicate(predicate));
+    }
+
     #[test]
     fn satisfies_predicate() {
         let columns = vec![
@@ -1135,6 +2121,7 @@ mod test {
                 Predicate::default(),
                 Selection::Some(&["region", "env"]),
                 BTreeMap::new(),
+                None,
             )
             .unwrap();
 
@@ -1155,6 +2142,7 @@ mod test {
                 ]),
                 Selection::Some(&["region", "env"]),
                 BTreeMap::new(),
+                None,
             )
             .unwrap();
 
@@ -1166,9 +2154,34 @@ mod test {
             ])
         );
 
+        // With a limit only some of the values are returned
+        let result = chunk
+            .column_values(
+                Predicate::default(),
+                Selection::Some(&["region", "env"]),
+                BTreeMap::new(),
+                Some(2),
+            )
+            .unwrap();
+        assert_eq!(result["region"].len(), 2);
+        assert!(result["region"]
+            .iter()
+            .all(|v| ["north", "south", "east"].contains(&v.as_str())));
+        assert_eq!(result["env"].len(), 2);
+
+        let result = chunk
+            .column_values(
+                Predicate::default(),
+                Selection::Some(&["region"]),
+                BTreeMap::new(),
+                Some(0),
+            )
+            .unwrap();
+        assert!(result["region"].is_empty());
+
         // Error when All column selection provided.
         assert!(matches!(
-            chunk.column_values(Predicate::default(), Selection::All, BTreeMap::new()),
+            chunk.column_values(Predicate::default(), Selection::All, BTreeMap::new(), None),
             Err(Error::UnsupportedOperation { .. })
         ));
     }
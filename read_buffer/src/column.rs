@@ -388,6 +388,11 @@ impl Column {
         value: &Value<'_>,
         dst: RowIDs,
     ) -> RowIDsOption {
+        // Regex predicates only ever match string values.
+        if op.is_regex() && !matches!(self, Self::String(_, _)) {
+            return RowIDsOption::None(dst);
+        }
+
         // If we can get an answer using only the meta-data on the column then
         // return that answer.
         match self.evaluate_predicate_on_meta(&op, &value) {
@@ -504,6 +509,10 @@ impl Column {
                     return PredicateMatch::All; // all rows are going to match.
                 }
             }
+
+            // The metadata can't tell which values match a regex so the
+            // column always needs to be read, unless it only has NULL values.
+            cmp::Operator::Regex | cmp::Operator::NotRegex => {}
         }
 
         if self.predicate_matches_no_values(&op, &value) {
@@ -820,6 +829,8 @@ impl<T: PartialOrd + std::fmt::Debug> MetaData<T> {
                 cmp::Operator::LT => range.1 < u,
                 // all values in column <= v
                 cmp::Operator::LTE => range.1 <= u,
+                // can't be determined from the range of values
+                cmp::Operator::Regex | cmp::Operator::NotRegex => false,
             },
             None => false, // only null values in column.
         }
@@ -846,6 +857,8 @@ impl<T: PartialOrd + std::fmt::Debug> MetaData<T> {
                 cmp::Operator::LT => range.0 >= u,
                 // min value in column is `> v` so no values can be `<= v`
                 cmp::Operator::LTE => range.0 > u,
+                // can't be determined from the range of values
+                cmp::Operator::Regex | cmp::Operator::NotRegex => false,
             },
             None => true, // only null values in column so no values satisfy `v`
         }
@@ -853,9 +866,8 @@ impl<T: PartialOrd + std::fmt::Debug> MetaData<T> {
 }
 
 // Converts an Arrow `StringArray` into a `Column`.
-impl From<arrow::array::StringArray> for Column {
-    fn from(arr: arrow::array::StringArray) -> Self {
-        let data = StringEncoding::from(arr);
+impl From<StringEncoding> for Column {
+    fn from(data: StringEncoding) -> Self {
         let meta = MetaData {
             range: data.column_range(),
             properties: ColumnProperties {
@@ -867,17 +879,15 @@ impl From<arrow::array::StringArray> for Column {
     }
 }
 
+impl From<arrow::array::StringArray> for Column {
+    fn from(arr: arrow::array::StringArray) -> Self {
+        Self::from(StringEncoding::from(arr))
+    }
+}
+
 impl From<arrow::array::DictionaryArray<arrow::datatypes::Int32Type>> for Column {
     fn from(arr: arrow::array::DictionaryArray<arrow::datatypes::Int32Type>) -> Self {
-        let data = StringEncoding::from(arr);
-        let meta = MetaData {
-            range: data.column_range(),
-            properties: ColumnProperties {
-                has_pre_computed_row_ids: data.has_pre_computed_row_id_sets(),
-            },
-        };
-
-        Self::String(meta, data)
+        Self::from(StringEncoding::from(arr))
     }
 }
 
@@ -1,7 +1,7 @@
 use std::{convert::TryFrom, fmt::Display};
 
 /// Possible comparison operators
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 #[allow(clippy::upper_case_acronyms)] // these look weird when not capitalized
 pub enum Operator {
     Equal,
@@ -10,6 +10,16 @@ pub enum Operator {
     GTE,
     LT,
     LTE,
+    Regex,
+    NotRegex,
+}
+
+impl Operator {
+    /// Returns true if the operator matches string values against a regular
+    /// expression.
+    pub fn is_regex(&self) -> bool {
+        matches!(self, Self::Regex | Self::NotRegex)
+    }
 }
 
 impl Display for Operator {
@@ -24,6 +34,8 @@ impl Display for Operator {
                 Self::GTE => ">=",
                 Self::LT => "<",
                 Self::LTE => "<=",
+                Self::Regex => "=~",
+                Self::NotRegex => "!~",
             }
         )
     }
@@ -40,6 +52,8 @@ impl TryFrom<&str> for Operator {
             ">=" => Ok(Self::GTE),
             "<" => Ok(Self::LT),
             "<=" => Ok(Self::LTE),
+            "=~" => Ok(Self::Regex),
+            "!~" => Ok(Self::NotRegex),
             v => Err(format!("unknown operator {:?}", v)),
         }
     }
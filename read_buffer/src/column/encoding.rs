@@ -1,3 +1,16 @@
 pub mod bool;
 pub mod scalar;
 pub mod string;
+
+/// The number of bytes a NULL value of logical type `L` contributes to the
+/// raw size of an encoding (see `size_raw`), i.e., to the estimated size of
+/// its values if they were stored contiguously and uncompressed.
+///
+/// A NULL value occupies an empty slot of the logical type, which is also
+/// what any non-NULL value costs before taking heap data into account: 8b
+/// for `i64`, `u64` and `f64`, 1b for `bool` and the size of a `String` for
+/// strings. All encodings use this cost so that raw sizes, and compression
+/// ratios derived from them, are comparable across encodings.
+pub const fn null_size_raw<L>() -> usize {
+    std::mem::size_of::<L>()
+}
@@ -5,6 +5,7 @@ use std::fmt::Debug;
 use arrow::array::{Array, BooleanArray};
 use cmp::Operator;
 
+use super::null_size_raw;
 use crate::column::{cmp, RowIDs};
 
 #[derive(Debug)]
@@ -48,15 +49,16 @@ impl Bool {
 
     /// The estimated total size in bytes of the underlying bool values in the
     /// column if they were stored contiguously and uncompressed. `include_nulls`
-    /// will effectively size each NULL value as 1b if `true`.
+    /// will size each NULL value as `null_size_raw::<bool>()` (1b) if `true`.
     pub fn size_raw(&self, include_nulls: bool) -> usize {
-        let base_size = std::mem::size_of::<Vec<bool>>();
+        let null_count = self.arr.null_count();
+        let base_size = std::mem::size_of::<Vec<bool>>()
+            + ((self.num_rows() as usize - null_count) * std::mem::size_of::<bool>());
 
-        if !self.contains_null() || include_nulls {
-            return base_size + self.num_rows() as usize;
+        if include_nulls {
+            return base_size + (null_count * null_size_raw::<bool>());
         }
-
-        base_size + self.num_rows() as usize - self.arr.null_count()
+        base_size
     }
 
     //
@@ -22,9 +22,9 @@ pub trait ScalarEncoding<L>: Debug + Display + Send + Sync {
     fn size(&self) -> usize;
 
     /// The estimated total size in bytes of the underlying encoded values if
-    /// they were stored contiguously as a vector of `L`. `include_null` should
-    /// decide whether to consider NULL values to take up `size_of::<L>()`
-    /// bytes.
+    /// they were stored contiguously as a vector of `L`. If `include_nulls` is
+    /// true NULL values are sized as [`null_size_raw`](super::null_size_raw),
+    /// otherwise they are excluded.
     fn size_raw(&self, include_nulls: bool) -> usize;
 
     /// The total number of rows in the encoding.
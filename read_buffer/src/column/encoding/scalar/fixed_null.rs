@@ -17,6 +17,7 @@ use arrow::{
     datatypes::ArrowNumericType,
 };
 
+use super::super::null_size_raw;
 use super::transcoders::Transcoder;
 use super::ScalarEncoding;
 use crate::column::{cmp, RowIDs};
@@ -266,13 +267,15 @@ where
 
     /// The estimated total size in bytes of the underlying values in the
     /// column if they were stored contiguously and uncompressed. `include_nulls`
-    /// will effectively size each NULL value as the size of an `L` if `true`.
+    /// will size each NULL value as `null_size_raw::<L>()` if `true`.
     fn size_raw(&self, include_nulls: bool) -> usize {
-        let base_size = size_of::<Vec<L>>();
-        if self.null_count() == 0 || include_nulls {
-            return base_size + (self.num_rows() as usize * size_of::<L>());
+        let null_count = self.arr.null_count();
+        let base_size =
+            size_of::<Vec<L>>() + ((self.num_rows() as usize - null_count) * size_of::<L>());
+        if include_nulls {
+            return base_size + (null_count * null_size_raw::<L>());
         }
-        base_size + ((self.num_rows() as usize - self.arr.null_count()) * size_of::<L>())
+        base_size
     }
 
     fn value(&self, row_id: u32) -> Option<L> {
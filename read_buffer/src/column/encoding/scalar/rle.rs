@@ -4,6 +4,7 @@ use crate::column::cmp;
 use crate::column::RowIDs;
 use std::{cmp::Ordering, fmt::Debug, iter, marker::PhantomData, mem::size_of};
 
+use super::super::null_size_raw;
 use super::transcoders::Transcoder;
 use super::ScalarEncoding;
 
@@ -348,18 +349,13 @@ where
     }
 
     fn size_raw(&self, include_nulls: bool) -> usize {
-        let base_size = size_of::<Vec<L>>();
+        let null_count = self.null_count as usize;
+        let base_size =
+            size_of::<Vec<L>>() + ((self.num_rows as usize - null_count) * size_of::<L>());
         if include_nulls {
-            return base_size + (self.num_rows() as usize * size_of::<Option<L>>());
+            return base_size + (null_count * null_size_raw::<L>());
         }
-
-        // remove NULL values from calculation
         base_size
-            + self
-                .run_lengths
-                .iter()
-                .filter_map(|(rl, v)| v.is_some().then(|| *rl as usize * size_of::<Option<L>>()))
-                .sum::<usize>()
     }
 
     fn null_count(&self) -> u32 {
@@ -729,19 +725,19 @@ mod test {
     fn size_raw() {
         let (mut enc, _) = new_encoding(vec![]);
 
-        // 24b Self + (0 * 16)
+        // 24b Self + (0 * 8)
         assert_eq!(enc.size_raw(true), 24);
         assert_eq!(enc.size_raw(false), 24);
 
         enc.push_none();
-        // 24b Self + (1 * 16) = 48
-        assert_eq!(enc.size_raw(true), 40);
+        // 24b Self + (1 * 8) = 32
+        assert_eq!(enc.size_raw(true), 32);
         assert_eq!(enc.size_raw(false), 24);
 
         enc.push_additional_some(1, 10);
-        // 24b Self + (11 * 16) = 208
-        assert_eq!(enc.size_raw(true), 200);
-        assert_eq!(enc.size_raw(false), 184);
+        // 24b Self + (11 * 8) = 112
+        assert_eq!(enc.size_raw(true), 112);
+        assert_eq!(enc.size_raw(false), 104);
     }
 
     #[test]
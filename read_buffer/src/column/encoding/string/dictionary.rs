@@ -13,6 +13,7 @@ use std::mem::size_of;
 
 use arrow::array::{Array, StringArray};
 
+use super::super::null_size_raw;
 use super::NULL_ID;
 use crate::column::{cmp, RowIDs};
 
@@ -81,8 +82,8 @@ impl Dictionary {
 
     /// A reasonable estimation of the on-heap size of the underlying string
     /// values in this column if they were stored uncompressed contiguously.
-    /// `include_nulls` determines whether to assign a pointer size to the null
-    /// values or not.
+    /// `include_nulls` determines whether to size the null values as
+    /// `null_size_raw::<String>()` or not.
     ///
     ///
     /// Perf: This runtime of this method is linear to the size of the column,
@@ -93,13 +94,10 @@ impl Dictionary {
             // the length of the string value encoded at the current row in the
             // column.
             total_size += match &self.entries[*idx as usize] {
-                Some(s) => s.len(),
+                Some(s) => s.len() + size_of::<String>(),
+                None if include_nulls => null_size_raw::<String>(),
                 None => 0,
             };
-
-            if include_nulls || idx != &NULL_ID {
-                total_size += size_of::<String>()
-            }
         }
         total_size + size_of::<Vec<String>>()
     }
@@ -211,7 +209,31 @@ impl Dictionary {
             cmp::Operator::LT | cmp::Operator::LTE | cmp::Operator::GT | cmp::Operator::GTE => {
                 self.row_ids_cmp(value, op, dst)
             }
+            cmp::Operator::Regex | cmp::Operator::NotRegex => {
+                unreachable!("regex predicates are applied via row_ids_matching")
+            }
+        }
+    }
+
+    /// Populates the provided destination container with the row ids of all
+    /// non-null values satisfying `f`, which is evaluated once per distinct
+    /// value in the column rather than once per row.
+    pub fn row_ids_matching(&self, f: impl Fn(&str) -> bool, mut dst: RowIDs) -> RowIDs {
+        dst.clear();
+
+        // index by encoded id; the NULL entry never matches.
+        let matching = self
+            .entries
+            .iter()
+            .map(|entry| entry.as_deref().map_or(false, &f))
+            .collect::<Vec<_>>();
+
+        for (i, next) in self.encoded_data.iter().enumerate() {
+            if matching[*next as usize] {
+                dst.add(i as u32);
+            }
         }
+        dst
     }
 
     // Finds row ids based on = or != operator.
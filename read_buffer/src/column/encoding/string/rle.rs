@@ -7,6 +7,7 @@ use croaring::Bitmap;
 
 use arrow::array::{Array, StringArray};
 
+use super::super::null_size_raw;
 use super::NULL_ID;
 use crate::column::{cmp, RowIDs};
 
@@ -103,19 +104,23 @@ impl RLE {
 
     /// A reasonable estimation of the on-heap size of the underlying string
     /// values in this column if they were stored uncompressed contiguously.
-    /// `include_nulls` determines whether to assign a pointer size to the null
-    /// values or not.
+    /// `include_nulls` determines whether to size the null values as
+    /// `null_size_raw::<String>()` or not.
     ///
     pub fn size_raw(&self, include_nulls: bool) -> usize {
         let mut total_size = 0;
         for (idx, rows) in &self.index_row_ids {
+            if idx == &NULL_ID {
+                if include_nulls {
+                    total_size += null_size_raw::<String>() * rows.len();
+                }
+                continue;
+            }
+
             // the length of the string value x number of times it appears
             // in the column.
-            total_size += self.index_entries[*idx as usize].len() * rows.len();
-
-            if include_nulls || idx != &NULL_ID {
-                total_size += size_of::<String>() * rows.len()
-            }
+            total_size +=
+                (self.index_entries[*idx as usize].len() + size_of::<String>()) * rows.len();
         }
         total_size + size_of::<Vec<String>>()
     }
@@ -287,9 +292,31 @@ impl RLE {
             cmp::Operator::LT | cmp::Operator::LTE | cmp::Operator::GT | cmp::Operator::GTE => {
                 self.row_ids_cmp(value, op, dst)
             }
+            cmp::Operator::Regex | cmp::Operator::NotRegex => {
+                unreachable!("regex predicates are applied via row_ids_matching")
+            }
         }
     }
 
+    /// Populates the provided destination container with the row ids of all
+    /// non-null values satisfying `f`, which is evaluated once per distinct
+    /// value in the column rather than once per row.
+    pub fn row_ids_matching(&self, f: impl Fn(&str) -> bool, mut dst: RowIDs) -> RowIDs {
+        dst.clear();
+
+        // Skip the first "" representing NULL
+        for (id, entry) in self.index_entries.iter().enumerate().skip(1) {
+            if !f(entry) {
+                continue;
+            }
+
+            if let Some(row_ids) = self.index_row_ids.get(&(id as u32)) {
+                dst.union(row_ids);
+            }
+        }
+        dst
+    }
+
     // Finds row ids based on = or != operator.
     fn row_ids_equal(&self, value: &str, op: &cmp::Operator, mut dst: RowIDs) -> RowIDs {
         dst.clear();
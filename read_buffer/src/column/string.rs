@@ -2,7 +2,8 @@ use std::collections::BTreeSet;
 
 use arrow::{self, array::Array};
 use either::Either;
-use observability_deps::tracing::info;
+use observability_deps::tracing::{debug, info};
+use regex::Regex;
 
 use super::cmp;
 use super::encoding::string::{dictionary, rle};
@@ -19,6 +20,34 @@ use crate::column::{RowIDs, Statistics, Value, Values};
 // compression is worth the memory and compute costs to work on it.
 pub const TEMP_CARDINALITY_DICTIONARY_ENCODING_LIMIT: usize = 100_000;
 
+// Columns where the ratio of distinct values to rows is above this limit are
+// encoded with a plain dictionary encoding. For such columns (think request
+// IDs) an RLE encoding produces almost as many runs as there are rows, so it
+// costs memory and compute without providing any compression.
+pub const DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT: f64 = 0.5;
+
+// The cardinality ratio is only considered for columns with at least this many
+// rows; for smaller columns the choice of encoding hardly matters.
+pub const CARDINALITY_RATIO_MIN_ROWS: usize = 1_000;
+
+// Picks the encoding for a column with `rows` rows containing the values in
+// `dictionary`.
+fn new_encoding(
+    dictionary: BTreeSet<String>,
+    rows: usize,
+    cardinality_ratio_limit: f64,
+) -> Encoding {
+    let cardinality = dictionary.len();
+    let high_cardinality_ratio = rows >= CARDINALITY_RATIO_MIN_ROWS
+        && cardinality as f64 / rows as f64 > cardinality_ratio_limit;
+
+    if cardinality > TEMP_CARDINALITY_DICTIONARY_ENCODING_LIMIT || high_cardinality_ratio {
+        Encoding::Plain(Dictionary::with_dictionary(dictionary))
+    } else {
+        Encoding::RLE(RLE::with_dictionary(dictionary))
+    }
+}
+
 pub enum StringEncoding {
     RleDictionary(RLE),
     Dictionary(Dictionary),
@@ -309,7 +338,30 @@ impl StringEncoding {
     }
 
     /// Returns the row ids that satisfy the provided predicate.
-    pub fn row_ids_filter(&self, op: &cmp::Operator, value: &str, dst: RowIDs) -> RowIDs {
+    ///
+    /// For the regex operators `value` is the pattern, which is compiled once
+    /// and matched against each distinct value in the column. NULL values
+    /// never match. Invalid patterns are rejected by `BinaryExpr::validate`,
+    /// and match no rows if they get this far.
+    pub fn row_ids_filter(&self, op: &cmp::Operator, value: &str, mut dst: RowIDs) -> RowIDs {
+        if op.is_regex() {
+            let regex = match Regex::new(value) {
+                Ok(regex) => regex,
+                Err(e) => {
+                    debug!(%e, pattern=%value, "invalid regex in predicate");
+                    dst.clear();
+                    return dst;
+                }
+            };
+
+            let negated = matches!(op, cmp::Operator::NotRegex);
+            let f = |v: &str| regex.is_match(v) != negated;
+            return match &self {
+                Self::RleDictionary(c) => c.row_ids_matching(f, dst),
+                Self::Dictionary(c) => c.row_ids_matching(f, dst),
+            };
+        }
+
         match &self {
             Self::RleDictionary(c) => c.row_ids_filter(value, op, dst),
             Self::Dictionary(c) => c.row_ids_filter(value, op, dst),
@@ -409,6 +461,15 @@ impl std::fmt::Display for StringEncoding {
 /// ideally it's a "write once read many" scenario.
 impl From<arrow::array::StringArray> for StringEncoding {
     fn from(arr: arrow::array::StringArray) -> Self {
+        Self::from_string_array(arr, DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT)
+    }
+}
+
+impl StringEncoding {
+    /// Converts an Arrow `StringArray` into a `StringEncoding`, using a plain
+    /// dictionary encoding if the ratio of distinct values to rows exceeds
+    /// `cardinality_ratio_limit`.
+    pub fn from_string_array(arr: arrow::array::StringArray, cardinality_ratio_limit: f64) -> Self {
         // build a sorted dictionary.
         let mut dictionary = BTreeSet::new();
 
@@ -418,11 +479,7 @@ impl From<arrow::array::StringArray> for StringEncoding {
             }
         }
 
-        let mut data: Encoding = if dictionary.len() > TEMP_CARDINALITY_DICTIONARY_ENCODING_LIMIT {
-            Encoding::Plain(Dictionary::with_dictionary(dictionary))
-        } else {
-            Encoding::RLE(RLE::with_dictionary(dictionary))
-        };
+        let mut data = new_encoding(dictionary, arr.len(), cardinality_ratio_limit);
 
         let mut prev = if !arr.is_null(0) {
             Some(arr.value(0))
@@ -474,6 +531,18 @@ impl From<arrow::array::StringArray> for StringEncoding {
 /// could reuse its encoding directly
 impl From<arrow::array::DictionaryArray<arrow::datatypes::Int32Type>> for StringEncoding {
     fn from(arr: arrow::array::DictionaryArray<arrow::datatypes::Int32Type>) -> Self {
+        Self::from_dictionary_array(arr, DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT)
+    }
+}
+
+impl StringEncoding {
+    /// Converts an Arrow `StringDictionary` into a `StringEncoding`, using a
+    /// plain dictionary encoding if the ratio of distinct values to rows
+    /// exceeds `cardinality_ratio_limit`.
+    pub fn from_dictionary_array(
+        arr: arrow::array::DictionaryArray<arrow::datatypes::Int32Type>,
+        cardinality_ratio_limit: f64,
+    ) -> Self {
         let keys = arr.keys();
         let values = arr.values();
         let values = values
@@ -486,11 +555,7 @@ impl From<arrow::array::DictionaryArray<arrow::datatypes::Int32Type>> for String
         let rb_to_set_dur = now.elapsed();
         let dict_len = dictionary.len();
 
-        let mut data: Encoding = if dictionary.len() > TEMP_CARDINALITY_DICTIONARY_ENCODING_LIMIT {
-            Encoding::Plain(Dictionary::with_dictionary(dictionary))
-        } else {
-            Encoding::RLE(RLE::with_dictionary(dictionary))
-        };
+        let mut data = new_encoding(dictionary, keys.len(), cardinality_ratio_limit);
 
         let mut prev = if !keys.is_null(0) {
             Some(keys.value(0))
@@ -551,11 +616,11 @@ impl From<&[Option<&str>]> for StringEncoding {
             dictionary.insert(x.to_string());
         }
 
-        let mut data: Encoding = if dictionary.len() > TEMP_CARDINALITY_DICTIONARY_ENCODING_LIMIT {
-            Encoding::Plain(Dictionary::with_dictionary(dictionary))
-        } else {
-            Encoding::RLE(RLE::with_dictionary(dictionary))
-        };
+        let mut data = new_encoding(
+            dictionary,
+            arr.len(),
+            DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT,
+        );
 
         let mut prev = &arr[0];
 
@@ -592,11 +657,11 @@ impl From<&[&str]> for StringEncoding {
         // build a sorted dictionary.
         let dictionary = arr.iter().map(|x| x.to_string()).collect::<BTreeSet<_>>();
 
-        let mut data: Encoding = if dictionary.len() > TEMP_CARDINALITY_DICTIONARY_ENCODING_LIMIT {
-            Encoding::Plain(Dictionary::with_dictionary(dictionary))
-        } else {
-            Encoding::RLE(RLE::with_dictionary(dictionary))
-        };
+        let mut data = new_encoding(
+            dictionary,
+            arr.len(),
+            DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT,
+        );
 
         let mut prev = &arr[0];
         let mut count = 1;
@@ -700,6 +765,49 @@ mod test {
         }
     }
 
+    #[test]
+    fn row_ids_filter_regex() {
+        let data = vec![
+            Some("us-east"),
+            Some("us-west"),
+            None,
+            Some("eu-west"),
+            Some("us-east"),
+            Some("asia"),
+        ];
+
+        let encodings = vec![
+            StringEncoding::RleDictionary(RLE::from(data.clone())),
+            StringEncoding::Dictionary(Dictionary::from(data)),
+        ];
+
+        for enc in encodings {
+            let cases = vec![
+                (cmp::Operator::Regex, "^us-", vec![0, 1, 4]),
+                (cmp::Operator::Regex, "west$", vec![1, 3]),
+                (cmp::Operator::Regex, "nope", vec![]),
+                // NULL values never match
+                (cmp::Operator::NotRegex, "^us-", vec![3, 5]),
+                (cmp::Operator::NotRegex, "nope", vec![0, 1, 3, 4, 5]),
+                // invalid pattern
+                (cmp::Operator::Regex, "(", vec![]),
+                (cmp::Operator::NotRegex, "(", vec![]),
+            ];
+
+            for (op, pattern, exp) in cases {
+                let row_ids = enc.row_ids_filter(&op, pattern, RowIDs::new_vector());
+                assert_eq!(
+                    row_ids.to_vec(),
+                    exp,
+                    "{} {} failed for {}",
+                    op,
+                    pattern,
+                    enc
+                );
+            }
+        }
+    }
+
     fn _values_as_dictionary(enc: &StringEncoding) {
         // column is: [apple, apple, pear, NULL, NULL, orange, beta]
 
@@ -10,9 +10,9 @@ mod value;
 
 // Identifiers that are exported as part of the public API.
 pub use chunk::{Chunk as RBChunk, ChunkMetrics, Error};
-pub use row_group::{BinaryExpr, Predicate};
+pub use row_group::{BinaryExpr, Predicate, RowGroup};
 pub use schema::*;
-pub use table::ReadFilterResults;
+pub use table::{ColumnStats, ReadFilterResults};
 
 /// THIS MODULE SHOULD ONLY BE IMPORTED FOR BENCHMARKS.
 ///
@@ -29,14 +29,4 @@ pub mod benchmarks {
         Column, RowIDs,
     };
     pub use crate::row_group::{ColumnType, RowGroup};
-    use crate::RBChunk;
-
-    // Allow external benchmarks to use this crate-only test method
-    pub fn upsert_table_with_row_group(
-        chunk: &mut RBChunk,
-        _table_name: impl Into<String>,
-        row_group: RowGroup,
-    ) {
-        chunk.upsert_table_with_row_group(row_group)
-    }
 }
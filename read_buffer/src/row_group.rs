@@ -4,6 +4,7 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     convert::{TryFrom, TryInto},
     fmt::Display,
+    hash::{Hash, Hasher},
     sync::Arc,
 };
 
@@ -11,7 +12,13 @@ use hashbrown::{hash_map, HashMap};
 use itertools::Itertools;
 use snafu::{ResultExt, Snafu};
 
-use crate::column::{self, cmp::Operator, Column, RowIDs, RowIDsOption};
+use crate::column::{
+    self,
+    cmp::Operator,
+    encoding::string::dictionary,
+    string::{StringEncoding, DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT},
+    Column, RowIDs, RowIDsOption,
+};
 use crate::schema;
 use crate::schema::{AggregateType, LogicalDataType, ResultSchema};
 use crate::value::{
@@ -46,6 +53,9 @@ pub enum Error {
 
     #[snafu(display("unsupported operation: {}", msg))]
     UnsupportedOperation { msg: String },
+
+    #[snafu(display("cannot concatenate results: {}", source))]
+    ConcatenatingResults { source: crate::value::Error },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -270,6 +280,52 @@ impl RowGroup {
         }
     }
 
+    /// Removes all rows that satisfy the predicate, returning the number of
+    /// rows removed along with a new row group holding the remaining rows.
+    ///
+    /// Row groups are immutable so the remaining rows are copied into a new
+    /// row group. If no rows satisfy the predicate then `0` is returned and
+    /// this row group should be kept as is. If all rows satisfy the predicate
+    /// then no row group is returned.
+    pub fn delete(&self, predicate: &Predicate) -> Result<(u32, Option<Self>)> {
+        if !self.could_satisfy_conjunctive_binary_expressions(predicate.iter()) {
+            return Ok((0, None));
+        }
+
+        let mut deleted_row_ids = match self.row_ids_from_predicate(predicate) {
+            RowIDsOption::None(_) => return Ok((0, None)),
+            RowIDsOption::All(_) => return Ok((self.rows(), None)),
+            RowIDsOption::Some(row_ids) => row_ids.to_vec(),
+        };
+        deleted_row_ids.sort_unstable();
+        if deleted_row_ids.len() as u32 == self.rows() {
+            return Ok((self.rows(), None));
+        }
+
+        let mut row_ids = RowIDs::new_vector();
+        for row_id in 0..self.rows() {
+            if deleted_row_ids.binary_search(&row_id).is_err() {
+                row_ids.add(row_id);
+            }
+        }
+
+        let column_names = self
+            .meta
+            .column_names
+            .iter()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>();
+        let schema = ResultSchema {
+            select_columns: self.meta.schema_for_column_names(&column_names),
+            ..Default::default()
+        };
+
+        let data = self.materialise_rows(&schema, RowIDsOption::Some(row_ids));
+        let rb = RecordBatch::try_from(ReadFilterResult { schema, data })?;
+
+        Ok((deleted_row_ids.len() as u32, Some(Self::from(rb))))
+    }
+
     fn materialise_rows(&self, schema: &ResultSchema, row_ids: RowIDsOption) -> Vec<Values<'_>> {
         let mut col_data = Vec::with_capacity(schema.len());
         match row_ids {
@@ -510,8 +566,29 @@ impl RowGroup {
             aggregate_columns_data.push(column_values);
         }
 
+        // First/last aggregates also need the timestamp of each row.
+        let time_column_data = if result
+            .schema
+            .aggregate_columns
+            .iter()
+            .any(|(_, agg_type, _)| matches!(agg_type, AggregateType::First | AggregateType::Last))
+        {
+            let col = self.time_column();
+            Some(match &filter_row_ids {
+                Some(row_ids) => col.values(row_ids),
+                None => col.all_values(),
+            })
+        } else {
+            None
+        };
+
         // Perform the group by using a hashmap
-        self.read_group_with_hashing(&mut result, &groupby_encoded_ids, aggregate_columns_data);
+        self.read_group_with_hashing(
+            &mut result,
+            &groupby_encoded_ids,
+            aggregate_columns_data,
+            time_column_data,
+        );
         result
     }
 
@@ -525,16 +602,27 @@ impl RowGroup {
         dst: &mut ReadAggregateResult<'a>,
         groupby_encoded_ids: &[Vec<u32>],
         aggregate_columns_data: Vec<Values<'a>>,
+        time_column_data: Option<Values<'a>>,
     ) {
         // An optimised approach to building the hashmap of group keys using a
         // single 128-bit integer as the group key. If grouping is on more than
         // four columns then a fallback to using an vector as a key will happen.
         if dst.schema.group_columns.len() <= 4 {
-            self.read_group_hash_with_u128_key(dst, &groupby_encoded_ids, aggregate_columns_data);
+            self.read_group_hash_with_u128_key(
+                dst,
+                &groupby_encoded_ids,
+                aggregate_columns_data,
+                time_column_data,
+            );
             return;
         }
 
-        self.read_group_hash_with_vec_key(dst, &groupby_encoded_ids, aggregate_columns_data);
+        self.read_group_hash_with_vec_key(
+            dst,
+            &groupby_encoded_ids,
+            aggregate_columns_data,
+            time_column_data,
+        );
     }
 
     // This function is used with `read_group_hash` when the number of columns
@@ -545,6 +633,7 @@ impl RowGroup {
         dst: &mut ReadAggregateResult<'a>,
         groupby_encoded_ids: &[Vec<u32>],
         aggregate_input_columns: Vec<Values<'a>>,
+        time_input_column: Option<Values<'a>>,
     ) {
         let total_rows = groupby_encoded_ids[0].len();
         assert!(groupby_encoded_ids.iter().all(|x| x.len() == total_rows));
@@ -595,11 +684,19 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
-                            &aggregate_input_columns[agg_col_i],
-                            row,
-                            *ordinal_id,
-                        )
+                        match &time_input_column {
+                            Some(times) => aggregate_result.update_with_time(
+                                &aggregate_input_columns[agg_col_i],
+                                times,
+                                row,
+                                *ordinal_id,
+                            ),
+                            None => aggregate_result.update(
+                                &aggregate_input_columns[agg_col_i],
+                                row,
+                                *ordinal_id,
+                            ),
+                        }
                     }
                 }
                 // group key does not exist, so create it.
@@ -608,11 +705,19 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
-                            &aggregate_input_columns[agg_col_i],
-                            row,
-                            next_ordinal_id,
-                        )
+                        match &time_input_column {
+                            Some(times) => aggregate_result.update_with_time(
+                                &aggregate_input_columns[agg_col_i],
+                                times,
+                                row,
+                                next_ordinal_id,
+                            ),
+                            None => aggregate_result.update(
+                                &aggregate_input_columns[agg_col_i],
+                                row,
+                                next_ordinal_id,
+                            ),
+                        }
                     }
 
                     // Add decoded group key values to the output group columns.
@@ -653,6 +758,7 @@ impl RowGroup {
         dst: &mut ReadAggregateResult<'a>,
         groupby_encoded_ids: &[Vec<u32>],
         aggregate_input_columns: Vec<Values<'a>>,
+        time_input_column: Option<Values<'a>>,
     ) {
         let total_rows = groupby_encoded_ids[0].len();
         assert!(groupby_encoded_ids.iter().all(|x| x.len() == total_rows));
@@ -704,11 +810,19 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
-                            &aggregate_input_columns[agg_col_i],
-                            row,
-                            *ordinal_id,
-                        )
+                        match &time_input_column {
+                            Some(times) => aggregate_result.update_with_time(
+                                &aggregate_input_columns[agg_col_i],
+                                times,
+                                row,
+                                *ordinal_id,
+                            ),
+                            None => aggregate_result.update(
+                                &aggregate_input_columns[agg_col_i],
+                                row,
+                                *ordinal_id,
+                            ),
+                        }
                     }
                 }
                 hash_map::RawEntryMut::Vacant(entry) => {
@@ -716,11 +830,19 @@ impl RowGroup {
                     // with the values present in the input columns at the
                     // current row.
                     for (agg_col_i, aggregate_result) in agg_cols_out.iter_mut().enumerate() {
-                        aggregate_result.update(
-                            &aggregate_input_columns[agg_col_i],
-                            row,
-                            next_ordinal_id,
-                        )
+                        match &time_input_column {
+                            Some(times) => aggregate_result.update_with_time(
+                                &aggregate_input_columns[agg_col_i],
+                                times,
+                                row,
+                                next_ordinal_id,
+                            ),
+                            None => aggregate_result.update(
+                                &aggregate_input_columns[agg_col_i],
+                                row,
+                                next_ordinal_id,
+                            ),
+                        }
                     }
 
                     // Add decoded group key values to the output group columns.
@@ -876,8 +998,11 @@ impl RowGroup {
                         let agg = agg_col.count(&group_key_row_ids.to_vec()) as u64;
                         agg_cols_out[agg_col_i].push(Value::Scalar(Scalar::U64(agg)))
                     }
-                    AggregateType::First => {}
-                    AggregateType::Last => {}
+                    AggregateType::First | AggregateType::Last => {
+                        let (agg, time) =
+                            self.first_last(agg_col, &group_key_row_ids.to_vec(), *typ);
+                        agg_cols_out[agg_col_i].push_with_time(agg, time);
+                    }
                     AggregateType::Min => {
                         let agg = agg_col.min(&group_key_row_ids.to_vec());
                         agg_cols_out[agg_col_i].push(agg);
@@ -920,8 +1045,87 @@ impl RowGroup {
     }
 
     // Applies aggregates on multiple columns with an optional predicate.
+    //
+    // When there is no predicate, aggregates that can be answered from the
+    // column statistics are answered without scanning the column, and the
+    // row ids are only materialised for those that can't.
     fn aggregate_columns<'a>(&'a self, predicate: &Predicate, dst: &mut ReadAggregateResult<'a>) {
-        let row_ids = match predicate.is_empty() {
+        let mut row_ids: Option<Vec<u32>> = None;
+
+        let mut aggregate_cols = Vec::with_capacity(dst.schema.aggregate_columns.len());
+        for (col_type, agg_type, data_type) in &dst.schema.aggregate_columns {
+            let mut agg_vec = AggregateVec::from((agg_type, data_type));
+
+            if predicate.is_empty() {
+                if let Some(value) = self.aggregate_from_stats(col_type.as_str(), *agg_type) {
+                    agg_vec.push(value);
+                    aggregate_cols.push(agg_vec);
+                    continue;
+                }
+            }
+
+            let row_ids = row_ids.get_or_insert_with(|| self.aggregate_row_ids(predicate));
+            let col = self.column_by_name(col_type.as_str()); // input aggregate column
+
+            // produce single aggregate for the input column subject to a
+            // predicate filter.
+            match agg_type {
+                AggregateType::Count => {
+                    let value = Value::Scalar(Scalar::U64(col.count(row_ids) as u64));
+                    agg_vec.push(value);
+                }
+                AggregateType::First | AggregateType::Last => {
+                    let (value, time) = self.first_last(col, row_ids, *agg_type);
+                    agg_vec.push_with_time(value, time);
+                }
+                AggregateType::Min => agg_vec.push(col.min(row_ids)),
+                AggregateType::Max => agg_vec.push(col.max(row_ids)),
+                AggregateType::Sum => agg_vec.push(Value::Scalar(col.sum(row_ids))),
+            }
+            aggregate_cols.push(agg_vec);
+        }
+
+        dst.aggregate_cols = aggregate_cols;
+    }
+
+    // Returns the non-null value in `col` with the earliest (`First`) or latest
+    // (`Last`) timestamp of the provided rows, along with that timestamp.
+    fn first_last<'a>(
+        &'a self,
+        col: &'a Column,
+        row_ids: &[u32],
+        agg_type: AggregateType,
+    ) -> (Value<'a>, Option<i64>) {
+        let keep = match agg_type {
+            AggregateType::First => Ordering::Less,
+            AggregateType::Last => Ordering::Greater,
+            _ => unreachable!("{} is not a first/last aggregate", agg_type),
+        };
+
+        let values = col.values(row_ids);
+        let times = self.time_column().values(row_ids);
+
+        let mut selected: Option<(usize, i64)> = None;
+        for i in 0..values.len() {
+            if values.is_null(i) {
+                continue;
+            }
+
+            let time = times.value(i).i64();
+            if selected.map_or(true, |(_, current)| time.cmp(&current) == keep) {
+                selected = Some((i, time));
+            }
+        }
+
+        match selected {
+            Some((i, time)) => (values.value(i), Some(time)),
+            None => (Value::Null, None),
+        }
+    }
+
+    // Returns the row ids satisfying the predicate for aggregating columns.
+    fn aggregate_row_ids(&self, predicate: &Predicate) -> Vec<u32> {
+        match predicate.is_empty() {
             true => {
                 // TODO(edd): PERF - teach each column encoding how to produce
                 // an aggregate for all its rows without needed
@@ -938,32 +1142,33 @@ impl RowGroup {
                     (0..self.rows()).into_iter().collect::<Vec<u32>>()
                 }
             },
-        };
-
-        dst.aggregate_cols = dst
-            .schema
-            .aggregate_columns
-            .iter()
-            .map(|(col_type, agg_type, data_type)| {
-                let col = self.column_by_name(col_type.as_str()); // input aggregate column
-                let mut agg_vec = AggregateVec::from((agg_type, data_type));
+        }
+    }
 
-                // produce single aggregate for the input column subject to a
-                // predicate filter.
-                match agg_type {
-                    AggregateType::Count => {
-                        let value = Value::Scalar(Scalar::U64(col.count(&row_ids) as u64));
-                        agg_vec.push(value);
-                    }
-                    AggregateType::First => unimplemented!("First not yet implemented"),
-                    AggregateType::Last => unimplemented!("Last not yet implemented"),
-                    AggregateType::Min => agg_vec.push(col.min(&row_ids)),
-                    AggregateType::Max => agg_vec.push(col.max(&row_ids)),
-                    AggregateType::Sum => agg_vec.push(Value::Scalar(col.sum(&row_ids))),
-                }
-                agg_vec
-            })
-            .collect::<Vec<_>>();
+    // Returns the aggregate of all rows in the column from the column's
+    // statistics, or `None` if the column has to be scanned to determine it.
+    fn aggregate_from_stats(
+        &self,
+        name: ColumnName<'_>,
+        agg_type: AggregateType,
+    ) -> Option<Value<'_>> {
+        match agg_type {
+            AggregateType::Count => match self.column_by_name(name).contains_null() {
+                true => None,
+                false => Some(Value::Scalar(Scalar::U64(self.rows() as u64))),
+            },
+            AggregateType::Min => self
+                .meta
+                .columns
+                .get(name)
+                .map(|meta| (&meta.range.0).into()),
+            AggregateType::Max => self
+                .meta
+                .columns
+                .get(name)
+                .map(|meta| (&meta.range.1).into()),
+            AggregateType::First | AggregateType::Last | AggregateType::Sum => None,
+        }
     }
 
     /// Given the predicate (which may be empty), determine a set of rows
@@ -1000,6 +1205,13 @@ impl RowGroup {
                             None
                         }
                     }
+                    Selection::Except(names) => {
+                        if names.iter().any(|selection| name == selection) {
+                            None
+                        } else {
+                            Some((name, &self.columns[id]))
+                        }
+                    }
                 },
             })
             .collect::<Vec<_>>();
@@ -1026,13 +1238,17 @@ impl RowGroup {
     }
 
     /// Returns the distinct set of values for the selected columns, constrained
-    /// by an optional predicate.
+    /// by an optional predicate. No more values are collected for a column
+    /// once `dst` holds `limit` values for it.
     pub fn column_values<'a>(
         &'a self,
         predicate: &Predicate,
         columns: &[ColumnName<'_>],
         mut dst: BTreeMap<String, BTreeSet<String>>,
+        limit: Option<usize>,
     ) -> BTreeMap<String, BTreeSet<String>> {
+        let limit = limit.unwrap_or(usize::MAX);
+
         // Build up candidate columns
         let candidate_columns = self
             .all_columns_by_name
@@ -1050,8 +1266,10 @@ impl RowGroup {
             .filter(|(name, column)| {
                 match dst.get(*name) {
                     // process the column if we haven't got all the distinct
-                    // values.
-                    Some(values) => column.has_other_non_null_string_values(values),
+                    // values, or as many as we were asked for.
+                    Some(values) => {
+                        values.len() < limit && column.has_other_non_null_string_values(values)
+                    }
                     // no existing values for this column - we will need to
                     // process it.
                     None => true,
@@ -1072,6 +1290,9 @@ impl RowGroup {
 
             let results = dst.entry(name.clone()).or_default();
             for v in column.distinct_values(row_itr).into_iter().flatten() {
+                if results.len() >= limit {
+                    break;
+                }
                 if !results.contains(v) {
                     results.insert(v.to_owned());
                 }
@@ -1084,6 +1305,37 @@ impl RowGroup {
     pub(crate) fn column_storage_statistics(&self) -> Vec<column::Statistics> {
         self.columns.iter().map(|c| c.storage_stats()).collect()
     }
+
+    /// Returns all rows of the row group as a record batch, with the columns
+    /// in the order they were provided when creating the row group.
+    pub(crate) fn to_record_batch(&self) -> Result<RecordBatch> {
+        let mut names = vec![""; self.columns.len()];
+        for (name, &id) in &self.all_columns_by_name {
+            names[id] = name.as_str();
+        }
+
+        RecordBatch::try_from(self.read_filter(&names, &Predicate::default()))
+    }
+
+    /// Returns the names of the string columns stored with a plain dictionary
+    /// encoding, rather than a run-length encoding.
+    pub(crate) fn dictionary_encoded_columns(&self) -> BTreeSet<String> {
+        self.all_columns_by_name
+            .iter()
+            .filter(|(_, &id)| {
+                self.columns[id].storage_stats().enc_type == dictionary::ENCODING_NAME
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    // Returns the storage statistics for the named column.
+    //
+    // It is the caller's responsibility to ensure the column exists in the read
+    // group. Panics if the column doesn't exist.
+    pub(crate) fn column_storage_statistics_for(&self, name: ColumnName<'_>) -> column::Statistics {
+        self.column_by_name(name).storage_stats()
+    }
 }
 
 impl std::fmt::Display for &RowGroup {
@@ -1115,6 +1367,44 @@ impl std::fmt::Display for &RowGroup {
 /// column.
 impl From<RecordBatch> for RowGroup {
     fn from(rb: RecordBatch) -> Self {
+        Self::from_record_batch(rb, DEFAULT_CARDINALITY_RATIO_DICTIONARY_ENCODING_LIMIT)
+    }
+}
+
+impl RowGroup {
+    /// Creates a row group from a record batch.
+    ///
+    /// String columns where the ratio of distinct values to rows exceeds
+    /// `cardinality_ratio_limit` are stored with a plain dictionary encoding
+    /// rather than a run-length encoded one.
+    pub fn from_record_batch(rb: RecordBatch, cardinality_ratio_limit: f64) -> Self {
+        Self::from_record_batch_with(rb, |_| cardinality_ratio_limit)
+    }
+
+    /// Creates a row group from a record batch produced by
+    /// [`RowGroup::to_record_batch`], storing the string columns named in
+    /// `dictionary_columns` with a plain dictionary encoding and all other
+    /// string columns with a run-length encoding.
+    pub(crate) fn from_record_batch_with_encodings(
+        rb: RecordBatch,
+        dictionary_columns: &BTreeSet<String>,
+    ) -> Self {
+        Self::from_record_batch_with(rb, |name| {
+            if dictionary_columns.contains(name) {
+                // any ratio exceeds this
+                -1.0
+            } else {
+                f64::INFINITY
+            }
+        })
+    }
+
+    // Creates a row group from a record batch, using the cardinality ratio
+    // limit returned for each string column's name.
+    fn from_record_batch_with(
+        rb: RecordBatch,
+        cardinality_ratio_limit: impl Fn(&str) -> f64,
+    ) -> Self {
         let rows = rb.num_rows();
         // TODO proper error handling here if the input schema is bad
         let schema: Schema = rb
@@ -1130,18 +1420,20 @@ impl From<RecordBatch> for RowGroup {
             match lp_type {
                 Some(InfluxColumnType::Tag) => {
                     let column_data = match arrow_column.data_type() {
-                        DataType::Utf8 => Column::from(arrow::array::StringArray::from(
-                            arrow_column.data().clone(),
+                        DataType::Utf8 => Column::from(StringEncoding::from_string_array(
+                            arrow::array::StringArray::from(arrow_column.data().clone()),
+                            cardinality_ratio_limit(col_name),
                         )),
                         DataType::Dictionary(key, value)
                             if key.as_ref() == &DataType::Int32
                                 && value.as_ref() == &DataType::Utf8 =>
                         {
-                            Column::from(
+                            Column::from(StringEncoding::from_dictionary_array(
                                 arrow::array::DictionaryArray::<arrow::datatypes::Int32Type>::from(
                                     arrow_column.data().clone(),
                                 ),
-                            )
+                                cardinality_ratio_limit(col_name),
+                            ))
                         }
                         _ => panic!("invalid tag column type"),
                     };
@@ -1162,8 +1454,9 @@ impl From<RecordBatch> for RowGroup {
                         DataType::Boolean => Column::from(arrow::array::BooleanArray::from(
                             arrow_column.data().clone(),
                         )),
-                        DataType::Utf8 => Column::from(arrow::array::StringArray::from(
-                            arrow_column.data().clone(),
+                        DataType::Utf8 => Column::from(StringEncoding::from_string_array(
+                            arrow::array::StringArray::from(arrow_column.data().clone()),
+                            cardinality_ratio_limit(col_name),
                         )),
                         dt => unimplemented!(
                             "data type {:?} currently not supported for field columns",
@@ -1209,7 +1502,7 @@ fn unpack_u128_group_key(group_key_packed: u128, n: usize, mut dst: Vec<u32>) ->
     dst
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Default, Debug, PartialEq, Hash)]
 pub struct Predicate(Vec<BinaryExpr>);
 
 impl Predicate {
@@ -1284,6 +1577,21 @@ pub enum Literal {
     Boolean(bool),
 }
 
+impl Hash for Literal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::String(v) => v.hash(state),
+            Self::Integer(v) => v.hash(state),
+            Self::Unsigned(v) => v.hash(state),
+            // -0.0 and 0.0 compare equal so must hash the same
+            Self::Float(v) if *v == 0.0 => 0_u64.hash(state),
+            Self::Float(v) => v.to_bits().hash(state),
+            Self::Boolean(v) => v.hash(state),
+        }
+    }
+}
+
 impl<'a> TryFrom<&DFScalarValue> for Literal {
     type Error = String;
 
@@ -1320,7 +1628,7 @@ impl<'a> TryFrom<&DFScalarValue> for Literal {
 
 /// An expression that contains a column name on the left side, an operator, and
 /// a literal value on the right side.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Hash)]
 pub struct BinaryExpr {
     col: String,
     op: Operator,
@@ -1348,6 +1656,28 @@ impl BinaryExpr {
         &self.value
     }
 
+    /// Checks that the expression can be evaluated, returning a description of
+    /// the problem if not. Regex operators require a string literal that is a
+    /// valid pattern.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.op.is_regex() {
+            match &self.value {
+                Literal::String(pattern) => {
+                    if let Err(e) = regex::Regex::new(pattern) {
+                        return Err(format!("invalid regex {:?}: {}", pattern, e));
+                    }
+                }
+                v => {
+                    return Err(format!(
+                        "operator {} requires a regex, got {:?}",
+                        self.op, v
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn literal_as_value(&self) -> Value<'_> {
         match self.literal() {
             Literal::String(v) => Value::String(v),
@@ -1607,6 +1937,10 @@ impl MetaData {
             // if the column min is at least as small as value then the column
             // could contain the value.
             Operator::LTE => column_min <= value,
+
+            // a regex can only match string values, and which values match
+            // can't be determined from the column range.
+            Operator::Regex | Operator::NotRegex => matches!(column_max, OwnedValue::String(_)),
         }
     }
 
@@ -1680,7 +2014,7 @@ pub struct ReadFilterResult<'row_group> {
     data: Vec<Values<'row_group>>,
 }
 
-impl ReadFilterResult<'_> {
+impl<'row_group> ReadFilterResult<'row_group> {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
@@ -1688,6 +2022,35 @@ impl ReadFilterResult<'_> {
     pub fn schema(&self) -> &ResultSchema {
         &self.schema
     }
+
+    /// Concatenates the results of several row groups, which must share the
+    /// same schema, into a single result. Empty results are skipped and
+    /// `None` is returned if all of them are empty.
+    pub fn concat(results: Vec<Self>) -> Result<Option<Self>> {
+        let mut results = results.into_iter().filter(|result| !result.is_empty());
+        let first = match results.next() {
+            Some(first) => first,
+            None => return Ok(None),
+        };
+
+        let schema = first.schema;
+        let mut columns: Vec<Vec<Values<'row_group>>> =
+            first.data.into_iter().map(|values| vec![values]).collect();
+        for result in results {
+            assert_eq!(result.schema(), &schema); // validate schema
+            for (column, values) in columns.iter_mut().zip(result.data) {
+                column.push(values);
+            }
+        }
+
+        let data = columns
+            .into_iter()
+            .map(Values::concat)
+            .collect::<Result<Vec<_>, _>>()
+            .context(ConcatenatingResults)?;
+
+        Ok(Some(Self { schema, data }))
+    }
 }
 
 impl TryFrom<ReadFilterResult<'_>> for RecordBatch {
@@ -1910,34 +2273,33 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                 // and an iterator of the same type to extend the aggregate vec.
                 for (col_i, (_, _, data_type)) in result.schema.aggregate_columns.iter().enumerate()
                 {
+                    let arr = other.aggregate_cols.remove(0);
+                    if let Some(times) = arr.times() {
+                        result.aggregate_cols[col_i].extend_with_times(times.iter().cloned());
+                    }
+
                     match data_type {
                         LogicalDataType::Integer => {
-                            let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_i64(arr.take_as_i64().into_iter());
                         }
                         LogicalDataType::Unsigned => {
-                            let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_u64(arr.take_as_u64().into_iter());
                         }
                         LogicalDataType::Float => {
-                            let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_f64(arr.take_as_f64().into_iter());
                         }
                         LogicalDataType::String => {
-                            let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_str(arr.take_as_str().into_iter());
                         }
                         LogicalDataType::Binary => {
-                            let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_bytes(arr.take_as_bytes().into_iter());
                         }
                         LogicalDataType::Boolean => {
-                            let arr = other.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_bool(arr.take_as_bool().into_iter());
                         }
@@ -1954,34 +2316,33 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                 // add the rest of self's aggregate columns
                 for (col_i, (_, _, data_type)) in result.schema.aggregate_columns.iter().enumerate()
                 {
+                    let arr = self.aggregate_cols.remove(0);
+                    if let Some(times) = arr.times() {
+                        result.aggregate_cols[col_i].extend_with_times(times.iter().cloned());
+                    }
+
                     match data_type {
                         LogicalDataType::Integer => {
-                            let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_i64(arr.take_as_i64().into_iter());
                         }
                         LogicalDataType::Unsigned => {
-                            let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_u64(arr.take_as_u64().into_iter());
                         }
                         LogicalDataType::Float => {
-                            let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_f64(arr.take_as_f64().into_iter());
                         }
                         LogicalDataType::String => {
-                            let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_str(arr.take_as_str().into_iter());
                         }
                         LogicalDataType::Binary => {
-                            let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_bytes(arr.take_as_bytes().into_iter());
                         }
                         LogicalDataType::Boolean => {
-                            let arr = self.aggregate_cols.remove(0);
                             result.aggregate_cols[col_i]
                                 .extend_with_bool(arr.take_as_bool().into_iter());
                         }
@@ -2014,7 +2375,7 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                         col.push(self.group_key_cols[col_i][self_i]);
                     }
                     for (col_i, col) in result.aggregate_cols.iter_mut().enumerate() {
-                        col.push(self.aggregate_cols[col_i].value(self_i));
+                        col.push_from(&self.aggregate_cols[col_i], self_i);
                     }
                     self_i += 1;
                 }
@@ -2026,9 +2387,32 @@ impl<'row_group> ReadAggregateResult<'row_group> {
 
                     // merge all the aggregates for this group key.
                     for (col_i, col) in result.aggregate_cols.iter_mut().enumerate() {
+                        let (_, agg_type, _) = &self.schema.aggregate_columns[col_i];
+
+                        // first/last keep whichever value has the
+                        // earliest/latest timestamp.
+                        if let (Some(self_times), Some(other_times)) = (
+                            self.aggregate_cols[col_i].times(),
+                            other.aggregate_cols[col_i].times(),
+                        ) {
+                            let use_other = match (self_times[self_i], other_times[other_i]) {
+                                (Some(self_time), Some(other_time)) => match agg_type {
+                                    AggregateType::First => other_time < self_time,
+                                    _ => other_time >= self_time,
+                                },
+                                (None, Some(_)) => true,
+                                (_, None) => false,
+                            };
+
+                            match use_other {
+                                true => col.push_from(&other.aggregate_cols[col_i], other_i),
+                                false => col.push_from(&self.aggregate_cols[col_i], self_i),
+                            }
+                            continue;
+                        }
+
                         let self_value = self.aggregate_cols[col_i].value(self_i);
                         let other_value = other.aggregate_cols[col_i].value(other_i);
-                        let (_, agg_type, _) = &self.schema.aggregate_columns[col_i];
                         col.push(match agg_type {
                             AggregateType::Count => self_value + other_value,
                             AggregateType::Min => match self_value.partial_cmp(&other_value) {
@@ -2048,7 +2432,9 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                                 None => self_value,
                             },
                             AggregateType::Sum => self_value + other_value,
-                            _ => unimplemented!("first/last not implemented"),
+                            AggregateType::First | AggregateType::Last => {
+                                unreachable!("first/last aggregates have timestamps")
+                            }
                         });
                     }
                     self_i += 1;
@@ -2060,7 +2446,7 @@ impl<'row_group> ReadAggregateResult<'row_group> {
                         col.push(other.group_key_cols[col_i][other_i]);
                     }
                     for (col_i, col) in result.aggregate_cols.iter_mut().enumerate() {
-                        col.push(other.aggregate_cols[col_i].value(other_i));
+                        col.push_from(&other.aggregate_cols[col_i], other_i);
                     }
                     other_i += 1;
                 }
@@ -2499,6 +2885,90 @@ west,4
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn read_filter_regex() {
+        let mut columns = vec![];
+        let tc = ColumnType::Time(Column::from(&[1_i64, 2, 3, 4, 5, 6][..]));
+        columns.push(("time".to_string(), tc));
+
+        let rc = ColumnType::Tag(Column::from(
+            &[
+                "us-west",
+                "us-east",
+                "eu-west",
+                "us-west",
+                "asia",
+                "eu-central",
+            ][..],
+        ));
+        columns.push(("region".to_string(), rc));
+
+        let fc = ColumnType::Field(Column::from(&[100_u64, 101, 200, 203, 203, 10][..]));
+        columns.push(("count".to_string(), fc));
+
+        let row_group = RowGroup::new(6, columns);
+
+        let cases = vec![
+            (
+                BinaryExpr::from(("region", "=~", "^us-")),
+                "region,time
+us-west,1
+us-east,2
+us-west,4
+",
+                true,
+            ),
+            (
+                BinaryExpr::from(("region", "!~", "west$")),
+                "region,time
+us-east,2
+asia,5
+eu-central,6
+",
+                true,
+            ),
+            (
+                BinaryExpr::from(("region", "=~", "^africa")),
+                "region,time
+",
+                false,
+            ),
+        ];
+
+        for (expr, expected, satisfies) in cases {
+            assert!(row_group.could_satisfy_conjunctive_binary_expressions(&[expr.clone()]));
+
+            let results = row_group.read_filter(&["region", "time"], &col_pred(expr.clone()));
+            assert_eq!(format!("{:?}", &results), expected, "{:?}", expr);
+            assert_eq!(
+                row_group.satisfies_predicate(&col_pred(expr.clone())),
+                satisfies,
+                "{:?}",
+                expr
+            );
+        }
+
+        // regex predicates never match non-string columns
+        let expr = BinaryExpr::from(("count", "=~", "1"));
+        assert!(!row_group.could_satisfy_conjunctive_binary_expressions(&[expr.clone()]));
+        assert!(!row_group.satisfies_predicate(&col_pred(expr)));
+    }
+
+    #[test]
+    fn binary_expr_validate() {
+        assert!(BinaryExpr::from(("region", "=~", "^us-"))
+            .validate()
+            .is_ok());
+        assert!(BinaryExpr::from(("region", "!~", "west$"))
+            .validate()
+            .is_ok());
+        assert!(BinaryExpr::from(("region", "=", "(")).validate().is_ok());
+
+        assert!(BinaryExpr::from(("region", "=~", "(")).validate().is_err());
+        assert!(BinaryExpr::from(("region", "!~", "(")).validate().is_err());
+        assert!(BinaryExpr::from(("count", "=~", 1_i64)).validate().is_err());
+    }
+
     #[test]
     fn read_filter_dictionaries() {
         let mut columns = vec![];
@@ -2605,6 +3075,76 @@ POST,west,2
         read_aggregate_single_groupby_column(&row_group);
     }
 
+    #[test]
+    fn read_aggregate_from_stats() {
+        let mut columns = vec![];
+        let tc = ColumnType::Time(Column::from(&[3_i64, 1, 6, 2, 5, 4][..]));
+        columns.push(("time".to_string(), tc));
+
+        let rc = ColumnType::Tag(Column::from(
+            &["west", "west", "east", "west", "south", "north"][..],
+        ));
+        columns.push(("region".to_string(), rc));
+
+        let fc = ColumnType::Field(Column::from(&[100_u64, 101, 200, 203, 203, 10][..]));
+        columns.push(("counter".to_string(), fc));
+
+        let sc = ColumnType::Field(Column::from(arrow::array::Float64Array::from(vec![
+            Some(1.5),
+            None,
+            Some(-3.2),
+            None,
+            Some(20.0),
+            Some(4.0),
+        ])));
+        columns.push(("sketchy_sensor".to_string(), sc));
+
+        let row_group = RowGroup::new(6, columns);
+
+        let aggregates = vec![
+            ("time", AggregateType::Min),
+            ("time", AggregateType::Max),
+            ("region", AggregateType::Min),
+            ("region", AggregateType::Max),
+            ("counter", AggregateType::Min),
+            ("counter", AggregateType::Max),
+            ("counter", AggregateType::Count),
+            ("sketchy_sensor", AggregateType::Min),
+            ("sketchy_sensor", AggregateType::Max),
+            ("sketchy_sensor", AggregateType::Count),
+        ];
+
+        // no predicate - answered from column statistics where possible.
+        let from_stats = row_group.read_aggregate(&Predicate::default(), &[], &aggregates);
+
+        // a predicate covering all rows forces the columns to be scanned.
+        let scanned =
+            row_group.read_aggregate(&Predicate::with_time_range(&[], 0, 7), &[], &aggregates);
+
+        assert_eq!(from_stats.aggregate_cols, scanned.aggregate_cols);
+
+        let values = from_stats
+            .aggregate_cols
+            .iter()
+            .map(|col| col.value(0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Value::Scalar(Scalar::I64(1)),
+                Value::Scalar(Scalar::I64(6)),
+                Value::String("east"),
+                Value::String("west"),
+                Value::Scalar(Scalar::U64(10)),
+                Value::Scalar(Scalar::U64(203)),
+                Value::Scalar(Scalar::U64(6)),
+                Value::Scalar(Scalar::F64(-3.2)),
+                Value::Scalar(Scalar::F64(20.0)),
+                Value::Scalar(Scalar::U64(4)),
+            ]
+        );
+    }
+
     // the read_group path where grouping is on fewer than five columns.
     fn read_aggregate_hash_u128_key(row_group: &RowGroup) {
         let cases = vec![
@@ -3376,13 +3916,18 @@ west,host-c,pro,10,6
 
         let rg = RowGroup::new(3, columns);
 
-        let result = rg.column_values(&Predicate::default(), &["region"], BTreeMap::new());
+        let result = rg.column_values(&Predicate::default(), &["region"], BTreeMap::new(), None);
         assert_eq!(
             result,
             to_map(vec![("region", &["north", "west", "south"])])
         );
 
-        let result = rg.column_values(&Predicate::default(), &["env", "region"], BTreeMap::new());
+        let result = rg.column_values(
+            &Predicate::default(),
+            &["env", "region"],
+            BTreeMap::new(),
+            None,
+        );
         assert_eq!(
             result,
             to_map(vec![
@@ -3395,6 +3940,7 @@ west,host-c,pro,10,6
             &Predicate::new(vec![BinaryExpr::from(("time", ">", 1_i64))]),
             &["env", "region"],
             BTreeMap::new(),
+            None,
         );
         assert_eq!(
             result,
@@ -3410,6 +3956,7 @@ west,host-c,pro,10,6
             &Predicate::new(vec![BinaryExpr::from(("time", ">", 1_i64))]),
             &["env", "region"],
             dst,
+            None,
         );
         assert_eq!(
             result,
@@ -3420,10 +3967,73 @@ west,host-c,pro,10,6
             &Predicate::new(vec![BinaryExpr::from(("time", ">", 4_i64))]),
             &["env", "region"],
             BTreeMap::new(),
+            None,
         );
         assert_eq!(result, to_map(vec![]));
     }
 
+    #[test]
+    fn high_cardinality_string_encoding() {
+        use arrow::{
+            array::{DictionaryArray, TimestampNanosecondArray},
+            datatypes::Int32Type,
+        };
+        use internal_types::schema::builder::SchemaBuilder;
+
+        let rows = 2_000;
+        let request_ids = (0..rows)
+            .map(|i| format!("request-{}", i))
+            .collect::<Vec<_>>();
+
+        let schema = SchemaBuilder::new()
+            .non_null_tag("region")
+            .non_null_tag("request_id")
+            .timestamp()
+            .build()
+            .unwrap();
+        let data: Vec<ArrayRef> = vec![
+            Arc::new(
+                (0..rows)
+                    .map(|i| if i % 2 == 0 { "west" } else { "east" })
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ),
+            Arc::new(
+                request_ids
+                    .iter()
+                    .map(|id| id.as_str())
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ),
+            Arc::new(TimestampNanosecondArray::from_vec(
+                (0..rows as i64).collect(),
+                None,
+            )),
+        ];
+        let rb = RecordBatch::try_new(schema.into(), data).unwrap();
+
+        let rg = RowGroup::from(rb.clone());
+        assert_eq!(rg.column_by_name("region").storage_stats().enc_type, "RLE");
+        assert_eq!(
+            rg.column_by_name("request_id").storage_stats().enc_type,
+            "DICT"
+        );
+
+        // the plain dictionary encoded column still answers queries
+        let result = rg.column_values(
+            &Predicate::new(vec![BinaryExpr::from(("time", "=", 42_i64))]),
+            &["request_id"],
+            BTreeMap::new(),
+            None,
+        );
+        assert_eq!(result, to_map(vec![("request_id", &["request-42"])]));
+
+        // the limit is configurable
+        let rg = RowGroup::from_record_batch(rb, 1.0);
+        assert_eq!(
+            rg.column_by_name("request_id").storage_stats().enc_type,
+            "RLE"
+        );
+    }
+
     use datafusion::logical_plan::*;
     use datafusion::scalar::ScalarValue;
     use std::convert::TryFrom;
@@ -1,15 +1,18 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     convert::TryInto,
-    fmt::Display,
+    num::NonZeroU64,
     sync::Arc,
 };
 
 use parking_lot::RwLock;
-use snafu::{ensure, Snafu};
+use snafu::{ensure, ResultExt, Snafu};
 
 use arrow::record_batch::RecordBatch;
-use data_types::{chunk_metadata::ChunkColumnSummary, partition_metadata::TableSummary};
+use data_types::{
+    chunk_metadata::ChunkColumnSummary,
+    partition_metadata::{InfluxDbType, Statistics, TableSummary},
+};
 use internal_types::selection::Selection;
 
 use crate::schema::{AggregateType, ColumnType, LogicalDataType, ResultSchema};
@@ -29,6 +32,9 @@ pub enum Error {
 
     #[snafu(display("unsupported column operation on {}: {}", column_name, msg))]
     UnsupportedColumnOperation { msg: String, column_name: String },
+
+    #[snafu(display("error processing row group: {}", source))]
+    RowGroupError { source: row_group::Error },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -112,6 +118,38 @@ impl Table {
         row_groups.data.push(Arc::new(rg));
     }
 
+    /// Moves all of the row groups of `other` into this table.
+    ///
+    /// It is the caller's responsibility to ensure both tables have the same
+    /// schema (see `has_same_schema`).
+    pub fn absorb(&mut self, other: Self) {
+        let other = other.table_data.into_inner();
+        let mut row_groups = self.table_data.write();
+
+        for rg in other.data {
+            row_groups.meta = Arc::new(MetaData::update_with(
+                MetaData::clone(&row_groups.meta), // clone meta-data not Arc
+                &rg,
+            ));
+            row_groups.data.push(rg);
+        }
+    }
+
+    /// Determines if this table and `other` have the same columns with the
+    /// same types. Empty tables have the same schema as any other table.
+    pub fn has_same_schema(&self, other: &Self) -> bool {
+        let this = self.meta();
+        let other = other.meta();
+        this.columns.is_empty() || other.columns.is_empty() || this.columns == other.columns
+    }
+
+    /// Determines if `rg` has the same columns with the same types as the
+    /// row groups already in this table, and so can be added to it.
+    pub fn can_add_row_group(&self, rg: &RowGroup) -> bool {
+        let this = self.meta();
+        this.columns.is_empty() || this.columns == rg.metadata().columns
+    }
+
     /// TODO(edd): wire up
     ///
     /// Remove the row group at `position` from table, returning an error if the
@@ -131,6 +169,41 @@ impl Table {
         Ok(())
     }
 
+    /// Deletes all rows that satisfy the predicate from the table, returning
+    /// the number of rows deleted.
+    ///
+    /// Row groups containing deleted rows are replaced by new row groups
+    /// holding their remaining rows, and dropped if no rows remain.
+    pub fn delete(&self, predicate: &Predicate) -> Result<u64> {
+        let mut table_data = self.table_data.write();
+
+        let mut deleted = 0;
+        let mut row_groups = Vec::with_capacity(table_data.data.len());
+        for rg in &table_data.data {
+            let (rows, remaining) = rg.delete(predicate).context(RowGroupError)?;
+            if rows == 0 {
+                row_groups.push(Arc::clone(rg));
+                continue;
+            }
+
+            deleted += rows as u64;
+            if let Some(remaining) = remaining {
+                row_groups.push(Arc::new(remaining));
+            }
+        }
+
+        if deleted > 0 {
+            table_data.meta = match row_groups.is_empty() {
+                // keep the schema of the table, which now has no rows
+                true => Arc::new(table_data.meta.without_rows()),
+                false => Arc::new(MetaData::from(&row_groups)), // rebuild meta
+            };
+            table_data.data = row_groups;
+        }
+
+        Ok(deleted)
+    }
+
     /// The name of the table (equivalent to measurement or table name).
     pub fn name(&self) -> &str {
         &self.name
@@ -192,6 +265,11 @@ impl Table {
         self.table_data.read().data.len()
     }
 
+    // Returns a snapshot of this table's row groups.
+    pub(crate) fn row_groups_snapshot(&self) -> Vec<Arc<RowGroup>> {
+        self.table_data.read().data.clone()
+    }
+
     /// The number of rows in this table.
     pub fn rows(&self) -> u64 {
         self.table_data.read().meta.rows
@@ -202,10 +280,95 @@ impl Table {
         self.table_data.read().meta.to_summary(&self.name)
     }
 
+    /// Returns an estimate of the number of distinct non-NULL values in the
+    /// column, assembled from row group meta-data without scanning any column
+    /// data. Returns `None` if the column does not exist.
+    ///
+    /// Row groups that don't track the column's cardinality contribute their
+    /// number of non-NULL values. The largest row group cardinality is a lower
+    /// bound for the table and the sum over all row groups is an upper bound;
+    /// the estimate is the mid-point between the two.
+    pub fn column_cardinality_estimate(&self, column_name: ColumnName<'_>) -> Option<u64> {
+        let table_data = self.table_data.read();
+        if !table_data.meta.has_column(column_name) {
+            return None;
+        }
+
+        let mut max = 0;
+        let mut sum = 0;
+        for rg in &table_data.data {
+            let storage = rg.column_storage_statistics_for(column_name);
+            let distinct = match rg
+                .metadata()
+                .columns
+                .get(column_name)
+                .and_then(|c| c.distinct_count)
+            {
+                // the cardinality of an encoding counts NULL as a value
+                Some(count) if storage.nulls > 0 => count.get() - 1,
+                Some(count) => count.get(),
+                None => (storage.values - storage.nulls) as u64,
+            };
+
+            max = max.max(distinct);
+            sum += distinct;
+        }
+
+        Some(max + (sum - max) / 2)
+    }
+
+    /// Returns statistics for the selected columns, assembled from the table
+    /// and row group meta-data without scanning any column data.
+    pub fn column_statistics(&self, columns: &Selection<'_>) -> Vec<ColumnStats> {
+        let table_data = self.table_data.read();
+        let summary = table_data.meta.to_summary(&self.name);
+
+        table_data
+            .meta
+            .schema_for_selection(columns)
+            .iter()
+            .map(|(typ, _)| {
+                let name = typ.as_str();
+                let column = summary.column(name).unwrap(); // schema came from meta
+
+                let mut null_count = 0;
+                let mut encodings = BTreeSet::new();
+                let mut row_group_distinct = None;
+                for rg in &table_data.data {
+                    let storage = rg.column_storage_statistics_for(name);
+                    null_count += storage.nulls as u64;
+                    encodings.insert(storage.enc_type.to_string());
+
+                    let distinct = rg
+                        .metadata()
+                        .columns
+                        .get(name)
+                        .and_then(|c| c.distinct_count);
+                    row_group_distinct = row_group_distinct.max(distinct);
+                }
+
+                ColumnStats {
+                    name: column.name.clone(),
+                    influxdb_type: column.influxdb_type.clone(),
+                    stats: column.stats.clone(),
+                    null_count,
+                    // exact if known for the table, otherwise the largest
+                    // row group value is used as a lower bound.
+                    distinct_count: column.stats.distinct_count().or(row_group_distinct),
+                    encodings: encodings.into_iter().collect(),
+                }
+            })
+            .collect()
+    }
+
     /// Returns the column range associated with an InfluxDB Timestamp column
-    /// or None if the table's schema does not have such a column.
+    /// or None if the table's schema does not have such a column or the table
+    /// has no rows.
     pub fn time_range(&self) -> Option<(i64, i64)> {
         let table_data = self.table_data.read();
+        if table_data.meta.rows == 0 {
+            return None;
+        }
 
         let time_column = table_data
             .meta
@@ -295,18 +458,21 @@ impl Table {
         let (meta, row_groups) = self.filter_row_groups(predicate);
 
         let schema = ResultSchema {
-            select_columns: match columns {
-                Selection::All => meta.schema_for_all_columns(),
-                Selection::Some(column_names) => meta.schema_for_column_names(column_names),
-            },
+            select_columns: meta.schema_for_selection(columns),
             ..ResultSchema::default()
         };
 
+        // Row groups are only pruned using their statistics, so every row in
+        // them may still be emitted.
+        let estimated_rows = row_groups.iter().map(|rg| rg.rows() as usize).sum();
+
         // TODO(edd): I think I can remove `predicates` from the results
         ReadFilterResults {
             predicate: predicate.clone(),
             schema,
             row_groups,
+            estimated_rows,
+            rows_emitted: 0,
         }
     }
 
@@ -329,10 +495,7 @@ impl Table {
 
         // Filter out any column names that we do not have data for.
         let schema = ResultSchema {
-            group_columns: match group_columns {
-                Selection::All => meta.schema_for_all_columns(),
-                Selection::Some(column_names) => meta.schema_for_column_names(column_names),
-            },
+            group_columns: meta.schema_for_selection(group_columns),
             aggregate_columns: meta.schema_for_aggregate_column_names(aggregates),
             ..ResultSchema::default()
         };
@@ -484,11 +647,15 @@ impl Table {
     /// predicate. All values are deduplicated across row groups in the table.
     ///
     /// If the predicate is empty then all distinct values are returned.
+    ///
+    /// If `limit` is provided then at most `limit` arbitrary distinct values
+    /// are collected for each column.
     pub fn column_values<'a>(
         &'a self,
         predicate: &Predicate,
         columns: &[ColumnName<'_>],
         mut dst: BTreeMap<String, BTreeSet<String>>,
+        limit: Option<usize>,
     ) -> Result<BTreeMap<String, BTreeSet<String>>> {
         let (meta, row_groups) = self.filter_row_groups(predicate);
 
@@ -504,7 +671,7 @@ impl Table {
         }
 
         for row_group in row_groups {
-            dst = row_group.column_values(predicate, columns, dst)
+            dst = row_group.column_values(predicate, columns, dst, limit)
         }
 
         Ok(dst)
@@ -549,6 +716,38 @@ impl Table {
     }
 }
 
+/// Statistics describing a single column of a table, intended for use in
+/// planning decisions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub name: String,
+
+    /// The column's Influx data model type (if any)
+    pub influxdb_type: Option<InfluxDbType>,
+
+    /// The min/max values and row count of the column
+    pub stats: Statistics,
+
+    /// The number of NULL values in the column
+    pub null_count: u64,
+
+    /// The approximate number of distinct values in the column, if known.
+    pub distinct_count: Option<NonZeroU64>,
+
+    /// The distinct set of encodings used for the column across row groups
+    pub encodings: Vec<String>,
+}
+
+impl ColumnStats {
+    /// The fraction of rows in the column that are NULL
+    pub fn null_fraction(&self) -> f64 {
+        match self.stats.count() {
+            0 => 0.0,
+            rows => self.null_count as f64 / rows as f64,
+        }
+    }
+}
+
 // TODO(edd): reduce owned strings here by, e.g., using references as keys.
 #[derive(Clone, Default)]
 pub struct MetaData {
@@ -591,12 +790,43 @@ impl MetaData {
         (base_size + columns_meta_size + column_names_size) + self.size
     }
 
+    /// Returns meta data with the same columns as `self` that describes a
+    /// table without any rows, e.g., once all of its rows have been deleted.
+    ///
+    /// Column ranges are retained so the meta data remains well-formed, but
+    /// they are replaced once a row group is added.
+    pub fn without_rows(&self) -> Self {
+        let columns = self
+            .columns
+            .iter()
+            .map(|(name, column_meta)| {
+                let column_meta = row_group::ColumnMeta {
+                    distinct_count: None,
+                    ..column_meta.clone()
+                };
+                (name.clone(), column_meta)
+            })
+            .collect();
+
+        Self {
+            size: 0,
+            rows: 0,
+            columns,
+            column_names: self.column_names.clone(),
+        }
+    }
+
     /// Create a new `MetaData` by consuming `this` and incorporating `other`.
     pub fn update_with(mut this: Self, rg: &row_group::RowGroup) -> Self {
         let other = rg.metadata();
 
-        // first row group added to the table.
-        if this.columns.is_empty() {
+        // first row group added to the table, or the first since all of the
+        // table's rows were deleted.
+        if this.columns.is_empty() || this.rows == 0 {
+            // The incoming row group must have the same schema as the table
+            // had before its rows were deleted.
+            assert!(this.columns.is_empty() || this.columns == other.columns);
+
             this.size = other.size();
             this.rows = other.rows as u64;
             this.columns = other.columns.clone();
@@ -661,6 +891,35 @@ impl MetaData {
         column_schema
     }
 
+    /// As `schema_for_all_columns` but excluding the provided column names.
+    /// Names that do not exist within the `Table` schema are ignored.
+    pub fn schema_for_all_columns_except(
+        &self,
+        names: &[ColumnName<'_>],
+    ) -> Vec<(ColumnType, LogicalDataType)> {
+        self.column_names
+            .iter()
+            .filter(|name| !names.contains(&name.as_str()))
+            .map(|name| {
+                let schema = self.columns.get(name).unwrap();
+                (schema.typ.clone(), schema.logical_data_type)
+            })
+            .collect::<Vec<_>>()
+    }
+
+    /// Extract schema information for the columns identified by the provided
+    /// selection.
+    pub fn schema_for_selection(
+        &self,
+        selection: &Selection<'_>,
+    ) -> Vec<(ColumnType, LogicalDataType)> {
+        match selection {
+            Selection::All => self.schema_for_all_columns(),
+            Selection::Some(column_names) => self.schema_for_column_names(column_names),
+            Selection::Except(column_names) => self.schema_for_all_columns_except(column_names),
+        }
+    }
+
     // As `schema_for_column_names` but also embeds the provided aggregate type.
     fn schema_for_aggregate_column_names(
         &self,
@@ -693,6 +952,26 @@ impl MetaData {
 
     pub fn to_summary(&self, table_name: impl Into<String>) -> TableSummary {
         use data_types::partition_metadata::{ColumnSummary, StatValues, Statistics};
+
+        // A table without rows has no minimum or maximum values.
+        fn stat_values<T>(
+            min: T,
+            max: T,
+            count: u64,
+            distinct_count: Option<NonZeroU64>,
+        ) -> StatValues<T> {
+            let (min, max) = match count {
+                0 => (None, None),
+                _ => (Some(min), Some(max)),
+            };
+            StatValues {
+                min,
+                max,
+                distinct_count,
+                count,
+            }
+        }
+
         let columns = self
             .columns
             .iter()
@@ -701,41 +980,22 @@ impl MetaData {
                 let distinct_count = column_meta.distinct_count;
 
                 let stats = match &column_meta.range {
-                    (OwnedValue::String(min), OwnedValue::String(max)) => {
-                        Statistics::String(StatValues {
-                            min: Some(min.to_string()),
-                            max: Some(max.to_string()),
-                            distinct_count,
-                            count,
-                        })
-                    }
+                    (OwnedValue::String(min), OwnedValue::String(max)) => Statistics::String(
+                        stat_values(min.to_string(), max.to_string(), count, distinct_count),
+                    ),
                     (OwnedValue::Boolean(min), OwnedValue::Boolean(max)) => {
-                        Statistics::Bool(StatValues {
-                            min: Some(*min),
-                            max: Some(*max),
-                            distinct_count,
-                            count,
-                        })
+                        Statistics::Bool(stat_values(*min, *max, count, distinct_count))
                     }
                     (OwnedValue::Scalar(min), OwnedValue::Scalar(max)) => match (min, max) {
-                        (Scalar::I64(min), Scalar::I64(max)) => Statistics::I64(StatValues {
-                            min: Some(*min),
-                            max: Some(*max),
-                            distinct_count,
-                            count,
-                        }),
-                        (Scalar::U64(min), Scalar::U64(max)) => Statistics::U64(StatValues {
-                            min: Some(*min),
-                            max: Some(*max),
-                            distinct_count,
-                            count,
-                        }),
-                        (Scalar::F64(min), Scalar::F64(max)) => Statistics::F64(StatValues {
-                            min: Some(*min),
-                            max: Some(*max),
-                            distinct_count,
-                            count,
-                        }),
+                        (Scalar::I64(min), Scalar::I64(max)) => {
+                            Statistics::I64(stat_values(*min, *max, count, distinct_count))
+                        }
+                        (Scalar::U64(min), Scalar::U64(max)) => {
+                            Statistics::U64(stat_values(*min, *max, count, distinct_count))
+                        }
+                        (Scalar::F64(min), Scalar::F64(max)) => {
+                            Statistics::F64(stat_values(*min, *max, count, distinct_count))
+                        }
                         _ => panic!(
                             "unsupported type scalar stats in read buffer: {:?}, {:?}",
                             min, max
@@ -795,6 +1055,12 @@ pub struct ReadFilterResults {
     // TODO(edd): encapsulate this into a single executor function that just
     // executes on the next row group.
     predicate: Predicate,
+
+    // Total rows in the row groups that passed the predicates.
+    estimated_rows: usize,
+
+    // Rows in the record batches returned so far.
+    rows_emitted: usize,
 }
 
 impl ReadFilterResults {
@@ -812,20 +1078,35 @@ impl ReadFilterResults {
         &self.schema
     }
 
-    // useful for testing - materialise all results but don't convert them to
-    // record batches. Skips any row groups that don't have any results
-    fn row_group_results(&self) -> Vec<row_group::ReadFilterResult<'_>> {
+    /// An upper bound on the number of rows these results will emit.
+    ///
+    /// Row groups are pruned using their statistics only, so rows that will
+    /// be removed by the predicate are still counted.
+    pub fn estimated_rows(&self) -> usize {
+        self.estimated_rows
+    }
+
+    /// The number of rows in the record batches emitted so far.
+    pub fn rows_emitted(&self) -> usize {
+        self.rows_emitted
+    }
+
+    // useful for testing - materialise all results into a single result but
+    // don't convert it to a record batch. Returns `None` if no row group has
+    // any results.
+    fn materialise(&self) -> row_group::Result<Option<row_group::ReadFilterResult<'_>>> {
         let select_columns = &self
             .schema()
             .select_column_names_iter()
             .map(|name| name.as_str())
             .collect::<Vec<_>>();
 
-        self.row_groups
-            .iter()
-            .map(|row_group| row_group.read_filter(select_columns, &self.predicate))
-            .filter(|result| !result.is_empty())
-            .collect()
+        row_group::ReadFilterResult::concat(
+            self.row_groups
+                .iter()
+                .map(|row_group| row_group.read_filter(select_columns, &self.predicate))
+                .collect(),
+        )
     }
 }
 
@@ -852,29 +1133,9 @@ impl Iterator for ReadFilterResults {
         }
 
         assert_eq!(result.schema(), self.schema()); // validate schema
-        Some(result.try_into().unwrap())
-    }
-}
-
-// Helper type that can pretty print a set of results for `read_filter`.
-struct DisplayReadFilterResults<'a>(Vec<row_group::ReadFilterResult<'a>>);
-
-impl<'a> Display for DisplayReadFilterResults<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0.is_empty() {
-            return Ok(());
-        }
-
-        // write out the schema of the first result as the table header
-        std::fmt::Display::fmt(&self.0[0].schema(), f)?;
-        writeln!(f)?;
-
-        // write out each row group result
-        for row_group in self.0.iter() {
-            std::fmt::Display::fmt(&row_group, f)?;
-        }
-
-        Ok(())
+        let batch: RecordBatch = result.try_into().unwrap();
+        self.rows_emitted += batch.num_rows();
+        Some(batch)
     }
 }
 
@@ -1275,13 +1536,11 @@ mod test {
         };
         assert_eq!(results.schema(), &exp_schema);
 
-        let results = results.row_group_results();
-        for result in &results {
-            assert_eq!(result.schema(), &exp_schema);
-        }
+        let result = results.materialise().unwrap().unwrap();
+        assert_eq!(result.schema(), &exp_schema);
 
         assert_eq!(
-            format!("{}", DisplayReadFilterResults(results)),
+            format!("{:?}", &result),
             "time,count,region
 1,100,west
 2,101,west
@@ -1315,13 +1574,11 @@ mod test {
             ..ResultSchema::default()
         };
 
-        let results = results.row_group_results();
-        for result in &results {
-            assert_eq!(result.schema(), &exp_schema);
-        }
+        let result = results.materialise().unwrap().unwrap();
+        assert_eq!(result.schema(), &exp_schema);
 
         assert_eq!(
-            format!("{}", DisplayReadFilterResults(results)),
+            format!("{:?}", &result),
             "time,region
 1,west
 2,west
@@ -1333,6 +1590,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn read_filter_row_estimates() {
+        let mut table = Table::new("cpu");
+        for times in &[[1_i64, 2, 3], [10, 20, 30], [100, 200, 300]] {
+            let tc = ColumnType::Time(Column::from(&times[..]));
+            let fc = ColumnType::Field(Column::from(&[1_u64, 2, 3][..]));
+            let columns = vec![("time".to_string(), tc), ("count".to_string(), fc)];
+            table.add_row_group(RowGroup::new(3, columns));
+        }
+
+        // The first row group is pruned; the second and third are only
+        // partially within the time range.
+        let predicate = Predicate::with_time_range(&[], 15, 150);
+        let mut results = table.read_filter(&Selection::All, &predicate);
+        assert_eq!(results.estimated_rows(), 6);
+        assert_eq!(results.rows_emitted(), 0);
+
+        let mut emitted = 0;
+        while let Some(batch) = results.next() {
+            emitted += batch.num_rows();
+            assert_eq!(results.rows_emitted(), emitted);
+        }
+        assert_eq!(emitted, 3);
+        assert!(results.estimated_rows() >= results.rows_emitted());
+
+        // Nothing matches.
+        let predicate = Predicate::with_time_range(&[], 1000, 2000);
+        let mut results = table.read_filter(&Selection::All, &predicate);
+        assert_eq!(results.estimated_rows(), 0);
+        assert!(results.next().is_none());
+        assert_eq!(results.rows_emitted(), 0);
+    }
+
     #[test]
     fn read_aggregate_no_groups() {
         // Build first row group.
@@ -1422,6 +1712,84 @@ mod test {
         ),);
     }
 
+    #[test]
+    fn read_aggregate_first_last() {
+        // Build first row group.
+        let columns = vec![
+            (
+                "time".to_string(),
+                ColumnType::create_time(&[100, 200, 300]),
+            ),
+            (
+                "region".to_string(),
+                ColumnType::create_tag(&["west", "west", "east"]),
+            ),
+            (
+                "temp".to_string(),
+                ColumnType::Field(Column::from(&[1.5, 2.5, 3.5][..])),
+            ),
+        ];
+        let rg = RowGroup::new(3, columns);
+        let mut table = Table::new("cpu");
+        table.add_row_group(rg);
+
+        // Build another row group, which has the earliest value for "west" and
+        // the latest values overall.
+        let columns = vec![
+            ("time".to_string(), ColumnType::create_time(&[50, 400])),
+            (
+                "region".to_string(),
+                ColumnType::create_tag(&["west", "east"]),
+            ),
+            (
+                "temp".to_string(),
+                ColumnType::Field(Column::from(&[10.5, 20.5][..])),
+            ),
+        ];
+        let rg = RowGroup::new(2, columns);
+        table.add_row_group(rg);
+
+        let aggregates = [
+            ("temp", AggregateType::First),
+            ("temp", AggregateType::Last),
+        ];
+
+        // no grouping
+        let mut results = table
+            .read_aggregate(Predicate::default(), &Selection::Some(&[]), &aggregates)
+            .unwrap();
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "temp_first,temp_last\n10.5,20.5\n",
+        );
+
+        // group by region
+        let mut results = table
+            .read_aggregate(
+                Predicate::default(),
+                &Selection::Some(&["region"]),
+                &aggregates,
+            )
+            .unwrap();
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "region,temp_first,temp_last\neast,3.5,20.5\nwest,10.5,2.5\n",
+        );
+
+        // group by region with a predicate
+        let mut results = table
+            .read_aggregate(
+                Predicate::new(vec![BinaryExpr::from(("region", "=", "west"))]),
+                &Selection::Some(&["region"]),
+                &aggregates,
+            )
+            .unwrap();
+        assert_eq!(
+            DisplayReadAggregateResults(vec![results.next_merged_result().unwrap()]).to_string(),
+            "region,temp_first,temp_last\nwest,10.5,2.5\n",
+        );
+    }
+
     #[test]
     fn read_aggregate_result_display() {
         let result_a = ReadAggregateResult {
@@ -1,8 +1,24 @@
-use std::{convert::TryFrom, fmt::Formatter};
+use std::{cmp::Ordering, convert::TryFrom, fmt::Formatter};
 use std::{mem::size_of, sync::Arc};
 
+use snafu::{OptionExt, Snafu};
+
 use crate::{AggregateType, LogicalDataType};
 
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("no values to concatenate"))]
+    NoValues,
+
+    #[snafu(display("cannot concatenate {} values with {} values", expected, found))]
+    ConcatTypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
 #[derive(Clone, PartialEq, Debug)]
 /// A type that holds aggregates where each variant encodes the underlying data
 /// type and aggregate type for a vector of data. An `AggregateVec` can be
@@ -100,14 +116,47 @@ impl AggregateVec {
             Self::MaxString(arr) => Value::from(arr[offset].as_deref()),
             Self::MaxBytes(arr) => Value::from(arr[offset].as_deref()),
             Self::MaxBool(arr) => Value::from(arr[offset]),
-            _ => unimplemented!("first/last not yet implemented"),
+            Self::FirstU64((arr, _)) => Value::from(arr[offset]),
+            Self::FirstI64((arr, _)) => Value::from(arr[offset]),
+            Self::FirstF64((arr, _)) => Value::from(arr[offset]),
+            Self::FirstString((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::FirstBytes((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::FirstBool((arr, _)) => Value::from(arr[offset]),
+            Self::LastU64((arr, _)) => Value::from(arr[offset]),
+            Self::LastI64((arr, _)) => Value::from(arr[offset]),
+            Self::LastF64((arr, _)) => Value::from(arr[offset]),
+            Self::LastString((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::LastBytes((arr, _)) => Value::from(arr[offset].as_deref()),
+            Self::LastBool((arr, _)) => Value::from(arr[offset]),
+        }
+    }
+
+    /// Returns the timestamps associated with each value of a first/last
+    /// aggregate, or `None` for all other aggregates.
+    pub fn times(&self) -> Option<&[Option<i64>]> {
+        match self {
+            Self::FirstU64((_, times))
+            | Self::FirstI64((_, times))
+            | Self::FirstF64((_, times))
+            | Self::FirstString((_, times))
+            | Self::FirstBytes((_, times))
+            | Self::FirstBool((_, times))
+            | Self::LastU64((_, times))
+            | Self::LastI64((_, times))
+            | Self::LastF64((_, times))
+            | Self::LastString((_, times))
+            | Self::LastBytes((_, times))
+            | Self::LastBool((_, times)) => Some(times.as_slice()),
+            _ => None,
         }
     }
 
     /// Updates with a new value located in the provided input column help in
     /// `Values`.
     ///
-    /// Panics if the type of `Value` does not satisfy the aggregate type.
+    /// Panics if the type of `Value` does not satisfy the aggregate type, or if
+    /// the aggregate is a first/last aggregate, which must be updated via
+    /// `update_with_time`.
     pub fn update(&mut self, values: &Values<'_>, row_id: usize, offset: usize) {
         if values.is_null(row_id) {
             return;
@@ -291,16 +340,97 @@ impl AggregateVec {
                     None => arr[offset] = Some(values.value_bool(row_id)),
                 }
             }
-            // TODO - implement first/last
-            _ => unimplemented!("aggregate update not implemented"),
+            _ => panic!("{} must be updated with a timestamp", self),
+        }
+    }
+
+    /// Updates with a new value located in the provided input column held in
+    /// `Values`, whose timestamp is located at the same row in `times`.
+    ///
+    /// First/last aggregates keep the value with the earliest/latest timestamp,
+    /// all other aggregates ignore the timestamp.
+    pub fn update_with_time(
+        &mut self,
+        values: &Values<'_>,
+        times: &Values<'_>,
+        row_id: usize,
+        offset: usize,
+    ) {
+        if values.is_null(row_id) {
+            return;
+        }
+
+        let time = times.value_i64(row_id);
+        match self {
+            Self::FirstU64((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Less, || {
+                    values.value_u64(row_id)
+                })
+            }
+            Self::FirstI64((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Less, || {
+                    values.value_i64(row_id)
+                })
+            }
+            Self::FirstF64((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Less, || {
+                    values.value_f64(row_id)
+                })
+            }
+            Self::FirstString((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Less, || {
+                    values.value_str(row_id).to_owned()
+                })
+            }
+            Self::FirstBytes((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Less, || {
+                    values.value_bytes(row_id).to_owned()
+                })
+            }
+            Self::FirstBool((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Less, || {
+                    values.value_bool(row_id)
+                })
+            }
+            Self::LastU64((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Greater, || {
+                    values.value_u64(row_id)
+                })
+            }
+            Self::LastI64((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Greater, || {
+                    values.value_i64(row_id)
+                })
+            }
+            Self::LastF64((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Greater, || {
+                    values.value_f64(row_id)
+                })
+            }
+            Self::LastString((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Greater, || {
+                    values.value_str(row_id).to_owned()
+                })
+            }
+            Self::LastBytes((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Greater, || {
+                    values.value_bytes(row_id).to_owned()
+                })
+            }
+            Self::LastBool((arr, arr_times)) => {
+                update_by_time(arr, arr_times, offset, time, Ordering::Greater, || {
+                    values.value_bool(row_id)
+                })
+            }
+            _ => self.update(values, row_id, offset),
         }
     }
 
     /// Appends the provided value to the end of the aggregate vector.
     /// Panics if the type of `Value` does not satisfy the aggregate type.
     ///
-    /// Note: updating pushed first/last variants is not currently a supported
-    /// operation.
+    /// Note: this does not append a timestamp to first/last variants, which
+    /// should be appended to via `push_with_time`.
     pub fn push(&mut self, value: Value<'_>) {
         match self {
             Self::Count(arr) => {
@@ -502,6 +632,38 @@ impl AggregateVec {
         }
     }
 
+    /// Appends the provided value and its timestamp to the end of a first/last
+    /// aggregate vector.
+    ///
+    /// Panics if the aggregate is not a first/last aggregate.
+    pub fn push_with_time(&mut self, value: Value<'_>, time: Option<i64>) {
+        self.push(value);
+        match self {
+            Self::FirstU64((_, times))
+            | Self::FirstI64((_, times))
+            | Self::FirstF64((_, times))
+            | Self::FirstString((_, times))
+            | Self::FirstBytes((_, times))
+            | Self::FirstBool((_, times))
+            | Self::LastU64((_, times))
+            | Self::LastI64((_, times))
+            | Self::LastF64((_, times))
+            | Self::LastString((_, times))
+            | Self::LastBytes((_, times))
+            | Self::LastBool((_, times)) => times.push(time),
+            _ => panic!("cannot push timestamp onto {}", self),
+        }
+    }
+
+    /// Appends the value specified by `offset` in `other`, along with its
+    /// timestamp in the case of first/last aggregates.
+    pub fn push_from(&mut self, other: &Self, offset: usize) {
+        match other.times() {
+            Some(times) => self.push_with_time(other.value(offset), times[offset]),
+            None => self.push(other.value(offset)),
+        }
+    }
+
     /// Writes a textual representation of the value specified by `offset` to
     /// the provided formatter.
     pub fn write_value(&self, offset: usize, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -628,6 +790,8 @@ impl AggregateVec {
             Self::SumI64(arr) => arr,
             Self::MinI64(arr) => arr,
             Self::MaxI64(arr) => arr,
+            Self::FirstI64((arr, _)) => arr,
+            Self::LastI64((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<i64>>", self),
         }
     }
@@ -639,6 +803,8 @@ impl AggregateVec {
             Self::SumU64(arr) => arr,
             Self::MinU64(arr) => arr,
             Self::MaxU64(arr) => arr,
+            Self::FirstU64((arr, _)) => arr,
+            Self::LastU64((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<u64>>", self),
         }
     }
@@ -649,6 +815,8 @@ impl AggregateVec {
             Self::SumF64(arr) => arr,
             Self::MinF64(arr) => arr,
             Self::MaxF64(arr) => arr,
+            Self::FirstF64((arr, _)) => arr,
+            Self::LastF64((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<f64>>", self),
         }
     }
@@ -658,6 +826,8 @@ impl AggregateVec {
         match self {
             Self::MinString(arr) => arr,
             Self::MaxString(arr) => arr,
+            Self::FirstString((arr, _)) => arr,
+            Self::LastString((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<&str>>", self),
         }
     }
@@ -667,6 +837,8 @@ impl AggregateVec {
         match self {
             Self::MinBytes(arr) => arr,
             Self::MaxBytes(arr) => arr,
+            Self::FirstBytes((arr, _)) => arr,
+            Self::LastBytes((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<Option<&[u8]>>", self),
         }
     }
@@ -676,6 +848,8 @@ impl AggregateVec {
         match self {
             Self::MinBool(arr) => arr,
             Self::MaxBool(arr) => arr,
+            Self::FirstBool((arr, _)) => arr,
+            Self::LastBool((arr, _)) => arr,
             _ => panic!("cannot convert {} to Vec<u64>", self),
         }
     }
@@ -692,6 +866,12 @@ impl AggregateVec {
             Self::MaxI64(arr) => {
                 arr.extend(itr);
             }
+            Self::FirstI64((arr, _)) => {
+                arr.extend(itr);
+            }
+            Self::LastI64((arr, _)) => {
+                arr.extend(itr);
+            }
             _ => panic!("unsupported iterator"),
         }
     }
@@ -711,6 +891,12 @@ impl AggregateVec {
             Self::MaxU64(arr) => {
                 arr.extend(itr);
             }
+            Self::FirstU64((arr, _)) => {
+                arr.extend(itr);
+            }
+            Self::LastU64((arr, _)) => {
+                arr.extend(itr);
+            }
             _ => panic!("unsupported iterator"),
         }
     }
@@ -727,6 +913,12 @@ impl AggregateVec {
             Self::MaxF64(arr) => {
                 arr.extend(itr);
             }
+            Self::FirstF64((arr, _)) => {
+                arr.extend(itr);
+            }
+            Self::LastF64((arr, _)) => {
+                arr.extend(itr);
+            }
             _ => panic!("unsupported iterator"),
         }
     }
@@ -740,6 +932,12 @@ impl AggregateVec {
             Self::MaxString(arr) => {
                 arr.extend(itr);
             }
+            Self::FirstString((arr, _)) => {
+                arr.extend(itr);
+            }
+            Self::LastString((arr, _)) => {
+                arr.extend(itr);
+            }
             _ => panic!("unsupported iterator"),
         }
     }
@@ -753,6 +951,12 @@ impl AggregateVec {
             Self::MaxBytes(arr) => {
                 arr.extend(itr);
             }
+            Self::FirstBytes((arr, _)) => {
+                arr.extend(itr);
+            }
+            Self::LastBytes((arr, _)) => {
+                arr.extend(itr);
+            }
             _ => panic!("unsupported iterator"),
         }
     }
@@ -766,6 +970,32 @@ impl AggregateVec {
             Self::MaxBool(arr) => {
                 arr.extend(itr);
             }
+            Self::FirstBool((arr, _)) => {
+                arr.extend(itr);
+            }
+            Self::LastBool((arr, _)) => {
+                arr.extend(itr);
+            }
+            _ => panic!("unsupported iterator"),
+        }
+    }
+
+    /// Extends the timestamps of a first/last `AggregateVec` with the provided
+    /// `Option<i64>` iterator.
+    pub fn extend_with_times(&mut self, itr: impl Iterator<Item = Option<i64>>) {
+        match self {
+            Self::FirstU64((_, times))
+            | Self::FirstI64((_, times))
+            | Self::FirstF64((_, times))
+            | Self::FirstString((_, times))
+            | Self::FirstBytes((_, times))
+            | Self::FirstBool((_, times))
+            | Self::LastU64((_, times))
+            | Self::LastI64((_, times))
+            | Self::LastF64((_, times))
+            | Self::LastString((_, times))
+            | Self::LastBytes((_, times))
+            | Self::LastBool((_, times)) => times.extend(itr),
             _ => panic!("unsupported iterator"),
         }
     }
@@ -872,6 +1102,28 @@ impl AggregateVec {
     }
 }
 
+// Sets the value at `offset` to the one produced by `value` if `time` is ordered
+// according to `keep` relative to the timestamp of the current value, or if
+// there is no current value.
+fn update_by_time<T>(
+    arr: &mut Vec<Option<T>>,
+    times: &mut Vec<Option<i64>>,
+    offset: usize,
+    time: i64,
+    keep: Ordering,
+    value: impl FnOnce() -> T,
+) {
+    if offset >= arr.len() {
+        arr.resize_with(offset + 1, || None);
+        times.resize(offset + 1, None);
+    }
+
+    if times[offset].map_or(true, |current| time.cmp(&current) == keep) {
+        arr[offset] = Some(value());
+        times[offset] = Some(time);
+    }
+}
+
 impl std::fmt::Display for AggregateVec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1202,6 +1454,18 @@ impl PartialOrd<Value<'_>> for OwnedValue {
     }
 }
 
+impl<'a> From<&'a OwnedValue> for Value<'a> {
+    fn from(value: &'a OwnedValue) -> Self {
+        match value {
+            OwnedValue::Null => Self::Null,
+            OwnedValue::String(s) => Self::String(s.as_str()),
+            OwnedValue::ByteArray(arr) => Self::ByteArray(arr.as_slice()),
+            OwnedValue::Boolean(b) => Self::Boolean(*b),
+            OwnedValue::Scalar(s) => Self::Scalar(*s),
+        }
+    }
+}
+
 /// Each variant is a possible value type that can be returned from a column.
 #[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
 pub enum Value<'a> {
@@ -1525,6 +1789,200 @@ impl<'a> Values<'a> {
             _ => panic!("value cannot be returned as &str"),
         }
     }
+
+    /// Returns the minimum non-null value, or `None` if all values are NULL
+    /// or there are no values.
+    pub fn min(&self) -> Option<OwnedValue> {
+        self.select(Ordering::Less)
+    }
+
+    /// Returns the maximum non-null value, or `None` if all values are NULL
+    /// or there are no values.
+    pub fn max(&self) -> Option<OwnedValue> {
+        self.select(Ordering::Greater)
+    }
+
+    /// Concatenates batches of the same type of values, in order, into a
+    /// single `Values`.
+    ///
+    /// Nullable and non-nullable batches of the same scalar type can be mixed,
+    /// in which case the result is nullable. Dictionary batches are merged
+    /// into a single dictionary, unless mixed with non-dictionary strings.
+    pub fn concat(batches: Vec<Values<'a>>) -> Result<Values<'a>> {
+        let mut batches = batches.into_iter();
+        let first = batches.next().context(NoValues)?;
+        batches.try_fold(first, |acc, batch| acc.append(batch))
+    }
+
+    // Appends `other` onto self, returning the resulting values.
+    fn append(self, other: Values<'a>) -> Result<Values<'a>> {
+        Ok(match (self, other) {
+            (Self::String(mut a), Self::String(b)) => {
+                a.extend(b);
+                Self::String(a)
+            }
+            (Self::String(mut a), b @ Self::Dictionary(_, _)) => {
+                a.extend(b.into_strings());
+                Self::String(a)
+            }
+            (a @ Self::Dictionary(_, _), Self::String(b)) => {
+                let mut a = a.into_strings();
+                a.extend(b);
+                Self::String(a)
+            }
+            (Self::Dictionary(a_keys, a_values), Self::Dictionary(b_keys, b_values)) => {
+                let mut values = a_values
+                    .iter()
+                    .chain(b_values.iter())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                values.sort();
+                values.dedup();
+
+                // map the keys of each batch onto the merged dictionary.
+                let remap = |dict: &[Option<&str>]| {
+                    dict.iter()
+                        .map(|v| values.binary_search(v).unwrap() as u32)
+                        .collect::<Vec<_>>()
+                };
+                let (a_map, b_map) = (remap(&a_values), remap(&b_values));
+
+                let keys = a_keys
+                    .iter()
+                    .map(|k| a_map[*k as usize])
+                    .chain(b_keys.iter().map(|k| b_map[*k as usize]))
+                    .collect();
+                Self::Dictionary(keys, values)
+            }
+            (Self::I64(mut a), Self::I64(b)) => {
+                a.extend(b);
+                Self::I64(a)
+            }
+            (Self::I64(a), Self::I64N(b)) => Self::I64N(a.into_iter().map(Some).chain(b).collect()),
+            (Self::I64N(mut a), Self::I64(b)) => {
+                a.extend(b.into_iter().map(Some));
+                Self::I64N(a)
+            }
+            (Self::I64N(mut a), Self::I64N(b)) => {
+                a.extend(b);
+                Self::I64N(a)
+            }
+            (Self::U64(mut a), Self::U64(b)) => {
+                a.extend(b);
+                Self::U64(a)
+            }
+            (Self::U64(a), Self::U64N(b)) => Self::U64N(a.into_iter().map(Some).chain(b).collect()),
+            (Self::U64N(mut a), Self::U64(b)) => {
+                a.extend(b.into_iter().map(Some));
+                Self::U64N(a)
+            }
+            (Self::U64N(mut a), Self::U64N(b)) => {
+                a.extend(b);
+                Self::U64N(a)
+            }
+            (Self::F64(mut a), Self::F64(b)) => {
+                a.extend(b);
+                Self::F64(a)
+            }
+            (Self::F64(a), Self::F64N(b)) => Self::F64N(a.into_iter().map(Some).chain(b).collect()),
+            (Self::F64N(mut a), Self::F64(b)) => {
+                a.extend(b.into_iter().map(Some));
+                Self::F64N(a)
+            }
+            (Self::F64N(mut a), Self::F64N(b)) => {
+                a.extend(b);
+                Self::F64N(a)
+            }
+            (Self::Bool(mut a), Self::Bool(b)) => {
+                a.extend(b);
+                Self::Bool(a)
+            }
+            (Self::ByteArray(mut a), Self::ByteArray(b)) => {
+                a.extend(b);
+                Self::ByteArray(a)
+            }
+            (a, b) => {
+                return ConcatTypeMismatch {
+                    expected: a.type_name(),
+                    found: b.type_name(),
+                }
+                .fail()
+            }
+        })
+    }
+
+    // Decodes string values, which must be either `String` or `Dictionary`.
+    fn into_strings(self) -> Vec<Option<&'a str>> {
+        match self {
+            Self::String(c) => c,
+            Self::Dictionary(keys, values) => keys.iter().map(|k| values[*k as usize]).collect(),
+            _ => panic!("values are not strings"),
+        }
+    }
+
+    // The name of the variant, used to describe values in errors.
+    fn type_name(&self) -> &'static str {
+        match self {
+            Self::String(_) => "String",
+            Self::Dictionary(_, _) => "Dictionary",
+            Self::I64(_) => "I64",
+            Self::U64(_) => "U64",
+            Self::F64(_) => "F64",
+            Self::I64N(_) => "I64N",
+            Self::U64N(_) => "U64N",
+            Self::F64N(_) => "F64N",
+            Self::Bool(_) => "Bool",
+            Self::ByteArray(_) => "ByteArray",
+        }
+    }
+
+    // Returns the non-null value that orders `keep` relative to all other
+    // non-null values.
+    fn select(&self, keep: Ordering) -> Option<OwnedValue> {
+        match &self {
+            Self::String(c) => {
+                select_value(c.iter().flatten(), keep).map(|v| OwnedValue::String(v.to_string()))
+            }
+            Self::Dictionary(keys, values) => {
+                select_value(keys.iter().filter_map(|k| values[*k as usize]), keep)
+                    .map(|v| OwnedValue::String(v.to_string()))
+            }
+            Self::I64(c) => {
+                select_value(c.iter(), keep).map(|v| OwnedValue::Scalar(Scalar::I64(*v)))
+            }
+            Self::U64(c) => {
+                select_value(c.iter(), keep).map(|v| OwnedValue::Scalar(Scalar::U64(*v)))
+            }
+            Self::F64(c) => {
+                select_value(c.iter(), keep).map(|v| OwnedValue::Scalar(Scalar::F64(*v)))
+            }
+            Self::I64N(c) => {
+                select_value(c.iter().flatten(), keep).map(|v| OwnedValue::Scalar(Scalar::I64(*v)))
+            }
+            Self::U64N(c) => {
+                select_value(c.iter().flatten(), keep).map(|v| OwnedValue::Scalar(Scalar::U64(*v)))
+            }
+            Self::F64N(c) => {
+                select_value(c.iter().flatten(), keep).map(|v| OwnedValue::Scalar(Scalar::F64(*v)))
+            }
+            Self::Bool(c) => {
+                select_value(c.iter().flatten(), keep).map(|v| OwnedValue::Boolean(*v))
+            }
+            Self::ByteArray(c) => {
+                select_value(c.iter().flatten(), keep).map(|v| OwnedValue::ByteArray(v.to_vec()))
+            }
+        }
+    }
+}
+
+// Returns the value that orders `keep` relative to all other values, keeping
+// the first such value. Values that cannot be compared (e.g., NaN) are only
+// selected if they are first.
+fn select_value<T: PartialOrd>(values: impl Iterator<Item = T>, keep: Ordering) -> Option<T> {
+    values.fold(None, |selected, v| match selected {
+        Some(selected) if v.partial_cmp(&selected) != Some(keep) => Some(selected),
+        _ => Some(v),
+    })
 }
 
 use arrow::{
@@ -1702,6 +2160,128 @@ mod test {
     use arrow::array::ArrayRef;
     use std::cmp::Ordering;
 
+    #[test]
+    fn values_min_max() {
+        let cases = vec![
+            (
+                Values::String(vec![Some("b"), None, Some("a"), Some("c")]),
+                Some(OwnedValue::String("a".to_owned())),
+                Some(OwnedValue::String("c".to_owned())),
+            ),
+            (
+                Values::Dictionary(vec![1, 0, 1], vec![None, Some("a"), Some("b")]),
+                Some(OwnedValue::String("a".to_owned())),
+                Some(OwnedValue::String("a".to_owned())),
+            ),
+            (
+                Values::I64(vec![3, -1, 10]),
+                Some(OwnedValue::Scalar(Scalar::I64(-1))),
+                Some(OwnedValue::Scalar(Scalar::I64(10))),
+            ),
+            (
+                Values::U64(vec![3, 1, 10]),
+                Some(OwnedValue::Scalar(Scalar::U64(1))),
+                Some(OwnedValue::Scalar(Scalar::U64(10))),
+            ),
+            (
+                Values::F64(vec![3.2, -1.5, 10.0]),
+                Some(OwnedValue::Scalar(Scalar::F64(-1.5))),
+                Some(OwnedValue::Scalar(Scalar::F64(10.0))),
+            ),
+            (
+                Values::I64N(vec![None, Some(3), Some(-1), None]),
+                Some(OwnedValue::Scalar(Scalar::I64(-1))),
+                Some(OwnedValue::Scalar(Scalar::I64(3))),
+            ),
+            (
+                Values::U64N(vec![Some(3), None, Some(1)]),
+                Some(OwnedValue::Scalar(Scalar::U64(1))),
+                Some(OwnedValue::Scalar(Scalar::U64(3))),
+            ),
+            (
+                Values::F64N(vec![Some(3.2), None, Some(-1.5)]),
+                Some(OwnedValue::Scalar(Scalar::F64(-1.5))),
+                Some(OwnedValue::Scalar(Scalar::F64(3.2))),
+            ),
+            (
+                Values::Bool(vec![Some(true), None, Some(false)]),
+                Some(OwnedValue::Boolean(false)),
+                Some(OwnedValue::Boolean(true)),
+            ),
+            (
+                Values::ByteArray(vec![Some(&b"b"[..]), Some(&b"a"[..]), None]),
+                Some(OwnedValue::ByteArray(b"a".to_vec())),
+                Some(OwnedValue::ByteArray(b"b".to_vec())),
+            ),
+            // all-NULL and empty columns have no min or max
+            (Values::I64N(vec![None, None]), None, None),
+            (Values::String(vec![None]), None, None),
+            (Values::Dictionary(vec![0, 0], vec![None]), None, None),
+            (Values::Bool(vec![None]), None, None),
+            (Values::F64(vec![]), None, None),
+        ];
+
+        for (values, exp_min, exp_max) in cases {
+            assert_eq!(values.min(), exp_min, "min of {:?}", values);
+            assert_eq!(values.max(), exp_max, "max of {:?}", values);
+        }
+    }
+
+    #[test]
+    fn values_concat() {
+        let values = Values::concat(vec![
+            Values::I64N(vec![Some(1), None]),
+            Values::I64N(vec![]),
+            Values::I64N(vec![None, Some(4), Some(5)]),
+        ])
+        .unwrap();
+        assert_eq!(
+            values,
+            Values::I64N(vec![Some(1), None, None, Some(4), Some(5)])
+        );
+
+        // non-nullable batches become nullable when mixed with nullable ones.
+        let values = Values::concat(vec![
+            Values::I64(vec![1, 2]),
+            Values::I64N(vec![None, Some(4)]),
+        ])
+        .unwrap();
+        assert_eq!(values, Values::I64N(vec![Some(1), Some(2), None, Some(4)]));
+
+        // dictionaries are merged.
+        let values = Values::concat(vec![
+            Values::Dictionary(vec![1, 0, 1], vec![None, Some("west")]),
+            Values::Dictionary(vec![0, 1], vec![Some("east"), Some("west")]),
+        ])
+        .unwrap();
+        assert_eq!(
+            values,
+            Values::Dictionary(vec![2, 0, 2, 1, 2], vec![None, Some("east"), Some("west")])
+        );
+
+        assert!(matches!(Values::concat(vec![]), Err(Error::NoValues)));
+    }
+
+    #[test]
+    fn values_concat_type_mismatch() {
+        let err = Values::concat(vec![
+            Values::I64N(vec![Some(1), None]),
+            Values::U64N(vec![Some(2)]),
+        ])
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ConcatTypeMismatch {
+                expected: "I64N",
+                found: "U64N"
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "cannot concatenate I64N values with U64N values"
+        );
+    }
+
     #[test]
     fn aggregate_vec_update() {
         // i64
@@ -5,13 +5,16 @@ use std::{
 
 use data_types::{database_rules::DatabaseRules, server_id::ServerId, DatabaseName};
 use metrics::MetricRegistry;
-use object_store::{path::ObjectStorePath, ObjectStore};
+use object_store::{
+    path::{parsed::DirsAndFileName, ObjectStorePath, Path},
+    ObjectStore,
+};
 use parquet_file::catalog::PreservedCatalog;
 use query::exec::Executor;
 
 /// This module contains code for managing the configuration of the server.
 use crate::{
-    db::{catalog::Catalog, DatabaseToCommit, Db},
+    db::{catalog::Catalog, process_clock::ClockSource, DatabaseToCommit, Db},
     write_buffer::WriteBuffer,
     Error, JobRegistry, Result,
 };
@@ -20,6 +23,8 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
 pub(crate) const DB_RULES_FILE_NAME: &str = "rules.pb";
+pub(crate) const DB_RULES_JSON_FILE_NAME: &str = "rules.json";
+pub(crate) const DB_RULES_VERSIONS_DIR: &str = "rules";
 
 /// The Config tracks the configuration of databases and their rules along
 /// with host groups for replication. It is used as an in-memory structure
@@ -35,6 +40,7 @@ pub(crate) struct Config {
     jobs: Arc<JobRegistry>,
     state: RwLock<ConfigState>,
     metric_registry: Arc<MetricRegistry>,
+    clock_source: Arc<dyn ClockSource>,
 }
 
 pub(crate) enum UpdateError<E> {
@@ -54,12 +60,14 @@ impl Config {
         jobs: Arc<JobRegistry>,
         metric_registry: Arc<MetricRegistry>,
         remote_template: Option<RemoteTemplate>,
+        clock_source: Arc<dyn ClockSource>,
     ) -> Self {
         Self {
             shutdown: Default::default(),
             state: RwLock::new(ConfigState::new(remote_template)),
             jobs,
             metric_registry,
+            clock_source,
         }
     }
 
@@ -184,6 +192,16 @@ impl Config {
             .flatten()
     }
 
+    /// Get all fully initialized databases, sorted by name.
+    pub(crate) fn dbs_sorted(&self) -> Vec<Arc<Db>> {
+        let state = self.state.read().expect("mutex poisoned");
+        state
+            .databases
+            .values()
+            .filter_map(|db_state| db_state.db())
+            .collect()
+    }
+
     /// Check if there is a database with the given name that is registered but is uninitialized.
     pub(crate) fn has_uninitialized_database(&self, name: &DatabaseName<'_>) -> bool {
         let state = self.state.read().expect("mutex poisoned");
@@ -210,7 +228,7 @@ impl Config {
     /// Update datbase rules of a fully initialized database.
     pub(crate) fn update_db_rules<F, E>(
         &self,
-        db_name: &DatabaseName<'static>,
+        db_name: &DatabaseName<'_>,
         update: F,
     ) -> std::result::Result<DatabaseRules, UpdateError<E>>
     where
@@ -315,6 +333,53 @@ pub fn object_store_path_for_database_config<P: ObjectStorePath>(
     path
 }
 
+/// Get object store path for the human readable JSON copy of the database
+/// config under the given root.
+pub fn object_store_path_for_database_config_json<P: ObjectStorePath>(
+    root: &P,
+    name: &DatabaseName<'_>,
+) -> P {
+    let mut path = root.clone();
+    path.push_dir(name.to_string());
+    path.set_file_name(DB_RULES_JSON_FILE_NAME);
+    path
+}
+
+/// Get object store path for the directory holding all historic versions of the database config under the given root.
+pub fn object_store_path_for_database_config_versions<P: ObjectStorePath>(
+    root: &P,
+    name: &DatabaseName<'_>,
+) -> P {
+    let mut path = root.clone();
+    path.push_dir(name.to_string());
+    path.push_dir(DB_RULES_VERSIONS_DIR);
+    path
+}
+
+/// Get object store path for a specific version of the database config under the given root.
+pub fn object_store_path_for_database_config_version<P: ObjectStorePath>(
+    root: &P,
+    name: &DatabaseName<'_>,
+    version: u64,
+) -> P {
+    let mut path = object_store_path_for_database_config_versions(root, name);
+    // pad the version so that versions sort lexicographically
+    path.set_file_name(format!("{:0>20}.pb", version));
+    path
+}
+
+/// Extract the database config version from a path created by
+/// [`object_store_path_for_database_config_version`].
+pub fn database_config_version_from_path(path: Path) -> Option<u64> {
+    let parsed: DirsAndFileName = path.into();
+    parsed
+        .file_name?
+        .encoded()
+        .strip_suffix(".pb")?
+        .parse()
+        .ok()
+}
+
 /// A gRPC connection string.
 pub type GRpcConnectionString = String;
 
@@ -618,6 +683,7 @@ impl<'a> DatabaseHandle<'a> {
                     catalog,
                     rules: rules.clone(),
                     write_buffer,
+                    clock_source: Arc::clone(&self.config.clock_source),
                 };
                 let db = Arc::new(Db::new(database_to_commit, Arc::clone(&self.config.jobs)));
 
@@ -680,7 +746,7 @@ mod test {
 
     use object_store::{memory::InMemory, ObjectStore, ObjectStoreApi};
 
-    use crate::db::load::load_or_create_preserved_catalog;
+    use crate::db::{load::load_or_create_preserved_catalog, process_clock::ProcessClock};
 
     use super::*;
     use std::num::NonZeroU32;
@@ -697,6 +763,7 @@ mod test {
             Arc::new(JobRegistry::new()),
             Arc::clone(&metric_registry),
             None,
+            Arc::new(ProcessClock::new()),
         );
         let rules = DatabaseRules::new(name.clone());
 
@@ -849,6 +916,7 @@ mod test {
             Arc::new(JobRegistry::new()),
             Arc::clone(&metric_registry),
             None,
+            Arc::new(ProcessClock::new()),
         );
         let rules = DatabaseRules::new(name.clone());
 
@@ -955,6 +1023,7 @@ mod test {
             Arc::new(JobRegistry::new()),
             Arc::clone(&metric_registry),
             None,
+            Arc::new(ProcessClock::new()),
         );
 
         // block DB
@@ -1006,6 +1075,7 @@ mod test {
             Arc::new(JobRegistry::new()),
             Arc::clone(&metric_registry),
             None,
+            Arc::new(ProcessClock::new()),
         );
         let rules = DatabaseRules::new(name.clone());
         let (preserved_catalog, catalog) = load_or_create_preserved_catalog(
@@ -1070,6 +1140,25 @@ mod test {
         assert_eq!(rules_path, expected_path);
     }
 
+    #[test]
+    fn object_store_path_for_database_config_version() {
+        let storage = ObjectStore::new_in_memory(InMemory::new());
+        let mut base_path = storage.new_path();
+        base_path.push_dir("1");
+
+        let name = DatabaseName::new("foo").unwrap();
+        let rules_path =
+            super::object_store_path_for_database_config_version(&base_path, &name, 42);
+
+        let mut expected_path = base_path;
+        expected_path.push_dir("foo");
+        expected_path.push_dir("rules");
+        expected_path.set_file_name("00000000000000000042.pb");
+
+        assert_eq!(rules_path, expected_path);
+        assert_eq!(database_config_version_from_path(rules_path), Some(42));
+    }
+
     #[test]
     fn resolve_remote() {
         let metric_registry = Arc::new(metrics::MetricRegistry::new());
@@ -1077,6 +1166,7 @@ mod test {
             Arc::new(JobRegistry::new()),
             Arc::clone(&metric_registry),
             Some(RemoteTemplate::new("http://iox-query-{id}:8082")),
+            Arc::new(ProcessClock::new()),
         );
 
         let server_id = ServerId::new(NonZeroU32::new(42).unwrap());
@@ -6,7 +6,7 @@ use crate::{
     db::{
         access::QueryCatalogAccess,
         catalog::{
-            chunk::{CatalogChunk, ChunkStage},
+            chunk::{CatalogChunk, ChunkStage, ChunkStageFrozenRepr},
             partition::Partition,
             Catalog, TableNameFilter,
         },
@@ -17,10 +17,12 @@ use crate::{
 };
 use ::lifecycle::{LockableChunk, LockablePartition};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, TimeZone, Utc};
 use data_types::{
-    chunk_metadata::ChunkSummary,
+    chunk_metadata::{ChunkAddr, ChunkSummary},
     database_rules::DatabaseRules,
+    error::ErrorLogger,
+    job::Job,
     partition_metadata::{PartitionSummary, TableSummary},
     server_id::ServerId,
 };
@@ -38,17 +40,19 @@ use parquet_file::{
 };
 use query::{exec::Executor, predicate::Predicate, QueryDatabase};
 use rand_distr::{Distribution, Poisson};
+use read_buffer::{ChunkMetrics as ReadBufferChunkMetrics, RBChunk};
 use snafu::{ensure, ResultExt, Snafu};
 use std::{
     any::Any,
     collections::HashMap,
     num::NonZeroUsize,
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicI64, AtomicUsize, Ordering},
         Arc,
     },
     time::{Duration, Instant},
 };
+use tracker::TaskTracker;
 
 pub mod access;
 pub mod catalog;
@@ -56,7 +60,7 @@ mod chunk;
 mod lifecycle;
 pub mod load;
 pub mod pred;
-mod process_clock;
+pub mod process_clock;
 mod streams;
 mod system_tables;
 
@@ -83,6 +87,21 @@ pub enum Error {
     #[snafu(display("Cannot write to this database: no mutable buffer configured"))]
     DatabaseNotWriteable {},
 
+    #[snafu(display("Cannot apply delete to table {}: {}", table_name, source))]
+    InvalidDeletePredicate {
+        table_name: String,
+        source: pred::Error,
+    },
+
+    #[snafu(display(
+        "Cannot apply delete to chunk {}: deleting from persisted chunks is not supported",
+        chunk
+    ))]
+    DeletePersistedChunk { chunk: ChunkAddr },
+
+    #[snafu(display("Error applying delete to chunk: {}", source))]
+    DeletingFromChunk { source: catalog::chunk::Error },
+
     #[snafu(display("Hard buffer size limit reached"))]
     HardLimitReached {},
 
@@ -113,10 +132,25 @@ pub enum Error {
 
     #[snafu(display("error finding min/max time on table batch: {}", source))]
     TableBatchTimeError { source: entry::Error },
+
+    #[snafu(display(
+        "Cannot remove persisted chunks of expired partition {}:{} from preserved catalog: {}",
+        table_name,
+        partition_key,
+        source
+    ))]
+    DropExpiredPersistedChunks {
+        table_name: String,
+        partition_key: String,
+        source: parquet_file::catalog::Error,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Sentinel for `Db::last_write_at` before the first write
+const NEVER_WRITTEN: i64 = i64::MIN;
+
 /// This is the main IOx Database object. It is the root object of any
 /// specific InfluxDB IOx instance
 ///
@@ -209,8 +243,8 @@ pub struct Db {
 
     /// Process clock used in establishing a partial ordering of operations via a Lamport Clock.
     ///
-    /// Value is nanoseconds since the Unix Epoch.
-    process_clock: process_clock::ProcessClock,
+    /// Sequences the entries stored when no write buffer is configured.
+    process_clock: Arc<dyn process_clock::ClockSource>,
 
     /// Number of iterations of the worker lifecycle loop for this Db
     worker_iterations_lifecycle: AtomicUsize,
@@ -218,6 +252,10 @@ pub struct Db {
     /// Number of iterations of the worker cleanup loop for this Db
     worker_iterations_cleanup: AtomicUsize,
 
+    /// Nanoseconds since the Unix Epoch of the last write to this Db, or
+    /// `NEVER_WRITTEN`
+    last_write_at: AtomicI64,
+
     /// Metric labels
     metric_labels: Vec<KeyValue>,
 
@@ -242,6 +280,7 @@ pub(crate) struct DatabaseToCommit {
     pub(crate) catalog: Catalog,
     pub(crate) rules: DatabaseRules,
     pub(crate) write_buffer: Option<Arc<dyn WriteBuffer>>,
+    pub(crate) clock_source: Arc<dyn process_clock::ClockSource>,
 }
 
 impl Db {
@@ -264,8 +303,6 @@ impl Db {
         );
         let catalog_access = Arc::new(catalog_access);
 
-        let process_clock = process_clock::ProcessClock::new();
-
         Self {
             rules,
             server_id,
@@ -276,9 +313,10 @@ impl Db {
             jobs,
             metrics_registry,
             catalog_access,
-            process_clock,
+            process_clock: database_to_commit.clock_source,
             worker_iterations_lifecycle: AtomicUsize::new(0),
             worker_iterations_cleanup: AtomicUsize::new(0),
+            last_write_at: AtomicI64::new(NEVER_WRITTEN),
             metric_labels,
             write_buffer: database_to_commit.write_buffer,
             cleanup_lock: Default::default(),
@@ -340,6 +378,18 @@ impl Db {
         Ok(LockableCatalogChunk { db: self, chunk })
     }
 
+    pub fn lockable_partition(
+        &self,
+        table_name: &str,
+        partition_key: &str,
+    ) -> catalog::Result<LockableCatalogPartition<'_>> {
+        let partition = self.partition(table_name, partition_key)?;
+        Ok(LockableCatalogPartition {
+            db: self,
+            partition,
+        })
+    }
+
     /// Drops the specified chunk from the catalog and all storage systems
     pub fn drop_chunk(&self, table_name: &str, partition_key: &str, chunk_id: u32) -> Result<()> {
         debug!(%table_name, %partition_key, %chunk_id, "dropping chunk");
@@ -435,6 +485,261 @@ impl Db {
         lifecycle::unload_read_buffer_chunk(chunk).context(LifecycleError)
     }
 
+    /// Deletes the rows of `table_name` matching `predicate` from all chunks of
+    /// the table, returning the number of rows deleted.
+    ///
+    /// Chunks still in the mutable buffer are moved to the read buffer, which is
+    /// where rows are deleted, and chunks left without any rows are dropped.
+    /// Deleting from chunks persisted to object storage is not supported.
+    pub fn delete(&self, table_name: &str, predicate: &read_buffer::Predicate) -> Result<u64> {
+        let partitions = match self.catalog.table(table_name) {
+            Ok(table) => table.partitions().cloned().collect::<Vec<_>>(),
+            // nothing was written to the table, so there is nothing to delete
+            Err(_) => return Ok(0),
+        };
+
+        // Validate and delete under the same partition and chunk locks, so
+        // that neither writes nor lifecycle actions land in between
+        let mut partitions = partitions
+            .iter()
+            .map(|partition| partition.write())
+            .collect::<Vec<_>>();
+        let chunks = partitions
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, partition)| {
+                partition
+                    .keyed_chunks()
+                    .map(move |(chunk_id, chunk)| (idx, chunk_id, Arc::clone(chunk)))
+            })
+            .collect::<Vec<_>>();
+
+        let mut deleted = 0;
+        let mut emptied = vec![];
+        {
+            let mut chunks = chunks
+                .iter()
+                .map(|(idx, chunk_id, chunk)| (*idx, *chunk_id, chunk.write()))
+                .collect::<Vec<_>>();
+
+            // Check every chunk before deleting anything, so that an unsupported
+            // chunk doesn't leave the delete partially applied
+            for (_, _, chunk) in &chunks {
+                if let Some(action) = chunk.lifecycle_action() {
+                    return Err(catalog::partition::Error::LifecycleInProgress {
+                        chunk: chunk.addr().clone(),
+                        action: *action.metadata(),
+                    }
+                    .into());
+                }
+                ensure!(
+                    !matches!(chunk.stage(), ChunkStage::Persisted { .. }),
+                    DeletePersistedChunk {
+                        chunk: chunk.addr().clone()
+                    }
+                );
+            }
+
+            for (idx, chunk_id, chunk) in &mut chunks {
+                let in_mutable_buffer = match chunk.stage() {
+                    ChunkStage::Open { .. } => true,
+                    ChunkStage::Frozen { representation, .. } => matches!(
+                        representation,
+                        ChunkStageFrozenRepr::MutableBufferSnapshot(_)
+                    ),
+                    ChunkStage::Persisted { .. } => false,
+                };
+                if in_mutable_buffer {
+                    self.load_chunk_to_read_buffer(chunk)?;
+                }
+
+                let rows = chunk.delete(predicate).context(DeletingFromChunk)?;
+                if rows > 0 && chunk.table_summary().count() == 0 {
+                    emptied.push((*idx, *chunk_id));
+                }
+                deleted += rows;
+            }
+        }
+
+        // dropping a chunk locks it, so only once the chunk locks are released
+        for (idx, chunk_id) in emptied {
+            partitions[idx].drop_chunk(chunk_id)?;
+        }
+
+        Ok(deleted)
+    }
+
+    // Moves a chunk whose data is in the mutable buffer to the read buffer,
+    // while the chunk stays locked
+    fn load_chunk_to_read_buffer(&self, chunk: &mut CatalogChunk) -> Result<()> {
+        let addr = chunk.addr().clone();
+        let (_, registration) = self.jobs.register(Job::CloseChunk {
+            db_name: addr.db_name.to_string(),
+            partition_key: addr.partition_key.to_string(),
+            table_name: addr.table_name.to_string(),
+            chunk_id: addr.chunk_id,
+        });
+        let mb_chunk = chunk.set_moving(&registration).context(DeletingFromChunk)?;
+
+        let metrics = self
+            .metrics_registry
+            .register_domain_with_labels("read_buffer", self.metric_labels.clone());
+        let mut rb_chunk = RBChunk::new(
+            addr.table_name.as_ref(),
+            ReadBufferChunkMetrics::new(&metrics, self.catalog.metrics().memory().read_buffer()),
+        );
+        lifecycle::load_snapshot(&mut rb_chunk, &addr.table_name, &mb_chunk);
+
+        chunk
+            .set_moved(Arc::new(rb_chunk))
+            .context(DeletingFromChunk)
+    }
+
+    /// Copies the specified chunk into a new read buffer chunk of the same
+    /// table and partition within `dst`, as a background job
+    pub fn copy_chunk(
+        &self,
+        table_name: &str,
+        partition_key: &str,
+        chunk_id: u32,
+        dst: Arc<Db>,
+    ) -> Result<TaskTracker<Job>> {
+        let chunk = self.lockable_chunk(table_name, partition_key, chunk_id)?;
+        let (tracker, fut) = lifecycle::copy_chunk(chunk.read(), dst).context(LifecycleError)?;
+        let _ = tokio::spawn(async move { fut.await.log_if_error("copying chunk") });
+        Ok(tracker)
+    }
+
+    /// Drops the chunks of every partition whose key, as derived from the
+    /// partition template, only covers times older than the retention
+    /// period before `now`. Partitions whose keys aren't derived from time
+    /// are never dropped.
+    ///
+    /// Persisted chunks are also removed from the preserved catalog. Their
+    /// parquet files are deleted from object storage by the regular cleanup of
+    /// unreferenced files, as queries may still be reading them.
+    ///
+    /// Partitions with a lifecycle action in progress on any of their chunks
+    /// are skipped and dropped by a later call. Returns a tracker for each
+    /// partition dropped, or the first error encountered while removing
+    /// persisted data. In the latter case the in-memory chunks of that
+    /// partition are already gone, but it is dropped again once the preserved
+    /// catalog is reloaded.
+    pub async fn drop_expired_partitions(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<TaskTracker<Job>>> {
+        let (retention, template) = {
+            let rules = self.rules.read();
+            match rules.lifecycle_rules.retention_duration {
+                Some(retention) => (retention, rules.partition_template.clone()),
+                None => return Ok(vec![]),
+            }
+        };
+
+        let cutoff = match chrono::Duration::from_std(retention)
+            .ok()
+            .and_then(|retention| now.checked_sub_signed(retention))
+        {
+            Some(cutoff) => cutoff,
+            None => return Ok(vec![]),
+        };
+
+        let mut trackers = vec![];
+        for partition in self.catalog.partitions() {
+            let (table_name, partition_key, registration, parquet_files) = {
+                let mut partition = partition.write();
+                if partition.chunks().next().is_none()
+                    || !template.partition_is_before(partition.key(), cutoff)
+                {
+                    continue;
+                }
+
+                let busy = partition
+                    .chunks()
+                    .any(|chunk| chunk.read().lifecycle_action().is_some());
+                if busy {
+                    debug!(
+                        table_name = partition.table_name(),
+                        partition_key = partition.key(),
+                        "expired partition has a lifecycle action in progress, not dropping it yet"
+                    );
+                    continue;
+                }
+
+                let (tracker, registration) = self.jobs.register(Job::DropExpiredPartition {
+                    db_name: partition.db_name().to_string(),
+                    partition_key: partition.key().to_string(),
+                    table_name: partition.table_name().to_string(),
+                });
+                trackers.push(tracker);
+
+                info!(
+                    table_name = partition.table_name(),
+                    partition_key = partition.key(),
+                    %cutoff,
+                    "dropping expired partition"
+                );
+
+                let mut parquet_files = vec![];
+                let chunk_ids: Vec<_> = partition.keyed_chunks().map(|(id, _)| id).collect();
+                for chunk_id in chunk_ids {
+                    // no lifecycle action can have started since the check above as we hold the partition lock
+                    let chunk = partition.drop_chunk(chunk_id)?;
+                    let chunk = chunk.read();
+                    if let ChunkStage::Persisted { parquet, .. } = chunk.stage() {
+                        parquet_files.push(parquet.path());
+                    }
+                }
+
+                (
+                    partition.table_name().to_string(),
+                    partition.key().to_string(),
+                    registration,
+                    parquet_files,
+                )
+            };
+
+            if !parquet_files.is_empty() {
+                self.drop_persisted_files(&table_name, &partition_key, &parquet_files)
+                    .await?;
+            }
+
+            // the work is complete, mark the job as finished
+            std::mem::drop(registration);
+        }
+
+        Ok(trackers)
+    }
+
+    /// Removes the given parquet files of a dropped partition from the
+    /// preserved catalog.
+    async fn drop_persisted_files(
+        &self,
+        table_name: &str,
+        partition_key: &str,
+        parquet_files: &[object_store::path::Path],
+    ) -> Result<()> {
+        let mut transaction = self.preserved_catalog.open_transaction().await;
+        for path in parquet_files {
+            transaction.remove_parquet(&path.clone().into()).context(
+                DropExpiredPersistedChunks {
+                    table_name,
+                    partition_key,
+                },
+            )?;
+        }
+        transaction
+            .commit()
+            .await
+            .context(DropExpiredPersistedChunks {
+                table_name,
+                partition_key,
+            })?;
+
+        Ok(())
+    }
+
     /// Return chunk summary information for all chunks in the specified
     /// partition across all storage systems
     pub fn partition_chunk_summaries(&self, partition_key: &str) -> Vec<ChunkSummary> {
@@ -483,6 +788,20 @@ impl Db {
         self.worker_iterations_cleanup.load(Ordering::Relaxed)
     }
 
+    /// Records that this Db was written to now
+    pub fn touch(&self) {
+        let now = Utc::now().timestamp_nanos();
+        self.last_write_at.fetch_max(now, Ordering::Relaxed);
+    }
+
+    /// Returns when this Db was last written to, if ever
+    pub fn last_write_at(&self) -> Option<DateTime<Utc>> {
+        match self.last_write_at.load(Ordering::Relaxed) {
+            NEVER_WRITTEN => None,
+            nanos => Some(Utc.timestamp_nanos(nanos)),
+        }
+    }
+
     /// Background worker function
     pub async fn background_worker(
         self: &Arc<Self>,
@@ -498,6 +817,9 @@ impl Db {
                 while !shutdown.is_cancelled() {
                     self.worker_iterations_lifecycle
                         .fetch_add(1, Ordering::Relaxed);
+                    if let Err(e) = self.drop_expired_partitions(Utc::now()).await {
+                        error!(%e, "error dropping expired partitions");
+                    }
                     tokio::select! {
                         _ = policy.check_for_work(Utc::now(), std::time::Instant::now()) => {},
                         _ = shutdown.cancelled() => break,
@@ -581,15 +903,32 @@ impl Db {
                 DatabaseNotWriteable {}.fail()
             }
             (None, false) => {
-                // If no write buffer is configured, nothing is
-                // sequencing entries so skip doing so here
-                let sequenced_entry = Arc::new(SequencedEntry::new_unsequenced(entry));
+                // If no write buffer is configured, order the entry with the
+                // process clock so that it carries a sequence nonetheless
+                let sequenced_entry = Arc::new(
+                    SequencedEntry::new_from_process_clock(
+                        self.process_clock.next(),
+                        self.server_id,
+                        entry,
+                    )
+                    .context(SequencedEntryError)?,
+                );
 
                 self.store_sequenced_entry(sequenced_entry)
             }
         }
     }
 
+    /// Returns true if the memory used by this database's buffers is above the
+    /// configured `buffer_size_soft` limit.
+    pub fn over_soft_limit(&self) -> bool {
+        let buffer_size_soft = self.rules.read().lifecycle_rules.buffer_size_soft;
+        match buffer_size_soft {
+            Some(soft_limit) => self.catalog.metrics().memory().total() > soft_limit.get(),
+            None => false,
+        }
+    }
+
     /// Given a `SequencedEntry`, if the mutable buffer is configured, the `SequencedEntry` is then
     /// written into the mutable buffer.
     pub fn store_sequenced_entry(&self, sequenced_entry: Arc<SequencedEntry>) -> Result<()> {
@@ -613,6 +952,16 @@ impl Db {
             }
         }
 
+        if let Some(deletes) = sequenced_entry.deletes() {
+            for delete in deletes {
+                let table_name = delete.table_name();
+                let predicate = pred::parse_delete_predicate(delete.predicate())
+                    .context(InvalidDeletePredicate { table_name })?;
+                let rows = self.delete(table_name, &predicate)?;
+                debug!(%table_name, predicate=%delete.predicate(), %rows, "applied delete");
+            }
+        }
+
         if let Some(partitioned_writes) = sequenced_entry.partition_writes() {
             let sequence = sequenced_entry.as_ref().sequence();
 
@@ -845,7 +1194,7 @@ mod tests {
     use arrow_util::{assert_batches_eq, assert_batches_sorted_eq};
     use data_types::{
         chunk_metadata::ChunkStorage,
-        database_rules::{Order, Sort, SortOrder},
+        database_rules::{Order, PartitionTemplate, Sort, SortOrder, TemplatePart},
         partition_metadata::{ColumnSummary, InfluxDbType, StatValues, Statistics, TableSummary},
     };
     use entry::test_helpers::lp_to_entry;
@@ -875,6 +1224,61 @@ mod tests {
     type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
     type Result<T, E = Error> = std::result::Result<T, E>;
 
+    #[tokio::test]
+    async fn write_while_other_partition_locked() {
+        let db = Arc::new(make_db().await.db);
+        write_lp(&db, "cpu bar=1 10").await;
+
+        // hold the lock of the written partition
+        let partition = db.catalog.partition("cpu", "1970-01-01T00").unwrap();
+        let guard = partition.write();
+
+        let write = |lp: &'static str| {
+            let db = Arc::clone(&db);
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let entry = Arc::new(SequencedEntry::new_unsequenced(lp_to_entry(lp)));
+                tx.send(db.store_sequenced_entry(entry)).unwrap();
+            });
+            rx
+        };
+
+        // writes into other partitions don't wait for the lock
+        let other = write("cpu bar=2 7200000000000\nmem bar=2 10");
+        other
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap()
+            .unwrap();
+
+        // writes into the locked partition do
+        let same = write("cpu bar=3 20");
+        assert!(same.recv_timeout(Duration::from_millis(100)).is_err());
+
+        std::mem::drop(guard);
+        same.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+
+        // writes into existing partitions only take a read lock of the catalog
+        let tables = db.catalog.tables.read();
+        let existing = write("cpu bar=4 30\nmem bar=4 30");
+        existing
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap()
+            .unwrap();
+
+        // creating a partition waits for the catalog write lock
+        let new = write("cpu bar=5 14400000000000");
+        assert!(new.recv_timeout(Duration::from_millis(100)).is_err());
+
+        std::mem::drop(tables);
+        new.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+
+        let partition_keys: HashSet<_> = ["1970-01-01T00", "1970-01-01T02", "1970-01-01T04"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(db.catalog.partition_keys(), partition_keys);
+    }
+
     #[tokio::test]
     async fn write_no_mutable_buffer() {
         // Validate that writes are rejected if there is no mutable buffer
@@ -888,6 +1292,86 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn delete_sequenced_entry() {
+        let db = Arc::new(make_db().await.db);
+        write_lp(&db, "cpu,host=a bar=1 10\ncpu,host=b bar=2 20").await;
+        write_lp(&db, "cpu,host=a bar=3 7200000000000").await;
+        write_lp(&db, "mem,host=a bar=4 10").await;
+
+        // one chunk is already in the read buffer, the others are open
+        let partition_key = "1970-01-01T00";
+        db.rollover_partition("cpu", partition_key).await.unwrap();
+        db.move_chunk_to_read_buffer("cpu", partition_key, 0)
+            .await
+            .unwrap();
+
+        let entry = entry::deletes_to_entry(&[("cpu", "host = 'a'"), ("unknown", "")]);
+        db.store_sequenced_entry(Arc::new(SequencedEntry::new_unsequenced(entry)))
+            .unwrap();
+
+        let batches = run_query(Arc::clone(&db), "select * from cpu").await;
+        let expected = vec![
+            "+-----+------+-------------------------------+",
+            "| bar | host | time                          |",
+            "+-----+------+-------------------------------+",
+            "| 2   | b    | 1970-01-01 00:00:00.000000020 |",
+            "+-----+------+-------------------------------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // the chunk left without rows is dropped and other tables are untouched
+        assert!(db
+            .catalog
+            .partition("cpu", "1970-01-01T02")
+            .unwrap()
+            .read()
+            .chunks()
+            .next()
+            .is_none());
+        let batches = run_query(Arc::clone(&db), "select * from mem").await;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        // writes after the delete are not affected by it
+        write_lp(&db, "cpu,host=a bar=5 30").await;
+        let batches = run_query(Arc::clone(&db), "select * from cpu").await;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+    }
+
+    #[tokio::test]
+    async fn delete_sequenced_entry_invalid() {
+        let db = Arc::new(make_db().await.db);
+        write_lp(&db, "cpu,host=a bar=1 10").await;
+
+        let entry = entry::deletes_to_entry(&[("cpu", "host")]);
+        let res = db.store_sequenced_entry(Arc::new(SequencedEntry::new_unsequenced(entry)));
+        assert_contains!(
+            res.unwrap_err().to_string(),
+            "Cannot apply delete to table cpu: Invalid delete predicate 'host'"
+        );
+
+        // deleting from persisted chunks is rejected without deleting anything
+        let partition_key = "1970-01-01T00";
+        db.rollover_partition("cpu", partition_key).await.unwrap();
+        db.move_chunk_to_read_buffer("cpu", partition_key, 0)
+            .await
+            .unwrap();
+        db.write_chunk_to_object_store("cpu", partition_key, 0)
+            .await
+            .unwrap();
+        write_lp(&db, "cpu,host=a bar=2 20").await;
+
+        let entry = entry::deletes_to_entry(&[("cpu", "host = 'a'")]);
+        let res = db.store_sequenced_entry(Arc::new(SequencedEntry::new_unsequenced(entry)));
+        assert_contains!(
+            res.unwrap_err().to_string(),
+            "deleting from persisted chunks is not supported"
+        );
+
+        let batches = run_query(Arc::clone(&db), "select * from cpu").await;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+    }
+
     #[tokio::test]
     async fn write_with_write_buffer_no_mutable_buffer() {
         // Writes should be forwarded to the write buffer and *not* rejected if the write buffer is
@@ -1773,6 +2257,26 @@ mod tests {
         assert_eq!(open_max.timestamp_nanos(), 20);
     }
 
+    #[tokio::test]
+    async fn write_with_no_write_buffer_uses_clock_source() {
+        let db = TestDb::builder()
+            .server_id(ServerId::try_from(7).unwrap())
+            .clock_source(Arc::new(process_clock::MonotonicCounter::new()))
+            .build()
+            .await
+            .db;
+
+        write_lp(&db, "cpu bar=1 10").await;
+        write_lp(&db, "cpu bar=1 20").await;
+
+        // the writes are sequenced by the server with the configured clock
+        let partition = db.catalog.partition("cpu", "1970-01-01T00").unwrap();
+        let mut partition = partition.write();
+        let windows = partition.persistence_windows().unwrap();
+        let seq = windows.minimum_unpersisted_sequence().unwrap();
+        assert_eq!(seq.get(&7).unwrap(), &MinMaxSequence::new(1, 2));
+    }
+
     #[tokio::test]
     async fn test_chunk_timestamps() {
         let start = Utc::now();
@@ -2811,4 +3315,144 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn drop_expired_partitions() {
+        let object_store = Arc::new(ObjectStore::new_in_memory(InMemory::new()));
+        let server_id = ServerId::try_from(1).unwrap();
+        let db_name = "drop_expired_partitions";
+        let db = TestDb::builder()
+            .object_store(Arc::clone(&object_store))
+            .server_id(server_id)
+            .db_name(db_name)
+            .build()
+            .await
+            .db;
+        {
+            let mut rules = db.rules.write();
+            rules.partition_template = PartitionTemplate {
+                parts: vec![TemplatePart::TimeFormat("%Y-%m-%dT%H".to_string())],
+                ..Default::default()
+            };
+            rules.lifecycle_rules.retention_duration = Some(Duration::from_secs(7 * 24 * 60 * 60));
+        }
+
+        // 2021-03-01 (persisted and open chunk) and 2021-03-14
+        write_lp(&db, "cpu bar=1 1614556800000000000").await;
+        db.rollover_partition("cpu", "2021-03-01T00").await.unwrap();
+        db.move_chunk_to_read_buffer("cpu", "2021-03-01T00", 0)
+            .await
+            .unwrap();
+        db.write_chunk_to_object_store("cpu", "2021-03-01T00", 0)
+            .await
+            .unwrap();
+        write_lp(&db, "cpu bar=3 1614556800000000001").await;
+        write_lp(&db, "cpu bar=2 1615680000000000000").await;
+        let parquet_files = |files: HashSet<String>| {
+            files
+                .into_iter()
+                .filter(|path| path.ends_with(".parquet"))
+                .count()
+        };
+        assert_eq!(
+            parquet_files(get_object_store_files(&object_store).await),
+            1
+        );
+
+        let now = Utc.ymd(2021, 3, 15).and_hms(0, 0, 0);
+        let trackers = db.drop_expired_partitions(now).await.unwrap();
+        assert_eq!(trackers.len(), 1);
+        assert!(trackers[0].is_complete());
+        assert_eq!(
+            trackers[0].metadata(),
+            &Job::DropExpiredPartition {
+                db_name: db_name.to_string(),
+                partition_key: "2021-03-01T00".to_string(),
+                table_name: "cpu".to_string(),
+            }
+        );
+
+        assert!(db.partition_chunk_summaries("2021-03-01T00").is_empty());
+        assert_eq!(db.partition_chunk_summaries("2021-03-14T00").len(), 1);
+
+        // the parquet file is left for the background cleanup, as queries may
+        // still be reading it
+        assert_eq!(
+            parquet_files(get_object_store_files(&object_store).await),
+            1
+        );
+
+        // already dropped partitions are not dropped again
+        assert!(db.drop_expired_partitions(now).await.unwrap().is_empty());
+
+        // nothing is dropped without a retention period
+        db.rules.write().lifecycle_rules.retention_duration = None;
+        let later = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        assert!(db.drop_expired_partitions(later).await.unwrap().is_empty());
+        assert_eq!(db.partition_chunk_summaries("2021-03-14T00").len(), 1);
+
+        // dropped chunks do not come back when the preserved catalog is reloaded
+        std::mem::drop(db);
+        let db = TestDb::builder()
+            .object_store(Arc::clone(&object_store))
+            .server_id(server_id)
+            .db_name(db_name)
+            .build()
+            .await
+            .db;
+        assert!(db.partition_chunk_summaries("2021-03-01T00").is_empty());
+    }
+
+    #[tokio::test]
+    async fn drop_expired_partitions_skips_busy_partitions() {
+        let db = make_db().await.db;
+        {
+            let mut rules = db.rules.write();
+            rules.partition_template = PartitionTemplate {
+                parts: vec![TemplatePart::TimeFormat("%Y-%m-%dT%H".to_string())],
+                ..Default::default()
+            };
+            rules.lifecycle_rules.retention_duration = Some(Duration::from_secs(7 * 24 * 60 * 60));
+        }
+
+        // 2021-03-01
+        write_lp(&db, "cpu bar=1 1614556800000000000").await;
+        let chunk = db.chunk("cpu", "2021-03-01T00", 0).unwrap();
+        let (_tracker, registration) = db.jobs.register(Job::CompactChunks {
+            db_name: "placeholder".to_string(),
+            partition_key: "2021-03-01T00".to_string(),
+            table_name: "cpu".to_string(),
+            chunks: vec![0],
+        });
+        chunk.write().set_compacting(&registration).unwrap();
+
+        // no job is reported and nothing is dropped
+        let now = Utc.ymd(2021, 3, 15).and_hms(0, 0, 0);
+        assert!(db.drop_expired_partitions(now).await.unwrap().is_empty());
+        assert_eq!(db.partition_chunk_summaries("2021-03-01T00").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn copy_chunk_incompatible_schema() {
+        let src = make_db().await.db;
+        let dst = Arc::new(make_db().await.db);
+        write_lp(&src, "cpu bar=1 10").await;
+        write_lp(&dst, "cpu bar=\"x\" 20").await;
+        let partition_key = "1970-01-01T00";
+
+        // the job fails and the destination is unchanged
+        let chunk = src.lockable_chunk("cpu", partition_key, 0).unwrap();
+        let (_tracker, fut) = lifecycle::copy_chunk(chunk.read(), Arc::clone(&dst)).unwrap();
+        assert!(matches!(
+            fut.await,
+            Ok(Err(lifecycle::Error::IncompatibleSchema { .. }))
+        ));
+        assert_eq!(dst.partition_chunk_summaries(partition_key).len(), 1);
+
+        // copying a chunk that doesn't exist fails without starting a job
+        assert!(matches!(
+            src.copy_chunk("cpu", partition_key, 42, Arc::clone(&dst)),
+            Err(super::Error::CatalogError { .. })
+        ));
+    }
 }
@@ -10,6 +10,10 @@ use data_types::{
     chunk_metadata::DetailedChunkSummary,
     database_rules::{Order, Sort, SortOrder},
 };
+use internal_types::schema::{
+    merge::{Error as SchemaMergeError, SchemaMerger},
+    Schema,
+};
 use snafu::Snafu;
 use tracker::{MappedRwLockReadGuard, RwLock, RwLockReadGuard};
 
@@ -88,7 +92,7 @@ pub struct Catalog {
     /// key is table name
     ///
     /// TODO: Remove this unnecessary additional layer of locking
-    tables: RwLock<HashMap<Arc<str>, Table>>,
+    pub(super) tables: RwLock<HashMap<Arc<str>, Table>>,
 
     metrics: CatalogMetrics,
 
@@ -202,12 +206,37 @@ impl Catalog {
         table_name: impl AsRef<str>,
         partition_key: impl AsRef<str>,
     ) -> Arc<RwLock<Partition>> {
+        // Most writes are into existing partitions, so avoid taking the
+        // catalog-wide write lock, which would serialize writes into different
+        // partitions, unless the partition has to be created.
+        if let Some(partition) = self
+            .tables
+            .read()
+            .get(table_name.as_ref())
+            .and_then(|table| table.partition(partition_key.as_ref()))
+        {
+            return Arc::clone(partition);
+        }
+
         let mut tables = self.tables.write();
+        let table = self.get_or_create_table(&mut tables, table_name.as_ref());
+
+        let partition = table.get_or_create_partition(partition_key);
+        Arc::clone(&partition)
+    }
+
+    // Gets or creates the table `table_name` within `tables`, which must be
+    // this catalog's tables.
+    fn get_or_create_table<'a>(
+        &self,
+        tables: &'a mut HashMap<Arc<str>, Table>,
+        table_name: &str,
+    ) -> &'a mut Table {
         let (_, table) = tables
             .raw_entry_mut()
-            .from_key(table_name.as_ref())
+            .from_key(table_name)
             .or_insert_with(|| {
-                let table_name = Arc::from(table_name.as_ref());
+                let table_name = Arc::from(table_name);
                 let table = Table::new(
                     Arc::clone(&self.db_name),
                     Arc::clone(&table_name),
@@ -216,9 +245,38 @@ impl Catalog {
 
                 (table_name, table)
             });
+        table
+    }
+
+    /// Creates a new read buffer chunk in the partition `partition_key` of
+    /// `table_name`, creating the partition if necessary, provided `schema`
+    /// can be merged with the schemas of all existing chunks of the table.
+    ///
+    /// The check and the insert happen whilst holding the lock on the
+    /// catalog's tables, so concurrent calls can't both add chunks whose
+    /// schemas conflict with each other.
+    pub fn create_rub_chunk_with_compatible_schema(
+        &self,
+        table_name: impl AsRef<str>,
+        partition_key: impl AsRef<str>,
+        chunk: read_buffer::RBChunk,
+        schema: Schema,
+    ) -> Result<Arc<RwLock<CatalogChunk>>, SchemaMergeError> {
+        let mut tables = self.tables.write();
+        let table = self.get_or_create_table(&mut tables, table_name.as_ref());
+
+        let mut merger = SchemaMerger::new();
+        merger.merge(&schema)?;
+        for partition in table.partitions() {
+            let partition = partition.read();
+            for chunk in partition.chunks() {
+                merger.merge(&chunk.read().schema())?;
+            }
+        }
 
         let partition = table.get_or_create_partition(partition_key);
-        Arc::clone(&partition)
+        let chunk = partition.write().create_rub_chunk(chunk, schema);
+        Ok(chunk)
     }
 
     /// Returns a list of summaries for each partition.
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
-use snafu::Snafu;
+use snafu::{ResultExt, Snafu};
 
 use data_types::chunk_metadata::ChunkAddr;
 use data_types::{
@@ -60,6 +60,12 @@ pub enum Error {
         chunk
     ))]
     IncompleteLifecycleAction { chunk: ChunkAddr, action: String },
+
+    #[snafu(display("Error deleting rows from read buffer chunk {}: {}", chunk, source))]
+    ReadBufferDelete {
+        chunk: ChunkAddr,
+        source: read_buffer::Error,
+    },
 }
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -465,6 +471,15 @@ impl CatalogChunk {
         DetailedChunkSummary { inner, columns }
     }
 
+    /// Returns the schema of the data in the chunk
+    pub fn schema(&self) -> Arc<Schema> {
+        match &self.stage {
+            ChunkStage::Open { mb_chunk, .. } => mb_chunk.snapshot().full_schema(),
+            ChunkStage::Frozen { meta, .. } => Arc::clone(&meta.schema),
+            ChunkStage::Persisted { meta, .. } => Arc::clone(&meta.schema),
+        }
+    }
+
     /// Return the summary information about the table stored in this Chunk
     pub fn table_summary(&self) -> Arc<TableSummary> {
         match &self.stage {
@@ -641,6 +656,38 @@ impl CatalogChunk {
         }
     }
 
+    /// Deletes the rows matching `predicate` from the chunk, returning the
+    /// number of rows deleted, and refreshes the cached table summary.
+    ///
+    /// Only chunks in the _frozen_ stage whose data is held in the read buffer
+    /// support deletes.
+    pub fn delete(&mut self, predicate: &read_buffer::Predicate) -> Result<u64> {
+        match &mut self.stage {
+            ChunkStage::Frozen {
+                meta,
+                representation: ChunkStageFrozenRepr::ReadBuffer(repr),
+            } => {
+                let deleted = repr.delete(predicate).context(ReadBufferDelete {
+                    chunk: self.addr.clone(),
+                })?;
+
+                if deleted > 0 {
+                    let summary = repr
+                        .table_summaries()
+                        .into_iter()
+                        .next()
+                        .expect("read buffer chunk has a single table");
+                    *meta = Arc::new(ChunkMetadata {
+                        table_summary: Arc::new(summary),
+                        schema: Arc::clone(&meta.schema),
+                    });
+                }
+                Ok(deleted)
+            }
+            stage => unexpected_state!(self, "deleting", "Frozen with ReadBuffer", stage),
+        }
+    }
+
     /// Start lifecycle action that should move the chunk into the _persisted_ stage.
     pub fn set_writing_to_object_store(
         &mut self,
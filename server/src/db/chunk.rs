@@ -403,6 +403,7 @@ impl QueryChunk for DbChunk {
                         rb_predicate,
                         Selection::Some(&[column_name]),
                         BTreeMap::new(),
+                        None,
                     )
                     .context(ReadBufferChunkError {
                         chunk_id: self.id(),
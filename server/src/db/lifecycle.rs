@@ -19,12 +19,14 @@ use crate::db::catalog::partition::Partition;
 use crate::Db;
 
 pub(crate) use compact::compact_chunks;
+pub(crate) use copy::copy_chunk;
 pub(crate) use error::{Error, Result};
-pub(crate) use move_chunk::move_chunk_to_read_buffer;
+pub(crate) use move_chunk::{load_snapshot, move_chunk_to_read_buffer};
 pub(crate) use unload::unload_read_buffer_chunk;
 pub(crate) use write::write_chunk_to_object_store;
 
 mod compact;
+mod copy;
 mod error;
 mod move_chunk;
 mod unload;
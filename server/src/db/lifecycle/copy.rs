@@ -0,0 +1,95 @@
+//! This module contains the code to copy a chunk into another database
+
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use snafu::ResultExt;
+
+use data_types::job::Job;
+use internal_types::selection::Selection;
+use lifecycle::LifecycleReadGuard;
+use query::predicate::Predicate;
+use query::{QueryChunk, QueryChunkMeta};
+use read_buffer::{ChunkMetrics, RBChunk};
+use tracker::{TaskTracker, TrackedFuture, TrackedFutureExt};
+
+use crate::db::catalog::chunk::CatalogChunk;
+use crate::db::DbChunk;
+use crate::Db;
+
+use super::{
+    error::{IncompatibleSchema, ReadingChunk, Result},
+    LockableCatalogChunk,
+};
+
+/// Copy the provided chunk into a new read buffer chunk of the same
+/// table and partition within `dst`, returning the newly created chunk
+///
+/// The job fails if the schema of the chunk conflicts with the schema
+/// of any chunk of the same table already in `dst`
+pub(crate) fn copy_chunk(
+    guard: LifecycleReadGuard<'_, CatalogChunk, LockableCatalogChunk<'_>>,
+    dst: Arc<Db>,
+) -> Result<(
+    TaskTracker<Job>,
+    TrackedFuture<impl Future<Output = Result<Arc<DbChunk>>> + Send>,
+)> {
+    let db = guard.data().db;
+    let addr = guard.addr().clone();
+    let dst_db_name = dst.rules.read().name.to_string();
+
+    let (tracker, registration) = db.jobs.register(Job::CopyChunk {
+        db_name: addr.db_name.to_string(),
+        partition_key: addr.partition_key.to_string(),
+        table_name: addr.table_name.to_string(),
+        chunk_id: addr.chunk_id,
+        dst_db_name: dst_db_name.clone(),
+    });
+
+    // Snapshot the chunk, then drop the lock
+    let chunk = DbChunk::snapshot(&*guard);
+    std::mem::drop(guard);
+
+    // create a new read buffer chunk with memory tracking in the destination
+    let metrics = dst
+        .metrics_registry
+        .register_domain_with_labels("read_buffer", dst.metric_labels.clone());
+
+    let mut rb_chunk = RBChunk::new(
+        &addr.table_name,
+        ChunkMetrics::new(&metrics, dst.catalog.metrics().memory().read_buffer()),
+    );
+
+    let fut = async move {
+        let table_name = addr.table_name.as_ref();
+        let schema = chunk.schema();
+
+        let mut stream = chunk
+            .read_filter(&Predicate::default(), Selection::All)
+            .context(ReadingChunk { addr: addr.clone() })?;
+
+        // Collect results into RUB chunk
+        while let Some(batch) = stream.next().await {
+            rb_chunk.upsert_table(table_name, batch?)
+        }
+
+        let new_chunk = dst
+            .catalog
+            .create_rub_chunk_with_compatible_schema(
+                table_name,
+                addr.partition_key.as_ref(),
+                rb_chunk,
+                schema.as_ref().clone(),
+            )
+            .context(IncompatibleSchema {
+                addr: addr.clone(),
+                dst_db_name: &dst_db_name,
+            })?;
+
+        let guard = new_chunk.read();
+        Ok(DbChunk::snapshot(&guard))
+    };
+
+    Ok((tracker, fut.track(registration)))
+}
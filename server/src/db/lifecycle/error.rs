@@ -55,6 +55,24 @@ pub enum Error {
 
     #[snafu(display("Cannot write chunk: {}", addr))]
     CannotWriteChunk { addr: ChunkAddr },
+
+    #[snafu(display("Error reading chunk {}: {}", addr, source))]
+    ReadingChunk {
+        source: crate::db::chunk::Error,
+        addr: ChunkAddr,
+    },
+
+    #[snafu(display(
+        "Cannot copy chunk {} to database {}: incompatible schema: {}",
+        addr,
+        dst_db_name,
+        source
+    ))]
+    IncompatibleSchema {
+        source: internal_types::schema::merge::Error,
+        addr: ChunkAddr,
+        dst_db_name: String,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
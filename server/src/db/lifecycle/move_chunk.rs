@@ -3,6 +3,7 @@ pub(crate) use crate::db::chunk::DbChunk;
 use ::lifecycle::LifecycleWriteGuard;
 use data_types::job::Job;
 use internal_types::{arrow::sort::sort_record_batch, selection::Selection};
+use mutable_buffer::chunk::snapshot::ChunkSnapshot as MBChunkSnapshot;
 
 use observability_deps::tracing::{debug, info};
 use read_buffer::{ChunkMetrics as ReadBufferChunkMetrics, RBChunk};
@@ -56,15 +57,7 @@ pub fn move_chunk_to_read_buffer(
 
         // load table into the new chunk one by one.
         debug!(chunk=%addr, "loading table to read buffer");
-        let batch = mb_chunk
-            .read_filter(Selection::All)
-            // It is probably reasonable to recover from this error
-            // (reset the chunk state to Open) but until that is
-            // implemented (and tested) just panic
-            .expect("Loading chunk to mutable buffer");
-
-        let sorted = sort_record_batch(batch).expect("failed to sort");
-        rb_chunk.upsert_table(&table_summary.name, sorted);
+        load_snapshot(&mut rb_chunk, &table_summary.name, &mb_chunk);
 
         // Can drop and re-acquire as lifecycle action prevents concurrent modification
         let mut guard = chunk.write();
@@ -81,3 +74,16 @@ pub fn move_chunk_to_read_buffer(
 
     Ok((tracker, fut.track(registration)))
 }
+
+/// Loads all the data of a mutable buffer snapshot into `rb_chunk`, sorted
+pub(crate) fn load_snapshot(rb_chunk: &mut RBChunk, table_name: &str, mb_chunk: &MBChunkSnapshot) {
+    let batch = mb_chunk
+        .read_filter(Selection::All)
+        // It is probably reasonable to recover from this error
+        // (reset the chunk state to Open) but until that is
+        // implemented (and tested) just panic
+        .expect("Loading chunk to mutable buffer");
+
+    let sorted = sort_record_batch(batch).expect("failed to sort");
+    rb_chunk.upsert_table(table_name, sorted);
+}
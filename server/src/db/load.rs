@@ -6,9 +6,11 @@ use std::sync::Arc;
 use data_types::server_id::ServerId;
 use metrics::{KeyValue, MetricRegistry};
 use object_store::{path::parsed::DirsAndFileName, ObjectStore};
-use observability_deps::tracing::{error, info};
+use observability_deps::tracing::{error, info, warn};
 use parquet_file::{
-    catalog::{CatalogParquetInfo, CatalogState, ChunkCreationFailed, PreservedCatalog},
+    catalog::{
+        CatalogParquetInfo, CatalogRepair, CatalogState, ChunkCreationFailed, PreservedCatalog,
+    },
     chunk::{ChunkMetrics as ParquetChunkMetrics, ParquetChunk},
 };
 use snafu::ResultExt;
@@ -21,7 +23,8 @@ use super::catalog::Catalog;
 ///
 /// If no catalog exists yet, a new one will be created.
 ///
-/// **For now, if the catalog is broken, it will be wiped! (https://github.com/influxdata/influxdb_iox/issues/1522)**
+/// **For now, if the catalog is broken, it will be wiped if `wipe_on_error` is set!
+/// (https://github.com/influxdata/influxdb_iox/issues/1522)**
 pub async fn load_or_create_preserved_catalog(
     db_name: &str,
     object_store: Arc<ObjectStore>,
@@ -29,19 +32,64 @@ pub async fn load_or_create_preserved_catalog(
     metrics_registry: Arc<MetricRegistry>,
     wipe_on_error: bool,
 ) -> std::result::Result<(PreservedCatalog, Catalog), parquet_file::catalog::Error> {
-    // first try to load existing catalogs
-    match PreservedCatalog::load(
-        Arc::clone(&object_store),
+    // repair is disabled, so there is never a repair report
+    let (preserved_catalog, catalog, _repair) = load_or_repair_preserved_catalog(
+        db_name,
+        object_store,
         server_id,
-        db_name.to_string(),
-        CatalogEmptyInput::new(db_name, server_id, Arc::clone(&metrics_registry)),
+        metrics_registry,
+        wipe_on_error,
+        false,
     )
-    .await
-    {
-        Ok(Some(catalog)) => {
+    .await?;
+    Ok((preserved_catalog, catalog))
+}
+
+/// Load preserved catalog state from store like [`load_or_create_preserved_catalog`], but optionally repair it.
+///
+/// If `repair_on_error` is set and a transaction of the catalog is corrupt, the catalog is truncated to the last good
+/// transaction (see [`PreservedCatalog::load_with_repair`]) and the performed repair is returned. The repair is only
+/// attempted before the catalog would be wiped.
+pub async fn load_or_repair_preserved_catalog(
+    db_name: &str,
+    object_store: Arc<ObjectStore>,
+    server_id: ServerId,
+    metrics_registry: Arc<MetricRegistry>,
+    wipe_on_error: bool,
+    repair_on_error: bool,
+) -> std::result::Result<
+    (PreservedCatalog, Catalog, Option<CatalogRepair>),
+    parquet_file::catalog::Error,
+> {
+    // first try to load existing catalogs
+    let empty_input = CatalogEmptyInput::new(db_name, server_id, Arc::clone(&metrics_registry));
+    let res = if repair_on_error {
+        PreservedCatalog::load_with_repair(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            empty_input,
+        )
+        .await
+    } else {
+        PreservedCatalog::load(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            empty_input,
+        )
+        .await
+        .map(|res| res.map(|(preserved_catalog, catalog)| (preserved_catalog, catalog, None)))
+    };
+
+    match res {
+        Ok(Some((preserved_catalog, catalog, repair))) => {
             // successfull load
             info!("Found existing catalog for DB {}", db_name);
-            Ok(catalog)
+            if let Some(repair) = &repair {
+                warn!("Repaired catalog for DB {}: {}", db_name, repair);
+            }
+            Ok((preserved_catalog, catalog, repair))
         }
         Ok(None) => {
             // no catalog yet => create one
@@ -50,13 +98,14 @@ pub async fn load_or_create_preserved_catalog(
                 db_name
             );
 
-            create_preserved_catalog(
+            let (preserved_catalog, catalog) = create_preserved_catalog(
                 db_name,
                 Arc::clone(&object_store),
                 server_id,
                 Arc::clone(&metrics_registry),
             )
-            .await
+            .await?;
+            Ok((preserved_catalog, catalog, None))
         }
         Err(e) => {
             if wipe_on_error {
@@ -66,13 +115,14 @@ pub async fn load_or_create_preserved_catalog(
 
                 PreservedCatalog::wipe(&object_store, server_id, db_name).await?;
 
-                create_preserved_catalog(
+                let (preserved_catalog, catalog) = create_preserved_catalog(
                     db_name,
                     Arc::clone(&object_store),
                     server_id,
                     Arc::clone(&metrics_registry),
                 )
-                .await
+                .await?;
+                Ok((preserved_catalog, catalog, None))
             } else {
                 Err(e)
             }
@@ -247,6 +297,82 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn load_or_repair_preserved_catalog_repairs_corrupt_transaction() {
+        let object_store = Arc::new(ObjectStore::new_in_memory(InMemory::new()));
+        let server_id = ServerId::try_from(1).unwrap();
+        let db_name = "preserved_catalog_test";
+
+        let (preserved_catalog, _catalog) = PreservedCatalog::new_empty::<TestCatalogState>(
+            Arc::clone(&object_store),
+            server_id,
+            db_name.to_string(),
+            (),
+        )
+        .await
+        .unwrap();
+        preserved_catalog
+            .open_transaction()
+            .await
+            .commit()
+            .await
+            .unwrap();
+        parquet_file::catalog::test_helpers::break_catalog_with_weird_version(&preserved_catalog)
+            .await;
+        drop(preserved_catalog);
+
+        // w/o repair and wipe the catalog cannot be loaded
+        let metrics_registry = Arc::new(metrics::MetricRegistry::new());
+        let err = load_or_create_preserved_catalog(
+            db_name,
+            Arc::clone(&object_store),
+            server_id,
+            Arc::clone(&metrics_registry),
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            parquet_file::catalog::Error::TransactionVersionMismatch {
+                revision_counter: 1,
+                ..
+            }
+        ));
+
+        // repair falls back to the last good transaction
+        let (preserved_catalog, _catalog, repair) = load_or_repair_preserved_catalog(
+            db_name,
+            Arc::clone(&object_store),
+            server_id,
+            Arc::clone(&metrics_registry),
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        let repair = repair.unwrap();
+        assert_eq!(repair.corrupt_revision, 1);
+        assert_eq!(repair.last_good_revision, 0);
+        assert!(repair.orphaned_files.is_empty());
+        assert_eq!(preserved_catalog.revision_counter(), 0);
+        drop(preserved_catalog);
+
+        // repaired catalog loads cleanly
+        let (preserved_catalog, _catalog, repair) = load_or_repair_preserved_catalog(
+            db_name,
+            object_store,
+            server_id,
+            metrics_registry,
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        assert!(repair.is_none());
+        assert_eq!(preserved_catalog.revision_counter(), 0);
+    }
+
     #[tokio::test]
     async fn test_catalog_state() {
         let metrics_registry = Arc::new(::metrics::MetricRegistry::new());
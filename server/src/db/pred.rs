@@ -10,6 +10,9 @@ use snafu::Snafu;
 pub enum Error {
     #[snafu(display("Error translating predicate: {}", msg))]
     ReadBufferPredicate { msg: String, pred: Predicate },
+
+    #[snafu(display("Invalid delete predicate '{}': {}", predicate, msg))]
+    DeletePredicate { predicate: String, msg: String },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -42,6 +45,90 @@ pub fn to_read_buffer_predicate(predicate: &Predicate) -> Result<read_buffer::Pr
     }
 }
 
+/// The operators supported in delete predicates. Two character operators come
+/// first so that they are preferred over their one character prefixes.
+const DELETE_OPERATORS: &[&str] = &["!=", ">=", "<=", "=~", "!~", "=", ">", "<"];
+
+/// Parses the predicate of a replicated delete into a [`read_buffer::Predicate`].
+///
+/// A delete predicate is a conjunction of `<column> <op> <value>` expressions
+/// separated by `AND`, for example `host = 'a' AND region != west`. Values
+/// enclosed in single quotes are strings, otherwise they are parsed as a
+/// boolean, integer or float where possible and as a string if not. An empty
+/// predicate matches all rows.
+pub fn parse_delete_predicate(predicate: &str) -> Result<read_buffer::Predicate> {
+    if predicate.trim().is_empty() {
+        return Ok(read_buffer::Predicate::default());
+    }
+
+    let exprs = split_conjunction(predicate)
+        .into_iter()
+        .map(|expr| {
+            parse_delete_expr(expr).map_err(|msg| Error::DeletePredicate {
+                predicate: predicate.to_string(),
+                msg,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(read_buffer::Predicate::new(exprs))
+}
+
+/// Splits a delete predicate on the `AND` separating its expressions, leaving
+/// any `AND` within a single quoted string literal in place.
+fn split_conjunction(predicate: &str) -> Vec<&str> {
+    let mut exprs = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    for (pos, c) in predicate.char_indices() {
+        if c == '\'' {
+            quoted = !quoted;
+        } else if !quoted && pos >= start {
+            let rest = &predicate[pos..];
+            if rest.starts_with(" AND ") || rest.starts_with(" and ") {
+                exprs.push(&predicate[start..pos]);
+                start = pos + " AND ".len();
+            }
+        }
+    }
+    exprs.push(&predicate[start..]);
+    exprs
+}
+
+fn parse_delete_expr(expr: &str) -> Result<read_buffer::BinaryExpr, String> {
+    let (pos, op) = expr
+        .char_indices()
+        .find_map(|(pos, _)| {
+            DELETE_OPERATORS
+                .iter()
+                .find(|op| expr[pos..].starts_with(*op))
+                .map(|op| (pos, *op))
+        })
+        .ok_or_else(|| format!("no operator in expression '{}'", expr.trim()))?;
+
+    let column = expr[..pos].trim();
+    let value = expr[pos + op.len()..].trim();
+    if column.is_empty() || value.is_empty() {
+        return Err(format!("incomplete expression '{}'", expr.trim()));
+    }
+
+    let quoted = value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'');
+    let expr = match value {
+        v if quoted => read_buffer::BinaryExpr::from((column, op, &v[1..v.len() - 1])),
+        // the value of a regex operator is always a pattern
+        v if op == "=~" || op == "!~" => read_buffer::BinaryExpr::from((column, op, v)),
+        "true" => read_buffer::BinaryExpr::from((column, op, true)),
+        "false" => read_buffer::BinaryExpr::from((column, op, false)),
+        v => match (v.parse::<i64>(), v.parse::<f64>()) {
+            (Ok(v), _) => read_buffer::BinaryExpr::from((column, op, v)),
+            (_, Ok(v)) => read_buffer::BinaryExpr::from((column, op, v)),
+            _ => read_buffer::BinaryExpr::from((column, op, v)),
+        },
+    };
+    expr.validate()?;
+    Ok(expr)
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -167,7 +254,73 @@ pub mod test {
                 Error::ReadBufferPredicate { msg, pred: _ } => {
                     assert_eq!(msg, exp.to_owned());
                 }
+                e => panic!("unexpected error: {}", e),
             }
         }
     }
+
+    #[test]
+    fn delete_predicate() {
+        let cases = vec![
+            ("", RBPredicate::default()),
+            ("  ", RBPredicate::default()),
+            (
+                "host = 'a'",
+                RBPredicate::new(vec![RBBinaryExpr::from(("host", "=", "a"))]),
+            ),
+            (
+                "host!=a AND counter >= 3 and temp < 2.5 AND up = true",
+                RBPredicate::new(vec![
+                    RBBinaryExpr::from(("host", "!=", "a")),
+                    RBBinaryExpr::from(("counter", ">=", 3_i64)),
+                    RBBinaryExpr::from(("temp", "<", 2.5)),
+                    RBBinaryExpr::from(("up", "=", true)),
+                ]),
+            ),
+            (
+                "region = '10'",
+                RBPredicate::new(vec![RBBinaryExpr::from(("region", "=", "10"))]),
+            ),
+            (
+                "region =~ '^us-' AND host !~ 10",
+                RBPredicate::new(vec![
+                    RBBinaryExpr::from(("region", "=~", "^us-")),
+                    RBBinaryExpr::from(("host", "!~", "10")),
+                ]),
+            ),
+            (
+                "name = 'salt AND pepper' and region = 'a and b'",
+                RBPredicate::new(vec![
+                    RBBinaryExpr::from(("name", "=", "salt AND pepper")),
+                    RBBinaryExpr::from(("region", "=", "a and b")),
+                ]),
+            ),
+        ];
+
+        for (predicate, exp) in cases {
+            assert_eq!(parse_delete_predicate(predicate).unwrap(), exp);
+        }
+
+        let cases = vec![
+            ("host", "no operator in expression 'host'"),
+            ("= 'a'", "incomplete expression '= 'a''"),
+            (
+                "host = 'a' AND region =",
+                "incomplete expression 'region ='",
+            ),
+        ];
+
+        for (predicate, exp) in cases {
+            match parse_delete_predicate(predicate).unwrap_err() {
+                Error::DeletePredicate { msg, .. } => assert_eq!(msg, exp),
+                e => panic!("unexpected error: {}", e),
+            }
+        }
+
+        // invalid regexes are rejected when parsed rather than matching nothing
+        match parse_delete_predicate("region =~ '('").unwrap_err() {
+            Error::DeletePredicate { msg, .. } => assert!(msg.starts_with("invalid regex \"(\"")),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
 }
@@ -4,9 +4,19 @@ use chrono::Utc;
 use entry::ClockValue;
 use std::{
     convert::{TryFrom, TryInto},
+    fmt::Debug,
     sync::atomic::{AtomicU64, Ordering},
 };
 
+/// A source of strictly increasing clock values, used to order operations.
+pub trait ClockSource: Debug + Send + Sync {
+    /// Returns a clock value greater than any previously returned one, even
+    /// when called concurrently.
+    fn next(&self) -> ClockValue;
+}
+
+/// A hybrid logical clock that follows the system time in nanoseconds since
+/// the epoch, but never goes backwards and never repeats a value.
 #[derive(Debug)]
 pub struct ProcessClock {
     inner: AtomicU64,
@@ -27,7 +37,6 @@ impl ProcessClock {
     ///
     /// We expect that updates to the process clock are not so frequent and the system is slow
     /// enough that the returned value will be incremented by at least 1.
-    #[allow(dead_code)]
     pub fn next(&self) -> ClockValue {
         let next = loop {
             if let Ok(next) = self.try_update() {
@@ -38,7 +47,6 @@ impl ProcessClock {
         ClockValue::try_from(next).expect("process clock should not be 0")
     }
 
-    #[allow(dead_code)]
     fn try_update(&self) -> Result<u64, u64> {
         let now = system_clock_now();
         let current_process_clock = self.inner.load(Ordering::SeqCst);
@@ -57,6 +65,39 @@ impl ProcessClock {
     }
 }
 
+impl Default for ProcessClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource for ProcessClock {
+    fn next(&self) -> ClockValue {
+        Self::next(self)
+    }
+}
+
+/// A clock that counts up from 1, independently of the system time. Useful
+/// for deterministic clock values in tests.
+#[derive(Debug, Default)]
+pub struct MonotonicCounter {
+    inner: AtomicU64,
+}
+
+impl MonotonicCounter {
+    /// Create a counter whose first value is 1.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ClockSource for MonotonicCounter {
+    fn next(&self) -> ClockValue {
+        let next = self.inner.fetch_add(1, Ordering::SeqCst) + 1;
+        ClockValue::try_from(next).expect("counter should not be 0")
+    }
+}
+
 // Convenience function for getting the current time in a `u64` represented as nanoseconds since
 // the epoch
 //
@@ -73,15 +114,14 @@ fn system_clock_now() -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::TestDb;
     use std::{sync::Arc, thread, time::Duration};
 
-    #[tokio::test]
-    async fn process_clock_defaults_to_current_time_in_ns() {
+    #[test]
+    fn process_clock_defaults_to_current_time_in_ns() {
         let before = system_clock_now();
 
-        let db = Arc::new(TestDb::builder().build().await.db);
-        let db_process_clock = db.process_clock.inner.load(Ordering::SeqCst);
+        let pc = ProcessClock::new();
+        let db_process_clock = pc.inner.load(Ordering::SeqCst);
 
         let after = system_clock_now();
 
@@ -99,10 +139,10 @@ mod tests {
         );
     }
 
-    #[tokio::test]
-    async fn next_process_clock_always_increments() {
+    #[test]
+    fn next_process_clock_always_increments() {
         // Process clock defaults to the current time
-        let db = Arc::new(TestDb::builder().build().await.db);
+        let pc = ProcessClock::new();
 
         // Set the process clock value to a time in the future, so that when compared to the
         // current time, the process clock value will be greater
@@ -111,18 +151,22 @@ mod tests {
             .try_into()
             .unwrap();
 
-        db.process_clock.inner.store(later, Ordering::SeqCst);
+        pc.inner.store(later, Ordering::SeqCst);
 
         // Every call to next_process_clock should increment at least 1, even in this case
         // where the system time will be less than the process clock
-        assert_eq!(
-            db.process_clock.next(),
-            ClockValue::try_from(later + 1).unwrap()
-        );
-        assert_eq!(
-            db.process_clock.next(),
-            ClockValue::try_from(later + 2).unwrap()
-        );
+        assert_eq!(pc.next(), ClockValue::try_from(later + 1).unwrap());
+        assert_eq!(pc.next(), ClockValue::try_from(later + 2).unwrap());
+    }
+
+    #[test]
+    fn monotonic_counter() {
+        let counter = MonotonicCounter::new();
+        let clock: &dyn ClockSource = &counter;
+
+        assert_eq!(clock.next(), ClockValue::try_from(1).unwrap());
+        assert_eq!(clock.next(), ClockValue::try_from(2).unwrap());
+        assert_eq!(clock.next(), ClockValue::try_from(3).unwrap());
     }
 
     #[test]
@@ -0,0 +1,290 @@
+//! This module contains the format used to export a database from one server
+//! and import it into another.
+//!
+//! An export is a header followed by a sequence of objects, each of which is
+//! a file from the database's directory in object storage:
+//!
+//! ```text
+//! header: magic (8 bytes) | version (u32) | server id (u32) | db name
+//! object: directory count (u32) | directories | file name | length (u64) | data
+//! ```
+//!
+//! Strings are stored as a `u32` byte length followed by UTF-8 bytes, and all
+//! integers are little endian. Object paths are relative to the database's
+//! directory so they can be placed under the importing server's root.
+
+use std::convert::TryInto;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use data_types::server_id::ServerId;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+/// Identifies a database export
+const MAGIC: &[u8; 8] = b"IOXDBEXP";
+
+/// The version of the export format written by this server
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("not a database export"))]
+    BadMagic,
+
+    #[snafu(display(
+        "unsupported export format version {}, expected {}",
+        version,
+        EXPORT_FORMAT_VERSION
+    ))]
+    UnsupportedVersion { version: u32 },
+
+    #[snafu(display("database export is truncated"))]
+    Truncated,
+
+    #[snafu(display("invalid string in database export: {}", source))]
+    InvalidString { source: std::string::FromUtf8Error },
+
+    #[snafu(display("server id {} in database export is invalid", server_id))]
+    InvalidServerId { server_id: u32 },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The header of a database export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportHeader {
+    /// The ID of the server the database was exported from
+    pub server_id: ServerId,
+
+    /// The name of the exported database
+    pub db_name: String,
+}
+
+/// A single object from the database's directory in object storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedObject {
+    /// The directories of the object, relative to the database's directory
+    pub directories: Vec<String>,
+
+    /// The file name of the object
+    pub file_name: String,
+
+    /// The contents of the object
+    pub data: Bytes,
+}
+
+pub fn encode_header(header: &ExportHeader) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_slice(MAGIC);
+    buf.put_u32_le(EXPORT_FORMAT_VERSION);
+    buf.put_u32_le(header.server_id.get_u32());
+    put_string(&mut buf, &header.db_name);
+    buf.freeze()
+}
+
+pub fn encode_object(object: &ExportedObject) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_u32_le(object.directories.len() as u32);
+    for dir in &object.directories {
+        put_string(&mut buf, dir);
+    }
+    put_string(&mut buf, &object.file_name);
+    buf.put_u64_le(object.data.len() as u64);
+    buf.put_slice(&object.data);
+    buf.freeze()
+}
+
+/// Decodes a database export incrementally, as its bytes arrive.
+///
+/// Received bytes are buffered until they form the header or a complete
+/// object, so at most one object is held in memory at a time.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buf: BytesMut,
+    header: Option<ExportHeader>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the next bytes of the export
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Decodes the header of the export, returning `None` if more bytes are
+    /// needed first.
+    pub fn header(&mut self) -> Result<Option<ExportHeader>> {
+        if self.header.is_none() {
+            let mut cursor = &self.buf[..];
+            let header = match get_header(&mut cursor) {
+                Err(Error::Truncated) => return Ok(None),
+                header => header?,
+            };
+            let len = self.buf.len() - cursor.len();
+            self.buf.advance(len);
+            self.header = Some(header);
+        }
+
+        Ok(self.header.clone())
+    }
+
+    /// Decodes the next object of the export, returning `None` if more bytes
+    /// are needed first.
+    ///
+    /// The header must have been decoded before.
+    pub fn next_object(&mut self) -> Result<Option<ExportedObject>> {
+        assert!(self.header.is_some(), "header not decoded yet");
+
+        let mut cursor = &self.buf[..];
+        let (directories, file_name, len) = match get_object_prefix(&mut cursor) {
+            Err(Error::Truncated) => return Ok(None),
+            prefix => prefix?,
+        };
+        if cursor.remaining() < len {
+            return Ok(None);
+        }
+        let prefix_len = self.buf.len() - cursor.len();
+
+        self.buf.advance(prefix_len);
+        Ok(Some(ExportedObject {
+            directories,
+            file_name,
+            data: self.buf.split_to(len).freeze(),
+        }))
+    }
+
+    /// Checks that the export was complete, once all its bytes were received
+    /// and all its objects decoded.
+    pub fn finish(&self) -> Result<()> {
+        ensure!(self.header.is_some() && self.buf.is_empty(), Truncated);
+        Ok(())
+    }
+}
+
+fn get_header(data: &mut impl Buf) -> Result<ExportHeader> {
+    ensure!(data.remaining() >= MAGIC.len(), Truncated);
+    ensure!(&data.copy_to_bytes(MAGIC.len())[..] == MAGIC, BadMagic);
+
+    let version = get_u32(data)?;
+    ensure!(
+        version == EXPORT_FORMAT_VERSION,
+        UnsupportedVersion { version }
+    );
+
+    let server_id = get_u32(data)?;
+    let server_id = server_id
+        .try_into()
+        .ok()
+        .context(InvalidServerId { server_id })?;
+    let db_name = get_string(data)?;
+
+    Ok(ExportHeader { server_id, db_name })
+}
+
+/// Decodes the directories, file name and data length of an object
+fn get_object_prefix(data: &mut impl Buf) -> Result<(Vec<String>, String, usize)> {
+    let num_directories = get_u32(data)?;
+    let directories = (0..num_directories)
+        .map(|_| get_string(data))
+        .collect::<Result<_>>()?;
+    let file_name = get_string(data)?;
+    let len = get_u64(data)? as usize;
+
+    Ok((directories, file_name, len))
+}
+
+fn put_string(buf: &mut BytesMut, s: &str) {
+    buf.put_u32_le(s.len() as u32);
+    buf.put_slice(s.as_bytes());
+}
+
+fn get_u32(data: &mut impl Buf) -> Result<u32> {
+    ensure!(data.remaining() >= 4, Truncated);
+    Ok(data.get_u32_le())
+}
+
+fn get_u64(data: &mut impl Buf) -> Result<u64> {
+    ensure!(data.remaining() >= 8, Truncated);
+    Ok(data.get_u64_le())
+}
+
+fn get_string(data: &mut impl Buf) -> Result<String> {
+    let len = get_u32(data)? as usize;
+    ensure!(data.remaining() >= len, Truncated);
+    String::from_utf8(data.copy_to_bytes(len).to_vec()).context(InvalidString)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn export() -> Bytes {
+        let header = ExportHeader {
+            server_id: ServerId::try_from(3).unwrap(),
+            db_name: "my_db".to_string(),
+        };
+        let object = ExportedObject {
+            directories: vec!["data".to_string(), "cpu".to_string()],
+            file_name: "1.parquet".to_string(),
+            data: Bytes::from_static(b"some data"),
+        };
+
+        let mut buf = BytesMut::new();
+        buf.put(encode_header(&header));
+        buf.put(encode_object(&object));
+        buf.freeze()
+    }
+
+    /// Decodes an export received in chunks of `chunk_size` bytes
+    fn decode(data: Bytes, chunk_size: usize) -> Result<(ExportHeader, Vec<ExportedObject>)> {
+        let mut decoder = Decoder::new();
+        let mut header = None;
+        let mut objects = vec![];
+        for chunk in data.chunks(chunk_size) {
+            decoder.push(chunk);
+            if header.is_none() {
+                header = decoder.header()?;
+            }
+            if header.is_some() {
+                while let Some(object) = decoder.next_object()? {
+                    objects.push(object);
+                }
+            }
+        }
+        decoder.finish()?;
+
+        Ok((header.unwrap(), objects))
+    }
+
+    #[test]
+    fn round_trip() {
+        for chunk_size in &[1, 7, 1024] {
+            let (header, objects) = decode(export(), *chunk_size).unwrap();
+            assert_eq!(header.server_id.get_u32(), 3);
+            assert_eq!(header.db_name, "my_db");
+            assert_eq!(objects.len(), 1);
+            assert_eq!(objects[0].directories, vec!["data", "cpu"]);
+            assert_eq!(objects[0].file_name, "1.parquet");
+            assert_eq!(&objects[0].data[..], b"some data");
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        let data = export();
+
+        let err = decode(data.slice(..data.len() - 1), 1024).unwrap_err();
+        assert!(matches!(err, Error::Truncated));
+
+        let err = decode(Bytes::from_static(b"NOTANEXPORT"), 1024).unwrap_err();
+        assert!(matches!(err, Error::BadMagic));
+
+        let mut bad_version = BytesMut::from(&data[..]);
+        bad_version[MAGIC.len()] = 42;
+        let err = decode(bad_version.freeze(), 1024).unwrap_err();
+        assert!(matches!(err, Error::UnsupportedVersion { version: 42 }));
+    }
+}
@@ -0,0 +1,167 @@
+//! A bounded cache of the idempotency keys of recently accepted writes.
+use std::{
+    collections::{HashMap, VecDeque},
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+/// Remembers the idempotency keys of writes, per database, for `ttl` after
+/// they were accepted, holding at most `capacity` of them.
+///
+/// Once full, recording a new key forgets the oldest one, so a retry may be
+/// ingested again if more than `capacity` keyed writes happened since the
+/// original one.
+#[derive(Debug)]
+pub(crate) struct IdempotencyCache {
+    capacity: NonZeroUsize,
+    ttl: Duration,
+    state: Mutex<IdempotencyCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct IdempotencyCacheState {
+    /// Recorded keys along with the time they were recorded at.
+    entries: HashMap<(String, String), Instant>,
+
+    /// Recorded keys in the order they were recorded, which is also the
+    /// order in which they expire.
+    order: VecDeque<(Instant, (String, String))>,
+}
+
+impl IdempotencyCache {
+    pub(crate) fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            state: Default::default(),
+        }
+    }
+
+    /// Returns true if `key` was recorded for `db_name` less than `ttl` before
+    /// `now`.
+    #[cfg(test)]
+    pub(crate) fn contains(&self, db_name: &str, key: &str, now: Instant) -> bool {
+        self.state
+            .lock()
+            .entries
+            .get(&(db_name.to_string(), key.to_string()))
+            .map_or(false, |recorded| {
+                now.saturating_duration_since(*recorded) < self.ttl
+            })
+    }
+
+    /// Records `key` for `db_name` at `now` unless it was recorded less than
+    /// `ttl` before `now`, returning whether it was recorded. Checking and
+    /// recording happen atomically, so of concurrent callers with the same key
+    /// only one records it.
+    ///
+    /// Expired keys and, if the cache is full, the oldest key are forgotten.
+    /// Recording a key that is already known doesn't extend its lifetime.
+    pub(crate) fn try_insert(&self, db_name: &str, key: &str, now: Instant) -> bool {
+        let mut state = self.state.lock();
+        let key = (db_name.to_string(), key.to_string());
+
+        while let Some((recorded, _)) = state.order.front() {
+            let expired = now.saturating_duration_since(*recorded) >= self.ttl;
+            let full =
+                state.entries.len() >= self.capacity.get() && !state.entries.contains_key(&key);
+            if !expired && !full {
+                break;
+            }
+
+            let (recorded, oldest) = state.order.pop_front().unwrap();
+            // the key may have been removed and recorded again since
+            if state.entries.get(&oldest) == Some(&recorded) {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        if state.entries.contains_key(&key) {
+            return false;
+        }
+        state.entries.insert(key.clone(), now);
+        state.order.push_back((now, key));
+        true
+    }
+
+    /// Forgets `key` for `db_name`, e.g. because the write it was recorded for
+    /// failed and may be retried.
+    pub(crate) fn remove(&self, db_name: &str, key: &str) {
+        self.state
+            .lock()
+            .entries
+            .remove(&(db_name.to_string(), key.to_string()));
+    }
+
+    /// Returns the number of recorded keys, including expired ones that were
+    /// not forgotten yet.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_keys() {
+        let cache = IdempotencyCache::new(NonZeroUsize::new(10).unwrap(), Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(!cache.contains("db", "a", start));
+        assert!(cache.try_insert("db", "a", start));
+        assert!(cache.contains("db", "a", start));
+        assert!(cache.contains("db", "a", start + Duration::from_secs(59)));
+        assert!(!cache.contains("db", "a", start + Duration::from_secs(60)));
+
+        // keys are scoped to a database
+        assert!(!cache.contains("other", "a", start));
+
+        // expired keys are forgotten when recording new ones
+        assert!(cache.try_insert("db", "b", start + Duration::from_secs(30)));
+        assert!(cache.try_insert("db", "c", start + Duration::from_secs(61)));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains("db", "b", start + Duration::from_secs(61)));
+
+        // only unexpired keys can't be recorded again
+        assert!(!cache.try_insert("db", "b", start + Duration::from_secs(62)));
+        assert!(cache.try_insert("db", "b", start + Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn remove() {
+        let cache = IdempotencyCache::new(NonZeroUsize::new(10).unwrap(), Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(cache.try_insert("db", "a", start));
+        cache.remove("db", "a");
+        assert!(!cache.contains("db", "a", start));
+
+        // the forgotten record doesn't expire the key recorded again
+        assert!(cache.try_insert("db", "a", start + Duration::from_secs(30)));
+        assert!(cache.try_insert("db", "b", start + Duration::from_secs(61)));
+        assert!(cache.contains("db", "a", start + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn bounded() {
+        let cache = IdempotencyCache::new(NonZeroUsize::new(2).unwrap(), Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert!(cache.try_insert("db", "a", now));
+        assert!(cache.try_insert("db", "b", now));
+        // recording a key again fails and doesn't make the cache grow
+        assert!(!cache.try_insert("db", "b", now));
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.try_insert("db", "c", now));
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains("db", "a", now));
+        assert!(cache.contains("db", "b", now));
+        assert!(cache.contains("db", "c", now));
+    }
+}
@@ -9,9 +9,9 @@ use object_store::{
 };
 use observability_deps::tracing::{debug, error, info, warn};
 use parking_lot::Mutex;
-use parquet_file::catalog::PreservedCatalog;
+use parquet_file::catalog::{CatalogRepair, PreservedCatalog};
 use query::exec::Executor;
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::{
     collections::HashMap,
     sync::{
@@ -19,14 +19,14 @@ use std::{
         Arc,
     },
 };
-use tokio::sync::Semaphore;
+use tokio::sync::{Notify, Semaphore};
 
 use crate::{
     config::{
         object_store_path_for_database_config, Config, DatabaseHandle, DatabaseStateCode,
         DB_RULES_FILE_NAME,
     },
-    db::load::load_or_create_preserved_catalog,
+    db::load::load_or_repair_preserved_catalog,
     write_buffer, DatabaseError,
 };
 
@@ -126,16 +126,27 @@ pub struct InitStatus {
     /// cannot be configured.
     initialize_semaphore: Semaphore,
 
+    /// Notified whenever an attempt to initialize the server finishes, whether it succeeded or not.
+    init_finished: Notify,
+
     /// Error occurred during generic server init (e.g. listing store content).
     error_generic: Mutex<Option<Arc<Error>>>,
 
     /// Errors that occurred during some DB init.
     errors_databases: Arc<Mutex<HashMap<String, Arc<Error>>>>,
 
+    /// Preserved catalogs that were repaired during some DB init.
+    repairs_databases: Arc<Mutex<HashMap<String, Arc<CatalogRepair>>>>,
+
     /// Automatic wipe-on-error recovery
     ///
     /// See https://github.com/influxdata/influxdb_iox/issues/1522)
     pub(crate) wipe_on_error: AtomicBool,
+
+    /// Automatic recovery by truncating a corrupt preserved catalog to its last good transaction.
+    ///
+    /// This is tried before wiping the catalog. Since it drops transactions it is disabled by default.
+    pub(crate) repair_on_error: AtomicBool,
 }
 
 impl InitStatus {
@@ -146,9 +157,12 @@ impl InitStatus {
             initialized: AtomicBool::new(false),
             // Always set semaphore permits to `1`, see design comments in `Server::initialize_semaphore`.
             initialize_semaphore: Semaphore::new(1),
+            init_finished: Notify::new(),
             error_generic: Default::default(),
             errors_databases: Default::default(),
+            repairs_databases: Default::default(),
             wipe_on_error: AtomicBool::new(true),
+            repair_on_error: AtomicBool::new(false),
         }
     }
 
@@ -189,6 +203,12 @@ impl InitStatus {
         guard.get(db_name).cloned()
     }
 
+    /// Repair that was performed on the preserved catalog of a specific database during initialization.
+    pub fn catalog_repair(&self, db_name: &str) -> Option<Arc<CatalogRepair>> {
+        let guard = self.repairs_databases.lock();
+        guard.get(db_name).cloned()
+    }
+
     /// Loads the database configurations based on the databases in the
     /// object store. Any databases in the config already won't be
     /// replaced.
@@ -243,6 +263,28 @@ impl InitStatus {
                 *guard = Some(Arc::new(e));
             }
         }
+
+        self.init_finished.notify_waiters();
+    }
+
+    /// Waits until the server is initialized, i.e. all databases found in the object store finished loading.
+    ///
+    /// Returns the generic init error if initialization failed. Errors of individual databases are NOT reported
+    /// here, see [`databases_with_errors`](Self::databases_with_errors).
+    pub(crate) async fn wait_for_init(&self) -> Result<(), Arc<Error>> {
+        loop {
+            // Create the future before checking the flags so that a notification sent in between is not missed.
+            let notified = self.init_finished.notified();
+
+            if self.initialized() {
+                return Ok(());
+            }
+            if let Some(e) = self.error_generic() {
+                return Err(e);
+            }
+
+            notified.await;
+        }
     }
 
     async fn maybe_initialize_server_inner(
@@ -267,7 +309,9 @@ impl InitStatus {
                 let config = Arc::clone(&config);
                 let exec = Arc::clone(&exec);
                 let errors_databases = Arc::clone(&self.errors_databases);
+                let repairs_databases = Arc::clone(&self.repairs_databases);
                 let wipe_on_error = self.wipe_on_error.load(Ordering::Relaxed);
+                let repair_on_error = self.repair_on_error.load(Ordering::Relaxed);
                 let root = root.clone();
 
                 path.set_file_name(DB_RULES_FILE_NAME);
@@ -275,7 +319,8 @@ impl InitStatus {
                 match db_name_from_rules_path(&path) {
                     Ok(db_name) => {
                         let handle = tokio::task::spawn(async move {
-                            match Self::initialize_database(
+                            let mut repair = None;
+                            let res = Self::initialize_database(
                                 server_id,
                                 store,
                                 config,
@@ -283,9 +328,17 @@ impl InitStatus {
                                 root,
                                 db_name.clone(),
                                 wipe_on_error,
+                                repair_on_error,
+                                &mut repair,
                             )
-                            .await
-                            {
+                            .await;
+
+                            if let Some(repair) = repair {
+                                let mut guard = repairs_databases.lock();
+                                guard.insert(db_name.to_string(), Arc::new(repair));
+                            }
+
+                            match res {
                                 Ok(()) => {
                                     info!(%db_name, "database initialized");
                                 }
@@ -311,7 +364,12 @@ impl InitStatus {
         Ok(())
     }
 
-    async fn initialize_database(
+    /// Initialize a single database.
+    ///
+    /// If the preserved catalog had to be repaired, the performed repair is stored in `repair`, even if the init fails
+    /// afterwards.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn initialize_database(
         server_id: ServerId,
         store: Arc<ObjectStore>,
         config: Arc<Config>,
@@ -319,6 +377,8 @@ impl InitStatus {
         root: Path,
         db_name: DatabaseName<'static>,
         wipe_on_error: bool,
+        repair_on_error: bool,
+        repair: &mut Option<CatalogRepair>,
     ) -> Result<()> {
         // Reserve name before expensive IO (e.g. loading the preserved catalog)
         let mut handle = config
@@ -330,6 +390,8 @@ impl InitStatus {
             &mut handle,
             &root,
             wipe_on_error,
+            repair_on_error,
+            repair,
         )
         .await
         {
@@ -351,6 +413,30 @@ impl InitStatus {
         }
     }
 
+    /// Loads a database whose files were just written to object storage, e.g.
+    /// by an import, into the state of `handle`.
+    ///
+    /// Unlike [`initialize_database`](Self::initialize_database), nothing is
+    /// committed: the caller commits the handle if the database was fully
+    /// loaded and may remove the written files before dropping it otherwise.
+    pub(crate) async fn load_imported_database(
+        handle: &mut DatabaseHandle<'_>,
+        root: &Path,
+    ) -> Result<()> {
+        let rules_path = object_store_path_for_database_config(root, &handle.db_name());
+        let complete = Self::try_advance_database_init_process_until_complete(
+            handle, root, false, false, &mut None,
+        )
+        .await?;
+        ensure!(
+            complete,
+            NoDatabaseConfigError {
+                location: rules_path
+            }
+        );
+        Ok(())
+    }
+
     async fn load_database_rules(
         store: Arc<ObjectStore>,
         path: Path,
@@ -408,13 +494,27 @@ impl InitStatus {
 
             let root = self.root_path(&store)?;
             let wipe_on_error = self.wipe_on_error.load(Ordering::Relaxed);
-            match Self::try_advance_database_init_process_until_complete(
+            let repair_on_error = self.repair_on_error.load(Ordering::Relaxed);
+            let mut repair = None;
+            let res = Self::try_advance_database_init_process_until_complete(
                 &mut handle,
                 &root,
                 wipe_on_error,
+                repair_on_error,
+                &mut repair,
             )
-            .await
+            .await;
+
             {
+                // the catalog was just wiped, so any earlier repair report is outdated
+                let mut guard = self.repairs_databases.lock();
+                match repair {
+                    Some(repair) => guard.insert(db_name.to_string(), Arc::new(repair)),
+                    None => guard.remove(&db_name.to_string()),
+                };
+            }
+
+            match res {
                 Ok(_) => {
                     // yeah, recovered DB
                     handle.commit();
@@ -460,13 +560,25 @@ impl InitStatus {
     /// Returns an error if there was an error along the way (in which case the handle should still be commit to safe
     /// the intermediate result). Returns `Ok(true)` if DB init is finished and `Ok(false)` if the DB can be forgotten
     /// (e.g. because not rules file is present.)
+    ///
+    /// If the preserved catalog had to be repaired, the performed repair is stored in `repair`.
     async fn try_advance_database_init_process_until_complete(
         handle: &mut DatabaseHandle<'_>,
         root: &Path,
         wipe_on_error: bool,
+        repair_on_error: bool,
+        repair: &mut Option<CatalogRepair>,
     ) -> Result<bool> {
         loop {
-            match Self::try_advance_database_init_process(handle, root, wipe_on_error).await? {
+            match Self::try_advance_database_init_process(
+                handle,
+                root,
+                wipe_on_error,
+                repair_on_error,
+                repair,
+            )
+            .await?
+            {
                 InitProgress::Unfinished => {}
                 InitProgress::Done => {
                     return Ok(true);
@@ -483,6 +595,8 @@ impl InitStatus {
         handle: &mut DatabaseHandle<'_>,
         root: &Path,
         wipe_on_error: bool,
+        repair_on_error: bool,
+        repair: &mut Option<CatalogRepair>,
     ) -> Result<InitProgress> {
         match handle.state_code() {
             DatabaseStateCode::Known => {
@@ -506,16 +620,19 @@ impl InitStatus {
             }
             DatabaseStateCode::RulesLoaded => {
                 // rules already loaded => continue with loading preserved catalog
-                let (preserved_catalog, catalog) = load_or_create_preserved_catalog(
-                    &handle.db_name(),
-                    handle.object_store(),
-                    handle.server_id(),
-                    handle.metrics_registry(),
-                    wipe_on_error,
-                )
-                .await
-                .map_err(|e| Box::new(e) as _)
-                .context(CatalogLoadError)?;
+                let (preserved_catalog, catalog, catalog_repair) =
+                    load_or_repair_preserved_catalog(
+                        &handle.db_name(),
+                        handle.object_store(),
+                        handle.server_id(),
+                        handle.metrics_registry(),
+                        wipe_on_error,
+                        repair_on_error,
+                    )
+                    .await
+                    .map_err(|e| Box::new(e) as _)
+                    .context(CatalogLoadError)?;
+                *repair = catalog_repair;
 
                 let rules = handle
                     .rules()
@@ -69,44 +69,73 @@
 )]
 
 use std::convert::TryInto;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
 use config::DatabaseStateCode;
 use db::load::create_preserved_catalog;
 use init::InitStatus;
 use observability_deps::tracing::{debug, info, warn};
 use parking_lot::Mutex;
-use snafu::{OptionExt, ResultExt, Snafu};
+use parquet_file::catalog::{
+    relocate_transaction, CatalogRepair, CHECKPOINT_FILE_SUFFIX, TRANSACTION_FILE_SUFFIX,
+};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 
 use data_types::{
+    chunk_metadata::{ChunkStorage, ChunkSummary},
     database_rules::DatabaseRules,
     job::Job,
     server_id::ServerId,
     {DatabaseName, DatabaseNameError},
 };
-use entry::{lines_to_sharded_entries, Entry, ShardedEntry};
+use entry::{lines_to_sharded_entries_with_coercion, Entry, FieldTypeCoercion, ShardedEntry};
 use influxdb_line_protocol::ParsedLine;
 use metrics::{KeyValue, MetricObserverBuilder, MetricRegistry};
-use object_store::{ObjectStore, ObjectStoreApi};
-use query::{exec::Executor, DatabaseStore};
+use object_store::{
+    path::{parsed::DirsAndFileName, ObjectStorePath},
+    ObjectStore, ObjectStoreApi,
+};
+use query::{exec::Executor, frontend::sql::SqlQueryPlanner, DatabaseStore, QueryDatabase};
 use tracker::{TaskId, TaskRegistration, TaskRegistryWithHistory, TaskTracker, TrackedFutureExt};
 
 pub use crate::config::RemoteTemplate;
-use crate::config::{object_store_path_for_database_config, Config, GRpcConnectionString};
-use cache_loader_async::cache_api::LoadingCache;
-use data_types::database_rules::{NodeGroup, RoutingRules, Shard, ShardConfig, ShardId};
+use crate::config::{
+    database_config_version_from_path, object_store_path_for_database_config,
+    object_store_path_for_database_config_json, object_store_path_for_database_config_version,
+    object_store_path_for_database_config_versions, Config, GRpcConnectionString,
+};
+use data_types::database_rules::{
+    NodeGroup, RoutingConfig, RoutingRules, RuleValidationIssue, Shard, ShardConfig, ShardId,
+};
+pub use db::process_clock::{ClockSource, MonotonicCounter, ProcessClock};
 pub use db::Db;
-use generated_types::database_rules::encode_database_rules;
-use influxdb_iox_client::{connection::Builder, write};
-use lifecycle::LockableChunk;
-use rand::seq::SliceRandom;
-use std::collections::HashMap;
+use futures::{Stream, StreamExt, TryStreamExt};
+use generated_types::database_rules::{
+    decode_database_rules, encode_database_rules, encode_database_rules_json,
+};
+use idempotency::IdempotencyCache;
+use influxdb_iox_client::{
+    connection::{Builder, DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT},
+    write,
+};
+use lifecycle::{LockableChunk, LockablePartition};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use remote_cache::RemoteCache;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 mod config;
 pub mod db;
+pub mod export;
+mod idempotency;
 mod init;
+mod remote_cache;
 mod write_buffer;
 
 /// Utility modules used by benchmarks and tests
@@ -131,6 +160,9 @@ pub enum Error {
     #[snafu(display("chunk not found: {}", source))]
     ChunkNotFound { source: db::catalog::Error },
 
+    #[snafu(display("partition not found: {}", source))]
+    PartitionNotFound { source: db::catalog::Error },
+
     #[snafu(display("getting mutable buffer chunk: {}", source))]
     MutableBufferChunk { source: DatabaseError },
 
@@ -151,11 +183,43 @@ pub enum Error {
     ))]
     ServerNotInitialized { server_id: ServerId },
 
+    #[snafu(display("server failed to initialize: {}", error))]
+    ServerInitFailed { error: Arc<crate::init::Error> },
+
+    #[snafu(display("databases failed to load: {}", db_names.join(", ")))]
+    DatabasesFailedToLoad { db_names: Vec<String> },
+
+    #[snafu(display("databases not ready after {:?}", timeout))]
+    DatabasesNotReady { timeout: Duration },
+
     #[snafu(display("error serializing database rules to protobuf: {}", source))]
     ErrorSerializingRulesProtobuf {
         source: generated_types::database_rules::EncodeError,
     },
 
+    #[snafu(display("error serializing database rules to JSON: {}", source))]
+    ErrorSerializingRulesJson {
+        source: generated_types::database_rules::EncodeError,
+    },
+
+    #[snafu(display("error deserializing database rules from protobuf: {}", source))]
+    ErrorDeserializingRulesProtobuf {
+        source: generated_types::database_rules::DecodeError,
+    },
+
+    #[snafu(display("rules version {} not found for database: {}", version, db_name))]
+    RulesVersionNotFound { db_name: String, version: u64 },
+
+    #[snafu(display(
+        "stored rules of database {} are invalid: {}",
+        db_name,
+        issues.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    ))]
+    InvalidStoredRules {
+        db_name: String,
+        issues: Vec<RuleValidationIssue>,
+    },
+
     #[snafu(display("error deserializing configuration {}", source))]
     ErrorDeserializing { source: serde_json::Error },
 
@@ -182,16 +246,26 @@ pub enum Error {
     LineConversion { source: entry::Error },
 
     #[snafu(display("error decoding entry flatbuffers: {}", source))]
-    DecodingEntry {
-        source: flatbuffers::InvalidFlatbuffer,
-    },
+    DecodingEntry { source: entry::DecodeError },
+
+    #[snafu(display("invalid entry: {}", source))]
+    InvalidEntry { source: entry::ValidationError },
 
     #[snafu(display("shard not found: {}", shard_id))]
     ShardNotFound { shard_id: ShardId },
 
+    #[snafu(display("job not found: {}", id.0))]
+    JobNotFound { id: TaskId },
+
     #[snafu(display("hard buffer limit reached"))]
     HardLimitReached {},
 
+    #[snafu(display("error planning query: {}", source))]
+    PlanningQuery { source: query::frontend::sql::Error },
+
+    #[snafu(display("error executing query: {}", source))]
+    ExecutingQuery { source: query::exec::Error },
+
     #[snafu(display("no remote configured for node group: {:?}", node_group))]
     NoRemoteConfigured { node_group: NodeGroup },
 
@@ -203,6 +277,32 @@ pub enum Error {
     #[snafu(display("remote error: {}", source))]
     RemoteError { source: ConnectionManagerError },
 
+    #[snafu(display(
+        "routing quorum not reached, {} of {} required targets accepted the write: {:?}",
+        succeeded,
+        required,
+        failures
+    ))]
+    RoutingQuorumNotReached {
+        required: usize,
+        succeeded: usize,
+        failures: Vec<(NodeGroup, Error)>,
+    },
+
+    #[snafu(display(
+        "replication factor of shard {} not reached, {} of {} required nodes accepted the write: {:?}",
+        shard_id,
+        succeeded,
+        required,
+        failures
+    ))]
+    ReplicationFactorNotReached {
+        shard_id: ShardId,
+        required: usize,
+        succeeded: usize,
+        failures: Vec<(ServerId, Error)>,
+    },
+
     #[snafu(display("cannot create preserved catalog: {}", source))]
     CannotCreatePreservedCatalog { source: DatabaseError },
 
@@ -227,12 +327,27 @@ pub enum Error {
 
     #[snafu(display("server is shutting down"))]
     ServerShuttingDown,
+
+    #[snafu(display("invalid database export: {}", source))]
+    InvalidExport { source: export::Error },
+
+    #[snafu(display("cannot relocate imported preserved catalog: {}", source))]
+    RelocatingCatalog {
+        source: parquet_file::catalog::Error,
+    },
+
+    #[snafu(display("error loading imported database: {}", source))]
+    ImportingDatabase { source: crate::init::Error },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 const JOB_HISTORY_SIZE: usize = 1000;
 
+/// How long `Server::health_detail` waits for a remote server to be
+/// connected to before considering it unreachable.
+const REMOTE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// The global job registry
 #[derive(Debug)]
 pub struct JobRegistry {
@@ -270,12 +385,40 @@ pub struct ServerConfig {
     // to number of cores on the system.
     num_worker_threads: Option<usize>,
 
+    // priority of the executor worker threads. If not specified, defaults
+    // to `query::exec::DEFAULT_WORKER_PRIORITY`.
+    worker_thread_priority: Option<i32>,
+
     /// The `ObjectStore` instance to use for persistence
     object_store: Arc<ObjectStore>,
 
     metric_registry: Arc<MetricRegistry>,
 
     remote_template: Option<RemoteTemplate>,
+
+    timestamp_source: TimestampSource,
+
+    per_table_metrics: bool,
+
+    jemalloc_sample_interval: Duration,
+
+    remote_selection_seed: Option<u64>,
+
+    max_query_result_rows: Option<usize>,
+
+    persist_rules_json: bool,
+
+    idempotency_cache_capacity: NonZeroUsize,
+
+    idempotency_key_ttl: Duration,
+
+    clock_source: Arc<dyn ClockSource>,
+
+    auto_create_databases: bool,
+
+    repair_catalog_on_error: bool,
+
+    field_type_coercion: FieldTypeCoercion,
 }
 
 impl ServerConfig {
@@ -287,9 +430,23 @@ impl ServerConfig {
     ) -> Self {
         Self {
             num_worker_threads: None,
+            worker_thread_priority: None,
             object_store,
             metric_registry,
             remote_template,
+            timestamp_source: TimestampSource::default(),
+            per_table_metrics: false,
+            jemalloc_sample_interval: DEFAULT_JEMALLOC_SAMPLE_INTERVAL,
+            remote_selection_seed: None,
+            max_query_result_rows: None,
+            persist_rules_json: false,
+            idempotency_cache_capacity: NonZeroUsize::new(DEFAULT_IDEMPOTENCY_CACHE_CAPACITY)
+                .expect("default capacity is non-zero"),
+            idempotency_key_ttl: DEFAULT_IDEMPOTENCY_KEY_TTL,
+            clock_source: Arc::new(ProcessClock::new()),
+            auto_create_databases: true,
+            repair_catalog_on_error: false,
+            field_type_coercion: FieldTypeCoercion::default(),
         }
     }
 
@@ -299,12 +456,213 @@ impl ServerConfig {
         self
     }
 
+    /// Run queries on worker threads with the given priority (value passed
+    /// to `setpriority`). Query threads always run on a dedicated thread pool
+    /// separate from the one handling ingest and API requests; lowering their
+    /// priority prevents heavy queries from starving writes.
+    pub fn with_worker_thread_priority(mut self, priority: i32) -> Self {
+        self.worker_thread_priority = Some(priority);
+        self
+    }
+
+    /// Assign timestamps from `timestamp_source` to written lines that don't
+    /// specify one.
+    pub fn with_timestamp_source(mut self, timestamp_source: TimestampSource) -> Self {
+        self.timestamp_source = timestamp_source;
+        self
+    }
+
+    /// Record ingest metrics per table, in addition to per database.
+    ///
+    /// Each table written to adds a new label value, so this should only be
+    /// enabled when the number of tables is bounded.
+    pub fn with_per_table_metrics(mut self, per_table_metrics: bool) -> Self {
+        self.per_table_metrics = per_table_metrics;
+        self
+    }
+
+    /// Refresh jemalloc statistics at most once per `interval`; metric
+    /// scrapes within the interval observe the previously sampled values.
+    pub fn with_jemalloc_sample_interval(mut self, interval: Duration) -> Self {
+        self.jemalloc_sample_interval = interval;
+        self
+    }
+
+    /// Seed the random order in which the remotes of a node group are tried
+    /// when writing downstream, making it deterministic. Intended for tests;
+    /// by default a thread local random number generator is used.
+    pub fn with_remote_selection_seed(mut self, seed: u64) -> Self {
+        self.remote_selection_seed = Some(seed);
+        self
+    }
+
+    /// Fail queries from the HTTP API once their results exceed `max_rows`
+    /// rows, rather than materializing them all in memory.
+    pub fn with_max_query_result_rows(mut self, max_rows: usize) -> Self {
+        self.max_query_result_rows = Some(max_rows);
+        self
+    }
+
+    /// Additionally write the rules of each database as `rules.json` next to
+    /// `rules.pb`, so they can be inspected in object storage. The protobuf
+    /// remains the source of truth when loading.
+    pub fn with_persist_rules_json(mut self, persist_rules_json: bool) -> Self {
+        self.persist_rules_json = persist_rules_json;
+        self
+    }
+
+    /// Remember the idempotency keys of at most `capacity` writes, each for
+    /// `ttl` after the write was accepted. See
+    /// [`Server::write_lines_idempotent`].
+    pub fn with_idempotency_cache(mut self, capacity: NonZeroUsize, ttl: Duration) -> Self {
+        self.idempotency_cache_capacity = capacity;
+        self.idempotency_key_ttl = ttl;
+        self
+    }
+
+    /// Order the entries written to local databases without a write buffer
+    /// with values from `clock_source`, rather than a [`ProcessClock`]
+    /// following the system time.
+    pub fn with_clock_source(mut self, clock_source: Arc<dyn ClockSource>) -> Self {
+        self.clock_source = clock_source;
+        self
+    }
+
+    /// Create databases with default rules when a query or write through
+    /// [`DatabaseStore::db_or_create`] names one that doesn't exist. Enabled
+    /// by default; disabling it is recommended so that a misspelled database
+    /// name is reported rather than creating a new, empty database.
+    pub fn with_auto_create_databases(mut self, auto_create_databases: bool) -> Self {
+        self.auto_create_databases = auto_create_databases;
+        self
+    }
+
+    /// Truncate a preserved catalog with a corrupt transaction to its last
+    /// good transaction while loading a database, instead of failing (or
+    /// wiping it). Disabled by default because all transactions from the
+    /// corrupt one onwards are lost; performed repairs are reported by
+    /// [`Server::catalog_repair`].
+    pub fn with_repair_catalog_on_error(mut self, repair_catalog_on_error: bool) -> Self {
+        self.repair_catalog_on_error = repair_catalog_on_error;
+        self
+    }
+
+    /// Handle fields whose numeric type changes between the lines of a write
+    /// according to `field_type_coercion`, instead of rejecting the write.
+    pub fn with_field_type_coercion(mut self, field_type_coercion: FieldTypeCoercion) -> Self {
+        self.field_type_coercion = field_type_coercion;
+        self
+    }
+
     /// return a reference to the object store in this configuration
     pub fn store(&self) -> Arc<ObjectStore> {
         Arc::clone(&self.object_store)
     }
 }
 
+/// The source of the timestamp assigned to written lines that don't specify
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampSource {
+    /// Use the time at which the lines are written.
+    Now,
+
+    /// Use a fixed time, in nanoseconds since the epoch.
+    Fixed(i64),
+}
+
+impl Default for TimestampSource {
+    fn default() -> Self {
+        Self::Now
+    }
+}
+
+impl TimestampSource {
+    /// Returns the timestamp, in nanoseconds since the epoch.
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            Self::Now => chrono::Utc::now().timestamp_nanos(),
+            Self::Fixed(timestamp) => *timestamp,
+        }
+    }
+}
+
+/// The default minimum interval between refreshes of the jemalloc statistics
+pub const DEFAULT_JEMALLOC_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The default number of write idempotency keys remembered by a server
+pub const DEFAULT_IDEMPOTENCY_CACHE_CAPACITY: usize = 10_000;
+
+/// The default time for which a write idempotency key is remembered
+pub const DEFAULT_IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// A sample of the jemalloc allocator statistics, in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct JemallocStats {
+    active: usize,
+    allocated: usize,
+    metadata: usize,
+    mapped: usize,
+    resident: usize,
+    retained: usize,
+}
+
+impl JemallocStats {
+    /// Advances the jemalloc epoch and reads the refreshed statistics
+    fn read() -> Self {
+        use tikv_jemalloc_ctl::{epoch, stats};
+        epoch::advance().unwrap();
+
+        Self {
+            active: stats::active::read().unwrap(),
+            allocated: stats::allocated::read().unwrap(),
+            metadata: stats::metadata::read().unwrap(),
+            mapped: stats::mapped::read().unwrap(),
+            resident: stats::resident::read().unwrap(),
+            retained: stats::retained::read().unwrap(),
+        }
+    }
+}
+
+/// Caches the most recent `JemallocStats` sample so that advancing the
+/// jemalloc epoch happens at most once per `min_interval`.
+#[derive(Debug)]
+struct JemallocStatsCache {
+    min_interval: Duration,
+    last: Mutex<Option<(Instant, JemallocStats)>>,
+}
+
+impl JemallocStatsCache {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: Default::default(),
+        }
+    }
+
+    fn get(&self) -> JemallocStats {
+        self.get_with(Instant::now(), JemallocStats::read)
+    }
+
+    /// Returns the cached sample if it was taken less than `min_interval`
+    /// before `now`, otherwise calls `refresh` and caches its result.
+    fn get_with(&self, now: Instant, refresh: impl FnOnce() -> JemallocStats) -> JemallocStats {
+        let mut last = self.last.lock();
+        match *last {
+            Some((sampled_at, stats))
+                if now.saturating_duration_since(sampled_at) < self.min_interval =>
+            {
+                stats
+            }
+            _ => {
+                let stats = refresh();
+                *last = Some((now, stats));
+                stats
+            }
+        }
+    }
+}
+
 // A collection of metrics used to instrument the Server.
 #[derive(Debug)]
 pub struct ServerMetrics {
@@ -322,42 +680,35 @@ pub struct ServerMetrics {
 
     /// The number of Entry bytes ingested
     pub ingest_entries_bytes_total: metrics::Counter,
+
+    /// The number of rows ingested per table, only recorded if per table
+    /// metrics are enabled
+    pub ingest_table_rows_total: metrics::Counter,
 }
 
 impl ServerMetrics {
-    pub fn new(registry: Arc<metrics::MetricRegistry>) -> Self {
+    pub fn new(registry: Arc<metrics::MetricRegistry>, jemalloc_sample_interval: Duration) -> Self {
         // Server manages multiple domains.
         let http_domain = registry.register_domain("http");
         let ingest_domain = registry.register_domain("ingest");
         let jemalloc_domain = registry.register_domain("jemalloc");
 
         // This isn't really a property of the server, perhaps it should be somewhere else?
+        let jemalloc_stats = JemallocStatsCache::new(jemalloc_sample_interval);
         jemalloc_domain.register_observer(None, &[], |observer: MetricObserverBuilder<'_>| {
             observer.register_gauge_u64(
                 "memstats",
                 Some("bytes"),
                 "jemalloc memstats",
-                |observer| {
-                    use tikv_jemalloc_ctl::{epoch, stats};
-                    epoch::advance().unwrap();
-
-                    let active = stats::active::read().unwrap();
-                    observer.observe(active as u64, &[KeyValue::new("stat", "active")]);
-
-                    let allocated = stats::allocated::read().unwrap();
-                    observer.observe(allocated as u64, &[KeyValue::new("stat", "alloc")]);
-
-                    let metadata = stats::metadata::read().unwrap();
-                    observer.observe(metadata as u64, &[KeyValue::new("stat", "metadata")]);
-
-                    let mapped = stats::mapped::read().unwrap();
-                    observer.observe(mapped as u64, &[KeyValue::new("stat", "mapped")]);
-
-                    let resident = stats::resident::read().unwrap();
-                    observer.observe(resident as u64, &[KeyValue::new("stat", "resident")]);
-
-                    let retained = stats::retained::read().unwrap();
-                    observer.observe(retained as u64, &[KeyValue::new("stat", "retained")]);
+                move |observer| {
+                    let stats = jemalloc_stats.get();
+
+                    observer.observe(stats.active as u64, &[KeyValue::new("stat", "active")]);
+                    observer.observe(stats.allocated as u64, &[KeyValue::new("stat", "alloc")]);
+                    observer.observe(stats.metadata as u64, &[KeyValue::new("stat", "metadata")]);
+                    observer.observe(stats.mapped as u64, &[KeyValue::new("stat", "mapped")]);
+                    observer.observe(stats.resident as u64, &[KeyValue::new("stat", "resident")]);
+                    observer.observe(stats.retained as u64, &[KeyValue::new("stat", "retained")]);
                 },
             )
         });
@@ -384,8 +735,26 @@ impl ServerMetrics {
                 Some("bytes"),
                 "total Entry bytes ingested",
             ),
+            ingest_table_rows_total: ingest_domain.register_counter_metric(
+                "table_rows",
+                None,
+                "total rows ingested per table",
+            ),
+        }
+    }
+}
+
+/// Returns the number of rows written to each table by `entry`.
+fn entry_table_rows(entry: &Entry) -> BTreeMap<String, usize> {
+    let mut table_rows = BTreeMap::new();
+    for partition_write in entry.partition_writes().unwrap_or_default() {
+        for table_batch in partition_write.table_batches() {
+            *table_rows
+                .entry(table_batch.name().to_string())
+                .or_default() += table_batch.row_count();
         }
     }
+    table_rows
 }
 
 /// `Server` is the container struct for how servers store data internally, as
@@ -406,6 +775,182 @@ pub struct Server<M: ConnectionManager> {
     pub registry: Arc<metrics::MetricRegistry>,
 
     init_status: Arc<InitStatus>,
+
+    /// Serializes changes of database rules, so that every change is assigned
+    /// a unique version and the latest stored version matches the rules in
+    /// memory.
+    rules_persist_lock: tokio::sync::Mutex<()>,
+
+    timestamp_source: TimestampSource,
+
+    per_table_metrics: bool,
+
+    /// Orders the remotes tried when writing downstream, if seeded by
+    /// [`ServerConfig::with_remote_selection_seed`]. Otherwise the thread
+    /// local random number generator is used.
+    remote_rng: Option<Mutex<StdRng>>,
+
+    max_query_result_rows: Option<usize>,
+
+    persist_rules_json: bool,
+
+    /// Keys of recent writes made through [`Server::write_lines_idempotent`].
+    idempotency_keys: IdempotencyCache,
+
+    auto_create_databases: bool,
+
+    field_type_coercion: FieldTypeCoercion,
+}
+
+/// The result of a write that was accepted by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WriteOutcome {
+    /// The write was accepted.
+    Accepted,
+
+    /// The write was accepted but the database's buffer is above its
+    /// `buffer_size_soft` limit. Clients should slow down to avoid hitting
+    /// the hard limit.
+    OverSoftLimit,
+}
+
+impl Default for WriteOutcome {
+    fn default() -> Self {
+        Self::Accepted
+    }
+}
+
+/// Where the entries of a write go, as determined by the database's routing
+/// rules.
+#[derive(Debug)]
+enum WriteTarget {
+    /// Mirror each entry to the targets of the routing config.
+    Routing(RoutingConfig),
+
+    /// Write each entry to its shard, or locally if it has none.
+    Shards(Arc<HashMap<ShardId, Shard>>),
+}
+
+/// Identifies one of the entries written by
+/// [`Server::write_lines_partial`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardedWrite {
+    /// The shard the entry was sent to, or `None` if it wasn't sharded.
+    pub shard_id: Option<ShardId>,
+
+    /// The partitions the entry holds rows for.
+    pub partition_keys: BTreeSet<String>,
+}
+
+impl ShardedWrite {
+    fn new(sharded_entry: &ShardedEntry) -> Self {
+        Self {
+            shard_id: sharded_entry.shard_id,
+            partition_keys: sharded_entry
+                .entry
+                .partition_writes()
+                .unwrap_or_default()
+                .iter()
+                .map(|w| w.key().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// The outcome of [`Server::write_lines_partial`], listing which entries were
+/// written and which failed.
+#[derive(Debug, Default)]
+pub struct PartialWriteOutcome {
+    /// Entries that were written, with the outcome of the write.
+    pub succeeded: Vec<(ShardedWrite, WriteOutcome)>,
+
+    /// Entries that could not be written, with the error.
+    pub failed: Vec<(ShardedWrite, Error)>,
+}
+
+impl PartialWriteOutcome {
+    /// Returns true if all entries were written.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Describes the data written by [`Server::write_lines_with_summary`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WriteSummary {
+    /// Number of rows written per shard. Rows that were not sharded are
+    /// counted under `None`.
+    pub shard_rows: BTreeMap<Option<ShardId>, usize>,
+
+    /// Number of rows written per partition key.
+    pub partition_rows: BTreeMap<String, usize>,
+
+    /// Names of the tables written to.
+    pub tables: BTreeSet<String>,
+
+    /// The outcome of the writes into local databases.
+    pub outcome: WriteOutcome,
+}
+
+impl WriteSummary {
+    fn new(sharded_entries: &[ShardedEntry]) -> Self {
+        let mut summary = Self::default();
+
+        for sharded_entry in sharded_entries {
+            for partition_write in sharded_entry.entry.partition_writes().unwrap_or_default() {
+                for table_batch in partition_write.table_batches() {
+                    let rows = table_batch.row_count();
+
+                    *summary
+                        .shard_rows
+                        .entry(sharded_entry.shard_id)
+                        .or_default() += rows;
+                    *summary
+                        .partition_rows
+                        .entry(partition_write.key().to_string())
+                        .or_default() += rows;
+                    summary.tables.insert(table_batch.name().to_string());
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// The total number of rows written.
+    pub fn total_rows(&self) -> usize {
+        self.shard_rows.values().sum()
+    }
+}
+
+/// The rules and status of a database, as returned by
+/// [`Server::list_databases`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseInfo {
+    /// The current rules of the database
+    pub rules: DatabaseRules,
+    /// The number of chunks in the database
+    pub chunk_count: usize,
+    /// The memory used by the buffers of the database, in bytes
+    pub buffer_bytes: usize,
+}
+
+/// A summary of the state of a server and its components, more detailed than
+/// whether it is up at all.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthDetail {
+    /// The ID of the server, `None` if it has not been set yet
+    pub server_id: Option<ServerId>,
+    /// The number of databases loaded by the server
+    pub databases: usize,
+    /// The number of jobs that have not completed yet
+    pub running_jobs: usize,
+    /// The memory used by the buffers of all databases, in bytes
+    pub buffer_bytes: usize,
+    /// The number of configured remote servers
+    pub remotes: usize,
+    /// The number of configured remote servers that could be connected to
+    pub remotes_reachable: usize,
 }
 
 #[derive(Debug)]
@@ -429,29 +974,75 @@ where
 
         let ServerConfig {
             num_worker_threads,
+            worker_thread_priority,
             object_store,
             // to test the metrics provide a different registry to the `ServerConfig`.
             metric_registry,
             remote_template,
+            timestamp_source,
+            per_table_metrics,
+            jemalloc_sample_interval,
+            remote_selection_seed,
+            max_query_result_rows,
+            persist_rules_json,
+            idempotency_cache_capacity,
+            idempotency_key_ttl,
+            clock_source,
+            auto_create_databases,
+            repair_catalog_on_error,
+            field_type_coercion,
         } = config;
         let num_worker_threads = num_worker_threads.unwrap_or_else(num_cpus::get);
+        let worker_thread_priority =
+            worker_thread_priority.unwrap_or(query::exec::DEFAULT_WORKER_PRIORITY);
+
+        let init_status = InitStatus::new();
+        init_status.repair_on_error.store(
+            repair_catalog_on_error,
+            std::sync::atomic::Ordering::Relaxed,
+        );
 
         Self {
             config: Arc::new(Config::new(
                 Arc::clone(&jobs),
                 Arc::clone(&metric_registry),
                 remote_template,
+                clock_source,
             )),
             store: object_store,
             connection_manager: Arc::new(connection_manager),
-            exec: Arc::new(Executor::new(num_worker_threads)),
+            exec: Arc::new(Executor::new_with_worker_priority(
+                num_worker_threads,
+                worker_thread_priority,
+            )),
             jobs,
-            metrics: Arc::new(ServerMetrics::new(Arc::clone(&metric_registry))),
+            metrics: Arc::new(ServerMetrics::new(
+                Arc::clone(&metric_registry),
+                jemalloc_sample_interval,
+            )),
             registry: Arc::clone(&metric_registry),
-            init_status: Arc::new(InitStatus::new()),
+            init_status: Arc::new(init_status),
+            rules_persist_lock: tokio::sync::Mutex::new(()),
+            timestamp_source,
+            per_table_metrics,
+            remote_rng: remote_selection_seed.map(|seed| Mutex::new(StdRng::seed_from_u64(seed))),
+            max_query_result_rows,
+            persist_rules_json,
+            idempotency_keys: IdempotencyCache::new(
+                idempotency_cache_capacity,
+                idempotency_key_ttl,
+            ),
+            auto_create_databases,
+            field_type_coercion,
         }
     }
 
+    /// The maximum number of rows a query from the HTTP API may return, if
+    /// limited by [`ServerConfig::with_max_query_result_rows`].
+    pub fn max_query_result_rows(&self) -> Option<usize> {
+        self.max_query_result_rows
+    }
+
     /// sets the id of the server, which is used for replication and the base
     /// path in object storage.
     ///
@@ -485,6 +1076,31 @@ where
         self.init_status.error_database(db_name)
     }
 
+    /// Repair of the preserved catalog that was performed while loading a
+    /// specific database, see [`ServerConfig::with_repair_catalog_on_error`].
+    pub fn catalog_repair(&self, db_name: &str) -> Option<Arc<CatalogRepair>> {
+        self.init_status.catalog_repair(db_name)
+    }
+
+    /// Waits until the databases found in the object store have been loaded and the server is ready to read/write.
+    ///
+    /// Returns an error if the server failed to initialize, if any database failed to load or if `timeout` elapsed
+    /// first. The server ID must be set and [`maybe_initialize_server`](Self::maybe_initialize_server) be called
+    /// (e.g. by the [`background_worker`](Self::background_worker)) for this to ever complete.
+    pub async fn wait_for_databases_ready(&self, timeout: Duration) -> Result<()> {
+        tokio::time::timeout(timeout, self.init_status.wait_for_init())
+            .await
+            .map_err(|_| Error::DatabasesNotReady { timeout })?
+            .map_err(|error| Error::ServerInitFailed { error })?;
+
+        let db_names = self.databases_with_errors();
+        if !db_names.is_empty() {
+            return Err(Error::DatabasesFailedToLoad { db_names });
+        }
+
+        Ok(())
+    }
+
     /// Require that server is loaded. Databases are loaded and server is ready to read/write.
     fn require_initialized(&self) -> Result<ServerId> {
         // since a server ID is the pre-requirement for init, check this first
@@ -498,6 +1114,28 @@ where
         }
     }
 
+    /// Checks `rules` for problems without applying them, reporting every
+    /// problem found rather than only the first.
+    pub fn validate_rules(
+        &self,
+        rules: &DatabaseRules,
+    ) -> std::result::Result<(), Vec<RuleValidationIssue>> {
+        let mut issues = rules.partition_template.validate();
+        issues.extend(rules.lifecycle_rules.validate());
+        if let Some(RoutingRules::ShardConfig(shard_config)) = &rules.routing_rules {
+            issues.extend(shard_config.validate());
+        }
+        if let Some(connection_string) = &rules.write_buffer_connection_string {
+            issues.extend(write_buffer::validate_connection_string(connection_string));
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
     /// Tells the server the set of rules for a database.
     pub async fn create_database(&self, rules: DatabaseRules) -> Result<()> {
         // Return an error if this server is not yet ready
@@ -535,49 +1173,496 @@ where
         Ok(())
     }
 
+    /// Persists the rules of a database to object storage.
+    ///
+    /// Every call stores a new version under the `rules/` prefix of the
+    /// database, with a monotonically increasing version number, before
+    /// overwriting the canonical `rules.pb` that holds the current rules. If
+    /// enabled by [`ServerConfig::with_persist_rules_json`], a JSON copy is
+    /// then written to `rules.json`.
     pub async fn persist_database_rules<'a>(&self, rules: DatabaseRules) -> Result<()> {
-        let location = object_store_path_for_database_config(
-            &self
-                .init_status
-                .root_path(&self.store)
-                .context(GetIdError)?,
-            &rules.name,
-        );
+        let _guard = self.rules_persist_lock.lock().await;
+        self.persist_database_rules_locked(rules).await
+    }
+
+    // Like `persist_database_rules`, for callers already holding
+    // `rules_persist_lock`
+    async fn persist_database_rules_locked(&self, rules: DatabaseRules) -> Result<()> {
+        let root = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+        let location = object_store_path_for_database_config(&root, &rules.name);
+
+        let version = self
+            .list_rules_versions(&rules.name)
+            .await?
+            .last()
+            .map_or(1, |v| v + 1);
+        let version_location =
+            object_store_path_for_database_config_version(&root, &rules.name, version);
+
+        let json = if self.persist_rules_json {
+            let data =
+                encode_database_rules_json(rules.clone()).context(ErrorSerializingRulesJson)?;
+            Some((
+                object_store_path_for_database_config_json(&root, &rules.name),
+                data,
+            ))
+        } else {
+            None
+        };
 
         let mut data = BytesMut::new();
         encode_database_rules(rules, &mut data).context(ErrorSerializingRulesProtobuf)?;
+        let data = data.freeze();
 
-        let len = data.len();
+        // Write the version first so the current rules are always part of the
+        // history.
+        self.put_bytes(&version_location, data.clone()).await?;
+        self.put_bytes(&location, data).await?;
 
-        let stream_data = std::io::Result::Ok(data.freeze());
-        self.store
-            .put(
-                &location,
-                futures::stream::once(async move { stream_data }),
-                Some(len),
-            )
-            .await
-            .context(StoreError)?;
+        if let Some((json_location, data)) = json {
+            self.put_bytes(&json_location, Bytes::from(data)).await?;
+        }
         Ok(())
     }
 
-    /// Loads the database configurations based on the databases in the
-    /// object store. Any databases in the config already won't be
-    /// replaced.
-    ///
-    /// This requires the serverID to be set. It will be a no-op if the configs are already loaded and the server is ready.
-    pub async fn maybe_initialize_server(&self) {
-        self.init_status
-            .maybe_initialize_server(
-                Arc::clone(&self.store),
-                Arc::clone(&self.config),
-                Arc::clone(&self.exec),
-            )
-            .await;
+    /// Lists the versions of the rules stored for the given database in
+    /// ascending order.
+    pub async fn list_rules_versions(&self, db_name: &DatabaseName<'_>) -> Result<Vec<u64>> {
+        let root = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+        let prefix = object_store_path_for_database_config_versions(&root, db_name);
+
+        let mut versions: Vec<u64> = self
+            .store
+            .list(Some(&prefix))
+            .await
+            .context(StoreError)?
+            .map_ok(|paths| {
+                paths
+                    .into_iter()
+                    .filter_map(database_config_version_from_path)
+                    .collect::<Vec<_>>()
+            })
+            .try_concat()
+            .await
+            .context(StoreError)?;
+        versions.sort_unstable();
+
+        Ok(versions)
     }
 
-    /// `write_lines` takes in raw line protocol and converts it to a collection
-    /// of ShardedEntry which are then sent to other IOx servers based on
+    /// Reverts the rules of a database to a previously stored version.
+    ///
+    /// The restored rules are persisted as a new version, so a rollback can
+    /// itself be rolled back.
+    pub async fn rollback_rules(
+        &self,
+        db_name: &DatabaseName<'static>,
+        version: u64,
+    ) -> Result<DatabaseRules> {
+        if !self.list_rules_versions(db_name).await?.contains(&version) {
+            return RulesVersionNotFound {
+                db_name: db_name.to_string(),
+                version,
+            }
+            .fail();
+        }
+
+        let root = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+        let location = object_store_path_for_database_config_version(&root, db_name, version);
+
+        let data = self
+            .store
+            .get(&location)
+            .await
+            .context(StoreError)?
+            .map_ok(|b| BytesMut::from(&b[..]))
+            .try_concat()
+            .await
+            .context(StoreError)?;
+        let rules =
+            decode_database_rules(data.freeze()).context(ErrorDeserializingRulesProtobuf)?;
+
+        if &rules.name != db_name {
+            return RulesDatabaseNameMismatch {
+                actual: rules.name.to_string(),
+                expected: db_name.to_string(),
+            }
+            .fail();
+        }
+
+        self.update_db_rules(db_name, |_| Ok::<_, std::convert::Infallible>(rules))
+            .await
+            .map_err(|e| match e {
+                UpdateError::Update(e) => e,
+                UpdateError::Closure(e) => match e {},
+            })
+    }
+
+    /// Re-reads the current rules of a database from object storage and
+    /// applies them, picking up changes made to the stored rules by other
+    /// tools.
+    ///
+    /// The stored rules are validated first and the in-memory rules are left
+    /// untouched if they are invalid. Unlike
+    /// [`update_db_rules`](Self::update_db_rules), the rules are not persisted
+    /// again.
+    pub async fn reload_rules(&self, db_name: &DatabaseName<'_>) -> Result<DatabaseRules> {
+        if !self.database_exists(db_name) {
+            return DatabaseNotFound {
+                db_name: db_name.to_string(),
+            }
+            .fail();
+        }
+
+        let root = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+        let location = object_store_path_for_database_config(&root, db_name);
+
+        // hold the lock until the rules are applied, so that rules persisted
+        // concurrently are not replaced by the older copy read here
+        let _guard = self.rules_persist_lock.lock().await;
+
+        let data = self
+            .store
+            .get(&location)
+            .await
+            .context(StoreError)?
+            .map_ok(|b| BytesMut::from(&b[..]))
+            .try_concat()
+            .await
+            .context(StoreError)?;
+        let rules =
+            decode_database_rules(data.freeze()).context(ErrorDeserializingRulesProtobuf)?;
+
+        if &rules.name != db_name {
+            return RulesDatabaseNameMismatch {
+                actual: rules.name.to_string(),
+                expected: db_name.to_string(),
+            }
+            .fail();
+        }
+        if let Err(issues) = self.validate_rules(&rules) {
+            return InvalidStoredRules {
+                db_name: db_name.to_string(),
+                issues,
+            }
+            .fail();
+        }
+
+        self.config
+            .update_db_rules(db_name, |_| Ok::<_, std::convert::Infallible>(rules))
+            .map_err(|e| match e {
+                crate::config::UpdateError::Update(e) => e,
+                crate::config::UpdateError::Closure(e) => match e {},
+            })
+    }
+
+    /// Exports the rules and persisted data of a database as a stream of
+    /// bytes that can be passed to [`import_database`](Self::import_database)
+    /// on another server.
+    ///
+    /// Only data that has been persisted to object storage is exported.
+    /// Objects are read from object storage one at a time, as the stream is
+    /// consumed.
+    pub async fn export_database(
+        &self,
+        db_name: &DatabaseName<'_>,
+    ) -> Result<impl Stream<Item = Result<Bytes>>> {
+        let server_id = self.require_initialized()?;
+        self.config.db(db_name).context(DatabaseNotFound {
+            db_name: db_name.as_str(),
+        })?;
+
+        let mut prefix = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+        prefix.push_dir(db_name.to_string());
+        let prefix_dirs = DirsAndFileName::from(prefix.clone()).directories;
+
+        let paths: Vec<_> = self
+            .store
+            .list(Some(&prefix))
+            .await
+            .context(StoreError)?
+            .try_concat()
+            .await
+            .context(StoreError)?;
+
+        let header = export::encode_header(&export::ExportHeader {
+            server_id,
+            db_name: db_name.to_string(),
+        });
+
+        let objects = paths.into_iter().filter_map(move |path| {
+            let parsed = DirsAndFileName::from(path.clone());
+            // listing by prefix also matches databases whose name starts
+            // with this database's name
+            if !parsed.directories.starts_with(&prefix_dirs) {
+                return None;
+            }
+            let file_name = parsed.file_name.as_ref()?.to_string();
+            let directories = parsed.directories[prefix_dirs.len()..]
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            Some((path, directories, file_name))
+        });
+
+        let store = Arc::clone(&self.store);
+        let objects = futures::stream::iter(objects).then(move |(path, directories, file_name)| {
+            let store = Arc::clone(&store);
+            async move {
+                let data = store
+                    .get(&path)
+                    .await
+                    .context(StoreError)?
+                    .map_ok(|b| BytesMut::from(&b[..]))
+                    .try_concat()
+                    .await
+                    .context(StoreError)?;
+
+                Ok::<_, Error>(export::encode_object(&export::ExportedObject {
+                    directories,
+                    file_name,
+                    data: data.freeze(),
+                }))
+            }
+        });
+
+        Ok(futures::stream::once(async move { Ok(header) }).chain(objects))
+    }
+
+    /// Imports a database exported by
+    /// [`export_database`](Self::export_database) and loads it.
+    ///
+    /// The export may come from a server with another ID: the preserved
+    /// catalog is rewritten to reference the data files under this server's
+    /// root. Objects are written to object storage as they are received. If
+    /// the import fails, the objects written so far are removed again.
+    pub async fn import_database<S>(&self, data: S) -> Result<()>
+    where
+        S: Stream<Item = Bytes> + Send,
+    {
+        let server_id = self.require_initialized()?;
+        let root = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+
+        let mut data = Box::pin(data);
+        let mut decoder = export::Decoder::new();
+        let header = loop {
+            if let Some(header) = decoder.header().context(InvalidExport)? {
+                break header;
+            }
+            match data.next().await {
+                Some(bytes) => decoder.push(&bytes),
+                None => return Err(export::Error::Truncated).context(InvalidExport),
+            }
+        };
+
+        let db_name = DatabaseName::new(header.db_name).context(InvalidDatabaseName)?;
+        if self.config.db(&db_name).is_some() || self.config.has_uninitialized_database(&db_name) {
+            return Err(Error::DatabaseAlreadyExists {
+                db_name: db_name.to_string(),
+            });
+        }
+
+        // Reserve the name before writing any object, and keep it reserved
+        // until the import is either committed or cleaned up
+        let mut handle = self.config.create_db(
+            Arc::clone(&self.store),
+            Arc::clone(&self.exec),
+            server_id,
+            db_name.clone(),
+        )?;
+
+        let mut db_dir = root.clone();
+        db_dir.push_dir(db_name.to_string());
+        let relocate_to = Some(server_id).filter(|id| *id != header.server_id);
+
+        let mut written = vec![];
+        let mut result = self
+            .import_objects(&db_dir, relocate_to, &mut decoder, &mut data, &mut written)
+            .await;
+        if result.is_ok() {
+            result = InitStatus::load_imported_database(&mut handle, &root)
+                .await
+                .context(ImportingDatabase);
+        }
+
+        match result {
+            Ok(()) => handle.commit(),
+            Err(_) => {
+                for path in &written {
+                    if let Err(e) = self.store.delete(path).await {
+                        warn!(%db_name, %e, ?path, "cannot remove object of failed import");
+                    }
+                }
+                handle.abort();
+            }
+        }
+
+        result
+    }
+
+    /// Writes the objects of an export below `db_dir` as they are decoded,
+    /// recording the path of every object it attempted to write.
+    ///
+    /// If `relocate_to` is set, the preserved catalog is rewritten to
+    /// reference data files below the root of that server.
+    async fn import_objects<S>(
+        &self,
+        db_dir: &object_store::path::Path,
+        relocate_to: Option<ServerId>,
+        decoder: &mut export::Decoder,
+        data: &mut S,
+        written: &mut Vec<object_store::path::Path>,
+    ) -> Result<()>
+    where
+        S: Stream<Item = Bytes> + Unpin + Send,
+    {
+        loop {
+            while let Some(object) = decoder.next_object().context(InvalidExport)? {
+                let mut path = db_dir.clone();
+                for dir in &object.directories {
+                    path.push_dir(dir);
+                }
+                path.set_file_name(&object.file_name);
+
+                let is_catalog_file = [TRANSACTION_FILE_SUFFIX, CHECKPOINT_FILE_SUFFIX]
+                    .iter()
+                    .any(|suffix| object.file_name.ends_with(&format!(".{}", suffix)));
+                let bytes = match relocate_to {
+                    Some(server_id) if is_catalog_file => {
+                        relocate_transaction(&object.data, server_id)
+                            .context(RelocatingCatalog)?
+                            .into()
+                    }
+                    _ => object.data,
+                };
+
+                written.push(path.clone());
+                self.put_bytes(&path, bytes).await?;
+            }
+
+            match data.next().await {
+                Some(bytes) => decoder.push(&bytes),
+                None => break,
+            }
+        }
+
+        decoder.finish().context(InvalidExport)
+    }
+
+    /// Returns the number of bytes stored in object storage for each database
+    /// under this server's root, keyed by database name.
+    ///
+    /// Databases known to the server that have nothing stored yet are
+    /// reported with a usage of zero.
+    pub async fn object_store_usage(&self) -> Result<BTreeMap<String, u64>> {
+        self.require_initialized()?;
+
+        let root = self
+            .init_status
+            .root_path(&self.store)
+            .context(GetIdError)?;
+
+        let mut usage: BTreeMap<String, u64> = self
+            .config
+            .db_names_sorted()
+            .into_iter()
+            .map(|db_name| (db_name.to_string(), 0))
+            .collect();
+
+        let list_result = self
+            .store
+            .list_with_delimiter(&root)
+            .await
+            .context(StoreError)?;
+
+        for prefix in list_result.common_prefixes {
+            let db_name = match DirsAndFileName::from(prefix.clone()).directories.last() {
+                Some(part) => part.to_string(),
+                None => continue,
+            };
+            let size = self.object_store_prefix_size(prefix).await?;
+            *usage.entry(db_name).or_default() += size;
+        }
+
+        Ok(usage)
+    }
+
+    /// Sums the sizes of all objects under `prefix` by walking its common
+    /// prefixes.
+    async fn object_store_prefix_size(&self, prefix: object_store::path::Path) -> Result<u64> {
+        let mut size = 0;
+        let mut pending = vec![prefix];
+
+        while let Some(prefix) = pending.pop() {
+            let list_result = self
+                .store
+                .list_with_delimiter(&prefix)
+                .await
+                .context(StoreError)?;
+
+            size += list_result
+                .objects
+                .iter()
+                .map(|meta| meta.size as u64)
+                .sum::<u64>();
+            pending.extend(list_result.common_prefixes);
+        }
+
+        Ok(size)
+    }
+
+    async fn put_bytes(
+        &self,
+        location: &object_store::path::Path,
+        data: bytes::Bytes,
+    ) -> Result<()> {
+        let len = data.len();
+        let stream_data = std::io::Result::Ok(data);
+        self.store
+            .put(
+                location,
+                futures::stream::once(async move { stream_data }),
+                Some(len),
+            )
+            .await
+            .context(StoreError)
+    }
+
+    /// Loads the database configurations based on the databases in the
+    /// object store. Any databases in the config already won't be
+    /// replaced.
+    ///
+    /// This requires the serverID to be set. It will be a no-op if the configs are already loaded and the server is ready.
+    pub async fn maybe_initialize_server(&self) {
+        self.init_status
+            .maybe_initialize_server(
+                Arc::clone(&self.store),
+                Arc::clone(&self.config),
+                Arc::clone(&self.exec),
+            )
+            .await;
+    }
+
+    /// `write_lines` takes in raw line protocol and converts it to a collection
+    /// of ShardedEntry which are then sent to other IOx servers based on
     /// the ShardConfig or sent to the local database for buffering in the
     /// WriteBuffer and/or the MutableBuffer if configured.
     ///
@@ -589,6 +1674,68 @@ where
         lines: &[ParsedLine<'_>],
         default_time: i64,
     ) -> Result<()> {
+        self.write_lines_with_summary(db_name, lines, default_time)
+            .await
+            .map(|_| ())
+    }
+
+    /// Like [`write_lines_with_summary`](Self::write_lines_with_summary) but
+    /// lines without a timestamp are assigned one from the server's
+    /// [`TimestampSource`].
+    pub async fn write_lines_with_timestamp_source(
+        &self,
+        db_name: &str,
+        lines: &[ParsedLine<'_>],
+    ) -> Result<WriteSummary> {
+        let default_time = self.timestamp_source.timestamp();
+        self.write_lines_with_summary(db_name, lines, default_time)
+            .await
+    }
+
+    /// Like [`write_lines_with_timestamp_source`](Self::write_lines_with_timestamp_source)
+    /// but if an `idempotency_key` is provided, the write is skipped when a
+    /// write with the same key was already accepted for the database
+    /// recently. This allows clients to safely retry writes that timed out.
+    /// Skipped writes return an empty [`WriteSummary`].
+    ///
+    /// Keys are remembered on a best effort basis, as configured by
+    /// [`ServerConfig::with_idempotency_cache`]. Of concurrent writes with the
+    /// same key only one is applied.
+    pub async fn write_lines_idempotent(
+        &self,
+        db_name: &str,
+        lines: &[ParsedLine<'_>],
+        idempotency_key: Option<&str>,
+    ) -> Result<WriteSummary> {
+        let idempotency_key = match idempotency_key {
+            Some(key) => key,
+            None => return self.write_lines_with_timestamp_source(db_name, lines).await,
+        };
+
+        if !self
+            .idempotency_keys
+            .try_insert(db_name, idempotency_key, Instant::now())
+        {
+            debug!(%db_name, %idempotency_key, "skipping duplicate write");
+            return Ok(WriteSummary::default());
+        }
+
+        let result = self.write_lines_with_timestamp_source(db_name, lines).await;
+        if result.is_err() {
+            // allow the failed write to be retried
+            self.idempotency_keys.remove(db_name, idempotency_key);
+        }
+        result
+    }
+
+    /// Like [`write_lines`](Self::write_lines) but returns a [`WriteSummary`]
+    /// describing the rows that were written.
+    pub async fn write_lines_with_summary(
+        &self,
+        db_name: &str,
+        lines: &[ParsedLine<'_>],
+        default_time: i64,
+    ) -> Result<WriteSummary> {
         // Return an error if this server is not yet ready
         self.require_initialized()?;
 
@@ -598,29 +1745,108 @@ where
             .db(&db_name)
             .context(DatabaseNotFound { db_name: &*db_name })?;
 
-        // need to split this in two blocks because we cannot hold a lock across an async call.
-        let routing_config_target = {
-            let rules = db.rules.read();
-            if let Some(RoutingRules::RoutingConfig(routing_config)) = &rules.routing_rules {
-                let sharded_entries = lines_to_sharded_entries(
-                    lines,
-                    default_time,
-                    None as Option<&ShardConfig>,
-                    &*rules,
+        let (sharded_entries, target) = self.lines_to_sharded_writes(&db, lines, default_time)?;
+        let mut summary = WriteSummary::new(&sharded_entries);
+
+        match &target {
+            WriteTarget::Routing(routing_config) => {
+                for i in sharded_entries {
+                    self.write_entry_routed(&db_name, routing_config, i.entry)
+                        .await?;
+                }
+            }
+            WriteTarget::Shards(shards) => {
+                // Write to all shards in parallel; as soon as one fails return error
+                // immediately to the client and abort all other outstanding requests.
+                // This can take some time, but we're no longer holding the lock to the shard
+                // config.
+                let outcomes = futures_util::future::try_join_all(
+                    sharded_entries
+                        .into_iter()
+                        .map(|e| self.write_sharded_entry(&db_name, &db, Arc::clone(shards), e)),
                 )
-                .context(LineConversion)?;
-                Some((routing_config.target.clone(), sharded_entries))
-            } else {
-                None
+                .await?;
+
+                summary.outcome = outcomes.into_iter().max().unwrap_or_default();
             }
-        };
+        }
 
-        if let Some((target, sharded_entries)) = routing_config_target {
-            for i in sharded_entries {
-                self.write_entry_downstream(&db_name, &target, i.entry)
-                    .await?;
+        Ok(summary)
+    }
+
+    /// Like [`write_lines`](Self::write_lines) but rather than failing as a
+    /// whole if writing any of the entries the lines are split into fails,
+    /// all entries are attempted and the outcome of each is reported.
+    ///
+    /// Errors that prevent splitting the lines into entries, such as an
+    /// unknown database, still fail the whole call.
+    pub async fn write_lines_partial(
+        &self,
+        db_name: &str,
+        lines: &[ParsedLine<'_>],
+        default_time: i64,
+    ) -> Result<PartialWriteOutcome> {
+        // Return an error if this server is not yet ready
+        self.require_initialized()?;
+
+        let db_name = DatabaseName::new(db_name).context(InvalidDatabaseName)?;
+        let db = self
+            .config
+            .db(&db_name)
+            .context(DatabaseNotFound { db_name: &*db_name })?;
+
+        let (sharded_entries, target) = self.lines_to_sharded_writes(&db, lines, default_time)?;
+
+        let writes: Vec<_> = sharded_entries.iter().map(ShardedWrite::new).collect();
+        let (db_name, db, target) = (&db_name, &db, &target);
+        let results =
+            futures_util::future::join_all(sharded_entries.into_iter().map(|e| async move {
+                match target {
+                    WriteTarget::Routing(routing_config) => self
+                        .write_entry_routed(db_name, routing_config, e.entry)
+                        .await
+                        .map(|_| WriteOutcome::Accepted),
+                    WriteTarget::Shards(shards) => {
+                        self.write_sharded_entry(db_name, db, Arc::clone(shards), e)
+                            .await
+                    }
+                }
+            }))
+            .await;
+
+        let mut outcome = PartialWriteOutcome::default();
+        for (write, result) in writes.into_iter().zip(results) {
+            match result {
+                Ok(write_outcome) => outcome.succeeded.push((write, write_outcome)),
+                Err(e) => outcome.failed.push((write, e)),
             }
-            return Ok(());
+        }
+
+        Ok(outcome)
+    }
+
+    /// Splits lines into entries for `db`, along with where to write them.
+    fn lines_to_sharded_writes(
+        &self,
+        db: &Db,
+        lines: &[ParsedLine<'_>],
+        default_time: i64,
+    ) -> Result<(Vec<ShardedEntry>, WriteTarget)> {
+        let rules = db.rules.read();
+
+        if let Some(RoutingRules::RoutingConfig(routing_config)) = &rules.routing_rules {
+            let sharded_entries = lines_to_sharded_entries_with_coercion(
+                lines,
+                default_time,
+                None as Option<&ShardConfig>,
+                &*rules,
+                self.field_type_coercion,
+            )
+            .context(LineConversion)?;
+            return Ok((
+                sharded_entries,
+                WriteTarget::Routing(routing_config.clone()),
+            ));
         }
 
         // Split lines into shards while holding a read lock on the sharding config.
@@ -629,38 +1855,26 @@ where
         // groups. This map is atomically replaced every time the sharding
         // config is updated, hence it's safe to use after we release the shard config
         // lock.
-        let (sharded_entries, shards) = {
-            let rules = db.rules.read();
-
-            let shard_config = rules.routing_rules.as_ref().map(|cfg| match cfg {
-                RoutingRules::RoutingConfig(_) => todo!("routing config"),
-                RoutingRules::ShardConfig(shard_config) => shard_config,
-            });
-
-            let sharded_entries =
-                lines_to_sharded_entries(lines, default_time, shard_config, &*rules)
-                    .context(LineConversion)?;
-
-            let shards = shard_config
-                .as_ref()
-                .map(|cfg| Arc::clone(&cfg.shards))
-                .unwrap_or_default();
-
-            (sharded_entries, shards)
-        };
+        let shard_config = rules.routing_rules.as_ref().map(|cfg| match cfg {
+            RoutingRules::RoutingConfig(_) => todo!("routing config"),
+            RoutingRules::ShardConfig(shard_config) => shard_config,
+        });
 
-        // Write to all shards in parallel; as soon as one fails return error
-        // immediately to the client and abort all other outstanding requests.
-        // This can take some time, but we're no longer holding the lock to the shard
-        // config.
-        futures_util::future::try_join_all(
-            sharded_entries
-                .into_iter()
-                .map(|e| self.write_sharded_entry(&db_name, &db, Arc::clone(&shards), e)),
+        let sharded_entries = lines_to_sharded_entries_with_coercion(
+            lines,
+            default_time,
+            shard_config,
+            &*rules,
+            self.field_type_coercion,
         )
-        .await?;
+        .context(LineConversion)?;
 
-        Ok(())
+        let shards = shard_config
+            .as_ref()
+            .map(|cfg| Arc::clone(&cfg.shards))
+            .unwrap_or_default();
+
+        Ok((sharded_entries, WriteTarget::Shards(shards)))
     }
 
     async fn write_sharded_entry(
@@ -669,44 +1883,158 @@ where
         db: &Db,
         shards: Arc<HashMap<u32, Shard>>,
         sharded_entry: ShardedEntry,
-    ) -> Result<()> {
+    ) -> Result<WriteOutcome> {
         match sharded_entry.shard_id {
             Some(shard_id) => {
                 let shard = shards.get(&shard_id).context(ShardNotFound { shard_id })?;
                 match shard {
-                    Shard::Iox(node_group) => {
+                    Shard::Iox { node_group, .. } if shard.required_acks() == 1 => {
                         self.write_entry_downstream(db_name, node_group, sharded_entry.entry)
-                            .await?
+                            .await?;
+                        Ok(WriteOutcome::Accepted)
+                    }
+                    Shard::Iox { node_group, .. } => {
+                        self.write_entry_replicated(
+                            db_name,
+                            shard_id,
+                            node_group,
+                            shard.required_acks(),
+                            sharded_entry.entry,
+                        )
+                        .await?;
+                        Ok(WriteOutcome::Accepted)
                     }
                 }
             }
             None => {
                 self.write_entry_local(&db_name, db, sharded_entry.entry)
-                    .await?
+                    .await
             }
         }
-        Ok(())
     }
 
-    async fn write_entry_downstream(
+    /// Mirrors an entry to all the targets of a routing config in parallel.
+    ///
+    /// The write succeeds if at least [`RoutingConfig::required_acks`] targets
+    /// accepted it, otherwise the failed targets are reported.
+    async fn write_entry_routed(
         &self,
         db_name: &str,
-        node_group: &[ServerId],
+        routing_config: &RoutingConfig,
         entry: Entry,
     ) -> Result<()> {
-        let addrs: Vec<_> = node_group
-            .iter()
-            .filter_map(|&node| self.config.resolve_remote(node))
-            .collect();
-        if addrs.is_empty() {
+        let results = futures_util::future::join_all(
+            routing_config
+                .targets
+                .iter()
+                .map(|target| self.write_entry_downstream(db_name, target, entry.clone())),
+        )
+        .await;
+
+        let mut succeeded = 0;
+        let mut failures = vec![];
+        for (target, result) in routing_config.targets.iter().zip(results) {
+            match result {
+                Ok(()) => succeeded += 1,
+                Err(e) => failures.push((target.clone(), e)),
+            }
+        }
+
+        let required = routing_config.required_acks();
+        if succeeded < required {
+            return RoutingQuorumNotReached {
+                required,
+                succeeded,
+                failures,
+            }
+            .fail();
+        }
+
+        for (target, e) in &failures {
+            warn!(%db_name, ?target, %e, "routing target failed but quorum reached");
+        }
+
+        Ok(())
+    }
+
+    /// Forwards an entry to all the nodes of a shard's node group in
+    /// parallel.
+    ///
+    /// The write succeeds if at least `required` nodes accepted it, otherwise
+    /// the failed nodes are reported.
+    async fn write_entry_replicated(
+        &self,
+        db_name: &str,
+        shard_id: ShardId,
+        node_group: &[ServerId],
+        required: usize,
+        entry: Entry,
+    ) -> Result<()> {
+        let results = futures_util::future::join_all(
+            node_group
+                .iter()
+                .map(|node| self.write_entry_downstream(db_name, &[*node], entry.clone())),
+        )
+        .await;
+
+        let mut succeeded = 0;
+        let mut failures = vec![];
+        for (node, result) in node_group.iter().zip(results) {
+            match result {
+                Ok(()) => succeeded += 1,
+                Err(e) => failures.push((*node, e)),
+            }
+        }
+
+        if succeeded < required {
+            return ReplicationFactorNotReached {
+                shard_id,
+                required,
+                succeeded,
+                failures,
+            }
+            .fail();
+        }
+
+        for (node, e) in &failures {
+            warn!(
+                %db_name,
+                %shard_id,
+                %node,
+                %e,
+                "shard node failed but replication factor reached"
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn write_entry_downstream(
+        &self,
+        db_name: &str,
+        node_group: &[ServerId],
+        entry: Entry,
+    ) -> Result<()> {
+        let addrs: Vec<_> = node_group
+            .iter()
+            .filter_map(|&node| self.config.resolve_remote(node))
+            .collect();
+        if addrs.is_empty() {
             return NoRemoteConfigured { node_group }.fail();
         }
 
         let mut errors = HashMap::new();
         // this needs to be in its own statement because rand::thread_rng is not Send and the loop below is async.
         // braces around the expression would work but clippy don't know that and complains the braces are useless.
-        let random_addrs_iter = addrs.choose_multiple(&mut rand::thread_rng(), addrs.len());
-        for addr in random_addrs_iter {
+        let random_addrs: Vec<_> = match &self.remote_rng {
+            Some(rng) => addrs
+                .choose_multiple(&mut *rng.lock(), addrs.len())
+                .collect(),
+            None => addrs
+                .choose_multiple(&mut rand::thread_rng(), addrs.len())
+                .collect(),
+        };
+        for addr in random_addrs {
             match self.connection_manager.remote_server(addr).await {
                 Err(err) => {
                     info!("error obtaining remote for {}: {}", addr, err);
@@ -733,12 +2061,29 @@ where
             .db(&db_name)
             .context(DatabaseNotFound { db_name: &*db_name })?;
 
-        let entry = entry_bytes.try_into().context(DecodingEntry)?;
-        self.write_entry_local(&db_name, &db, entry).await
+        let entry: Entry = entry_bytes.try_into().context(DecodingEntry)?;
+        let validation = entry.validate(&db.rules.read().partition_template);
+        validation.context(InvalidEntry)?;
+
+        self.write_entry_local(&db_name, &db, entry)
+            .await
+            .map(|_| ())
     }
 
-    pub async fn write_entry_local(&self, db_name: &str, db: &Db, entry: Entry) -> Result<()> {
+    /// Writes an entry into a local database. The write succeeds with
+    /// [`WriteOutcome::OverSoftLimit`] if, after the write, the database's
+    /// buffer is above its soft limit.
+    pub async fn write_entry_local(
+        &self,
+        db_name: &str,
+        db: &Db,
+        entry: Entry,
+    ) -> Result<WriteOutcome> {
         let bytes = entry.data().len() as u64;
+        let table_rows = match self.per_table_metrics {
+            true => entry_table_rows(&entry),
+            false => BTreeMap::new(),
+        };
         db.store_entry(entry).await.map_err(|e| {
             self.metrics.ingest_entries_bytes_total.add_with_labels(
                 bytes,
@@ -763,17 +2108,85 @@ where
             ],
         );
 
-        Ok(())
+        for (table_name, rows) in table_rows {
+            self.metrics.ingest_table_rows_total.add_with_labels(
+                rows as u64,
+                &[
+                    metrics::KeyValue::new("db_name", db_name.to_string()),
+                    metrics::KeyValue::new("table_name", table_name),
+                ],
+            );
+        }
+
+        db.touch();
+
+        if db.over_soft_limit() {
+            return Ok(WriteOutcome::OverSoftLimit);
+        }
+
+        Ok(WriteOutcome::Accepted)
     }
 
     pub fn db(&self, name: &DatabaseName<'_>) -> Option<Arc<Db>> {
         self.config.db(name)
     }
 
+    /// Returns true if the named database exists on this server.
+    pub fn database_exists(&self, name: &DatabaseName<'_>) -> bool {
+        self.config.db(name).is_some()
+    }
+
     pub fn db_rules(&self, name: &DatabaseName<'_>) -> Option<DatabaseRules> {
         self.config.db(name).map(|d| d.rules.read().clone())
     }
 
+    /// Plans and executes the SQL query `sql` against the named database,
+    /// returning the resulting record batches.
+    pub async fn query_sql(&self, db_name: &str, sql: &str) -> Result<Vec<RecordBatch>> {
+        let db_name = DatabaseName::new(db_name).context(InvalidDatabaseName)?;
+        let db = self
+            .db(&db_name)
+            .context(DatabaseNotFound { db_name: &*db_name })?;
+
+        let physical_plan = SqlQueryPlanner::default()
+            .query(db, sql, &self.exec)
+            .context(PlanningQuery)?;
+        self.exec
+            .collect(physical_plan)
+            .await
+            .context(ExecutingQuery)
+    }
+
+    /// Records that the named database was written to now, for idle
+    /// detection. Writes through the server do this automatically.
+    pub fn touch_database(&self, name: &DatabaseName<'_>) -> Result<()> {
+        let db = self.config.db(name).context(DatabaseNotFound {
+            db_name: name.as_str(),
+        })?;
+        db.touch();
+        Ok(())
+    }
+
+    /// Returns when the named database was last written to, or `None` if it
+    /// doesn't exist or has not been written to since it was loaded.
+    pub fn database_last_write(&self, name: &DatabaseName<'_>) -> Option<DateTime<Utc>> {
+        self.config.db(name)?.last_write_at()
+    }
+
+    /// Returns the rules and status of all initialized databases, sorted by
+    /// name.
+    pub fn list_databases(&self) -> Vec<DatabaseInfo> {
+        self.config
+            .dbs_sorted()
+            .into_iter()
+            .map(|db| DatabaseInfo {
+                rules: db.rules.read().clone(),
+                chunk_count: db.catalog.chunk_summaries().len(),
+                buffer_bytes: db.catalog.metrics().memory().total(),
+            })
+            .collect()
+    }
+
     // Update database rules and save on success.
     pub async fn update_db_rules<F, E>(
         &self,
@@ -783,6 +2196,10 @@ where
     where
         F: FnOnce(DatabaseRules) -> Result<DatabaseRules, E> + Send,
     {
+        // hold the lock from the update until the rules are persisted, so that
+        // concurrent updates are persisted in the order they were applied
+        let _guard = self.rules_persist_lock.lock().await;
+
         let rules = self
             .config
             .update_db_rules(db_name, update)
@@ -790,7 +2207,7 @@ where
                 crate::config::UpdateError::Closure(e) => UpdateError::Closure(e),
                 crate::config::UpdateError::Update(e) => UpdateError::Update(e),
             })?;
-        self.persist_database_rules(rules.clone()).await?;
+        self.persist_database_rules_locked(rules.clone()).await?;
         Ok(rules)
     }
 
@@ -821,6 +2238,23 @@ where
         tracker
     }
 
+    /// Returns the summaries of all chunks in the named database, sorted by
+    /// partition, table and chunk id.
+    pub fn chunk_summaries(&self, db_name: &DatabaseName<'_>) -> Result<Vec<ChunkSummary>> {
+        let db = self.config.db(db_name).context(DatabaseNotFound {
+            db_name: db_name.as_str(),
+        })?;
+
+        let mut summaries = db
+            .chunk_summaries()
+            .map_err(|e| Error::UnknownDatabaseError {
+                source: Box::new(e),
+            })?;
+        summaries.sort_unstable();
+
+        Ok(summaries)
+    }
+
     /// Closes a chunk and starts moving its data to the read buffer, as a
     /// background job, dropping when complete.
     pub fn close_chunk(
@@ -852,6 +2286,99 @@ where
         })
     }
 
+    /// Compacts all read buffer chunks of a table within a partition into a
+    /// single chunk, as a background job, dropping the compacted chunks when
+    /// complete.
+    ///
+    /// If there is at most one read buffer chunk there is nothing to compact
+    /// and an already complete tracker is returned.
+    pub fn compact_chunks(
+        &self,
+        db_name: DatabaseName<'_>,
+        partition_key: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Result<TaskTracker<Job>> {
+        let db_name = db_name.to_string();
+        let name = DatabaseName::new(&db_name).context(InvalidDatabaseName)?;
+
+        let partition_key = partition_key.into();
+        let table_name = table_name.into();
+
+        let db = self
+            .config
+            .db(&name)
+            .context(DatabaseNotFound { db_name: &db_name })?;
+
+        let partition = db
+            .lockable_partition(&table_name, &partition_key)
+            .context(PartitionNotFound)?;
+        let partition = partition.read();
+
+        let chunks: Vec<_> = LockablePartition::chunks(&partition)
+            .into_iter()
+            .filter(|(_, chunk)| {
+                let chunk = chunk.read();
+                chunk.storage().1 == ChunkStorage::ReadBuffer && chunk.lifecycle_action().is_none()
+            })
+            .collect();
+
+        if chunks.len() < 2 {
+            return Ok(TaskTracker::complete(Job::CompactChunks {
+                db_name,
+                partition_key,
+                table_name,
+                chunks: chunks.into_iter().map(|(id, _)| id).collect(),
+            }));
+        }
+
+        let partition = partition.upgrade();
+        let chunks = chunks.iter().map(|(_, chunk)| chunk.write()).collect();
+
+        LockablePartition::compact_chunks(partition, chunks).map_err(|e| {
+            Error::UnknownDatabaseError {
+                source: Box::new(e),
+            }
+        })
+    }
+
+    /// Copies a chunk into a new read buffer chunk of the same table and
+    /// partition in another database, as a background job.
+    ///
+    /// The job fails if the chunk's schema is incompatible with the data
+    /// for that table already in the destination database.
+    pub fn copy_chunk(
+        &self,
+        db_name: DatabaseName<'_>,
+        partition_key: impl Into<String>,
+        table_name: impl Into<String>,
+        chunk_id: u32,
+        dst_db_name: DatabaseName<'_>,
+    ) -> Result<TaskTracker<Job>> {
+        let db_name = db_name.to_string();
+        let name = DatabaseName::new(&db_name).context(InvalidDatabaseName)?;
+        let dst_db_name = dst_db_name.to_string();
+        let dst_name = DatabaseName::new(&dst_db_name).context(InvalidDatabaseName)?;
+
+        let partition_key = partition_key.into();
+        let table_name = table_name.into();
+
+        let db = self
+            .config
+            .db(&name)
+            .context(DatabaseNotFound { db_name: &db_name })?;
+        let dst = self.config.db(&dst_name).context(DatabaseNotFound {
+            db_name: &dst_db_name,
+        })?;
+
+        db.copy_chunk(&table_name, &partition_key, chunk_id, dst)
+            .map_err(|e| match e {
+                db::Error::CatalogError { source } => Error::ChunkNotFound { source },
+                e => Error::UnknownDatabaseError {
+                    source: Box::new(e),
+                },
+            })
+    }
+
     /// Wipe preserved catalog of specific DB.
     ///
     /// The DB must not yet exist within this server for this to work! This is done to prevent race conditions between
@@ -893,6 +2420,78 @@ where
         self.jobs.inner.lock().get(id)
     }
 
+    /// Cancels the specified job, returning whether it was still running.
+    ///
+    /// Futures registered with the job are dropped the next time they
+    /// yield, so chunk lifecycle jobs abort between catalog updates rather
+    /// than part way through one.
+    pub fn cancel_job(&self, id: TaskId) -> Result<bool> {
+        let tracker = self.get_job(id).context(JobNotFound { id })?;
+        let running = !tracker.is_complete();
+        tracker.cancel();
+        Ok(running)
+    }
+
+    /// Returns the memory used by the buffers of all databases, in bytes.
+    pub fn buffer_size(&self) -> usize {
+        self.config
+            .db_names_sorted()
+            .iter()
+            .filter_map(|db_name| self.config.db(db_name))
+            .map(|db| db.catalog.metrics().memory().total())
+            .sum()
+    }
+
+    /// Tries to connect to each configured remote server concurrently,
+    /// returning whether it could be reached within `timeout`.
+    pub async fn remote_reachability(&self, timeout: Duration) -> BTreeMap<ServerId, bool> {
+        let probes = self
+            .remotes_sorted()
+            .into_iter()
+            .map(|(id, addr)| async move {
+                let reachable = match tokio::time::timeout(
+                    timeout,
+                    self.connection_manager.remote_server(&addr),
+                )
+                .await
+                {
+                    Ok(Ok(_)) => true,
+                    Ok(Err(e)) => {
+                        debug!(%id, %addr, %e, "remote server not reachable");
+                        false
+                    }
+                    Err(_) => {
+                        debug!(%id, %addr, ?timeout, "timed out connecting to remote server");
+                        false
+                    }
+                };
+                (id, reachable)
+            });
+
+        futures::future::join_all(probes)
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns a summary of the state of the server and its components.
+    pub async fn health_detail(&self) -> HealthDetail {
+        let reachability = self.remote_reachability(REMOTE_PROBE_TIMEOUT).await;
+
+        HealthDetail {
+            server_id: self.require_id().ok(),
+            databases: self.config.db_names_sorted().len(),
+            running_jobs: self
+                .tracked_jobs()
+                .iter()
+                .filter(|job| !job.is_complete())
+                .count(),
+            buffer_bytes: self.buffer_size(),
+            remotes: reachability.len(),
+            remotes_reachable: reachability.values().filter(|r| **r).count(),
+        }
+    }
+
     /// Background worker function for the server
     pub async fn background_worker(&self, shutdown: tokio_util::sync::CancellationToken) {
         info!("started background worker");
@@ -959,6 +2558,12 @@ where
         let db = match self.db(&db_name) {
             Some(db) => db,
             None => {
+                ensure!(
+                    self.auto_create_databases,
+                    DatabaseNotFound {
+                        db_name: db_name.as_str()
+                    }
+                );
                 self.create_database(DatabaseRules::new(db_name.clone()))
                     .await?;
                 self.db(&db_name).expect("db not inserted")
@@ -1006,40 +2611,108 @@ pub trait RemoteServer {
     async fn write_entry(&self, db: &str, entry: Entry) -> Result<(), ConnectionManagerError>;
 }
 
+/// The default maximum number of remote server connections kept open by the
+/// [`ConnectionManagerImpl`].
+pub const DEFAULT_REMOTE_CONNECTION_CACHE_CAPACITY: usize = 100;
+
 /// The connection manager maps a host identifier to a remote server.
+///
+/// Connections are cached, keeping at most a configurable number of them
+/// open. The least recently used connection is closed when a new one doesn't
+/// fit.
+///
+/// Connecting to a remote is bounded by a connect timeout, and each request
+/// to a remote by a request timeout, so a hung remote can't block writes for
+/// longer than that.
 #[derive(Debug)]
 pub struct ConnectionManagerImpl {
-    cache: LoadingCache<String, Arc<RemoteServerImpl>, CacheFillError>,
+    cache: RemoteCache<RemoteServerImpl>,
+    connect_timeout: Duration,
+    request_timeout: Duration,
 }
 
-// Error must be Clone because LoadingCache requires so.
 #[derive(Debug, Snafu, Clone)]
 pub enum CacheFillError {
     #[snafu(display("gRPC error: {}", source))]
     GrpcError {
         source: Arc<dyn std::error::Error + Send + Sync + 'static>,
     },
+
+    #[snafu(display("timed out after {:?} connecting to remote", timeout))]
+    ConnectTimeout { timeout: Duration },
 }
 
 impl ConnectionManagerImpl {
     pub fn new() -> Self {
-        let (cache, _) = LoadingCache::new(Self::cached_remote_server);
-        Self { cache }
+        Self::with_capacity(
+            NonZeroUsize::new(DEFAULT_REMOTE_CONNECTION_CACHE_CAPACITY)
+                .expect("default capacity is non-zero"),
+        )
+    }
+
+    /// Creates a connection manager that keeps at most `capacity` remote
+    /// server connections open.
+    pub fn with_capacity(capacity: NonZeroUsize) -> Self {
+        Self {
+            cache: RemoteCache::new(capacity),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets the maximum time to wait for a remote server to accept a
+    /// connection.
+    pub fn with_connect_timeout(self, connect_timeout: Duration) -> Self {
+        Self {
+            connect_timeout,
+            ..self
+        }
     }
 
-    async fn cached_remote_server(
-        connect: String,
+    /// Sets the maximum time a single request to a remote server may take.
+    pub fn with_request_timeout(self, request_timeout: Duration) -> Self {
+        Self {
+            request_timeout,
+            ..self
+        }
+    }
+
+    async fn connect_remote_server(
+        &self,
+        connect: &str,
     ) -> Result<Arc<RemoteServerImpl>, CacheFillError> {
-        let connection = Builder::default()
-            .build(&connect)
-            .await
-            .map_err(|e| Arc::new(e) as _)
-            .context(GrpcError)?;
+        let timeout = self.connect_timeout;
+        let build = Builder::default()
+            .connect_timeout(timeout)
+            .timeout(self.request_timeout)
+            .build(connect);
+
+        let connection = match tokio::time::timeout(timeout, build).await {
+            Ok(Ok(connection)) => connection,
+            Ok(Err(e)) if is_timeout(&e) => return ConnectTimeout { timeout }.fail(),
+            Ok(Err(e)) => return Err(Arc::new(e) as _).context(GrpcError),
+            Err(_) => return ConnectTimeout { timeout }.fail(),
+        };
+
         let client = write::Client::new(connection);
         Ok(Arc::new(RemoteServerImpl { client }))
     }
 }
 
+/// Returns true if `e` or any of its sources is an I/O timeout.
+fn is_timeout(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(e);
+    while let Some(e) = source {
+        if let Some(io_error) = e.downcast_ref::<std::io::Error>() {
+            if io_error.kind() == std::io::ErrorKind::TimedOut {
+                return true;
+            }
+        }
+        source = e.source();
+    }
+    false
+}
+
 impl Default for ConnectionManagerImpl {
     fn default() -> Self {
         Self::new()
@@ -1054,14 +2727,19 @@ impl ConnectionManager for ConnectionManagerImpl {
         &self,
         connect: &str,
     ) -> Result<Arc<Self::RemoteServer>, ConnectionManagerError> {
-        let ret = self
-            .cache
-            .get_with_meta(connect.to_string())
+        if let Some(remote) = self.cache.get(connect) {
+            debug!(was_cached=true, %connect, "getting remote connection");
+            return Ok(remote);
+        }
+
+        let remote = self
+            .connect_remote_server(connect)
             .await
             .map_err(|e| Box::new(e) as _)
             .context(RemoteServerConnectError)?;
-        debug!(was_cached=%ret.cached, %connect, "getting remote connection");
-        Ok(ret.result)
+        self.cache.insert(connect.to_string(), Arc::clone(&remote));
+        debug!(was_cached=false, %connect, "getting remote connection");
+        Ok(remote)
     }
 }
 
@@ -1098,7 +2776,7 @@ mod tests {
     use async_trait::async_trait;
     use bytes::Bytes;
     use futures::TryStreamExt;
-    use generated_types::database_rules::decode_database_rules;
+    use generated_types::database_rules::{decode_database_rules, decode_database_rules_json};
     use parquet_file::catalog::{test_helpers::TestCatalogState, PreservedCatalog};
     use snafu::Snafu;
     use tokio::task::JoinHandle;
@@ -1108,10 +2786,11 @@ mod tests {
     use data_types::database_rules::{
         HashRing, LifecycleRules, PartitionTemplate, ShardConfig, TemplatePart, NO_SHARD_CONFIG,
     };
+    use entry::lines_to_sharded_entries;
     use influxdb_line_protocol::parse_lines;
     use metrics::MetricRegistry;
     use object_store::{memory::InMemory, path::ObjectStorePath};
-    use query::{frontend::sql::SqlQueryPlanner, QueryDatabase};
+    use query::{frontend::sql::SqlQueryPlanner, QueryChunk};
 
     use super::*;
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -1184,6 +2863,7 @@ mod tests {
             name: name.clone(),
             partition_template: PartitionTemplate {
                 parts: vec![TemplatePart::TimeFormat("YYYY-MM".to_string())],
+                ..Default::default()
             },
             lifecycle_rules: LifecycleRules {
                 catalog_transactions_until_checkpoint: 13.try_into().unwrap(),
@@ -1192,6 +2872,8 @@ mod tests {
             routing_rules: None,
             worker_cleanup_avg_sleep: Duration::from_secs(2),
             write_buffer_connection_string: None,
+            max_partition_key_len: None,
+            partition_key_overflow: Default::default(),
         };
 
         // Create a database
@@ -1239,76 +2921,348 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn duplicate_database_name_rejected() {
-        // Covers #643
-
+    async fn persist_rules_json() {
         let manager = TestConnectionManager::new();
-        let server = Server::new(manager, config());
+        let server = Server::new(manager, config().with_persist_rules_json(true));
         server.set_id(ServerId::try_from(1).unwrap()).unwrap();
         server.maybe_initialize_server().await;
 
         let name = DatabaseName::new("bananas").unwrap();
-
-        // Create a database
         server
             .create_database(DatabaseRules::new(name.clone()))
             .await
             .expect("failed to create database");
 
-        // Then try and create another with the same name
-        let got = server
-            .create_database(DatabaseRules::new(name.clone()))
+        let read = |file_name: &'static str| {
+            let mut path = server.store.new_path();
+            path.push_all_dirs(&["1", name.as_str()]);
+            path.set_file_name(file_name);
+            let store = Arc::clone(&server.store);
+            async move {
+                store
+                    .get(&path)
+                    .await
+                    .unwrap()
+                    .map_ok(|b| bytes::BytesMut::from(&b[..]))
+                    .try_concat()
+                    .await
+                    .unwrap()
+                    .freeze()
+            }
+        };
+
+        let pb_rules = decode_database_rules(read("rules.pb").await).unwrap();
+        let json_rules = decode_database_rules_json(&read("rules.json").await).unwrap();
+        assert_eq!(pb_rules, json_rules);
+        assert_eq!(pb_rules, DatabaseRules::new(name.clone()));
+
+        // updates keep the JSON copy in sync
+        let updated = server
+            .update_db_rules(&name, |mut rules| {
+                rules.worker_cleanup_avg_sleep = Duration::from_secs(42);
+                Ok::<_, std::convert::Infallible>(rules)
+            })
             .await
-            .unwrap_err();
+            .unwrap();
 
-        if !matches!(got, Error::DatabaseAlreadyExists { .. }) {
-            panic!("expected already exists error");
-        }
+        let pb_rules = decode_database_rules(read("rules.pb").await).unwrap();
+        let json_rules = decode_database_rules_json(&read("rules.json").await).unwrap();
+        assert_eq!(pb_rules, json_rules);
+        assert_eq!(pb_rules, updated);
     }
 
-    async fn create_simple_database<M>(
-        server: &Server<M>,
-        name: impl Into<String> + Send,
-    ) -> Result<()>
-    where
-        M: ConnectionManager + Send + Sync,
-    {
-        let name = DatabaseName::new(name.into()).unwrap();
+    #[tokio::test]
+    async fn persist_rules_json_disabled_by_default() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
 
-        let rules = DatabaseRules {
-            name,
-            partition_template: PartitionTemplate {
-                parts: vec![TemplatePart::TimeFormat("YYYY-MM".to_string())],
-            },
-            lifecycle_rules: Default::default(),
-            routing_rules: None,
-            worker_cleanup_avg_sleep: Duration::from_secs(2),
-            write_buffer_connection_string: None,
-        };
+        let name = DatabaseName::new("bananas").unwrap();
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .expect("failed to create database");
 
-        // Create a database
-        server.create_database(rules.clone()).await
+        let mut path = server.store.new_path();
+        path.push_all_dirs(&["1", name.as_str()]);
+        path.set_file_name("rules.json");
+        assert!(server.store.get(&path).await.is_err());
     }
 
     #[tokio::test]
-    async fn load_databases() {
-        let temp_dir = TempDir::new().unwrap();
-
-        let store = ObjectStore::new_file(object_store::disk::File::new(temp_dir.path()));
+    async fn db_or_create_auto_creates_by_default() {
         let manager = TestConnectionManager::new();
-        let config = config_with_store(store);
-        let server = Server::new(manager, config);
+        let server = Server::new(manager, config());
         server.set_id(ServerId::try_from(1).unwrap()).unwrap();
         server.maybe_initialize_server().await;
-        create_simple_database(&server, "bananas")
-            .await
-            .expect("failed to create database");
 
-        let mut rules_path = server.store.new_path();
-        rules_path.push_all_dirs(&["1", "bananas"]);
-        rules_path.set_file_name("rules.pb");
+        let name = DatabaseName::new("bananas").unwrap();
+        assert!(!server.database_exists(&name));
 
-        std::mem::drop(server);
+        server.db_or_create("bananas").await.unwrap();
+        assert!(server.database_exists(&name));
+    }
+
+    #[tokio::test]
+    async fn db_or_create_without_auto_create() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config().with_auto_create_databases(false));
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let err = server.db_or_create("bananas").await.unwrap_err();
+        assert!(matches!(err, Error::DatabaseNotFound { db_name } if db_name == "bananas"));
+
+        let name = DatabaseName::new("bananas").unwrap();
+        assert!(!server.database_exists(&name));
+
+        // databases that exist are still returned
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .unwrap();
+        server.db_or_create("bananas").await.unwrap();
+        assert!(server.database_exists(&name));
+    }
+
+    #[tokio::test]
+    async fn query_sql() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo").unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        let lines = parsed_lines("cpu bar=1 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        let batches = server.query_sql("foo", "select * from cpu").await.unwrap();
+        let expected = vec![
+            "+-----+-------------------------------+",
+            "| bar | time                          |",
+            "+-----+-------------------------------+",
+            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "+-----+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let err = server
+            .query_sql("bar", "select * from cpu")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DatabaseNotFound { db_name } if db_name == "bar"));
+
+        let err = server
+            .query_sql("foo", "select * from mem")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::PlanningQuery { .. }));
+    }
+
+    #[tokio::test]
+    async fn rules_versions_and_rollback() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("bananas").unwrap();
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .expect("failed to create database");
+
+        let update = |secs| {
+            move |mut rules: DatabaseRules| {
+                rules.worker_cleanup_avg_sleep = Duration::from_secs(secs);
+                Ok::<_, Error>(rules)
+            }
+        };
+        let v2_rules = server.update_db_rules(&name, update(2)).await.unwrap();
+        let v3_rules = server.update_db_rules(&name, update(3)).await.unwrap();
+        assert_ne!(v2_rules, v3_rules);
+
+        assert_eq!(
+            server.list_rules_versions(&name).await.unwrap(),
+            vec![1, 2, 3]
+        );
+
+        let rolled_back = server.rollback_rules(&name, 2).await.unwrap();
+        assert_eq!(rolled_back, v2_rules);
+        assert_eq!(server.db_rules(&name).unwrap(), v2_rules);
+
+        // the rollback is recorded as a new version
+        assert_eq!(
+            server.list_rules_versions(&name).await.unwrap(),
+            vec![1, 2, 3, 4]
+        );
+
+        let mut rules_path = server.store.new_path();
+        rules_path.push_all_dirs(&["1", name.as_str()]);
+        rules_path.set_file_name("rules.pb");
+
+        let read_data = server
+            .store
+            .get(&rules_path)
+            .await
+            .unwrap()
+            .map_ok(|b| bytes::BytesMut::from(&b[..]))
+            .try_concat()
+            .await
+            .unwrap()
+            .freeze();
+        assert_eq!(decode_database_rules(read_data).unwrap(), v2_rules);
+
+        let err = server.rollback_rules(&name, 42).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::RulesVersionNotFound { version: 42, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn reload_rules() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("bananas").unwrap();
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .expect("failed to create database");
+
+        let mut rules_path = server.store.new_path();
+        rules_path.push_all_dirs(&["1", name.as_str()]);
+        rules_path.set_file_name("rules.pb");
+
+        let encode = |rules: DatabaseRules| {
+            let mut data = bytes::BytesMut::new();
+            encode_database_rules(rules, &mut data).unwrap();
+            data.freeze()
+        };
+
+        // edit the stored rules behind the server's back
+        let mut edited_rules = DatabaseRules::new(name.clone());
+        edited_rules.worker_cleanup_avg_sleep = Duration::from_secs(42);
+        server
+            .put_bytes(&rules_path, encode(edited_rules.clone()))
+            .await
+            .unwrap();
+        assert_ne!(server.db_rules(&name).unwrap(), edited_rules);
+
+        let reloaded = server.reload_rules(&name).await.unwrap();
+        assert_eq!(reloaded, edited_rules);
+        assert_eq!(server.db_rules(&name).unwrap(), edited_rules);
+
+        // reloading doesn't store a new version
+        assert_eq!(server.list_rules_versions(&name).await.unwrap(), vec![1]);
+
+        // invalid stored rules are not applied
+        let mut invalid_rules = edited_rules.clone();
+        invalid_rules.lifecycle_rules.buffer_size_soft = NonZeroUsize::new(200);
+        invalid_rules.lifecycle_rules.buffer_size_hard = NonZeroUsize::new(100);
+        server
+            .put_bytes(&rules_path, encode(invalid_rules))
+            .await
+            .unwrap();
+
+        let err = server.reload_rules(&name).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidStoredRules { .. }));
+        assert_eq!(server.db_rules(&name).unwrap(), edited_rules);
+
+        // unknown databases
+        let err = server
+            .reload_rules(&DatabaseName::new("apples").unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DatabaseNotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn duplicate_database_name_rejected() {
+        // Covers #643
+
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("bananas").unwrap();
+
+        // Create a database
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .expect("failed to create database");
+
+        // Then try and create another with the same name
+        let got = server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .unwrap_err();
+
+        if !matches!(got, Error::DatabaseAlreadyExists { .. }) {
+            panic!("expected already exists error");
+        }
+    }
+
+    async fn create_simple_database<M>(
+        server: &Server<M>,
+        name: impl Into<String> + Send,
+    ) -> Result<()>
+    where
+        M: ConnectionManager + Send + Sync,
+    {
+        let name = DatabaseName::new(name.into()).unwrap();
+
+        let rules = DatabaseRules {
+            name,
+            partition_template: PartitionTemplate {
+                parts: vec![TemplatePart::TimeFormat("YYYY-MM".to_string())],
+                ..Default::default()
+            },
+            lifecycle_rules: Default::default(),
+            routing_rules: None,
+            worker_cleanup_avg_sleep: Duration::from_secs(2),
+            write_buffer_connection_string: None,
+            max_partition_key_len: None,
+            partition_key_overflow: Default::default(),
+        };
+
+        // Create a database
+        server.create_database(rules.clone()).await
+    }
+
+    #[tokio::test]
+    async fn load_databases() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let store = ObjectStore::new_file(object_store::disk::File::new(temp_dir.path()));
+        let manager = TestConnectionManager::new();
+        let config = config_with_store(store);
+        let server = Server::new(manager, config);
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+        create_simple_database(&server, "bananas")
+            .await
+            .expect("failed to create database");
+
+        let mut rules_path = server.store.new_path();
+        rules_path.push_all_dirs(&["1", "bananas"]);
+        rules_path.set_file_name("rules.pb");
+
+        std::mem::drop(server);
 
         let store = ObjectStore::new_file(object_store::disk::File::new(temp_dir.path()));
         let manager = TestConnectionManager::new();
@@ -1362,32 +3316,976 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn writes_local() {
+    async fn list_databases() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        for name in &["foo", "bar", "baz"] {
+            let rules = DatabaseRules::new(DatabaseName::new(*name).unwrap());
+            server.create_database(rules).await.unwrap();
+        }
+
+        let lines = parsed_lines("cpu bar=1 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        let databases = server.list_databases();
+        let names: Vec<_> = databases.iter().map(|d| d.rules.name.as_str()).collect();
+        assert_eq!(names, vec!["bar", "baz", "foo"]);
+
+        for database in &databases {
+            assert_eq!(
+                database.rules,
+                server.db_rules(&database.rules.name).unwrap()
+            );
+        }
+
+        assert_eq!(databases[0].chunk_count, 0);
+        assert_eq!(databases[0].buffer_bytes, 0);
+        assert_eq!(databases[2].chunk_count, 1);
+        assert!(databases[2].buffer_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn health_detail() {
+        const GOOD_REMOTE_ADDR: &str = "http://localhost:111";
+        const BAD_REMOTE_ADDR: &str = "http://localhost:666";
+
+        let mut manager = TestConnectionManager::new();
+        manager.remotes.insert(
+            GOOD_REMOTE_ADDR.to_owned(),
+            Arc::new(TestRemoteServer {
+                written: Arc::new(AtomicBool::new(false)),
+            }),
+        );
+        let server = Server::new(manager, config());
+
+        let detail = server.health_detail().await;
+        assert_eq!(detail.server_id, None);
+        assert_eq!(detail.databases, 0);
+        assert_eq!(detail.remotes, 0);
+
+        let server_id = ServerId::try_from(1).unwrap();
+        server.set_id(server_id).unwrap();
+        server.maybe_initialize_server().await;
+        server.update_remote(ServerId::try_from(2).unwrap(), GOOD_REMOTE_ADDR.into());
+        server.update_remote(ServerId::try_from(3).unwrap(), BAD_REMOTE_ADDR.into());
+
+        server
+            .create_database(DatabaseRules::new(DatabaseName::new("foo").unwrap()))
+            .await
+            .unwrap();
+        let lines = parsed_lines("cpu bar=1 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        let detail = server.health_detail().await;
+        assert_eq!(detail.server_id, Some(server_id));
+        assert_eq!(detail.databases, 1);
+        assert!(detail.buffer_bytes > 0);
+        assert_eq!(detail.remotes, 2);
+        assert_eq!(detail.remotes_reachable, 1);
+    }
+
+    #[tokio::test]
+    async fn remote_reachability_times_out() {
+        const GOOD_REMOTE_ADDR: &str = "http://localhost:111";
+        const SLOW_REMOTE_ADDR: &str = "http://localhost:222";
+
+        let mut manager = TestConnectionManager::new();
+        for addr in &[GOOD_REMOTE_ADDR, SLOW_REMOTE_ADDR] {
+            manager.remotes.insert(
+                addr.to_string(),
+                Arc::new(TestRemoteServer {
+                    written: Arc::new(AtomicBool::new(false)),
+                }),
+            );
+        }
+        manager.unresponsive.insert(SLOW_REMOTE_ADDR.to_string());
+        let server = Server::new(manager, config());
+
+        let good_id = ServerId::try_from(2).unwrap();
+        let slow_id = ServerId::try_from(3).unwrap();
+        server.update_remote(good_id, GOOD_REMOTE_ADDR.into());
+        server.update_remote(slow_id, SLOW_REMOTE_ADDR.into());
+
+        let reachability = tokio::time::timeout(
+            Duration::from_secs(10),
+            server.remote_reachability(Duration::from_millis(100)),
+        )
+        .await
+        .expect("unresponsive remotes are bounded by the timeout");
+
+        let expected: BTreeMap<_, _> = vec![(good_id, true), (slow_id, false)]
+            .into_iter()
+            .collect();
+        assert_eq!(reachability, expected);
+    }
+
+    #[tokio::test]
+    async fn object_store_usage() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        // "#" is percent-encoded in object store paths
+        for name in &["bananas", "apples", "cherries#1"] {
+            let rules = DatabaseRules::new(DatabaseName::new(*name).unwrap());
+            server
+                .create_database(rules)
+                .await
+                .expect("failed to create database");
+        }
+
+        let usage = server.object_store_usage().await.unwrap();
+        assert_eq!(
+            usage.keys().collect::<Vec<_>>(),
+            vec!["apples", "bananas", "cherries#1"]
+        );
+        assert!(usage.values().all(|size| *size > 0));
+    }
+
+    #[tokio::test]
+    async fn export_import_database() {
+        let server_id = ServerId::try_from(1).unwrap();
+        let db_name = DatabaseName::new("foo").unwrap();
+
+        let source = Server::new(TestConnectionManager::new(), config());
+        source.set_id(server_id).unwrap();
+        source.maybe_initialize_server().await;
+        source
+            .create_database(DatabaseRules::new(db_name.clone()))
+            .await
+            .unwrap();
+
+        let lines = parsed_lines("cpu bar=1 10");
+        source
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        // persist the data
+        let db = source.db(&db_name).unwrap();
+        let chunk_id = db
+            .rollover_partition("cpu", "")
+            .await
+            .unwrap()
+            .unwrap()
+            .id();
+        db.move_chunk_to_read_buffer("cpu", "", chunk_id)
+            .await
+            .unwrap();
+        db.write_chunk_to_object_store("cpu", "", chunk_id)
+            .await
+            .unwrap();
+
+        let export: Vec<_> = source
+            .export_database(&db_name)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        // import into servers with their own object store, with the same ID
+        // and with another one
+        for target_id in &[server_id, ServerId::try_from(2).unwrap()] {
+            let target = Server::new(TestConnectionManager::new(), config());
+            target.set_id(*target_id).unwrap();
+            target.maybe_initialize_server().await;
+            target
+                .import_database(futures::stream::iter(export.clone()))
+                .await
+                .unwrap();
+
+            assert_eq!(target.db_rules(&db_name).unwrap().name, db_name);
+
+            let db = target.db(&db_name).unwrap();
+            let planner = SqlQueryPlanner::default();
+            let executor = target.executor();
+            let physical_plan = planner
+                .query(db, "select * from cpu", executor.as_ref())
+                .unwrap();
+            let batches = executor.collect(physical_plan).await.unwrap();
+            let expected = vec![
+                "+-----+-------------------------------+",
+                "| bar | time                          |",
+                "+-----+-------------------------------+",
+                "| 1   | 1970-01-01 00:00:00.000000010 |",
+                "+-----+-------------------------------+",
+            ];
+            assert_batches_eq!(expected, &batches);
+
+            // importing again fails as the database now exists
+            let err = target
+                .import_database(futures::stream::iter(export.clone()))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::DatabaseAlreadyExists { .. }));
+        }
+
+        // a truncated export leaves nothing behind
+        let mut truncated = export.clone();
+        let last = truncated.pop().unwrap();
+        truncated.push(last.slice(..last.len() - 1));
+
+        let target = Server::new(TestConnectionManager::new(), config());
+        target.set_id(server_id).unwrap();
+        target.maybe_initialize_server().await;
+        let err = target
+            .import_database(futures::stream::iter(truncated))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidExport {
+                source: export::Error::Truncated
+            }
+        ));
+        assert!(target.db(&db_name).is_none());
+        let remaining: Vec<_> = target
+            .store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+        assert!(remaining.is_empty(), "{:?}", remaining);
+
+        // while another operation holds the name, nothing is written
+        let handle = target
+            .config
+            .create_db(
+                Arc::clone(&target.store),
+                Arc::clone(&target.exec),
+                server_id,
+                db_name.clone(),
+            )
+            .unwrap();
+        let err = target
+            .import_database(futures::stream::iter(export.clone()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DatabaseReserved { .. }));
+        let remaining: Vec<_> = target
+            .store
+            .list(None)
+            .await
+            .unwrap()
+            .try_concat()
+            .await
+            .unwrap();
+        assert!(remaining.is_empty(), "{:?}", remaining);
+        handle.abort();
+
+        // the database can be imported once the export is complete
+        target
+            .import_database(futures::stream::iter(export))
+            .await
+            .unwrap();
+        assert!(target.db(&db_name).is_some());
+    }
+
+    #[tokio::test]
+    async fn writes_local() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        let line = "cpu bar=1 10";
+        let lines: Vec<_> = parse_lines(line).map(|l| l.unwrap()).collect();
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        let db_name = DatabaseName::new("foo").unwrap();
+        let db = server.db(&db_name).unwrap();
+
+        let planner = SqlQueryPlanner::default();
+        let executor = server.executor();
+        let physical_plan = planner
+            .query(db, "select * from cpu", executor.as_ref())
+            .unwrap();
+
+        let batches = executor.collect(physical_plan).await.unwrap();
+        let expected = vec![
+            "+-----+-------------------------------+",
+            "| bar | time                          |",
+            "+-----+-------------------------------+",
+            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "+-----+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn writes_local_mixed_numeric_fields() {
+        let lines = parsed_lines("cpu bar=1i 10\ncpu bar=2u 20");
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+
+        let server = Server::new(TestConnectionManager::new(), config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .unwrap();
+
+        let err = server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::LineConversion { .. }), "{}", err);
+
+        let server = Server::new(
+            TestConnectionManager::new(),
+            config().with_field_type_coercion(FieldTypeCoercion::PromoteToFloat),
+        );
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+        server
+            .create_database(DatabaseRules::new(name.clone()))
+            .await
+            .unwrap();
+
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        let db = server.db(&name).unwrap();
+        let planner = SqlQueryPlanner::default();
+        let executor = server.executor();
+        let physical_plan = planner
+            .query(db, "select * from cpu", executor.as_ref())
+            .unwrap();
+
+        let batches = executor.collect(physical_plan).await.unwrap();
+        let expected = vec![
+            "+-----+-------------------------------+",
+            "| bar | time                          |",
+            "+-----+-------------------------------+",
+            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "| 2   | 1970-01-01 00:00:00.000000020 |",
+            "+-----+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn writes_complete_while_query_runs() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(
+            manager,
+            config()
+                .with_num_worker_threads(2)
+                .with_worker_thread_priority(15),
+        );
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let executor = server.executor();
+        assert_eq!(executor.num_threads(), 2);
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        // occupy both query worker threads with tasks that block until the
+        // gate opens, i.e. until the write completed
+        let gate = Arc::new(std::sync::Barrier::new(3));
+        let (started_tx, mut started_rx) = tokio::sync::mpsc::unbounded_channel();
+        let queries: Vec<_> = (0..2)
+            .map(|_| {
+                let gate = Arc::clone(&gate);
+                let started_tx = started_tx.clone();
+                let executor = Arc::clone(&executor);
+                tokio::spawn(async move {
+                    executor
+                        .run(async move {
+                            started_tx.send(()).unwrap();
+                            gate.wait();
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for _ in 0..2 {
+            started_rx.recv().await.unwrap();
+        }
+
+        // the executor is busy: no other task gets to run
+        let probe = executor.run(async {});
+        assert!(tokio::time::timeout(Duration::from_millis(100), probe)
+            .await
+            .is_err());
+
+        let lines = parsed_lines("cpu bar=1 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        gate.wait();
+        for query in queries {
+            query.await.unwrap().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn write_entry_local() {
+        let (metric_registry, config) = config_with_metric_registry();
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config);
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        let db_name = DatabaseName::new("foo").unwrap();
+        let db = server.db(&db_name).unwrap();
+
+        let line = "cpu bar=1 10";
+        let lines: Vec<_> = parse_lines(line).map(|l| l.unwrap()).collect();
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            NO_SHARD_CONFIG,
+            &*db.rules.read(),
+        )
+        .expect("sharded entries");
+
+        let entry = &sharded_entries[0].entry;
+        server
+            .write_entry("foo", entry.data().into())
+            .await
+            .expect("write entry");
+
+        let planner = SqlQueryPlanner::default();
+        let executor = server.executor();
+        let physical_plan = planner
+            .query(db, "select * from cpu", executor.as_ref())
+            .unwrap();
+
+        let batches = executor.collect(physical_plan).await.unwrap();
+        let expected = vec![
+            "+-----+-------------------------------+",
+            "| bar | time                          |",
+            "+-----+-------------------------------+",
+            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "+-----+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        metric_registry
+            .has_metric_family("ingest_entries_bytes_total")
+            .with_labels(&[("status", "ok"), ("db_name", "foo")])
+            .counter()
+            .eq(240.0)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_entry_delete() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        let db_name = DatabaseName::new("foo").unwrap();
+        let db = server.db(&db_name).unwrap();
+
+        let lines: Vec<_> = parse_lines("cpu,host=a bar=1 10\ncpu,host=b bar=2 20")
+            .map(|l| l.unwrap())
+            .collect();
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            NO_SHARD_CONFIG,
+            &*db.rules.read(),
+        )
+        .expect("sharded entries");
+        server
+            .write_entry("foo", sharded_entries[0].entry.data().into())
+            .await
+            .expect("write entry");
+
+        // a delete replicated from upstream removes the matching rows
+        let entry = entry::deletes_to_entry(&[("cpu", "host = 'a'")]);
+        server
+            .write_entry("foo", entry.data().into())
+            .await
+            .expect("write delete entry");
+
+        let planner = SqlQueryPlanner::default();
+        let executor = server.executor();
+        let physical_plan = planner
+            .query(db, "select * from cpu", executor.as_ref())
+            .unwrap();
+
+        let batches = executor.collect(physical_plan).await.unwrap();
+        let expected = vec![
+            "+-----+------+-------------------------------+",
+            "| bar | host | time                          |",
+            "+-----+------+-------------------------------+",
+            "| 2   | b    | 1970-01-01 00:00:00.000000020 |",
+            "+-----+------+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn write_entry_local_per_table_metrics() {
+        let (metric_registry, config) = config_with_metric_registry();
+        let config = config.with_per_table_metrics(true);
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config);
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        let lines = parsed_lines("cpu bar=1 10\ncpu bar=2 20\nmem used=3 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        metric_registry
+            .has_metric_family("ingest_table_rows_total")
+            .with_labels(&[("db_name", "foo"), ("table_name", "cpu")])
+            .counter()
+            .eq(2.0)
+            .unwrap();
+
+        metric_registry
+            .has_metric_family("ingest_table_rows_total")
+            .with_labels(&[("db_name", "foo"), ("table_name", "mem")])
+            .counter()
+            .eq(1.0)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn database_last_write() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let foo = DatabaseName::new("foo").unwrap();
+        let bar = DatabaseName::new("bar").unwrap();
+        for name in &[&foo, &bar] {
+            server
+                .create_database(DatabaseRules::new((*name).clone()))
+                .await
+                .unwrap();
+        }
+        assert_eq!(server.database_last_write(&foo), None);
+
+        let lines = parsed_lines("cpu bar=1 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+        let first = server.database_last_write(&foo).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+        let second = server.database_last_write(&foo).unwrap();
+        assert!(second > first);
+
+        // a database that was never written to
+        assert_eq!(server.database_last_write(&bar), None);
+        server.touch_database(&bar).unwrap();
+        assert!(server.database_last_write(&bar).unwrap() >= second);
+
+        let missing = DatabaseName::new("missing").unwrap();
+        assert_eq!(server.database_last_write(&missing), None);
+        assert!(matches!(
+            server.touch_database(&missing),
+            Err(Error::DatabaseNotFound { .. })
+        ));
+    }
+
+    // This tests sets up a database with a sharding config which defines exactly one shard
+    // backed by 3 remote nodes. One of the nodes is modeled to be "down", while the other two
+    // can record write entry events.
+    // This tests goes through a few trivial error cases before checking that the both working
+    // mock remote servers actually receive write entry events.
+    #[tokio::test]
+    async fn write_entry_downstream() {
+        const TEST_SHARD_ID: ShardId = 1;
+        const REMOTE_SELECTION_SEED: u64 = 42;
+        const GOOD_REMOTE_ADDR_1: &str = "http://localhost:111";
+        const GOOD_REMOTE_ADDR_2: &str = "http://localhost:222";
+        const BAD_REMOTE_ADDR: &str = "http://localhost:666";
+
+        let good_remote_id_1 = ServerId::try_from(1).unwrap();
+        let good_remote_id_2 = ServerId::try_from(2).unwrap();
+        let bad_remote_id = ServerId::try_from(666).unwrap();
+
+        let mut manager = TestConnectionManager::new();
+        let written_1 = Arc::new(AtomicBool::new(false));
+        manager.remotes.insert(
+            GOOD_REMOTE_ADDR_1.to_owned(),
+            Arc::new(TestRemoteServer {
+                written: Arc::clone(&written_1),
+            }),
+        );
+        let written_2 = Arc::new(AtomicBool::new(false));
+        manager.remotes.insert(
+            GOOD_REMOTE_ADDR_2.to_owned(),
+            Arc::new(TestRemoteServer {
+                written: Arc::clone(&written_2),
+            }),
+        );
+
+        let server = Server::new(
+            manager,
+            config().with_remote_selection_seed(REMOTE_SELECTION_SEED),
+        );
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let db_name = DatabaseName::new("foo").unwrap();
+        server
+            .create_database(DatabaseRules::new(db_name.clone()))
+            .await
+            .unwrap();
+
+        let remote_ids = vec![bad_remote_id, good_remote_id_1, good_remote_id_2];
+        let db = server.db(&db_name).unwrap();
+        {
+            let mut rules = db.rules.write();
+            let shard_config = ShardConfig {
+                hash_ring: Some(HashRing {
+                    shards: vec![TEST_SHARD_ID].into(),
+                    ..Default::default()
+                }),
+                shards: Arc::new(
+                    vec![(
+                        TEST_SHARD_ID,
+                        Shard::Iox {
+                            node_group: remote_ids.clone(),
+                            replication_factor: None,
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            };
+            rules.routing_rules = Some(RoutingRules::ShardConfig(shard_config));
+        }
+
+        let line = "cpu bar=1 10";
+        let lines: Vec<_> = parse_lines(line).map(|l| l.unwrap()).collect();
+
+        let err = server
+            .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::NoRemoteConfigured { node_group } if node_group == remote_ids)
+        );
+
+        // one remote is configured but it's down and we'll get connection error
+        server.update_remote(bad_remote_id, BAD_REMOTE_ADDR.into());
+        let err = server
+            .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::NoRemoteReachable { errors } if matches!(
+                errors[BAD_REMOTE_ADDR],
+                ConnectionManagerError::RemoteServerConnectError {..}
+            )
+        ));
+        assert_eq!(written_1.load(Ordering::Relaxed), false);
+        assert_eq!(written_2.load(Ordering::Relaxed), false);
+
+        // We configure the address for the other remote, this time connection will succeed
+        // despite the bad remote failing to connect.
+        server.update_remote(good_remote_id_1, GOOD_REMOTE_ADDR_1.into());
+        server.update_remote(good_remote_id_2, GOOD_REMOTE_ADDR_2.into());
+
+        // Remotes are tried in random order. The order is seeded, so replay the choices of the
+        // server to know which remote each write reaches, starting with the write above that only
+        // had the bad remote to choose from.
+        let mut rng = StdRng::seed_from_u64(REMOTE_SELECTION_SEED);
+        let _: Vec<_> = [BAD_REMOTE_ADDR].choose_multiple(&mut rng, 1).collect();
+
+        let addrs = [BAD_REMOTE_ADDR, GOOD_REMOTE_ADDR_1, GOOD_REMOTE_ADDR_2];
+        let (mut expected_1, mut expected_2) = (false, false);
+        while !(expected_1 && expected_2) {
+            let first_good = addrs
+                .choose_multiple(&mut rng, addrs.len())
+                .copied()
+                .find(|addr| *addr != BAD_REMOTE_ADDR)
+                .unwrap();
+            expected_1 |= first_good == GOOD_REMOTE_ADDR_1;
+            expected_2 |= first_good == GOOD_REMOTE_ADDR_2;
+
+            server
+                .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+                .await
+                .expect("cannot write lines");
+            assert_eq!(written_1.load(Ordering::Relaxed), expected_1);
+            assert_eq!(written_2.load(Ordering::Relaxed), expected_2);
+        }
+    }
+
+    #[tokio::test]
+    async fn write_lines_partial() {
+        const GOOD_SHARD_ID: ShardId = 1;
+        const BAD_SHARD_ID: ShardId = 2;
+        const GOOD_REMOTE_ADDR: &str = "http://localhost:111";
+        const BAD_REMOTE_ADDR: &str = "http://localhost:666";
+
+        let good_remote_id = ServerId::try_from(1).unwrap();
+        let bad_remote_id = ServerId::try_from(666).unwrap();
+
+        let mut manager = TestConnectionManager::new();
+        let written = Arc::new(AtomicBool::new(false));
+        manager.remotes.insert(
+            GOOD_REMOTE_ADDR.to_owned(),
+            Arc::new(TestRemoteServer {
+                written: Arc::clone(&written),
+            }),
+        );
+
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+        server.update_remote(good_remote_id, GOOD_REMOTE_ADDR.into());
+        server.update_remote(bad_remote_id, BAD_REMOTE_ADDR.into());
+
+        let db_name = DatabaseName::new("foo").unwrap();
+        server
+            .create_database(DatabaseRules::new(db_name.clone()))
+            .await
+            .unwrap();
+
+        let shard_config = ShardConfig {
+            hash_ring: Some(HashRing {
+                table_name: true,
+                shards: vec![GOOD_SHARD_ID, BAD_SHARD_ID].into(),
+                ..Default::default()
+            }),
+            shards: Arc::new(
+                vec![
+                    (
+                        GOOD_SHARD_ID,
+                        Shard::Iox {
+                            node_group: vec![good_remote_id],
+                            replication_factor: None,
+                        },
+                    ),
+                    (
+                        BAD_SHARD_ID,
+                        Shard::Iox {
+                            node_group: vec![bad_remote_id],
+                            replication_factor: None,
+                        },
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        };
+        server.db(&db_name).unwrap().rules.write().routing_rules =
+            Some(RoutingRules::ShardConfig(shard_config.clone()));
+
+        let lp: String = (0..20).map(|i| format!("t{} bar=1 10\n", i)).collect();
+        let lines = parsed_lines(&lp);
+        let preview = shard_config.preview(&lines).unwrap();
+        assert!(preview.contains_key(&Some(GOOD_SHARD_ID)));
+        assert!(preview.contains_key(&Some(BAD_SHARD_ID)));
+
+        let outcome = server
+            .write_lines_partial(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+        assert!(!outcome.is_complete());
+        assert!(written.load(Ordering::Relaxed));
+
+        assert_eq!(outcome.failed.len(), 1);
+        let (failed, err) = &outcome.failed[0];
+        assert_eq!(failed.shard_id, Some(BAD_SHARD_ID));
+        assert!(matches!(err, Error::NoRemoteReachable { .. }));
+
+        assert_eq!(outcome.succeeded.len(), 1);
+        let (succeeded, write_outcome) = &outcome.succeeded[0];
+        assert_eq!(succeeded.shard_id, Some(GOOD_SHARD_ID));
+        assert_eq!(*write_outcome, WriteOutcome::Accepted);
+
+        // the strict variant fails the whole write
+        server
+            .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn remote_selection_seed() {
+        const TEST_SHARD_ID: ShardId = 1;
+        const REMOTE_ADDRS: [&str; 3] = [
+            "http://localhost:111",
+            "http://localhost:222",
+            "http://localhost:333",
+        ];
+
+        // Returns the address of the remote reached by each of a series of writes
+        async fn write_order(seed: u64) -> Vec<&'static str> {
+            let mut manager = TestConnectionManager::new();
+            let written: Vec<_> = REMOTE_ADDRS
+                .iter()
+                .map(|addr| {
+                    let written = Arc::new(AtomicBool::new(false));
+                    manager.remotes.insert(
+                        addr.to_string(),
+                        Arc::new(TestRemoteServer {
+                            written: Arc::clone(&written),
+                        }),
+                    );
+                    written
+                })
+                .collect();
+
+            let server = Server::new(manager, config().with_remote_selection_seed(seed));
+            server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+            server.maybe_initialize_server().await;
+
+            let db_name = DatabaseName::new("foo").unwrap();
+            server
+                .create_database(DatabaseRules::new(db_name.clone()))
+                .await
+                .unwrap();
+
+            let mut remote_ids = vec![];
+            for (i, addr) in REMOTE_ADDRS.iter().enumerate() {
+                let id = ServerId::try_from(i as u32 + 1).unwrap();
+                server.update_remote(id, addr.to_string());
+                remote_ids.push(id);
+            }
+            server.db(&db_name).unwrap().rules.write().routing_rules =
+                Some(RoutingRules::ShardConfig(ShardConfig {
+                    hash_ring: Some(HashRing {
+                        shards: vec![TEST_SHARD_ID].into(),
+                        ..Default::default()
+                    }),
+                    shards: Arc::new(
+                        vec![(
+                            TEST_SHARD_ID,
+                            Shard::Iox {
+                                node_group: remote_ids,
+                                replication_factor: None,
+                            },
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    ..Default::default()
+                }));
+
+            let lines = parsed_lines("cpu bar=1 10");
+            let mut order = vec![];
+            for _ in 0..10 {
+                server
+                    .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+                    .await
+                    .unwrap();
+                let i = written
+                    .iter()
+                    .position(|w| w.swap(false, Ordering::Relaxed))
+                    .unwrap();
+                order.push(REMOTE_ADDRS[i]);
+            }
+            order
+        }
+
+        // the first remote in the seeded order is always reachable, so it receives the write
+        let mut rng = StdRng::seed_from_u64(7);
+        let expected: Vec<_> = (0..10)
+            .map(|_| {
+                *REMOTE_ADDRS
+                    .choose_multiple(&mut rng, REMOTE_ADDRS.len())
+                    .next()
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(write_order(7).await, write_order(7).await);
+        assert_eq!(write_order(7).await, expected);
+    }
+
+    #[tokio::test]
+    async fn write_lines_fixed_timestamp_source() {
+        const FIXED_TIME: i64 = 42_000_000_000;
+
         let manager = TestConnectionManager::new();
-        let server = Server::new(manager, config());
+        let config = config().with_timestamp_source(TimestampSource::Fixed(FIXED_TIME));
+        let server = Server::new(manager, config);
         server.set_id(ServerId::try_from(1).unwrap()).unwrap();
         server.maybe_initialize_server().await;
 
         let name = DatabaseName::new("foo".to_string()).unwrap();
         server
-            .create_database(DatabaseRules::new(name))
+            .create_database(DatabaseRules::new(name.clone()))
             .await
             .unwrap();
 
-        let line = "cpu bar=1 10";
-        let lines: Vec<_> = parse_lines(line).map(|l| l.unwrap()).collect();
+        let lines = parsed_lines("cpu bar=1\ncpu bar=2 10");
         server
-            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .write_lines_with_timestamp_source("foo", &lines)
             .await
             .unwrap();
 
-        let db_name = DatabaseName::new("foo").unwrap();
-        let db = server.db(&db_name).unwrap();
+        let db = server.db(&name).unwrap();
 
         let planner = SqlQueryPlanner::default();
         let executor = server.executor();
         let physical_plan = planner
-            .query(db, "select * from cpu", executor.as_ref())
+            .query(db, "select * from cpu order by bar", executor.as_ref())
             .unwrap();
 
         let batches = executor.collect(physical_plan).await.unwrap();
@@ -1395,106 +4293,90 @@ mod tests {
             "+-----+-------------------------------+",
             "| bar | time                          |",
             "+-----+-------------------------------+",
-            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "| 1   | 1970-01-01 00:00:42           |",
+            "| 2   | 1970-01-01 00:00:00.000000010 |",
             "+-----+-------------------------------+",
         ];
         assert_batches_eq!(expected, &batches);
     }
 
     #[tokio::test]
-    async fn write_entry_local() {
-        let (metric_registry, config) = config_with_metric_registry();
+    async fn write_lines_summary() {
         let manager = TestConnectionManager::new();
-        let server = Server::new(manager, config);
+        let server = Server::new(manager, config());
         server.set_id(ServerId::try_from(1).unwrap()).unwrap();
         server.maybe_initialize_server().await;
 
-        let name = DatabaseName::new("foo".to_string()).unwrap();
+        let db_name = DatabaseName::new("foo").unwrap();
         server
-            .create_database(DatabaseRules::new(name))
+            .create_database(DatabaseRules {
+                partition_template: PartitionTemplate {
+                    parts: vec![TemplatePart::Column("region".to_string())],
+                    ..Default::default()
+                },
+                ..DatabaseRules::new(db_name.clone())
+            })
             .await
             .unwrap();
 
-        let db_name = DatabaseName::new("foo").unwrap();
-        let db = server.db(&db_name).unwrap();
-
-        let line = "cpu bar=1 10";
-        let lines: Vec<_> = parse_lines(line).map(|l| l.unwrap()).collect();
-        let sharded_entries = lines_to_sharded_entries(
-            &lines,
-            ARBITRARY_DEFAULT_TIME,
-            NO_SHARD_CONFIG,
-            &*db.rules.read(),
-        )
-        .expect("sharded entries");
-
-        let entry = &sharded_entries[0].entry;
-        server
-            .write_entry("foo", entry.data().into())
+        let lp = vec![
+            "cpu,region=west user=1 10",
+            "cpu,region=west user=2 20",
+            "cpu,region=east user=3 30",
+            "mem,region=east free=4 40",
+        ]
+        .join("\n");
+        let lines: Vec<_> = parse_lines(&lp).map(|l| l.unwrap()).collect();
+
+        let summary = server
+            .write_lines_with_summary(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
             .await
-            .expect("write entry");
-
-        let planner = SqlQueryPlanner::default();
-        let executor = server.executor();
-        let physical_plan = planner
-            .query(db, "select * from cpu", executor.as_ref())
             .unwrap();
 
-        let batches = executor.collect(physical_plan).await.unwrap();
-        let expected = vec![
-            "+-----+-------------------------------+",
-            "| bar | time                          |",
-            "+-----+-------------------------------+",
-            "| 1   | 1970-01-01 00:00:00.000000010 |",
-            "+-----+-------------------------------+",
-        ];
-        assert_batches_eq!(expected, &batches);
-
-        metric_registry
-            .has_metric_family("ingest_entries_bytes_total")
-            .with_labels(&[("status", "ok"), ("db_name", "foo")])
-            .counter()
-            .eq(240.0)
-            .unwrap();
+        assert_eq!(summary.total_rows(), 4);
+        assert_eq!(
+            summary.shard_rows,
+            vec![(None, 4)].into_iter().collect::<BTreeMap<_, _>>()
+        );
+        assert_eq!(
+            summary.partition_rows,
+            vec![
+                ("region_east".to_string(), 2),
+                ("region_west".to_string(), 2)
+            ]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+        );
+        assert_eq!(
+            summary.tables,
+            vec!["cpu".to_string(), "mem".to_string()]
+                .into_iter()
+                .collect::<BTreeSet<_>>()
+        );
     }
 
-    // This tests sets up a database with a sharding config which defines exactly one shard
-    // backed by 3 remote nodes. One of the nodes is modeled to be "down", while the other two
-    // can record write entry events.
-    // This tests goes through a few trivial error cases before checking that the both working
-    // mock remote servers actually receive write entry events.
-    //
-    // This test is theoretically flaky, low probability though (in the order of 1e-30)
     #[tokio::test]
-    async fn write_entry_downstream() {
-        const TEST_SHARD_ID: ShardId = 1;
-        const GOOD_REMOTE_ADDR_1: &str = "http://localhost:111";
-        const GOOD_REMOTE_ADDR_2: &str = "http://localhost:222";
+    async fn write_entry_routed_quorum() {
+        const GOOD_REMOTE_ADDR: &str = "http://localhost:111";
         const BAD_REMOTE_ADDR: &str = "http://localhost:666";
 
-        let good_remote_id_1 = ServerId::try_from(1).unwrap();
-        let good_remote_id_2 = ServerId::try_from(2).unwrap();
+        let good_remote_id = ServerId::try_from(1).unwrap();
         let bad_remote_id = ServerId::try_from(666).unwrap();
 
         let mut manager = TestConnectionManager::new();
-        let written_1 = Arc::new(AtomicBool::new(false));
-        manager.remotes.insert(
-            GOOD_REMOTE_ADDR_1.to_owned(),
-            Arc::new(TestRemoteServer {
-                written: Arc::clone(&written_1),
-            }),
-        );
-        let written_2 = Arc::new(AtomicBool::new(false));
+        let written = Arc::new(AtomicBool::new(false));
         manager.remotes.insert(
-            GOOD_REMOTE_ADDR_2.to_owned(),
+            GOOD_REMOTE_ADDR.to_owned(),
             Arc::new(TestRemoteServer {
-                written: Arc::clone(&written_2),
+                written: Arc::clone(&written),
             }),
         );
 
         let server = Server::new(manager, config());
         server.set_id(ServerId::try_from(1).unwrap()).unwrap();
         server.maybe_initialize_server().await;
+        server.update_remote(good_remote_id, GOOD_REMOTE_ADDR.into());
+        server.update_remote(bad_remote_id, BAD_REMOTE_ADDR.into());
 
         let db_name = DatabaseName::new("foo").unwrap();
         server
@@ -1502,67 +4384,148 @@ mod tests {
             .await
             .unwrap();
 
-        let remote_ids = vec![bad_remote_id, good_remote_id_1, good_remote_id_2];
         let db = server.db(&db_name).unwrap();
-        {
-            let mut rules = db.rules.write();
-            let shard_config = ShardConfig {
-                hash_ring: Some(HashRing {
-                    shards: vec![TEST_SHARD_ID].into(),
-                    ..Default::default()
-                }),
-                shards: Arc::new(
-                    vec![(TEST_SHARD_ID, Shard::Iox(remote_ids.clone()))]
-                        .into_iter()
-                        .collect(),
-                ),
-                ..Default::default()
-            };
-            rules.routing_rules = Some(RoutingRules::ShardConfig(shard_config));
-        }
+        let set_quorum = |quorum: Option<usize>| {
+            db.rules.write().routing_rules = Some(RoutingRules::RoutingConfig(RoutingConfig {
+                targets: vec![vec![good_remote_id], vec![bad_remote_id]],
+                quorum: quorum.map(|q| std::num::NonZeroUsize::new(q).unwrap()),
+            }));
+        };
 
-        let line = "cpu bar=1 10";
-        let lines: Vec<_> = parse_lines(line).map(|l| l.unwrap()).collect();
+        let lines = parsed_lines("cpu bar=1 10");
 
+        // by default all targets must accept the write
+        set_quorum(None);
         let err = server
             .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
             .await
             .unwrap_err();
-        assert!(
-            matches!(err, Error::NoRemoteConfigured { node_group } if node_group == remote_ids)
-        );
+        match err {
+            Error::RoutingQuorumNotReached {
+                required,
+                succeeded,
+                failures,
+            } => {
+                assert_eq!(required, 2);
+                assert_eq!(succeeded, 1);
+                assert_eq!(failures.len(), 1);
+                assert_eq!(failures[0].0, vec![bad_remote_id]);
+                assert!(matches!(failures[0].1, Error::NoRemoteReachable { .. }));
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert!(written.load(Ordering::Relaxed));
 
-        // one remote is configured but it's down and we'll get connection error
-        server.update_remote(bad_remote_id, BAD_REMOTE_ADDR.into());
-        let err = server
+        // a quorum of one tolerates the failing target
+        written.store(false, Ordering::Relaxed);
+        set_quorum(Some(1));
+        server
             .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
             .await
-            .unwrap_err();
-        assert!(matches!(
-            err,
-            Error::NoRemoteReachable { errors } if matches!(
-                errors[BAD_REMOTE_ADDR],
-                ConnectionManagerError::RemoteServerConnectError {..}
-            )
-        ));
-        assert_eq!(written_1.load(Ordering::Relaxed), false);
-        assert_eq!(written_2.load(Ordering::Relaxed), false);
+            .expect("quorum reached");
+        assert!(written.load(Ordering::Relaxed));
+    }
 
-        // We configure the address for the other remote, this time connection will succeed
-        // despite the bad remote failing to connect.
-        server.update_remote(good_remote_id_1, GOOD_REMOTE_ADDR_1.into());
-        server.update_remote(good_remote_id_2, GOOD_REMOTE_ADDR_2.into());
+    #[tokio::test]
+    async fn write_entry_replicated() {
+        const TEST_SHARD_ID: ShardId = 1;
+        const REMOTE_ADDRS: [&str; 3] = [
+            "http://localhost:111",
+            "http://localhost:222",
+            "http://localhost:333",
+        ];
+
+        // Writes to a shard with a 3-node group and a replication factor of 2,
+        // where the remotes at the `failing` indexes cannot be reached.
+        async fn write(failing: &[usize]) -> (Result<()>, Vec<bool>) {
+            let mut manager = TestConnectionManager::new();
+            let mut written = vec![];
+            for (i, addr) in REMOTE_ADDRS.iter().enumerate() {
+                let w = Arc::new(AtomicBool::new(false));
+                if !failing.contains(&i) {
+                    manager.remotes.insert(
+                        addr.to_string(),
+                        Arc::new(TestRemoteServer {
+                            written: Arc::clone(&w),
+                        }),
+                    );
+                }
+                written.push(w);
+            }
+
+            let server = Server::new(manager, config());
+            server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+            server.maybe_initialize_server().await;
 
-        // Remotes are tried in random order, so we need to repeat the test a few times to have a reasonable
-        // probability both the remotes will get hit.
-        for _ in 0..100 {
+            let db_name = DatabaseName::new("foo").unwrap();
             server
-                .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+                .create_database(DatabaseRules::new(db_name.clone()))
                 .await
-                .expect("cannot write lines");
+                .unwrap();
+
+            let mut remote_ids = vec![];
+            for (i, addr) in REMOTE_ADDRS.iter().enumerate() {
+                let id = ServerId::try_from(i as u32 + 1).unwrap();
+                server.update_remote(id, addr.to_string());
+                remote_ids.push(id);
+            }
+            server.db(&db_name).unwrap().rules.write().routing_rules =
+                Some(RoutingRules::ShardConfig(ShardConfig {
+                    hash_ring: Some(HashRing {
+                        shards: vec![TEST_SHARD_ID].into(),
+                        ..Default::default()
+                    }),
+                    shards: Arc::new(
+                        vec![(
+                            TEST_SHARD_ID,
+                            Shard::Iox {
+                                node_group: remote_ids,
+                                replication_factor: std::num::NonZeroUsize::new(2),
+                            },
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    ..Default::default()
+                }));
+
+            let result = server
+                .write_lines(
+                    &db_name,
+                    &parsed_lines("cpu bar=1 10"),
+                    ARBITRARY_DEFAULT_TIME,
+                )
+                .await;
+            let written = written.iter().map(|w| w.load(Ordering::Relaxed)).collect();
+            (result, written)
+        }
+
+        // one failing remote is tolerated, and the write reaches all the others
+        let (result, written) = write(&[1]).await;
+        result.expect("replication factor reached");
+        assert_eq!(written, vec![true, false, true]);
+
+        // two failing remotes leave a single replica, which isn't enough
+        let (result, written) = write(&[0, 2]).await;
+        match result.unwrap_err() {
+            Error::ReplicationFactorNotReached {
+                shard_id,
+                required,
+                succeeded,
+                failures,
+            } => {
+                assert_eq!(shard_id, TEST_SHARD_ID);
+                assert_eq!(required, 2);
+                assert_eq!(succeeded, 1);
+                let failed: Vec<_> = failures.iter().map(|(node, _)| node.get_u32()).collect();
+                assert_eq!(failed, vec![1, 3]);
+                assert!(failures
+                    .iter()
+                    .all(|(_, e)| matches!(e, Error::NoRemoteReachable { .. })));
+            }
+            err => panic!("unexpected error: {}", err),
         }
-        assert_eq!(written_1.load(Ordering::Relaxed), true);
-        assert_eq!(written_2.load(Ordering::Relaxed), true);
+        assert_eq!(written, vec![false, true, false]);
     }
 
     #[tokio::test]
@@ -1616,21 +4579,225 @@ mod tests {
 
         let mut chunk_summaries = db.chunk_summaries().unwrap();
         chunk_summaries.sort_unstable();
-
-        let actual = chunk_summaries
+
+        let actual = chunk_summaries
+            .into_iter()
+            .map(|s| format!("{:?} {}", s.storage, s.id))
+            .collect::<Vec<_>>();
+
+        let expected = vec!["ReadBuffer 0"];
+
+        assert_eq!(
+            expected, actual,
+            "expected:\n{:#?}\n\nactual:{:#?}\n\n",
+            expected, actual
+        );
+
+        // ensure that we don't leave the server instance hanging around
+        cancel_token.cancel();
+        let _ = background_handle.await;
+    }
+
+    #[tokio::test]
+    async fn compact_chunks() {
+        test_helpers::maybe_start_logging();
+        let manager = TestConnectionManager::new();
+        let server = Arc::new(Server::new(manager, config()));
+
+        let cancel_token = CancellationToken::new();
+        let background_handle = spawn_worker(Arc::clone(&server), cancel_token.clone());
+
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let db_name = DatabaseName::new("foo").unwrap();
+        server
+            .create_database(DatabaseRules::new(db_name.clone()))
+            .await
+            .unwrap();
+
+        let partition_key = "";
+        let table_name = "cpu";
+
+        // create two read buffer chunks
+        for (chunk_id, lp) in [(0, "cpu bar=1 10\ncpu bar=2 20"), (1, "cpu bar=3 30")]
+            .iter()
+            .cloned()
+        {
+            let lines: Vec<_> = parse_lines(lp).map(|l| l.unwrap()).collect();
+            server
+                .write_lines(&db_name, &lines, ARBITRARY_DEFAULT_TIME)
+                .await
+                .unwrap();
+
+            server
+                .close_chunk(db_name.clone(), table_name, partition_key, chunk_id)
+                .unwrap()
+                .join()
+                .await;
+        }
+
+        let db = server.db(&db_name).unwrap();
+        let summaries = |db: &Db| {
+            let mut chunk_summaries = db.chunk_summaries().unwrap();
+            chunk_summaries.sort_unstable();
+            chunk_summaries
+                .into_iter()
+                .map(|s| (format!("{:?} {}", s.storage, s.id), s.row_count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summaries(&db),
+            vec![
+                ("ReadBuffer 0".to_string(), 2),
+                ("ReadBuffer 1".to_string(), 1)
+            ]
+        );
+
+        let tracker = server
+            .compact_chunks(db_name.clone(), partition_key, table_name)
+            .unwrap();
+
+        let expected_metadata = Job::CompactChunks {
+            db_name: db_name.to_string(),
+            partition_key: partition_key.to_string(),
+            table_name: table_name.to_string(),
+            chunks: vec![0, 1],
+        };
+        assert_eq!(tracker.metadata(), &expected_metadata);
+        tracker.join().await;
+
+        // the chunks are merged into a single chunk without losing rows
+        assert_eq!(summaries(&db), vec![("ReadBuffer 2".to_string(), 3)]);
+
+        // compacting a single chunk is a no-op
+        let tracker = server
+            .compact_chunks(db_name.clone(), partition_key, table_name)
+            .unwrap();
+        assert!(tracker.is_complete());
+        assert_eq!(summaries(&db), vec![("ReadBuffer 2".to_string(), 3)]);
+
+        cancel_token.cancel();
+        let _ = background_handle.await;
+    }
+
+    #[tokio::test]
+    async fn copy_chunk() {
+        test_helpers::maybe_start_logging();
+        let manager = TestConnectionManager::new();
+        let server = Arc::new(Server::new(manager, config()));
+
+        let cancel_token = CancellationToken::new();
+        let background_handle = spawn_worker(Arc::clone(&server), cancel_token.clone());
+
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let partition_key = "";
+        let table_name = "cpu";
+
+        let src_name = DatabaseName::new("src").unwrap();
+        let dst_name = DatabaseName::new("dst").unwrap();
+        let other_name = DatabaseName::new("other").unwrap();
+        for (db_name, lp) in [
+            (&src_name, "cpu bar=1 10"),
+            (&dst_name, "cpu bar=2 20"),
+            (&other_name, "cpu bar=\"x\" 30"),
+        ]
+        .iter()
+        .cloned()
+        {
+            server
+                .create_database(DatabaseRules::new(db_name.clone()))
+                .await
+                .unwrap();
+
+            let lines: Vec<_> = parse_lines(lp).map(|l| l.unwrap()).collect();
+            server
+                .write_lines(db_name, &lines, ARBITRARY_DEFAULT_TIME)
+                .await
+                .unwrap();
+
+            server
+                .close_chunk(db_name.clone(), table_name, partition_key, 0)
+                .unwrap()
+                .join()
+                .await;
+        }
+
+        let tracker = server
+            .copy_chunk(
+                src_name.clone(),
+                partition_key,
+                table_name,
+                0,
+                dst_name.clone(),
+            )
+            .unwrap();
+
+        let expected_metadata = Job::CopyChunk {
+            db_name: src_name.to_string(),
+            partition_key: partition_key.to_string(),
+            table_name: table_name.to_string(),
+            chunk_id: 0,
+            dst_db_name: dst_name.to_string(),
+        };
+        assert_eq!(tracker.metadata(), &expected_metadata);
+        tracker.join().await;
+
+        let db = server.db(&dst_name).unwrap();
+        let mut chunk_summaries = db.chunk_summaries().unwrap();
+        chunk_summaries.sort_unstable();
+        let summaries: Vec<_> = chunk_summaries
             .into_iter()
-            .map(|s| format!("{:?} {}", s.storage, s.id))
-            .collect::<Vec<_>>();
-
-        let expected = vec!["ReadBuffer 0"];
-
+            .map(|s| (format!("{:?} {}", s.storage, s.id), s.row_count))
+            .collect();
         assert_eq!(
-            expected, actual,
-            "expected:\n{:#?}\n\nactual:{:#?}\n\n",
-            expected, actual
+            summaries,
+            vec![
+                ("ReadBuffer 0".to_string(), 1),
+                ("ReadBuffer 1".to_string(), 1)
+            ]
         );
 
-        // ensure that we don't leave the server instance hanging around
+        let planner = SqlQueryPlanner::default();
+        let executor = server.executor();
+        let physical_plan = planner
+            .query(db, "select * from cpu order by time", executor.as_ref())
+            .unwrap();
+        let batches = executor.collect(physical_plan).await.unwrap();
+        let expected = vec![
+            "+-----+-------------------------------+",
+            "| bar | time                          |",
+            "+-----+-------------------------------+",
+            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "| 2   | 1970-01-01 00:00:00.000000020 |",
+            "+-----+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        // copying into a database with a conflicting schema fails the job,
+        // which leaves the destination unchanged
+        server
+            .copy_chunk(
+                src_name.clone(),
+                partition_key,
+                table_name,
+                0,
+                other_name.clone(),
+            )
+            .unwrap()
+            .join()
+            .await;
+        let db = server.db(&other_name).unwrap();
+        assert_eq!(db.chunk_summaries().unwrap().len(), 1);
+
+        // copying a chunk that doesn't exist fails immediately
+        let err = server
+            .copy_chunk(src_name, partition_key, table_name, 42, dst_name)
+            .unwrap_err();
+        assert!(matches!(err, Error::ChunkNotFound { .. }));
+
         cancel_token.cancel();
         let _ = background_handle.await;
     }
@@ -1656,6 +4823,60 @@ mod tests {
         let _ = background_handle.await;
     }
 
+    #[test]
+    fn jemalloc_stats_cached_within_interval() {
+        let cache = JemallocStatsCache::new(Duration::from_secs(1));
+        let refreshes = std::cell::Cell::new(0);
+        let refresh = || {
+            refreshes.set(refreshes.get() + 1);
+            JemallocStats {
+                active: refreshes.get(),
+                ..Default::default()
+            }
+        };
+
+        let start = Instant::now();
+        assert_eq!(cache.get_with(start, refresh).active, 1);
+        assert_eq!(
+            cache
+                .get_with(start + Duration::from_millis(500), refresh)
+                .active,
+            1
+        );
+        assert_eq!(refreshes.get(), 1);
+
+        assert_eq!(
+            cache
+                .get_with(start + Duration::from_secs(1), refresh)
+                .active,
+            2
+        );
+        assert_eq!(refreshes.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn cancel_job() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+
+        // a job that would otherwise run for an hour
+        let job = server.spawn_dummy_job(vec![3_600_000_000_000]);
+        assert!(!job.is_complete());
+
+        assert!(server.cancel_job(job.id()).unwrap());
+        tokio::time::timeout(tokio::time::Duration::from_secs(10), job.join())
+            .await
+            .expect("cancelled job did not stop");
+        assert!(job.is_complete());
+        assert!(job.is_cancelled());
+
+        // cancelling a completed job is a noop
+        assert!(!server.cancel_job(job.id()).unwrap());
+
+        let err = server.cancel_job(TaskId(usize::MAX)).unwrap_err();
+        assert!(matches!(err, Error::JobNotFound { .. }));
+    }
+
     #[derive(Snafu, Debug, Clone)]
     enum TestClusterError {
         #[snafu(display("Test cluster error:  {}", message))]
@@ -1665,12 +4886,15 @@ mod tests {
     #[derive(Debug)]
     struct TestConnectionManager {
         remotes: BTreeMap<String, Arc<TestRemoteServer>>,
+        /// Connecting to these remotes never completes
+        unresponsive: BTreeSet<String>,
     }
 
     impl TestConnectionManager {
         fn new() -> Self {
             Self {
                 remotes: BTreeMap::new(),
+                unresponsive: BTreeSet::new(),
             }
         }
     }
@@ -1688,6 +4912,9 @@ mod tests {
                 #[snafu(display("remote not found"))]
                 NotFound,
             }
+            if self.unresponsive.contains(id) {
+                futures::future::pending::<()>().await;
+            }
             Ok(Arc::clone(self.remotes.get(id).ok_or_else(|| {
                 ConnectionManagerError::RemoteServerConnectError {
                     source: Box::new(TestRemoteError::NotFound),
@@ -1774,6 +5001,128 @@ mod tests {
         assert!(matches!(res, Err(super::Error::HardLimitReached {})));
     }
 
+    #[tokio::test]
+    async fn write_entry_invalid() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        // columns with different row counts
+        let entry =
+            entry::test_helpers::entry_with_columns("key", &[("bar", &[1, 2]), ("time", &[10])]);
+
+        let res = server.write_entry("foo", entry.data().into()).await;
+        assert!(matches!(
+            res,
+            Err(super::Error::InvalidEntry {
+                source: entry::ValidationError::InconsistentRowCount { .. }
+            })
+        ));
+
+        let db = server.db(&DatabaseName::new("foo").unwrap()).unwrap();
+        assert!(db.partition_keys().unwrap().is_empty());
+
+        // the default partition template produces empty partition keys, which
+        // are valid
+        let lines = parsed_lines("cpu bar=1 10");
+        let sharded_entries = lines_to_sharded_entries(
+            &lines,
+            ARBITRARY_DEFAULT_TIME,
+            NO_SHARD_CONFIG,
+            &PartitionTemplate::default(),
+        )
+        .unwrap();
+        let entry = &sharded_entries[0].entry;
+
+        server
+            .write_entry("foo", entry.data().into())
+            .await
+            .unwrap();
+        assert_eq!(db.partition_keys().unwrap(), vec![String::new()]);
+
+        // but not for databases whose partition template produces keys
+        let name = DatabaseName::new("bar".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules {
+                partition_template: PartitionTemplate {
+                    parts: vec![TemplatePart::Table],
+                    ..Default::default()
+                },
+                ..DatabaseRules::new(name.clone())
+            })
+            .await
+            .unwrap();
+
+        let res = server.write_entry("bar", entry.data().into()).await;
+        assert!(matches!(
+            res,
+            Err(super::Error::InvalidEntry {
+                source: entry::ValidationError::EmptyPartitionKey
+            })
+        ));
+        let db = server.db(&name).unwrap();
+        assert!(db.partition_keys().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn write_entry_truncated() {
+        let manager = TestConnectionManager::new();
+        let server = Server::new(manager, config());
+        server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        server.maybe_initialize_server().await;
+
+        let name = DatabaseName::new("foo".to_string()).unwrap();
+        server
+            .create_database(DatabaseRules::new(name))
+            .await
+            .unwrap();
+
+        let res = server.write_entry("foo", vec![1, 2, 3]).await;
+        assert!(matches!(
+            res,
+            Err(super::Error::DecodingEntry {
+                source: entry::DecodeError::Truncated { .. }
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn remote_connect_timeout() {
+        let timeout = Duration::from_millis(200);
+        let manager = ConnectionManagerImpl::new().with_connect_timeout(timeout);
+
+        // a non-routable address, connecting to it hangs until timing out
+        let start = Instant::now();
+        let err = manager
+            .remote_server("http://10.255.255.1:8082")
+            .await
+            .expect_err("connecting should fail");
+        let elapsed = start.elapsed();
+
+        assert!(
+            elapsed < timeout + Duration::from_secs(1),
+            "took {:?} to fail",
+            elapsed
+        );
+
+        // some networks reject the address outright rather than dropping
+        // packets, in which case the error is not a timeout
+        if elapsed >= timeout {
+            assert!(
+                err.to_string().contains("timed out after"),
+                "unexpected error: {}",
+                err
+            );
+        }
+    }
+
     #[tokio::test]
     async fn cannot_create_db_until_server_is_initialized() {
         let temp_dir = TempDir::new().unwrap();
@@ -1828,6 +5177,88 @@ mod tests {
         let _ = background_handle.await;
     }
 
+    #[tokio::test]
+    async fn wait_for_databases_ready() {
+        let server_id = ServerId::try_from(1).unwrap();
+        let db_name = DatabaseName::new("foo").unwrap();
+
+        let manager = TestConnectionManager::new();
+        let config = config_with_store(ObjectStore::new_in_memory(InMemory::new()));
+        let store = config.store();
+
+        let server = Server::new(manager, config);
+        server.set_id(server_id).unwrap();
+        server.maybe_initialize_server().await;
+        server
+            .create_database(DatabaseRules::new(db_name.clone()))
+            .await
+            .unwrap();
+
+        let lines = parsed_lines("cpu bar=1 10");
+        server
+            .write_lines("foo", &lines, ARBITRARY_DEFAULT_TIME)
+            .await
+            .unwrap();
+
+        // persist the data so it is loaded from the preserved catalog
+        let db = server.db(&db_name).unwrap();
+        let chunk_id = db
+            .rollover_partition("cpu", "")
+            .await
+            .unwrap()
+            .unwrap()
+            .id();
+        db.move_chunk_to_read_buffer("cpu", "", chunk_id)
+            .await
+            .unwrap();
+        db.write_chunk_to_object_store("cpu", "", chunk_id)
+            .await
+            .unwrap();
+        drop(db);
+        server.config.drain().await;
+        drop(server);
+
+        // restart server, letting the background worker load the databases
+        let store = Arc::try_unwrap(store).unwrap();
+        let manager = TestConnectionManager::new();
+        let server = Arc::new(Server::new(manager, config_with_store(store)));
+
+        // not ready before the server ID is set
+        let err = server
+            .wait_for_databases_ready(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DatabasesNotReady { .. }));
+
+        let cancel_token = CancellationToken::new();
+        let background_handle = spawn_worker(Arc::clone(&server), cancel_token.clone());
+        server.set_id(server_id).unwrap();
+
+        server
+            .wait_for_databases_ready(Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        let db = server.db(&db_name).unwrap();
+        let planner = SqlQueryPlanner::default();
+        let executor = server.executor();
+        let physical_plan = planner
+            .query(db, "select * from cpu", executor.as_ref())
+            .unwrap();
+        let batches = executor.collect(physical_plan).await.unwrap();
+        let expected = vec![
+            "+-----+-------------------------------+",
+            "| bar | time                          |",
+            "+-----+-------------------------------+",
+            "| 1   | 1970-01-01 00:00:00.000000010 |",
+            "+-----+-------------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        cancel_token.cancel();
+        let _ = background_handle.await;
+    }
+
     #[tokio::test]
     async fn init_error_generic() {
         // use an object store that will hopefully fail to read
@@ -1899,6 +5330,17 @@ mod tests {
         // generic error MUST NOT be set
         assert!(server.error_generic().is_none());
 
+        // waiting reports the failed DB
+        let err = server
+            .wait_for_databases_ready(Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::DatabasesFailedToLoad { db_names } if db_names == &["bar"]),
+            "{}",
+            err
+        );
+
         // server is initialized
         assert!(server.initialized());
 
@@ -1931,6 +5373,84 @@ mod tests {
         assert_eq!(err.to_string(), "database already exists: bar");
     }
 
+    #[tokio::test]
+    async fn init_repairs_corrupt_catalog() {
+        let store = ObjectStore::new_in_memory(InMemory::new());
+        let server_id = ServerId::try_from(1).unwrap();
+
+        // Create temporary server to create single database
+        let manager = TestConnectionManager::new();
+        let config = config_with_store(store);
+        let store = config.store();
+
+        let server = Server::new(manager, config);
+        server.set_id(server_id).unwrap();
+        server.maybe_initialize_server().await;
+
+        create_simple_database(&server, "foo")
+            .await
+            .expect("failed to create database");
+        server.config.drain().await;
+        drop(server);
+
+        // break the latest catalog transaction
+        let (preserved_catalog, _state) = PreservedCatalog::load::<TestCatalogState>(
+            Arc::clone(&store),
+            server_id,
+            "foo".to_string(),
+            (),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        preserved_catalog
+            .open_transaction()
+            .await
+            .commit()
+            .await
+            .unwrap();
+        parquet_file::catalog::test_helpers::break_catalog_with_weird_version(&preserved_catalog)
+            .await;
+        drop(preserved_catalog);
+        let store = Arc::try_unwrap(store).unwrap();
+
+        // repair is disabled by default, so w/o wipe the DB cannot be loaded
+        let manager = TestConnectionManager::new();
+        let config = config_with_store(store);
+        let store = config.store();
+        let server = Server::new(manager, config);
+        server
+            .init_status
+            .wipe_on_error
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        server.set_id(server_id).unwrap();
+        server.maybe_initialize_server().await;
+
+        assert!(server.error_database("foo").is_some());
+        assert!(server.catalog_repair("foo").is_none());
+        server.config.drain().await;
+        drop(server);
+        let store = Arc::try_unwrap(store).unwrap();
+
+        // with repair enabled the DB is loaded and the repair is reported
+        let manager = TestConnectionManager::new();
+        let config = config_with_store(store).with_repair_catalog_on_error(true);
+        let server = Server::new(manager, config);
+        server
+            .init_status
+            .wipe_on_error
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        server.set_id(server_id).unwrap();
+        server.maybe_initialize_server().await;
+
+        assert!(server.error_database("foo").is_none());
+        assert!(server.db(&DatabaseName::new("foo").unwrap()).is_some());
+        let repair = server.catalog_repair("foo").unwrap();
+        assert_eq!(repair.corrupt_revision, 1);
+        assert_eq!(repair.last_good_revision, 0);
+        assert!(repair.orphaned_files.is_empty());
+    }
+
     #[tokio::test]
     async fn wipe_preserved_catalog() {
         // have the following DBs:
@@ -0,0 +1,128 @@
+//! A bounded cache of connections to remote servers.
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
+
+use parking_lot::Mutex;
+
+/// Caches remote server connections keyed by connection string, holding at
+/// most `capacity` of them.
+///
+/// Once full, inserting a new connection evicts the least recently used one.
+/// Evicted connections are dropped by the cache and get closed once no
+/// in-flight request holds on to them anymore.
+#[derive(Debug)]
+pub(crate) struct RemoteCache<T> {
+    capacity: NonZeroUsize,
+    state: Mutex<RemoteCacheState<T>>,
+}
+
+#[derive(Debug)]
+struct RemoteCacheState<T> {
+    /// Cached connections along with the tick they were last used at.
+    entries: HashMap<String, (u64, Arc<T>)>,
+
+    /// Monotonically increasing counter used to order accesses.
+    tick: u64,
+}
+
+impl<T> RemoteCache<T> {
+    pub(crate) fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(RemoteCacheState {
+                entries: Default::default(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// Returns the cached connection for `connect`, marking it as the most
+    /// recently used one.
+    pub(crate) fn get(&self, connect: &str) -> Option<Arc<T>> {
+        let mut state = self.state.lock();
+        state.tick += 1;
+        let tick = state.tick;
+
+        state.entries.get_mut(connect).map(|(last_used, remote)| {
+            *last_used = tick;
+            Arc::clone(remote)
+        })
+    }
+
+    /// Caches `remote` for `connect`, evicting the least recently used
+    /// connection if the cache is full.
+    ///
+    /// If a connection is already cached for `connect` it is replaced.
+    pub(crate) fn insert(&self, connect: String, remote: Arc<T>) {
+        let mut state = self.state.lock();
+        state.tick += 1;
+        let tick = state.tick;
+
+        if !state.entries.contains_key(&connect) && state.entries.len() >= self.capacity.get() {
+            let lru = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(connect, _)| connect.clone());
+
+            if let Some(lru) = lru {
+                state.entries.remove(&lru);
+            }
+        }
+
+        state.entries.insert(connect, (tick, remote));
+    }
+
+    /// Returns the number of cached connections.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = RemoteCache::new(NonZeroUsize::new(2).unwrap());
+
+        let a = Arc::new("a");
+        cache.insert("a".to_string(), Arc::clone(&a));
+        cache.insert("b".to_string(), Arc::new("b"));
+
+        // touch "a" so that "b" is the least recently used connection
+        assert_eq!(cache.get("a").as_deref(), Some(&"a"));
+
+        for connect in &["c", "d", "e"] {
+            cache.get("a");
+            cache.insert(connect.to_string(), Arc::new(*connect));
+            assert_eq!(cache.len(), 2);
+        }
+
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_none());
+        assert!(cache.get("d").is_none());
+        assert_eq!(cache.get("a").as_deref(), Some(&"a"));
+        assert_eq!(cache.get("e").as_deref(), Some(&"e"));
+
+        // evicted connections are dropped by the cache
+        cache.insert("f".to_string(), Arc::new("f"));
+        cache.insert("g".to_string(), Arc::new("g"));
+        assert!(cache.get("a").is_none());
+        assert_eq!(Arc::strong_count(&a), 1);
+    }
+
+    #[test]
+    fn replace_does_not_evict() {
+        let cache = RemoteCache::new(NonZeroUsize::new(2).unwrap());
+
+        cache.insert("a".to_string(), Arc::new(1));
+        cache.insert("b".to_string(), Arc::new(2));
+        cache.insert("a".to_string(), Arc::new(3));
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a").as_deref(), Some(&3));
+        assert_eq!(cache.get("b").as_deref(), Some(&2));
+    }
+}
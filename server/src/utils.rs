@@ -10,7 +10,11 @@ use object_store::{memory::InMemory, ObjectStore};
 use query::{exec::Executor, QueryDatabase};
 
 use crate::{
-    db::{load::load_or_create_preserved_catalog, DatabaseToCommit, Db},
+    db::{
+        load::load_or_create_preserved_catalog,
+        process_clock::{ClockSource, ProcessClock},
+        DatabaseToCommit, Db,
+    },
     write_buffer::WriteBuffer,
     JobRegistry,
 };
@@ -37,6 +41,7 @@ pub struct TestDbBuilder {
     worker_cleanup_avg_sleep: Option<Duration>,
     write_buffer: Option<Arc<dyn WriteBuffer>>,
     catalog_transactions_until_checkpoint: Option<NonZeroU64>,
+    clock_source: Option<Arc<dyn ClockSource>>,
 }
 
 impl TestDbBuilder {
@@ -88,6 +93,9 @@ impl TestDbBuilder {
             catalog,
             write_buffer: self.write_buffer,
             exec,
+            clock_source: self
+                .clock_source
+                .unwrap_or_else(|| Arc::new(ProcessClock::new())),
         };
 
         TestDb {
@@ -125,6 +133,11 @@ impl TestDbBuilder {
         self.catalog_transactions_until_checkpoint = Some(interval);
         self
     }
+
+    pub fn clock_source(mut self, clock_source: Arc<dyn ClockSource>) -> Self {
+        self.clock_source = Some(clock_source);
+        self
+    }
 }
 
 /// Used for testing: create a Database with a local store
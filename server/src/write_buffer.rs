@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use data_types::database_rules::DatabaseRules;
+use data_types::database_rules::{DatabaseRules, RuleValidationIssue};
 use entry::{Entry, Sequence};
 use rdkafka::{
     error::KafkaError,
@@ -27,6 +27,18 @@ pub fn new(rules: &DatabaseRules) -> Result<Option<Arc<dyn WriteBuffer>>, WriteB
     }
 }
 
+/// Returns the problems with a write buffer connection string, which must be a
+/// comma separated list of Kafka brokers.
+pub fn validate_connection_string(conn: &str) -> Vec<RuleValidationIssue> {
+    if conn.split(',').any(|broker| broker.trim().is_empty()) {
+        vec![RuleValidationIssue::InvalidWriteBufferConnectionString {
+            connection_string: conn.to_string(),
+        }]
+    } else {
+        vec![]
+    }
+}
+
 /// A Write Buffer takes an `Entry` and returns `Sequence` data that facilitates reading entries
 /// from the Write Buffer at a later time.
 #[async_trait]
@@ -134,6 +134,11 @@ struct Create {
     /// Maximum age of a write before triggering persistence
     #[structopt(long, default_value = "1800")]
     persist_age_threshold_seconds: u32,
+
+    /// Drop partitions once all of their data is older than this many
+    /// seconds. If 0, data is retained forever
+    #[structopt(long, default_value = "0")]
+    retention_seconds: u64,
 }
 
 /// Get list of databases
@@ -209,6 +214,7 @@ pub async fn command(url: String, config: Config) -> Result<()> {
                     late_arrive_window_seconds: command.late_arrive_window_seconds,
                     persist_row_threshold: command.persist_row_threshold,
                     persist_age_threshold_seconds: command.persist_age_threshold_seconds,
+                    retention_seconds: command.retention_seconds,
                 }),
 
                 // Default to hourly partitions
@@ -218,6 +224,7 @@ pub async fn command(url: String, config: Config) -> Result<()> {
                             "%Y-%m-%d %H:00:00".into(),
                         )),
                     }],
+                    ..Default::default()
                 }),
 
                 // Note no write buffer config
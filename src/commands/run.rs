@@ -3,7 +3,7 @@
 use crate::influxdb_ioxd::{self, serving_readiness::ServingReadinessState};
 use clap::arg_enum;
 use data_types::server_id::ServerId;
-use std::{net::SocketAddr, net::ToSocketAddrs, path::PathBuf};
+use std::{net::SocketAddr, net::ToSocketAddrs, num::NonZeroUsize, path::PathBuf};
 use structopt::StructOpt;
 use thiserror::Error;
 use trogging::cli::{LoggingConfig, TracingConfig};
@@ -91,6 +91,99 @@ pub struct Config {
     #[structopt(long = "--num-worker-threads", env = "INFLUXDB_IOX_NUM_WORKER_THREADS")]
     pub num_worker_threads: Option<usize>,
 
+    /// The priority of the query worker threads (as passed to
+    /// `setpriority`, higher values are less favorable).
+    ///
+    /// If not specified, query worker threads run with a lower priority than
+    /// the threads handling API requests so queries can't starve writes.
+    #[structopt(
+        long = "--worker-thread-priority",
+        env = "INFLUXDB_IOX_WORKER_THREAD_PRIORITY"
+    )]
+    pub worker_thread_priority: Option<i32>,
+
+    /// The maximum number of rows returned by a query through the HTTP API.
+    ///
+    /// Queries producing more rows fail with `413 Payload Too Large` instead
+    /// of buffering all of their results in memory. If not specified, results
+    /// are not limited.
+    #[structopt(
+        long = "--max-query-result-rows",
+        env = "INFLUXDB_IOX_MAX_QUERY_RESULT_ROWS"
+    )]
+    pub max_query_result_rows: Option<usize>,
+
+    /// Additionally store the rules of each database as human readable
+    /// `rules.json` next to the `rules.pb` in object storage.
+    ///
+    /// The JSON copy is only meant for inspection; the protobuf is always
+    /// used when loading the rules.
+    #[structopt(long = "--persist-rules-json")]
+    pub persist_rules_json: bool,
+
+    /// Reject queries and writes for databases that don't exist, instead of
+    /// creating them with default rules.
+    ///
+    /// Recommended, as otherwise a misspelled org or bucket name silently
+    /// creates a new, empty database.
+    #[structopt(
+        long = "--no-auto-create-databases",
+        env = "INFLUXDB_IOX_NO_AUTO_CREATE_DATABASES"
+    )]
+    pub no_auto_create_databases: bool,
+
+    /// Truncate a preserved catalog with a corrupt transaction to its last
+    /// good transaction when loading a database, instead of failing.
+    ///
+    /// All transactions from the corrupt one onwards are lost. Performed
+    /// repairs are reported in the server status.
+    #[structopt(
+        long = "--repair-catalog-on-error",
+        env = "INFLUXDB_IOX_REPAIR_CATALOG_ON_ERROR"
+    )]
+    pub repair_catalog_on_error: bool,
+
+    /// Convert the values of a field to floats when its numeric type changes
+    /// between the lines of a write, instead of rejecting the write.
+    ///
+    /// For example, a write with `val=1i` on one line and `val=2.5` or
+    /// `val=3u` on another stores all values of `val` as floats.
+    #[structopt(
+        long = "--promote-mixed-numeric-fields",
+        env = "INFLUXDB_IOX_PROMOTE_MIXED_NUMERIC_FIELDS"
+    )]
+    pub promote_mixed_numeric_fields: bool,
+
+    /// The maximum number of connections to other IOx servers kept open
+    /// when routing writes.
+    ///
+    /// Once reached, the least recently used connection is closed to make
+    /// room for a new one.
+    #[structopt(
+        long = "--remote-connection-cache-size",
+        env = "INFLUXDB_IOX_REMOTE_CONNECTION_CACHE_SIZE",
+        default_value = "100"
+    )]
+    pub remote_connection_cache_size: NonZeroUsize,
+
+    /// The maximum time, in milliseconds, to wait for another IOx server to
+    /// accept a connection when routing writes.
+    #[structopt(
+        long = "--remote-connect-timeout-ms",
+        env = "INFLUXDB_IOX_REMOTE_CONNECT_TIMEOUT_MS",
+        default_value = "1000"
+    )]
+    pub remote_connect_timeout_ms: u64,
+
+    /// The maximum time, in milliseconds, a single request to another IOx
+    /// server may take when routing writes.
+    #[structopt(
+        long = "--remote-request-timeout-ms",
+        env = "INFLUXDB_IOX_REMOTE_REQUEST_TIMEOUT_MS",
+        default_value = "30000"
+    )]
+    pub remote_request_timeout_ms: u64,
+
     #[structopt(
     long = "--object-store",
     env = "INFLUXDB_IOX_OBJECT_STORE",
@@ -1,4 +1,5 @@
 use crate::commands::run::{Config, ObjectStore as ObjStoreOpt};
+use entry::FieldTypeCoercion;
 use futures::{future::FusedFuture, pin_mut, FutureExt, TryStreamExt};
 use hyper::server::conn::AddrIncoming;
 use object_store::{
@@ -17,6 +18,7 @@ use tokio::time::Duration;
 use uuid::Uuid;
 
 mod http;
+mod jobs;
 mod planner;
 mod rpc;
 pub(crate) mod serving_readiness;
@@ -147,6 +149,35 @@ pub async fn main(config: Config) -> Result<()> {
         server_config
     };
 
+    let server_config = if let Some(priority) = config.worker_thread_priority {
+        info!(
+            worker_thread_priority = priority,
+            "Using specified worker thread priority"
+        );
+        server_config.with_worker_thread_priority(priority)
+    } else {
+        server_config
+    };
+
+    let server_config = if let Some(max_rows) = config.max_query_result_rows {
+        info!(
+            max_query_result_rows = max_rows,
+            "Limiting rows returned by HTTP queries"
+        );
+        server_config.with_max_query_result_rows(max_rows)
+    } else {
+        server_config
+    };
+    let server_config = server_config
+        .with_persist_rules_json(config.persist_rules_json)
+        .with_auto_create_databases(!config.no_auto_create_databases)
+        .with_repair_catalog_on_error(config.repair_catalog_on_error)
+        .with_field_type_coercion(if config.promote_mixed_numeric_fields {
+            FieldTypeCoercion::PromoteToFloat
+        } else {
+            FieldTypeCoercion::Strict
+        });
+
     if config.grpc_bind_address == config.http_bind_address {
         error!(
             %config.grpc_bind_address,
@@ -156,7 +187,9 @@ pub async fn main(config: Config) -> Result<()> {
         std::process::exit(1);
     }
 
-    let connection_manager = ConnectionManager::new();
+    let connection_manager = ConnectionManager::with_capacity(config.remote_connection_cache_size)
+        .with_connect_timeout(Duration::from_millis(config.remote_connect_timeout_ms))
+        .with_request_timeout(Duration::from_millis(config.remote_request_timeout_ms));
     let app_server = Arc::new(AppServer::new(connection_manager, server_config));
 
     // if this ID isn't set the server won't be usable until this is set via an API
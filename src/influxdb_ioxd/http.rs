@@ -11,34 +11,37 @@
 //! database names and may remove this quasi /v2 API.
 
 // Influx crates
+use super::jobs::job_operation;
 use super::planner::Planner;
 use data_types::{
+    database_rules::{RoutingRules, Shard, ShardId},
     names::{org_and_bucket_to_database, OrgBucketMappingError},
+    server_id::ServerId,
     DatabaseName,
 };
 use influxdb_iox_client::format::QueryOutputFormat;
-use influxdb_line_protocol::parse_lines;
+use influxdb_line_protocol::{parse_lines_with_line_numbers, ParsedLine};
 use query::QueryDatabase;
-use server::{ConnectionManager, Server as AppServer};
+use server::{ConnectionManager, Server as AppServer, WriteOutcome};
 
 // External crates
 use bytes::{Bytes, BytesMut};
-use chrono::Utc;
 use futures::{self, StreamExt};
-use http::header::{CONTENT_ENCODING, CONTENT_TYPE};
+use http::header::{ACCEPT, CONTENT_ENCODING, CONTENT_TYPE};
 use hyper::{http::HeaderValue, Body, Method, Request, Response, StatusCode};
 use observability_deps::{
     opentelemetry::KeyValue,
     tracing::{self, debug, error, info},
 };
 use routerify::{prelude::*, Middleware, RequestInfo, Router, RouterError, RouterService};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, ResultExt, Snafu};
 
 use hyper::server::conn::AddrIncoming;
 use pprof::protos::Message;
 use std::num::NonZeroI32;
 use std::{
+    collections::BTreeMap,
     fmt::Debug,
     str::{self, FromStr},
     sync::Arc,
@@ -64,6 +67,17 @@ pub enum ApiErrorCode {
 
     /// The database referenced does not exist.
     DB_NOT_FOUND = 103,
+
+    /// The line protocol in the request could not be parsed.
+    LP_PARSE_ERROR = 104,
+
+    /// The database has reached its buffer size limit and is not accepting
+    /// writes. Returned with status 503, the write may be retried later.
+    WRITE_BUFFER_FULL = 105,
+
+    /// The written data has a field whose type conflicts with the type of
+    /// previously written data. Returned with status 400.
+    FIELD_TYPE_CONFLICT = 106,
 }
 
 impl From<ApiErrorCode> for u32 {
@@ -98,7 +112,7 @@ pub enum ApplicationError {
     WritingPoints {
         org: String,
         bucket_name: String,
-        source: Box<dyn std::error::Error + Send + Sync>,
+        source: server::Error,
     },
 
     #[snafu(display("Internal error reading points from database {}:  {}", db_name, source))]
@@ -114,6 +128,9 @@ pub enum ApplicationError {
     #[snafu(display("Body exceeds limit of {} bytes", max_body_size))]
     RequestSizeExceeded { max_body_size: usize },
 
+    #[snafu(display("Query results exceed limit of {} rows", max_rows))]
+    ResultTooLarge { max_rows: usize },
+
     #[snafu(display("Expected query string in request, but none was provided"))]
     ExpectedQueryString {},
 
@@ -151,14 +168,51 @@ pub enum ApplicationError {
     #[snafu(display("Error reading request body as utf8: {}", source))]
     ReadingBodyAsUtf8 { source: std::str::Utf8Error },
 
-    #[snafu(display("Error parsing line protocol: {}", source))]
+    #[snafu(display(
+        "Error parsing line protocol on line {} '{}': {} ({} valid lines)",
+        line_number,
+        line,
+        source,
+        valid_lines
+    ))]
     ParsingLineProtocol {
         source: influxdb_line_protocol::Error,
+        /// The 1-based number of the first line that could not be parsed
+        line_number: usize,
+        /// The start of the line that could not be parsed
+        line: String,
+        /// The number of lines in the request that could be parsed
+        valid_lines: usize,
+    },
+
+    #[snafu(display(
+        "Timestamp {} overflows when converted from precision '{}' to nanoseconds",
+        timestamp,
+        precision.as_str()
+    ))]
+    TimestampOverflow {
+        timestamp: i64,
+        precision: Precision,
     },
 
     #[snafu(display("Error decompressing body as gzip: {}", source))]
     ReadingBodyAsGzip { source: std::io::Error },
 
+    #[snafu(display("Unsupported content type '{}', expected '{}'", content_type, expected))]
+    UnsupportedContentType {
+        content_type: String,
+        expected: &'static str,
+    },
+
+    #[snafu(display("Error decoding entry: {}", source))]
+    DecodingEntry { source: server::Error },
+
+    #[snafu(display("Internal error writing entry into database {}: {}", db_name, source))]
+    WritingEntry {
+        db_name: String,
+        source: server::Error,
+    },
+
     #[snafu(display("No handler for {:?} {}", method, path))]
     RouteNotFound { method: Method, path: String },
 
@@ -174,6 +228,14 @@ pub enum ApplicationError {
     #[snafu(display("Error creating database: {}", source))]
     ErrorCreatingDatabase { source: server::Error },
 
+    #[snafu(display("Error decoding database rules: {}", source))]
+    DecodingRules {
+        source: generated_types::database_rules::DecodeError,
+    },
+
+    #[snafu(display("Error computing object store usage: {}", source))]
+    ObjectStoreUsage { source: server::Error },
+
     #[snafu(display("Invalid database name: {}", source))]
     DatabaseNameError {
         source: data_types::DatabaseNameError,
@@ -182,6 +244,20 @@ pub enum ApplicationError {
     #[snafu(display("Database {} not found", name))]
     DatabaseNotFound { name: String },
 
+    #[snafu(display(
+        "Chunk {}:{}:{} not found in database {}",
+        partition_key,
+        table_name,
+        chunk_id,
+        db_name
+    ))]
+    ChunkNotFound {
+        db_name: String,
+        partition_key: String,
+        table_name: String,
+        chunk_id: u32,
+    },
+
     #[snafu(display("Database {} does not have a Write Buffer", name))]
     WriteBufferNotFound { name: String },
 
@@ -236,11 +312,23 @@ impl ApplicationError {
         match self {
             Self::BucketByName { .. } => self.internal_error(),
             Self::BucketMappingError { .. } => self.internal_error(),
+            Self::WritingPoints {
+                source: server::Error::HardLimitReached {},
+                ..
+            } => self.service_unavailable(),
+            Self::WritingPoints {
+                source:
+                    server::Error::LineConversion {
+                        source: entry::Error::TableColumnTypeMismatch { .. },
+                    },
+                ..
+            } => self.bad_request(),
             Self::WritingPoints { .. } => self.internal_error(),
             Self::Query { .. } => self.internal_error(),
             Self::QueryError { .. } => self.bad_request(),
             Self::BucketNotFound { .. } => self.not_found(),
             Self::RequestSizeExceeded { .. } => self.bad_request(),
+            Self::ResultTooLarge { .. } => self.payload_too_large(),
             Self::ExpectedQueryString { .. } => self.bad_request(),
             Self::InvalidQueryString { .. } => self.bad_request(),
             Self::InvalidRequestBody { .. } => self.bad_request(),
@@ -250,13 +338,24 @@ impl ApplicationError {
             Self::ReadingBody { .. } => self.bad_request(),
             Self::ReadingBodyAsUtf8 { .. } => self.bad_request(),
             Self::ParsingLineProtocol { .. } => self.bad_request(),
+            Self::TimestampOverflow { .. } => self.bad_request(),
             Self::ReadingBodyAsGzip { .. } => self.bad_request(),
+            Self::UnsupportedContentType { .. } => self.bad_request(),
+            Self::DecodingEntry { .. } => self.bad_request(),
+            Self::WritingEntry {
+                source: server::Error::HardLimitReached {},
+                ..
+            } => self.service_unavailable(),
+            Self::WritingEntry { .. } => self.internal_error(),
             Self::RouteNotFound { .. } => self.not_found(),
             Self::DatabaseError { .. } => self.internal_error(),
             Self::JsonGenerationError { .. } => self.internal_error(),
             Self::ErrorCreatingDatabase { .. } => self.bad_request(),
+            Self::DecodingRules { .. } => self.bad_request(),
+            Self::ObjectStoreUsage { .. } => self.internal_error(),
             Self::DatabaseNameError { .. } => self.bad_request(),
             Self::DatabaseNotFound { .. } => self.not_found(),
+            Self::ChunkNotFound { .. } => self.not_found(),
             Self::WriteBufferNotFound { .. } => self.not_found(),
             Self::CreatingResponse { .. } => self.internal_error(),
             Self::FormattingResult { .. } => self.internal_error(),
@@ -283,6 +382,20 @@ impl ApplicationError {
             .unwrap()
     }
 
+    fn service_unavailable(&self) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(self.body())
+            .unwrap()
+    }
+
+    fn payload_too_large(&self) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::PAYLOAD_TOO_LARGE)
+            .body(self.body())
+            .unwrap()
+    }
+
     fn not_found(&self) -> Response<Body> {
         Response::builder()
             .status(StatusCode::NOT_FOUND)
@@ -305,10 +418,20 @@ impl ApplicationError {
     }
 
     fn body(&self) -> Body {
-        let json =
-            serde_json::json!({"error": self.to_string(), "error_code": self.api_error_code()})
-                .to_string();
-        Body::from(json)
+        let mut json =
+            serde_json::json!({"error": self.to_string(), "error_code": self.api_error_code()});
+        if let Self::ParsingLineProtocol {
+            line_number,
+            line,
+            valid_lines,
+            ..
+        } = self
+        {
+            json["line_number"] = (*line_number).into();
+            json["line"] = line.as_str().into();
+            json["valid_lines"] = (*valid_lines).into();
+        }
+        Body::from(json.to_string())
     }
 
     /// Map the error type into an API error code.
@@ -316,6 +439,7 @@ impl ApplicationError {
         match self {
             Self::DatabaseNameError { .. } => ApiErrorCode::DB_INVALID_NAME,
             Self::DatabaseNotFound { .. } => ApiErrorCode::DB_NOT_FOUND,
+            Self::ParsingLineProtocol { .. } => ApiErrorCode::LP_PARSE_ERROR,
 
             // Some errors are wrapped
             Self::ErrorCreatingDatabase {
@@ -330,6 +454,24 @@ impl ApplicationError {
                 source: server::Error::DatabaseAlreadyExists { .. },
             } => ApiErrorCode::DB_ALREADY_EXISTS,
 
+            Self::WritingPoints {
+                source: server::Error::HardLimitReached {},
+                ..
+            } => ApiErrorCode::WRITE_BUFFER_FULL,
+
+            Self::WritingEntry {
+                source: server::Error::HardLimitReached {},
+                ..
+            } => ApiErrorCode::WRITE_BUFFER_FULL,
+
+            Self::WritingPoints {
+                source:
+                    server::Error::LineConversion {
+                        source: entry::Error::TableColumnTypeMismatch { .. },
+                    },
+                ..
+            } => ApiErrorCode::FIELD_TYPE_CONFLICT,
+
             // A "catch all" error code
             _ => ApiErrorCode::UNKNOWN,
         }
@@ -339,6 +481,17 @@ impl ApplicationError {
 
 const MAX_SIZE: usize = 10_485_760; // max write request size of 10MB
 
+/// Response header set on writes that were accepted while the database's
+/// buffer is above its soft limit.
+pub const BUFFER_PRESSURE_HEADER: &str = "X-IOx-Buffer-Pressure";
+
+/// Request header identifying a write, so that retrying it doesn't ingest its
+/// data again. See [`AppServer::write_lines_idempotent`].
+pub const IDEMPOTENCY_KEY_HEADER: &str = "X-IOx-Idempotency-Key";
+
+/// Content type of request bodies holding a serialized `Entry` flatbuffer.
+pub const ENTRY_CONTENT_TYPE: &str = "application/x-iox-entry";
+
 fn router<M>(server: Arc<AppServer<M>>) -> Router<Body, ApplicationError>
 where
     M: ConnectionManager + Send + Sync + Debug + 'static,
@@ -358,8 +511,20 @@ where
         })) // this endpoint is for API backward compatibility with InfluxDB 2.x
         .post("/api/v2/write", write::<M>)
         .get("/health", health::<M>)
+        .get("/iox/api/v1/health/detail", health_detail::<M>)
         .get("/metrics", handle_metrics::<M>)
+        .get("/iox/api/v1/databases", list_databases::<M>)
         .get("/iox/api/v1/databases/:name/query", query::<M>)
+        .post("/iox/api/v1/databases/:name/entry", write_entry::<M>)
+        .get("/iox/api/v1/databases/:name/chunks", list_chunks::<M>)
+        .post("/iox/api/v1/databases/:name/chunks/close", close_chunk::<M>)
+        .get("/iox/api/v1/jobs", list_jobs::<M>)
+        .get("/iox/api/v1/storage", object_store_usage::<M>)
+        .post("/iox/api/v1/databases/validate-rules", validate_rules::<M>)
+        .post(
+            "/iox/api/v1/databases/:name/explain-sharding",
+            explain_sharding::<M>,
+        )
         .get("/api/v1/partitions", list_partitions::<M>)
         .get("/debug/pprof", pprof_home::<M>)
         .get("/debug/pprof/profile", pprof_profile::<M>)
@@ -394,11 +559,59 @@ async fn error_handler(err: RouterError<ApplicationError>, req: RequestInfo) ->
     }
 }
 
+/// Precision of the integer timestamps in a write request
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum Precision {
+    #[serde(rename = "ns")]
+    Nanoseconds,
+    #[serde(rename = "us")]
+    Microseconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+    #[serde(rename = "s")]
+    Seconds,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self::Nanoseconds
+    }
+}
+
+impl Precision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Nanoseconds => "ns",
+            Self::Microseconds => "us",
+            Self::Milliseconds => "ms",
+            Self::Seconds => "s",
+        }
+    }
+
+    /// The number of nanoseconds in one unit of this precision
+    fn nanos_per_unit(&self) -> i64 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+        }
+    }
+
+    /// Converts a timestamp in this precision to nanoseconds, returning
+    /// `None` on overflow
+    fn to_nanos(&self, timestamp: i64) -> Option<i64> {
+        timestamp.checked_mul(self.nanos_per_unit())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 /// Body of the request to the /write endpoint
 struct WriteInfo {
     org: String,
     bucket: String,
+    #[serde(default)]
+    precision: Precision,
 }
 
 /// Parse the request's body into raw bytes, applying size limits and
@@ -453,6 +666,38 @@ async fn parse_body(req: hyper::Request<Body>) -> Result<Bytes, ApplicationError
     }
 }
 
+/// The maximum number of characters of a line that failed to parse included in
+/// the error.
+const LP_ERROR_LINE_LEN: usize = 100;
+
+/// Parses all lines of `body`, failing with the position of the first line
+/// that can't be parsed and the number of lines that can.
+fn parse_line_protocol(body: &str) -> Result<Vec<ParsedLine<'_>>, ApplicationError> {
+    let mut lines = vec![];
+    let mut first_error = None;
+
+    for (line_number, line, result) in parse_lines_with_line_numbers(body) {
+        match result {
+            Ok(parsed) => lines.push(parsed),
+            Err(e) => {
+                if first_error.is_none() {
+                    first_error = Some((line_number, line, e))
+                }
+            }
+        }
+    }
+
+    match first_error {
+        Some((line_number, line, source)) => Err(ApplicationError::ParsingLineProtocol {
+            source,
+            line_number,
+            line: line.trim_end().chars().take(LP_ERROR_LINE_LEN).collect(),
+            valid_lines: lines.len(),
+        }),
+        None => Ok(lines),
+    }
+}
+
 #[observability_deps::instrument(level = "debug")]
 async fn write<M>(req: Request<Body>) -> Result<Response<Body>, ApplicationError>
 where
@@ -476,26 +721,38 @@ where
     let db_name = org_and_bucket_to_database(&write_info.org, &write_info.bucket)
         .context(BucketMappingError)?;
 
+    let idempotency_key = req
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|key| {
+            key.to_str()
+                .map(ToString::to_string)
+                .context(ReadingHeaderAsUtf8 {
+                    header_name: IDEMPOTENCY_KEY_HEADER,
+                })
+        })
+        .transpose()?;
+
     let body = parse_body(req).await?;
 
     let body = str::from_utf8(&body).context(ReadingBodyAsUtf8)?;
 
-    // The time, in nanoseconds since the epoch, to assign to any points that don't
-    // contain a timestamp
-    let default_time = Utc::now().timestamp_nanos();
-
-    let mut num_fields = 0;
-    let mut num_lines = 0;
-
-    let lines = parse_lines(body)
-        .inspect(|line| {
-            if let Ok(line) = line {
-                num_fields += line.field_set.len();
-                num_lines += 1;
+    let mut lines = parse_line_protocol(body)?;
+    let num_lines = lines.len();
+    let num_fields: usize = lines.iter().map(|line| line.field_set.len()).sum();
+
+    let precision = write_info.precision;
+    if precision != Precision::Nanoseconds {
+        for line in &mut lines {
+            if let Some(timestamp) = line.timestamp {
+                let nanos = precision.to_nanos(timestamp).context(TimestampOverflow {
+                    timestamp,
+                    precision,
+                })?;
+                line.timestamp = Some(nanos);
             }
-        })
-        .collect::<Result<Vec<_>, influxdb_line_protocol::Error>>()
-        .context(ParsingLineProtocol)?;
+        }
+    }
 
     debug!(num_lines=lines.len(), %db_name, org=%write_info.org, bucket=%write_info.bucket, "inserting lines into database");
 
@@ -505,8 +762,8 @@ where
         KeyValue::new("path", path),
     ];
 
-    server
-        .write_lines(&db_name, &lines, default_time)
+    let summary = server
+        .write_lines_idempotent(&db_name, &lines, idempotency_key.as_deref())
         .await
         .map_err(|e| {
             let labels = &[
@@ -541,7 +798,7 @@ where
                 _ => ApplicationError::WritingPoints {
                     org: write_info.org.clone(),
                     bucket_name: write_info.bucket.clone(),
-                    source: Box::new(e),
+                    source: e,
                 },
             }
         })?;
@@ -568,6 +825,84 @@ where
         .add_with_labels(body.len() as u64, labels);
 
     obs.ok_with_labels(&metric_kv); // request completed successfully
+    let mut response = Response::builder().status(StatusCode::NO_CONTENT);
+    if summary.outcome == WriteOutcome::OverSoftLimit {
+        response = response.header(BUFFER_PRESSURE_HEADER, "high");
+    }
+    Ok(response.body(Body::empty()).unwrap())
+}
+
+/// Writes an already built `Entry` flatbuffer, sent as the request body, into
+/// the database.
+#[observability_deps::instrument(level = "debug")]
+async fn write_entry<M>(req: Request<Body>) -> Result<Response<Body>, ApplicationError>
+where
+    M: ConnectionManager + Send + Sync + Debug + 'static,
+{
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
+
+    let db_name_str = req
+        .param("name")
+        .expect("db name must have been set by routerify")
+        .clone();
+
+    let metric_kv = vec![
+        KeyValue::new("db_name", db_name_str.clone()),
+        KeyValue::new("path", path),
+    ];
+
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|content_type| {
+            content_type.to_str().context(ReadingHeaderAsUtf8 {
+                header_name: CONTENT_TYPE.as_str(),
+            })
+        })
+        .transpose()?
+        .unwrap_or_default();
+    if content_type != ENTRY_CONTENT_TYPE {
+        return UnsupportedContentType {
+            content_type,
+            expected: ENTRY_CONTENT_TYPE,
+        }
+        .fail();
+    }
+
+    let db_name = DatabaseName::new(&db_name_str).context(DatabaseNameError)?;
+    let body = parse_body(req).await?;
+    let bytes = body.len();
+
+    server
+        .write_entry(&db_name, body.to_vec())
+        .await
+        .map_err(|e| {
+            debug!(?e, ?db_name, ?bytes, "error writing entry");
+
+            match e {
+                server::Error::DatabaseNotFound { .. } => {
+                    obs.client_error_with_labels(&metric_kv); // user error
+                    ApplicationError::DatabaseNotFound {
+                        name: db_name.to_string(),
+                    }
+                }
+                server::Error::DecodingEntry { .. } | server::Error::InvalidEntry { .. } => {
+                    obs.client_error_with_labels(&metric_kv); // user error
+                    ApplicationError::DecodingEntry { source: e }
+                }
+                _ => {
+                    obs.error_with_labels(&metric_kv);
+                    ApplicationError::WritingEntry {
+                        db_name: db_name.to_string(),
+                        source: e,
+                    }
+                }
+            }
+        })?;
+
+    obs.ok_with_labels(&metric_kv);
     Ok(Response::builder()
         .status(StatusCode::NO_CONTENT)
         .body(Body::empty())
@@ -630,11 +965,21 @@ async fn query<M: ConnectionManager + Send + Sync + Debug + 'static>(
 
     // TODO: stream read results out rather than rendering the
     // whole thing in mem
-    let batches = executor
-        .collect(physical_plan)
-        .await
-        .map_err(|e| Box::new(e) as _)
-        .context(Query { db_name })?;
+    let batches = match server.max_query_result_rows() {
+        // count rows as batches arrive so an oversized result fails before it
+        // is fully materialized
+        Some(max_rows) => executor
+            .collect_with_row_limit(physical_plan, max_rows)
+            .await
+            .map_err(|e| Box::new(e) as _)
+            .context(Query { db_name: &*db_name })?
+            .context(ResultTooLarge { max_rows })?,
+        None => executor
+            .collect(physical_plan)
+            .await
+            .map_err(|e| Box::new(e) as _)
+            .context(Query { db_name: &*db_name })?,
+    };
 
     let results = format
         .format(&batches)
@@ -653,20 +998,84 @@ async fn query<M: ConnectionManager + Send + Sync + Debug + 'static>(
     Ok(response)
 }
 
+/// Health check response in the JSON shape returned by InfluxDB 2.x
+#[derive(Debug, Serialize)]
+struct HealthResponse {
+    name: &'static str,
+    message: &'static str,
+    status: &'static str,
+    checks: Vec<()>,
+    version: &'static str,
+    commit: &'static str,
+}
+
+/// Responds with `OK`, or with an InfluxDB 2.x style JSON body if the
+/// request accepts `application/json`.
 #[tracing::instrument(level = "debug")]
 async fn health<M: ConnectionManager + Send + Sync + Debug + 'static>(
     req: Request<Body>,
 ) -> Result<Response<Body>, ApplicationError> {
     let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
     let path = req.uri().path().to_string();
-    server
-        .metrics
-        .http_requests
-        .observation()
-        .ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    let obs = server.metrics.http_requests.observation();
+
+    let accepts_json = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map(|accept| accept.contains("application/json"))
+        .unwrap_or(false);
+
+    let response = if accepts_json {
+        let health = HealthResponse {
+            name: "influxdb",
+            message: "ready for queries and writes",
+            status: "pass",
+            checks: vec![],
+            version: env!("CARGO_PKG_VERSION"),
+            commit: option_env!("GIT_HASH").unwrap_or("UNKNOWN"),
+        };
+        let result = serde_json::to_string(&health).context(JsonGenerationError)?;
+
+        Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(result))
+            .context(CreatingResponse)?
+    } else {
+        Response::new(Body::from("OK"))
+    };
+
+    obs.ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    Ok(response)
+}
+
+/// Reports the state of the server and its components as JSON. Responds with
+/// `503 Service Unavailable` until the server ID is set, since the server
+/// can't serve any requests before that.
+#[tracing::instrument(level = "debug")]
+async fn health_detail<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let path = req.uri().path().to_string();
+    let obs = server.metrics.http_requests.observation();
+
+    let detail = server.health_detail().await;
+    let status = match detail.server_id {
+        Some(_) => StatusCode::OK,
+        None => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    let result = serde_json::to_string(&detail).context(JsonGenerationError)?;
 
-    let response_body = "OK";
-    Ok(Response::new(Body::from(response_body.to_string())))
+    let response = Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    Ok(response)
 }
 
 #[tracing::instrument(level = "debug")]
@@ -683,114 +1092,519 @@ async fn handle_metrics<M: ConnectionManager + Send + Sync + Debug + 'static>(
     Ok(Response::new(Body::from(server.registry.metrics_as_text())))
 }
 
-#[derive(Deserialize, Debug)]
-/// Arguments in the query string of the request to /partitions
-struct DatabaseInfo {
-    org: String,
-    bucket: String,
-}
-
 #[tracing::instrument(level = "debug")]
-async fn list_partitions<M: ConnectionManager + Send + Sync + Debug + 'static>(
+async fn list_chunks<M: ConnectionManager + Send + Sync + Debug + 'static>(
     req: Request<Body>,
 ) -> Result<Response<Body>, ApplicationError> {
     let path = req.uri().path().to_string();
-
     let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
-
-    // TODO - catch error conditions
     let obs = server.metrics.http_requests.observation();
-    let query = req.uri().query().context(ExpectedQueryString {})?;
-
-    let info: DatabaseInfo = serde_urlencoded::from_str(query).context(InvalidQueryString {
-        query_string: query,
-    })?;
 
-    let db_name =
-        org_and_bucket_to_database(&info.org, &info.bucket).context(BucketMappingError)?;
+    let db_name_str = req
+        .param("name")
+        .expect("db name must have been set by routerify")
+        .clone();
 
     let metric_kv = vec![
-        KeyValue::new("db_name", db_name.to_string()),
+        KeyValue::new("db_name", db_name_str.clone()),
         KeyValue::new("path", path),
     ];
 
-    let db = server.db(&db_name).context(BucketNotFound {
-        org: &info.org,
-        bucket: &info.bucket,
+    let db_name = DatabaseName::new(&db_name_str).context(DatabaseNameError)?;
+
+    let chunk_summaries = server.chunk_summaries(&db_name).map_err(|e| match e {
+        server::Error::DatabaseNotFound { .. } => ApplicationError::DatabaseNotFound {
+            name: db_name_str.clone(),
+        },
+        e => ApplicationError::DatabaseError {
+            database: db_name_str.clone(),
+            source: Box::new(e),
+        },
     })?;
 
-    let partition_keys =
-        db.partition_keys()
-            .map_err(|e| Box::new(e) as _)
-            .context(BucketByName {
-                org: &info.org,
-                bucket_name: &info.bucket,
-            })?;
+    let result = serde_json::to_string(&chunk_summaries).context(JsonGenerationError)?;
 
-    let result = serde_json::to_string(&partition_keys).context(JsonGenerationError)?;
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
 
     obs.ok_with_labels(&metric_kv);
-    Ok(Response::new(Body::from(result)))
+    Ok(response)
 }
 
 #[derive(Deserialize, Debug)]
-/// Arguments in the query string of the request to /snapshot
-struct SnapshotInfo {
-    org: String,
-    bucket: String,
-    partition: String,
+/// Arguments of the request to /iox/api/v1/databases/:name/chunks/close,
+/// given either in the query string or as JSON request body
+struct CloseChunkInfo {
+    partition_key: String,
     table_name: String,
+    chunk_id: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+/// Response body of the request to /iox/api/v1/databases/:name/chunks/close
+struct CloseChunkResponse {
+    /// The id of the job moving the chunk to the read buffer
+    job_id: usize,
 }
 
+/// Closes an open or closing mutable buffer chunk by moving it to the read
+/// buffer in a background job, responding with the id of that job.
 #[tracing::instrument(level = "debug")]
-async fn pprof_home<M: ConnectionManager + Send + Sync + Debug + 'static>(
+async fn close_chunk<M: ConnectionManager + Send + Sync + Debug + 'static>(
     req: Request<Body>,
 ) -> Result<Response<Body>, ApplicationError> {
-    let default_host = HeaderValue::from_static("localhost");
-    let host = req
-        .headers()
-        .get("host")
-        .unwrap_or(&default_host)
-        .to_str()
-        .unwrap_or_default();
-    let cmd = format!(
-        "/debug/pprof/profile?seconds={}",
-        PProfArgs::default_seconds()
-    );
-    Ok(Response::new(Body::from(format!(
-        r#"<a href="{}">http://{}{}</a>"#,
-        cmd, host, cmd
-    ))))
-}
-
-async fn dump_rsprof(seconds: u64, frequency: i32) -> pprof::Result<pprof::Report> {
-    let guard = pprof::ProfilerGuard::new(frequency)?;
-    info!(
-        "start profiling {} seconds with frequency {} /s",
-        seconds, frequency
-    );
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
 
-    tokio::time::sleep(Duration::from_secs(seconds)).await;
+    let db_name_str = req
+        .param("name")
+        .expect("db name must have been set by routerify")
+        .clone();
 
-    info!(
-        "done profiling {} seconds with frequency {} /s",
-        seconds, frequency
-    );
-    guard.report().build()
-}
+    let metric_kv = vec![
+        KeyValue::new("db_name", db_name_str.clone()),
+        KeyValue::new("path", path),
+    ];
 
-#[derive(Debug, Deserialize)]
-struct PProfArgs {
-    #[serde(default = "PProfArgs::default_seconds")]
-    seconds: u64,
-    #[serde(default = "PProfArgs::default_frequency")]
-    frequency: NonZeroI32,
-}
+    let db_name = DatabaseName::new(&db_name_str).context(DatabaseNameError)?;
 
-impl PProfArgs {
-    fn default_seconds() -> u64 {
-        30
-    }
+    let info: CloseChunkInfo = match req.uri().query() {
+        Some(query) => serde_urlencoded::from_str(query).context(InvalidQueryString {
+            query_string: query,
+        })?,
+        None => {
+            let body = parse_body(req).await?;
+            serde_json::from_slice(&body).context(InvalidRequestBody)?
+        }
+    };
+
+    let tracker = server
+        .close_chunk(
+            db_name,
+            &info.table_name,
+            &info.partition_key,
+            info.chunk_id,
+        )
+        .map_err(|e| match e {
+            server::Error::DatabaseNotFound { .. } => ApplicationError::DatabaseNotFound {
+                name: db_name_str.clone(),
+            },
+            server::Error::ChunkNotFound { .. } => ApplicationError::ChunkNotFound {
+                db_name: db_name_str.clone(),
+                partition_key: info.partition_key.clone(),
+                table_name: info.table_name.clone(),
+                chunk_id: info.chunk_id,
+            },
+            e => ApplicationError::DatabaseError {
+                database: db_name_str.clone(),
+                source: Box::new(e),
+            },
+        })?;
+
+    let result = serde_json::to_string(&CloseChunkResponse {
+        job_id: tracker.id().0,
+    })
+    .context(JsonGenerationError)?;
+
+    let response = Response::builder()
+        .status(StatusCode::ACCEPTED)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&metric_kv);
+    Ok(response)
+}
+
+/// Lists the jobs tracked by the server, with their status and metadata.
+#[tracing::instrument(level = "debug")]
+async fn list_jobs<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
+
+    let mut operations: Vec<_> = server
+        .tracked_jobs()
+        .into_iter()
+        .map(|tracker| job_operation(&tracker))
+        .collect();
+    operations.sort_by_key(|operation| operation.id);
+
+    let result = serde_json::to_string(&operations).context(JsonGenerationError)?;
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    Ok(response)
+}
+
+/// Reports the number of bytes each database uses in object storage.
+#[tracing::instrument(level = "debug")]
+async fn object_store_usage<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
+
+    let usage = server
+        .object_store_usage()
+        .await
+        .context(ObjectStoreUsage)?;
+
+    let result = serde_json::to_string(&usage).context(JsonGenerationError)?;
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    Ok(response)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+/// Response body of the request to .../explain-sharding
+struct ShardingExplanation {
+    /// Rows per shard the lines would be sent to
+    shards: Vec<ShardExplanation>,
+    /// Rows that would be written locally, as the database has no routing
+    /// rules
+    unsharded_rows: usize,
+    /// Rows that would be sent to every target of the database's routing
+    /// config, if it has one
+    routing: Option<RoutingExplanation>,
+    /// Lines a write would fail for, e.g. as no sharding rule matches them
+    rejected: Vec<RejectedLine>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct RoutingExplanation {
+    rows: usize,
+    /// The node groups every row is sent to
+    targets: Vec<Vec<ServerId>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct RejectedLine {
+    /// 1-based line number in the request body
+    line_number: usize,
+    error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct ShardExplanation {
+    shard_id: ShardId,
+    rows: usize,
+    /// The nodes the shard is sent to, empty if the shard is not configured
+    node_group: Vec<ServerId>,
+}
+
+/// Reports how the line protocol in the request body would be sharded by the
+/// database's shard config, without writing anything.
+#[tracing::instrument(level = "debug")]
+async fn explain_sharding<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
+
+    let db_name_str = req
+        .param("name")
+        .expect("db name must have been set by routerify")
+        .clone();
+
+    let metric_kv = vec![
+        KeyValue::new("db_name", db_name_str.clone()),
+        KeyValue::new("path", path),
+    ];
+
+    let db_name = DatabaseName::new(&db_name_str).context(DatabaseNameError)?;
+    let db = server
+        .db(&db_name)
+        .context(DatabaseNotFound { name: &db_name_str })?;
+
+    let body = parse_body(req).await?;
+    let body = str::from_utf8(&body).context(ReadingBodyAsUtf8)?;
+    let lines = parse_line_protocol(body)?;
+
+    let explanation = {
+        let rules = db.rules.read();
+        let mut explanation = ShardingExplanation {
+            shards: vec![],
+            unsharded_rows: 0,
+            routing: None,
+            rejected: vec![],
+        };
+        match &rules.routing_rules {
+            Some(RoutingRules::ShardConfig(shard_config)) => {
+                let mut rows_per_shard: BTreeMap<ShardId, usize> = BTreeMap::new();
+                for (idx, line) in lines.iter().enumerate() {
+                    let error = match shard_config.explain_shard(line) {
+                        Ok(Some(shard_id)) => {
+                            *rows_per_shard.entry(shard_id).or_default() += 1;
+                            continue;
+                        }
+                        Ok(None) => "no sharding rule matches the line".to_string(),
+                        Err(e) => e.to_string(),
+                    };
+                    explanation.rejected.push(RejectedLine {
+                        line_number: idx + 1,
+                        error,
+                    });
+                }
+
+                for (shard_id, rows) in rows_per_shard {
+                    let node_group = match shard_config.shards.get(&shard_id) {
+                        Some(Shard::Iox { node_group, .. }) => node_group.clone(),
+                        None => vec![],
+                    };
+                    explanation.shards.push(ShardExplanation {
+                        shard_id,
+                        rows,
+                        node_group,
+                    })
+                }
+            }
+            Some(RoutingRules::RoutingConfig(routing_config)) => {
+                explanation.routing = Some(RoutingExplanation {
+                    rows: lines.len(),
+                    targets: routing_config.targets.clone(),
+                })
+            }
+            None => explanation.unsharded_rows = lines.len(),
+        }
+        explanation
+    };
+
+    let result = serde_json::to_string(&explanation).context(JsonGenerationError)?;
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&metric_kv);
+    Ok(response)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+/// Response body of the request to /iox/api/v1/databases/validate-rules
+struct RulesValidation {
+    /// The problems found in the rules, empty if they can be applied
+    issues: Vec<String>,
+}
+
+/// Lints the database rules in the request body, given as the JSON encoding
+/// of their protobuf representation, without applying them.
+#[tracing::instrument(level = "debug")]
+async fn validate_rules<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
+
+    let body = parse_body(req).await?;
+    let rules = generated_types::database_rules::decode_database_rules_json(&body)
+        .context(DecodingRules)?;
+
+    let issues = match server.validate_rules(&rules) {
+        Ok(()) => vec![],
+        Err(issues) => issues.iter().map(ToString::to_string).collect(),
+    };
+    let result = serde_json::to_string(&RulesValidation { issues }).context(JsonGenerationError)?;
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    Ok(response)
+}
+
+#[derive(Deserialize, Debug, Default)]
+/// Arguments in the query string of the request to /iox/api/v1/databases
+struct ListDatabasesInfo {
+    /// Return the rules and status of each database instead of only its name
+    #[serde(default)]
+    detail: bool,
+}
+
+#[derive(Serialize, Debug)]
+/// Response body of the request to /iox/api/v1/databases?detail=true
+struct DatabaseDetail {
+    name: String,
+    rules: generated_types::influxdata::iox::management::v1::DatabaseRules,
+    chunk_count: usize,
+    buffer_bytes: usize,
+}
+
+/// Lists the names of all databases, or their rules and status if `detail`
+/// is set.
+#[tracing::instrument(level = "debug")]
+async fn list_databases<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let path = req.uri().path().to_string();
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+    let obs = server.metrics.http_requests.observation();
+
+    let info: ListDatabasesInfo = match req.uri().query() {
+        Some(query) => serde_urlencoded::from_str(query).context(InvalidQueryString {
+            query_string: query,
+        })?,
+        None => ListDatabasesInfo::default(),
+    };
+
+    let databases = server.list_databases();
+    let result = if info.detail {
+        let details: Vec<_> = databases
+            .into_iter()
+            .map(|database| DatabaseDetail {
+                name: database.rules.name.to_string(),
+                rules: database.rules.into(),
+                chunk_count: database.chunk_count,
+                buffer_bytes: database.buffer_bytes,
+            })
+            .collect();
+        serde_json::to_string(&details)
+    } else {
+        let names: Vec<_> = databases
+            .into_iter()
+            .map(|database| database.rules.name.to_string())
+            .collect();
+        serde_json::to_string(&names)
+    }
+    .context(JsonGenerationError)?;
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(result))
+        .context(CreatingResponse)?;
+
+    obs.ok_with_labels(&[metrics::KeyValue::new("path", path)]);
+    Ok(response)
+}
+
+#[derive(Deserialize, Debug)]
+/// Arguments in the query string of the request to /partitions
+struct DatabaseInfo {
+    org: String,
+    bucket: String,
+}
+
+#[tracing::instrument(level = "debug")]
+async fn list_partitions<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let path = req.uri().path().to_string();
+
+    let server = Arc::clone(&req.data::<Arc<AppServer<M>>>().expect("server state"));
+
+    // TODO - catch error conditions
+    let obs = server.metrics.http_requests.observation();
+    let query = req.uri().query().context(ExpectedQueryString {})?;
+
+    let info: DatabaseInfo = serde_urlencoded::from_str(query).context(InvalidQueryString {
+        query_string: query,
+    })?;
+
+    let db_name =
+        org_and_bucket_to_database(&info.org, &info.bucket).context(BucketMappingError)?;
+
+    let metric_kv = vec![
+        KeyValue::new("db_name", db_name.to_string()),
+        KeyValue::new("path", path),
+    ];
+
+    let db = server.db(&db_name).context(BucketNotFound {
+        org: &info.org,
+        bucket: &info.bucket,
+    })?;
+
+    let partition_keys =
+        db.partition_keys()
+            .map_err(|e| Box::new(e) as _)
+            .context(BucketByName {
+                org: &info.org,
+                bucket_name: &info.bucket,
+            })?;
+
+    let result = serde_json::to_string(&partition_keys).context(JsonGenerationError)?;
+
+    obs.ok_with_labels(&metric_kv);
+    Ok(Response::new(Body::from(result)))
+}
+
+#[derive(Deserialize, Debug)]
+/// Arguments in the query string of the request to /snapshot
+struct SnapshotInfo {
+    org: String,
+    bucket: String,
+    partition: String,
+    table_name: String,
+}
+
+#[tracing::instrument(level = "debug")]
+async fn pprof_home<M: ConnectionManager + Send + Sync + Debug + 'static>(
+    req: Request<Body>,
+) -> Result<Response<Body>, ApplicationError> {
+    let default_host = HeaderValue::from_static("localhost");
+    let host = req
+        .headers()
+        .get("host")
+        .unwrap_or(&default_host)
+        .to_str()
+        .unwrap_or_default();
+    let cmd = format!(
+        "/debug/pprof/profile?seconds={}",
+        PProfArgs::default_seconds()
+    );
+    Ok(Response::new(Body::from(format!(
+        r#"<a href="{}">http://{}{}</a>"#,
+        cmd, host, cmd
+    ))))
+}
+
+async fn dump_rsprof(seconds: u64, frequency: i32) -> pprof::Result<pprof::Report> {
+    let guard = pprof::ProfilerGuard::new(frequency)?;
+    info!(
+        "start profiling {} seconds with frequency {} /s",
+        seconds, frequency
+    );
+
+    tokio::time::sleep(Duration::from_secs(seconds)).await;
+
+    info!(
+        "done profiling {} seconds with frequency {} /s",
+        seconds, frequency
+    );
+    guard.report().build()
+}
+
+#[derive(Debug, Deserialize)]
+struct PProfArgs {
+    #[serde(default = "PProfArgs::default_seconds")]
+    seconds: u64,
+    #[serde(default = "PProfArgs::default_frequency")]
+    frequency: NonZeroI32,
+}
+
+impl PProfArgs {
+    fn default_seconds() -> u64 {
+        30
+    }
 
     // 99Hz to avoid coinciding with special periods
     fn default_frequency() -> NonZeroI32 {
@@ -862,7 +1676,12 @@ mod tests {
     use arrow_util::assert_batches_eq;
     use reqwest::{Client, Response};
 
-    use data_types::{database_rules::DatabaseRules, server_id::ServerId, DatabaseName};
+    use data_types::{
+        database_rules::{DatabaseRules, NO_SHARD_CONFIG},
+        job::Operation,
+        server_id::ServerId,
+        DatabaseName,
+    };
     use object_store::{memory::InMemory, ObjectStore};
     use serde::de::DeserializeOwned;
     use server::{db::Db, ConnectionManagerImpl, ServerConfig as AppServerConfig};
@@ -894,6 +1713,32 @@ mod tests {
         check_response("health", response, StatusCode::OK, Some("OK")).await;
     }
 
+    #[tokio::test]
+    async fn test_health_json() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let response = client
+            .get(&format!("{}/health", server_url))
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+
+        let health: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(health["name"], "influxdb");
+        assert_eq!(health["status"], "pass");
+        assert_eq!(health["version"], env!("CARGO_PKG_VERSION"));
+    }
+
     #[tokio::test]
     async fn test_write() {
         let (_, config) = config();
@@ -942,6 +1787,374 @@ mod tests {
         assert_batches_eq!(expected, &batches);
     }
 
+    #[tokio::test]
+    async fn test_write_entry() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        let rules = DatabaseRules::new(DatabaseName::new("MyOrg_MyBucket").unwrap());
+        app_server.create_database(rules.clone()).await.unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let entry_url = format!("{}/iox/api/v1/databases/MyOrg_MyBucket/entry", server_url);
+
+        let lines: Vec<_> = influxdb_line_protocol::parse_lines(
+            "h2o,location=santa_monica val=1.5 1617286224000000000",
+        )
+        .map(|line| line.unwrap())
+        .collect();
+        let sharded_entries =
+            entry::lines_to_sharded_entries(&lines, 0, NO_SHARD_CONFIG, &rules).unwrap();
+        let entry_bytes = sharded_entries[0].entry.data().to_vec();
+
+        // the content type identifies the body as an entry
+        let response = client
+            .post(&entry_url)
+            .body(entry_bytes.clone())
+            .send()
+            .await;
+        check_response(
+            "write_entry",
+            response,
+            StatusCode::BAD_REQUEST,
+            Some(
+                r#"{"error":"Unsupported content type '', expected 'application/x-iox-entry'","error_code":100}"#,
+            ),
+        )
+        .await;
+
+        // the body must be a valid entry
+        let response = client
+            .post(&entry_url)
+            .header(CONTENT_TYPE, ENTRY_CONTENT_TYPE)
+            .body("not an entry")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = client
+            .post(&entry_url)
+            .header(CONTENT_TYPE, ENTRY_CONTENT_TYPE)
+            .body(entry_bytes)
+            .send()
+            .await;
+        check_response("write_entry", response, StatusCode::NO_CONTENT, Some("")).await;
+
+        let test_db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .expect("Database exists");
+
+        let batches = run_query(test_db, "select * from h2o").await;
+        let expected = vec![
+            "+--------------+---------------------+-----+",
+            "| location     | time                | val |",
+            "+--------------+---------------------+-----+",
+            "| santa_monica | 2021-04-01 14:10:24 | 1.5 |",
+            "+--------------+---------------------+-----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+    }
+
+    #[tokio::test]
+    async fn test_write_idempotency_key() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+
+        let writes = [
+            ("first", "h2o,loc=a val=1 1617286224000000000"),
+            // a retry of the first write
+            ("first", "h2o,loc=a val=1 1617286224000000000"),
+            ("second", "h2o,loc=a val=1 1617286224000000000"),
+        ];
+
+        for (key, lp_data) in &writes {
+            let response = client
+                .post(&format!(
+                    "{}/api/v2/write?bucket=MyBucket&org=MyOrg",
+                    server_url
+                ))
+                .header(IDEMPOTENCY_KEY_HEADER, *key)
+                .body(*lp_data)
+                .send()
+                .await;
+
+            check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+        }
+
+        let test_db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .expect("Database exists");
+
+        // queries would hide the duplicated row, so count the rows stored
+        let rows: usize = test_db
+            .chunk_summaries()
+            .unwrap()
+            .iter()
+            .map(|summary| summary.row_count)
+            .sum();
+        assert_eq!(rows, 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_precision() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+
+        let writes = [
+            ("s", "h2o,loc=a val=1 1617286224"),
+            ("ms", "h2o,loc=b val=2 1617286225123"),
+        ];
+
+        for (precision, lp_data) in &writes {
+            let response = client
+                .post(&format!(
+                    "{}/api/v2/write?bucket=MyBucket&org=MyOrg&precision={}",
+                    server_url, precision
+                ))
+                .body(*lp_data)
+                .send()
+                .await;
+
+            check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+        }
+
+        let test_db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .expect("Database exists");
+
+        let batches = run_query(test_db, "select loc, val, time from h2o order by loc").await;
+        let expected = vec![
+            "+-----+-----+-------------------------+",
+            "| loc | val | time                    |",
+            "+-----+-----+-------------------------+",
+            "| a   | 1   | 2021-04-01 14:10:24     |",
+            "| b   | 2   | 2021-04-01 14:10:25.123 |",
+            "+-----+-----+-------------------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        // timestamps that overflow when scaled are rejected
+        let response = client
+            .post(&format!(
+                "{}/api/v2/write?bucket=MyBucket&org=MyOrg&precision=s",
+                server_url
+            ))
+            .body("h2o,loc=c val=3 9223372036854775807")
+            .send()
+            .await;
+
+        check_response(
+            "write",
+            response,
+            StatusCode::BAD_REQUEST,
+            Some(r#"{"error":"Timestamp 9223372036854775807 overflows when converted from precision 's' to nanoseconds","error_code":100}"#),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_write_error_codes() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let write_url = format!(
+            "{}/api/v2/write?bucket={}&org={}",
+            server_url, "MyBucket", "MyOrg"
+        );
+
+        // line protocol that can't be parsed
+        let response = client
+            .post(&write_url)
+            .body("h2o_temperature,location=santa_monica surface_degrees=")
+            .send()
+            .await;
+        check_error_code(
+            response,
+            StatusCode::BAD_REQUEST,
+            ApiErrorCode::LP_PARSE_ERROR,
+        )
+        .await;
+
+        // a field written with conflicting types
+        let response = client
+            .post(&write_url)
+            .body(
+                "h2o_temperature surface_degrees=65.2 1\nh2o_temperature surface_degrees=\"hot\" 2",
+            )
+            .send()
+            .await;
+        check_error_code(
+            response,
+            StatusCode::BAD_REQUEST,
+            ApiErrorCode::FIELD_TYPE_CONFLICT,
+        )
+        .await;
+
+        // a write exceeding the hard buffer limit
+        let test_db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .expect("Database exists");
+        test_db.rules.write().lifecycle_rules.buffer_size_hard =
+            Some(std::num::NonZeroUsize::new(10).unwrap());
+
+        let response = client
+            .post(&write_url)
+            .body("h2o_temperature surface_degrees=65.2 1")
+            .send()
+            .await;
+        check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+
+        let response = client
+            .post(&write_url)
+            .body("h2o_temperature surface_degrees=65.3 2")
+            .send()
+            .await;
+        check_error_code(
+            response,
+            StatusCode::SERVICE_UNAVAILABLE,
+            ApiErrorCode::WRITE_BUFFER_FULL,
+        )
+        .await;
+    }
+    #[tokio::test]
+    async fn test_write_parse_error_position() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let lp_data = vec![
+            "h2o_temperature,location=santa_monica surface_degrees=65.2 1",
+            "",
+            "h2o_temperature,location=santa_monica surface_degrees= 2",
+            "h2o_temperature,location=coyote_creek surface_degrees=50.4 3",
+        ]
+        .join("\n");
+        let response = client
+            .post(&format!(
+                "{}/api/v2/write?bucket={}&org={}",
+                server_url, "MyBucket", "MyOrg"
+            ))
+            .body(lp_data)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        let expected_code: u32 = ApiErrorCode::LP_PARSE_ERROR.into();
+        assert_eq!(body["error_code"], expected_code);
+        assert_eq!(body["line_number"], 3);
+        assert_eq!(
+            body["line"],
+            "h2o_temperature,location=santa_monica surface_degrees= 2"
+        );
+        assert_eq!(body["valid_lines"], 2);
+
+        // nothing was written
+        let db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .unwrap();
+        assert!(db.partition_keys().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_buffer_pressure() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let write_url = format!(
+            "{}/api/v2/write?bucket={}&org={}",
+            server_url, "MyBucket", "MyOrg"
+        );
+
+        // no soft limit configured
+        let response = client
+            .post(&write_url)
+            .body("h2o_temperature surface_degrees=65.2 1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.headers().get(BUFFER_PRESSURE_HEADER).is_none());
+
+        // over the soft but not the hard limit
+        let test_db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .expect("Database exists");
+        {
+            let mut rules = test_db.rules.write();
+            rules.lifecycle_rules.buffer_size_soft = Some(std::num::NonZeroUsize::new(10).unwrap());
+            rules.lifecycle_rules.buffer_size_hard =
+                Some(std::num::NonZeroUsize::new(1_000_000).unwrap());
+        }
+
+        let response = client
+            .post(&write_url)
+            .body("h2o_temperature surface_degrees=65.3 2")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers().get(BUFFER_PRESSURE_HEADER).unwrap(),
+            "high"
+        );
+    }
+
     #[tokio::test]
     async fn test_write_metrics() {
         let (metrics_registry, config) = config();
@@ -1016,18 +2229,82 @@ mod tests {
                 "{}/api/v2/write?bucket=NotMyBucket&org=NotMyOrg",
                 server_url,
             ))
-            .body(lp_data)
+            .body(lp_data)
+            .send()
+            .await
+            .unwrap();
+
+        // A single point was rejected
+        metrics_registry
+            .has_metric_family("ingest_points_total")
+            .with_labels(&[("db_name", "NotMyOrg_NotMyBucket"), ("status", "error")])
+            .counter()
+            .eq(1.0)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_query_result_too_large() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(
+            ConnectionManagerImpl::new(),
+            config.with_max_query_result_rows(1),
+        ));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let lp_data =
+            "h2o_temperature,location=santa_monica surface_degrees=65.2 1617286224000000000\n\
+             h2o_temperature,location=Boston surface_degrees=50.2 1617286224000000000";
+        let response = client
+            .post(&format!(
+                "{}/api/v2/write?bucket=MyBucket&org=MyOrg",
+                server_url
+            ))
+            .body(lp_data)
+            .send()
+            .await;
+        check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+
+        // a single row is within the limit
+        let response = client
+            .get(&format!(
+                "{}/iox/api/v1/databases/MyOrg_MyBucket/query?q={}&format=csv",
+                server_url,
+                "select%20location%20from%20h2o_temperature%20where%20location%3D%27Boston%27"
+            ))
+            .send()
+            .await;
+        check_response(
+            "query",
+            response,
+            StatusCode::OK,
+            Some("location\nBoston\n"),
+        )
+        .await;
+
+        let response = client
+            .get(&format!(
+                "{}/iox/api/v1/databases/MyOrg_MyBucket/query?q={}",
+                server_url, "select%20*%20from%20h2o_temperature"
+            ))
             .send()
-            .await
-            .unwrap();
-
-        // A single point was rejected
-        metrics_registry
-            .has_metric_family("ingest_points_total")
-            .with_labels(&[("db_name", "NotMyOrg_NotMyBucket"), ("status", "error")])
-            .counter()
-            .eq(1.0)
-            .unwrap();
+            .await;
+        check_response(
+            "query",
+            response,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Some("{\"error\":\"Query results exceed limit of 1 rows\",\"error_code\":100}"),
+        )
+        .await;
     }
 
     /// Sets up a test database with some data for testing the query endpoint
@@ -1161,6 +2438,525 @@ mod tests {
         check_response("query", response, StatusCode::OK, Some(res)).await;
     }
 
+    #[tokio::test]
+    async fn test_list_chunks() {
+        use data_types::chunk_metadata::{ChunkStorage, ChunkSummary};
+
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let lp_data = "h2o_temperature,location=santa_monica,state=CA surface_degrees=65.2,bottom_degrees=50.4 1617286224000000000";
+
+        let response = client
+            .post(&format!(
+                "{}/api/v2/write?bucket={}&org={}",
+                server_url, "MyBucket", "MyOrg"
+            ))
+            .body(lp_data)
+            .send()
+            .await;
+        check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+
+        // move the chunk to the read buffer
+        app_server
+            .close_chunk(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+                "h2o_temperature",
+                "",
+                0,
+            )
+            .unwrap()
+            .join()
+            .await;
+
+        let url = format!("{}/iox/api/v1/databases/MyOrg_MyBucket/chunks", server_url);
+        let summaries: Vec<ChunkSummary> = check_json_response(&client, &url, StatusCode::OK).await;
+
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.id, 0);
+        assert_eq!(summary.partition_key.as_ref(), "");
+        assert_eq!(summary.table_name.as_ref(), "h2o_temperature");
+        assert_eq!(summary.storage, ChunkStorage::ReadBuffer);
+        assert_eq!(summary.row_count, 1);
+        assert!(summary.estimated_bytes > 0);
+
+        // unknown databases are reported as not found
+        let response = client
+            .get(&format!(
+                "{}/iox/api/v1/databases/NotMyOrg_MyBucket/chunks",
+                server_url
+            ))
+            .send()
+            .await;
+        check_response("list_chunks", response, StatusCode::NOT_FOUND, Some("")).await;
+    }
+
+    #[tokio::test]
+    async fn test_close_chunk() {
+        use data_types::chunk_metadata::{ChunkStorage, ChunkSummary};
+        use tracker::TaskId;
+
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let lp_data = "h2o_temperature,location=santa_monica,state=CA surface_degrees=65.2,bottom_degrees=50.4 1617286224000000000";
+
+        let response = client
+            .post(&format!(
+                "{}/api/v2/write?bucket={}&org={}",
+                server_url, "MyBucket", "MyOrg"
+            ))
+            .body(lp_data)
+            .send()
+            .await;
+        check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+
+        let close_url = format!(
+            "{}/iox/api/v1/databases/MyOrg_MyBucket/chunks/close",
+            server_url
+        );
+
+        // unknown chunks are reported as not found
+        let response = client
+            .post(&format!(
+                "{}?partition_key=&table_name=h2o_temperature&chunk_id=42",
+                close_url
+            ))
+            .send()
+            .await;
+        check_response("close_chunk", response, StatusCode::NOT_FOUND, Some("")).await;
+
+        // close the chunk, passing its address as JSON body
+        let response = client
+            .post(&close_url)
+            .body(r#"{"partition_key": "", "table_name": "h2o_temperature", "chunk_id": 0}"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body: CloseChunkResponse = response.json().await.unwrap();
+
+        app_server
+            .get_job(TaskId(body.job_id))
+            .unwrap()
+            .join()
+            .await;
+
+        let url = format!("{}/iox/api/v1/databases/MyOrg_MyBucket/chunks", server_url);
+        let summaries: Vec<ChunkSummary> = check_json_response(&client, &url, StatusCode::OK).await;
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].id, 0);
+        assert_eq!(summaries[0].storage, ChunkStorage::ReadBuffer);
+
+        // unknown databases are reported as not found
+        let response = client
+            .post(&format!(
+                "{}/iox/api/v1/databases/NotMyOrg_MyBucket/chunks/close?partition_key=&table_name=h2o_temperature&chunk_id=0",
+                server_url
+            ))
+            .send()
+            .await;
+        check_response("close_chunk", response, StatusCode::NOT_FOUND, Some("")).await;
+    }
+
+    #[tokio::test]
+    async fn test_list_jobs() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        let db_name = DatabaseName::new("MyOrg_MyBucket").unwrap();
+        app_server
+            .create_database(DatabaseRules::new(db_name.clone()))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let lp_data = "h2o_temperature,location=santa_monica,state=CA surface_degrees=65.2,bottom_degrees=50.4 1617286224000000000";
+        let response = client
+            .post(&format!(
+                "{}/api/v2/write?bucket={}&org={}",
+                server_url, "MyBucket", "MyOrg"
+            ))
+            .body(lp_data)
+            .send()
+            .await;
+        check_response("write", response, StatusCode::NO_CONTENT, Some("")).await;
+
+        let dummy = app_server.spawn_dummy_job(vec![100]);
+        let close = app_server
+            .close_chunk(db_name, "h2o_temperature", "", 0)
+            .unwrap();
+        dummy.join().await;
+        close.join().await;
+
+        let url = format!("{}/iox/api/v1/jobs", server_url);
+        let jobs: serde_json::Value = check_json_response(&client, &url, StatusCode::OK).await;
+        let jobs = jobs.as_array().unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0]["id"], dummy.id().0);
+        assert_eq!(jobs[0]["status"], "Complete");
+        assert_eq!(jobs[0]["task_count"], 1);
+        assert_eq!(
+            jobs[0]["job"],
+            serde_json::json!({"kind": "dummy", "nanos": [100]})
+        );
+
+        assert_eq!(jobs[1]["id"], close.id().0);
+        assert_eq!(jobs[1]["status"], "Complete");
+        assert_eq!(
+            jobs[1]["job"],
+            serde_json::json!({
+                "kind": "close_chunk",
+                "db_name": "MyOrg_MyBucket",
+                "partition_key": "",
+                "table_name": "h2o_temperature",
+                "chunk_id": 0,
+            })
+        );
+
+        // jobs deserialize back from the listing
+        let operations: Vec<Operation> = check_json_response(&client, &url, StatusCode::OK).await;
+        assert_eq!(operations[0].job, Some(dummy.metadata().clone()));
+        assert_eq!(operations[1].job, Some(close.metadata().clone()));
+    }
+
+    #[tokio::test]
+    async fn test_list_databases() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        for name in &["foo", "bar", "baz"] {
+            app_server
+                .create_database(DatabaseRules::new(DatabaseName::new(*name).unwrap()))
+                .await
+                .unwrap();
+        }
+        let server_url = test_server(Arc::clone(&app_server));
+        let client = Client::new();
+
+        let url = format!("{}/iox/api/v1/databases", server_url);
+        let names: Vec<String> = check_json_response(&client, &url, StatusCode::OK).await;
+        assert_eq!(names, vec!["bar", "baz", "foo"]);
+
+        let url = format!("{}/iox/api/v1/databases?detail=true", server_url);
+        let details: serde_json::Value = check_json_response(&client, &url, StatusCode::OK).await;
+        let details = details.as_array().unwrap();
+        assert_eq!(details.len(), 3);
+        assert_eq!(details[0]["name"], "bar");
+        assert_eq!(details[0]["rules"]["name"], "bar");
+        assert_eq!(details[0]["chunk_count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_health_detail() {
+        use server::HealthDetail;
+
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let url = format!("{}/iox/api/v1/health/detail", server_url);
+
+        // not ready until the server ID is set
+        let detail: HealthDetail =
+            check_json_response(&client, &url, StatusCode::SERVICE_UNAVAILABLE).await;
+        assert_eq!(detail.server_id, None);
+
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let detail: HealthDetail = check_json_response(&client, &url, StatusCode::OK).await;
+        assert_eq!(detail.server_id, Some(ServerId::try_from(1).unwrap()));
+        assert_eq!(detail.databases, 1);
+        assert_eq!(detail.remotes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_object_store_usage() {
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        app_server
+            .create_database(DatabaseRules::new(
+                DatabaseName::new("MyOrg_MyBucket").unwrap(),
+            ))
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let url = format!("{}/iox/api/v1/storage", server_url);
+        let usage: BTreeMap<String, u64> = check_json_response(&client, &url, StatusCode::OK).await;
+
+        assert_eq!(usage.len(), 1);
+        assert!(usage["MyOrg_MyBucket"] > 0);
+    }
+
+    #[tokio::test]
+    async fn test_explain_sharding() {
+        use data_types::{
+            consistent_hasher::ConsistentHasher,
+            database_rules::{HashRing, Matcher, MatcherToShard, RoutingConfig, ShardConfig},
+        };
+        use regex::Regex;
+
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+
+        let node_1 = ServerId::try_from(10).unwrap();
+        let node_2 = ServerId::try_from(20).unwrap();
+        let shard_config = ShardConfig {
+            specific_targets: vec![MatcherToShard {
+                matcher: Matcher {
+                    table_name_regex: Some(Regex::new("^cpu$").unwrap()),
+                    predicate: None,
+                },
+                shard: 1,
+            }],
+            hash_ring: Some(HashRing {
+                shards: ConsistentHasher::new(&[2]),
+                ..Default::default()
+            }),
+            shards: Arc::new(
+                vec![
+                    (
+                        1,
+                        Shard::Iox {
+                            node_group: vec![node_1],
+                            replication_factor: None,
+                        },
+                    ),
+                    (
+                        2,
+                        Shard::Iox {
+                            node_group: vec![node_2],
+                            replication_factor: None,
+                        },
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        };
+        app_server
+            .create_database(DatabaseRules {
+                routing_rules: Some(RoutingRules::ShardConfig(shard_config.clone())),
+                ..DatabaseRules::new(DatabaseName::new("MyOrg_MyBucket").unwrap())
+            })
+            .await
+            .unwrap();
+        let server_url = test_server(Arc::clone(&app_server));
+
+        let client = Client::new();
+        let lp_data = vec![
+            "cpu,host=a usage=1 10",
+            "cpu,host=b usage=2 10",
+            "mem,host=a free=1 10",
+            "mem,host=b free=2 10",
+            "disk,host=a free=3 10",
+        ]
+        .join("\n");
+
+        let url = format!(
+            "{}/iox/api/v1/databases/MyOrg_MyBucket/explain-sharding",
+            server_url
+        );
+        let response = client
+            .post(&url)
+            .body(lp_data.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let explanation: ShardingExplanation = response.json().await.unwrap();
+        assert_eq!(
+            explanation,
+            ShardingExplanation {
+                shards: vec![
+                    ShardExplanation {
+                        shard_id: 1,
+                        rows: 2,
+                        node_group: vec![node_1],
+                    },
+                    ShardExplanation {
+                        shard_id: 2,
+                        rows: 3,
+                        node_group: vec![node_2],
+                    },
+                ],
+                unsharded_rows: 0,
+                routing: None,
+                rejected: vec![],
+            }
+        );
+
+        // lines no sharding rule matches are rejected, as they would be when
+        // written
+        let db = app_server
+            .db(&DatabaseName::new("MyOrg_MyBucket").unwrap())
+            .unwrap();
+        db.rules.write().routing_rules = Some(RoutingRules::ShardConfig(ShardConfig {
+            hash_ring: None,
+            ..shard_config.clone()
+        }));
+        let explanation: ShardingExplanation = client
+            .post(&url)
+            .body(lp_data.clone())
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            explanation,
+            ShardingExplanation {
+                shards: vec![ShardExplanation {
+                    shard_id: 1,
+                    rows: 2,
+                    node_group: vec![node_1],
+                }],
+                unsharded_rows: 0,
+                routing: None,
+                rejected: (3..=5)
+                    .map(|line_number| RejectedLine {
+                        line_number,
+                        error: "no sharding rule matches the line".to_string(),
+                    })
+                    .collect(),
+            }
+        );
+
+        // with a routing config, every line is sent to all of its targets
+        db.rules.write().routing_rules = Some(RoutingRules::RoutingConfig(RoutingConfig {
+            targets: vec![vec![node_1], vec![node_2]],
+            quorum: None,
+        }));
+        let explanation: ShardingExplanation = client
+            .post(&url)
+            .body(lp_data)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            explanation,
+            ShardingExplanation {
+                shards: vec![],
+                unsharded_rows: 0,
+                routing: Some(RoutingExplanation {
+                    rows: 5,
+                    targets: vec![vec![node_1], vec![node_2]],
+                }),
+                rejected: vec![],
+            }
+        );
+
+        // nothing was written
+        assert!(db.partition_keys().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rules() {
+        use data_types::database_rules::{LifecycleRules, PartitionTemplate, TemplatePart};
+        use generated_types::database_rules::encode_database_rules_json;
+        use std::num::NonZeroUsize;
+
+        let (_, config) = config();
+        let app_server = Arc::new(AppServer::new(ConnectionManagerImpl::new(), config));
+        app_server.set_id(ServerId::try_from(1).unwrap()).unwrap();
+        app_server.maybe_initialize_server().await;
+        let server_url = test_server(Arc::clone(&app_server));
+        let url = format!("{}/iox/api/v1/databases/validate-rules", server_url);
+
+        let valid = DatabaseRules::new(DatabaseName::new("MyOrg_MyBucket").unwrap());
+        let invalid = DatabaseRules {
+            partition_template: PartitionTemplate {
+                parts: vec![TemplatePart::TimeFormat("%Y-%Q".to_string())],
+                ..Default::default()
+            },
+            lifecycle_rules: LifecycleRules {
+                buffer_size_soft: NonZeroUsize::new(2000),
+                buffer_size_hard: NonZeroUsize::new(1000),
+                ..Default::default()
+            },
+            ..valid.clone()
+        };
+
+        let client = Client::new();
+        let response = client
+            .post(&url)
+            .body(encode_database_rules_json(valid).unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let validation: RulesValidation = response.json().await.unwrap();
+        assert_eq!(validation, RulesValidation { issues: vec![] });
+
+        let response = client
+            .post(&url)
+            .body(encode_database_rules_json(invalid).unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let validation: RulesValidation = response.json().await.unwrap();
+        assert_eq!(
+            validation,
+            RulesValidation {
+                issues: vec![
+                    "Partition template part 0 has an invalid time format '%Y-%Q'".to_string(),
+                    "Soft buffer size 2000 exceeds the hard buffer size 1000".to_string(),
+                ]
+            }
+        );
+
+        // rules that can't be decoded are rejected
+        let response = client.post(&url).body("{").send().await;
+        check_response("validate-rules", response, StatusCode::BAD_REQUEST, None).await;
+    }
+
     fn gzip_str(s: &str) -> Vec<u8> {
         use flate2::{write::GzEncoder, Compression};
         use std::io::Write;
@@ -1293,7 +3089,25 @@ mod tests {
         }
     }
 
-    #[allow(dead_code)]
+    /// checks a http response is an error with the expected API error code
+    async fn check_error_code(
+        response: Result<Response, reqwest::Error>,
+        expected_status: StatusCode,
+        expected_code: ApiErrorCode,
+    ) {
+        let response = response.expect("request failed");
+        assert_eq!(response.status(), expected_status);
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .expect("Converting request body to json");
+        println!("error response: {}", body);
+
+        let expected_code: u32 = expected_code.into();
+        assert_eq!(body["error_code"], expected_code);
+    }
+
     async fn check_json_response<T: DeserializeOwned + Eq + Debug>(
         client: &Client,
         url: &str,
@@ -0,0 +1,46 @@
+//! Describes the jobs tracked by the server, for use in IOx services
+use data_types::job::{Job, Operation, OperationStatus};
+use tracker::{TaskStatus, TaskTracker};
+
+/// Describes a tracked job along with its progress.
+///
+/// The tracker can't tell whether a job succeeded, so a finished job is
+/// either `Complete` or `Cancelled`, never `Errored`.
+pub fn job_operation(tracker: &TaskTracker<Job>) -> Operation {
+    let (task_count, pending_count, cpu_nanos, wall_nanos, status) = match tracker.get_status() {
+        TaskStatus::Creating => (0, 0, 0, 0, OperationStatus::Running),
+        TaskStatus::Running {
+            total_count,
+            pending_count,
+            cpu_nanos,
+        } => (
+            total_count,
+            pending_count,
+            cpu_nanos,
+            0,
+            OperationStatus::Running,
+        ),
+        TaskStatus::Complete {
+            total_count,
+            cpu_nanos,
+            wall_nanos,
+        } => {
+            let status = if tracker.is_cancelled() {
+                OperationStatus::Cancelled
+            } else {
+                OperationStatus::Complete
+            };
+            (total_count, 0, cpu_nanos, wall_nanos, status)
+        }
+    };
+
+    Operation {
+        id: tracker.id().0,
+        task_count: task_count as u64,
+        pending_count: pending_count as u64,
+        wall_time: std::time::Duration::from_nanos(wall_nanos as u64),
+        cpu_time: std::time::Duration::from_nanos(cpu_nanos as u64),
+        job: Some(tracker.metadata().clone()),
+        status,
+    }
+}
@@ -33,6 +33,11 @@ pub fn default_server_error_handler(error: server::Error) -> tonic::Status {
             description: source.to_string(),
         }
         .into(),
+        Error::InvalidEntry { source } => FieldViolation {
+            field: "entry".into(),
+            description: source.to_string(),
+        }
+        .into(),
         Error::HardLimitReached {} => QuotaFailure {
             subject: "influxdata.com/iox/buffer".to_string(),
             description: "hard buffer limit reached".to_string(),
@@ -44,6 +49,12 @@ pub fn default_server_error_handler(error: server::Error) -> tonic::Status {
             ..Default::default()
         }
         .into(),
+        Error::JobNotFound { id } => NotFound {
+            resource_type: "job".to_string(),
+            resource_name: id.to_string(),
+            ..Default::default()
+        }
+        .into(),
         Error::RemoteError { source } => tonic::Status::unavailable(source.to_string()),
         error => {
             error!(?error, "Unexpected error");
@@ -387,7 +387,32 @@ where
                         message: e.to_string(),
                     });
 
-                    DatabaseStatus { db_name, error }
+                    let catalog_repair =
+                        self.server
+                            .catalog_repair(&db_name)
+                            .map(|repair| CatalogRepair {
+                                corrupt_revision: repair.corrupt_revision,
+                                last_good_revision: repair.last_good_revision,
+                                error: Some(ProtobufError {
+                                    message: repair.error.to_string(),
+                                }),
+                                deleted_files: repair
+                                    .deleted
+                                    .iter()
+                                    .map(|path| path.display())
+                                    .collect(),
+                                orphaned_files: repair
+                                    .orphaned_files
+                                    .iter()
+                                    .map(|path| path.display())
+                                    .collect(),
+                            });
+
+                    DatabaseStatus {
+                        db_name,
+                        error,
+                        catalog_repair,
+                    }
                 })
                 .collect()
         } else {
@@ -6,7 +6,7 @@ use observability_deps::tracing::debug;
 use prost::Message;
 use tonic::Response;
 
-use data_types::job::Job;
+use data_types::job::{Job, OperationStatus};
 use generated_types::google::FieldViolationExt;
 use generated_types::{
     google::{
@@ -18,9 +18,11 @@ use generated_types::{
     influxdata::iox::management::v1 as management,
     protobuf_type_url,
 };
-use tracker::{TaskId, TaskStatus, TaskTracker};
+use tracker::{TaskId, TaskTracker};
 
 use server::{ConnectionManager, Server};
+
+use crate::influxdb_ioxd::jobs::job_operation;
 use std::convert::TryInto;
 
 /// Implementation of the write service
@@ -29,49 +31,14 @@ struct OperationsService<M: ConnectionManager> {
 }
 
 pub fn encode_tracker(tracker: TaskTracker<Job>) -> Result<Operation, tonic::Status> {
-    let id = tracker.id();
-    let is_cancelled = tracker.is_cancelled();
-    let status = tracker.get_status();
-
-    let (operation_metadata, is_complete) = match status {
-        TaskStatus::Creating => {
-            let metadata = management::OperationMetadata {
-                job: Some(tracker.metadata().clone().into()),
-                ..Default::default()
-            };
-
-            (metadata, false)
-        }
-        TaskStatus::Running {
-            total_count,
-            pending_count,
-            cpu_nanos,
-        } => {
-            let metadata = management::OperationMetadata {
-                cpu_nanos: cpu_nanos as _,
-                task_count: total_count as _,
-                pending_count: pending_count as _,
-                job: Some(tracker.metadata().clone().into()),
-                ..Default::default()
-            };
-
-            (metadata, false)
-        }
-        TaskStatus::Complete {
-            total_count,
-            cpu_nanos,
-            wall_nanos,
-        } => {
-            let metadata = management::OperationMetadata {
-                cpu_nanos: cpu_nanos as _,
-                task_count: total_count as _,
-                wall_nanos: wall_nanos as _,
-                job: Some(tracker.metadata().clone().into()),
-                ..Default::default()
-            };
-
-            (metadata, true)
-        }
+    let summary = job_operation(&tracker);
+
+    let operation_metadata = management::OperationMetadata {
+        cpu_nanos: summary.cpu_time.as_nanos() as _,
+        task_count: summary.task_count,
+        pending_count: summary.pending_count,
+        wall_nanos: summary.wall_time.as_nanos() as _,
+        job: summary.job.map(Into::into),
     };
 
     let mut buffer = BytesMut::new();
@@ -85,25 +52,25 @@ pub fn encode_tracker(tracker: TaskTracker<Job>) -> Result<Operation, tonic::Sta
         value: buffer.freeze(),
     };
 
-    let result = match (is_complete, is_cancelled) {
-        (true, true) => Some(operation::Result::Error(Status {
+    let result = match summary.status {
+        OperationStatus::Cancelled => Some(operation::Result::Error(Status {
             code: tonic::Code::Cancelled as _,
             message: "Job cancelled".to_string(),
             details: vec![],
         })),
 
-        (true, false) => Some(operation::Result::Response(Any {
+        OperationStatus::Complete => Some(operation::Result::Response(Any {
             type_url: "type.googleapis.com/google.protobuf.Empty".to_string(),
             value: Default::default(), // TODO: Verify this is correct
         })),
 
-        _ => None,
+        OperationStatus::Running | OperationStatus::Errored => None,
     };
 
     Ok(Operation {
-        name: id.to_string(),
+        name: summary.id.to_string(),
         metadata: Some(metadata),
-        done: is_complete,
+        done: result.is_some(),
         result,
     })
 }
@@ -11,6 +11,6 @@ async fn test_http_error_messages() {
         .await
         .expect_err("Should have errored");
 
-    let expected_error = "HTTP request returned an error: 400 Bad Request, `{\"error\":\"Error parsing line protocol: A generic parsing error occurred: TakeWhile1\",\"error_code\":100}`";
+    let expected_error = "HTTP request returned an error: 400 Bad Request, `{\"error\":\"Error parsing line protocol on line 1 'arbitrary': A generic parsing error occurred: TakeWhile1 (0 valid lines)\",\"error_code\":104,\"line\":\"arbitrary\",\"line_number\":1,\"valid_lines\":0}`";
     assert_eq!(result.to_string(), expected_error);
 }
@@ -207,6 +207,7 @@ async fn test_create_get_update_database() {
             parts: vec![partition_template::Part {
                 part: Some(partition_template::part::Part::Table(Empty {})),
             }],
+            ..Default::default()
         }),
         lifecycle_rules: Some(LifecycleRules {
             buffer_size_hard: 553,
@@ -227,6 +228,8 @@ async fn test_create_get_update_database() {
             nanos: 0,
         }),
         write_buffer_connection_string: "".into(),
+        max_partition_key_len: 0,
+        partition_key_overflow: PartitionKeyOverflow::Error as _,
     };
 
     client
@@ -310,6 +310,7 @@ pub async fn create_readable_database_plus(
             parts: vec![partition_template::Part {
                 part: Some(partition_template::part::Part::Table(Empty {})),
             }],
+            ..Default::default()
         }),
         lifecycle_rules: Some(LifecycleRules {
             buffer_size_hard: 1024 * 1024,
@@ -345,6 +346,7 @@ pub async fn create_quickly_persisting_database(
                     "%Y-%m-%d %H:00:00".into(),
                 )),
             }],
+            ..Default::default()
         }),
         lifecycle_rules: Some(LifecycleRules {
             mutable_linger_seconds: 1,
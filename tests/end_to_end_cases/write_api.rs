@@ -231,6 +231,7 @@ async fn test_write_routed() {
                             id: TEST_REMOTE_ID_1,
                         }],
                     })),
+                    replication_factor: 0,
                 },
             ),
             (
@@ -241,6 +242,7 @@ async fn test_write_routed() {
                             id: TEST_REMOTE_ID_2,
                         }],
                     })),
+                    replication_factor: 0,
                 },
             ),
             (
@@ -251,6 +253,7 @@ async fn test_write_routed() {
                             id: TEST_REMOTE_ID_3,
                         }],
                     })),
+                    replication_factor: 0,
                 },
             ),
         ]
@@ -402,6 +405,7 @@ async fn test_write_routed_errors() {
                 sink: Some(shard::Sink::Iox(NodeGroup {
                     nodes: vec![Node { id: TEST_REMOTE_ID }],
                 })),
+                replication_factor: 0,
             },
         )]
         .into_iter()
@@ -514,9 +518,10 @@ async fn test_write_routed_no_shard() {
             .await
             .expect("cannot get database on router");
         let routing_config = RoutingConfig {
-            target: Some(NodeGroup {
+            targets: vec![NodeGroup {
                 nodes: vec![Node { id: *remote_id }],
-            }),
+            }],
+            quorum: 0,
         };
         router_db_rules.routing_rules = Some(RoutingRules::RoutingConfig(routing_config));
 